@@ -17,6 +17,9 @@
 
 use super::BloomFilter;
 use crate::codec::family::Family;
+use crate::common::BitArray;
+use crate::common::SketchBuilder;
+use crate::error::Error;
 use crate::hash::DEFAULT_UPDATE_SEED;
 
 /// Builder for creating [`BloomFilter`] instances.
@@ -156,15 +159,11 @@ impl BloomFilterBuilder {
     ///
     /// Panics if neither `with_accuracy()` nor `with_size()` was called.
     pub fn build(self) -> BloomFilter {
-        let num_hashes = self.num_hashes;
-        let num_words = self.num_bits.div_ceil(64) as usize;
-        let bit_array = vec![0u64; num_words].into_boxed_slice();
-
         BloomFilter {
             seed: self.seed,
-            num_hashes,
-            num_bits_set: 0,
-            bit_array,
+            num_hashes: self.num_hashes,
+            bit_array: BitArray::new(self.num_bits),
+            derived: false,
         }
     }
 
@@ -235,3 +234,12 @@ impl BloomFilterBuilder {
         ) as u16
     }
 }
+
+impl SketchBuilder for BloomFilterBuilder {
+    type Sketch = BloomFilter;
+
+    /// Build the Bloom filter. This never fails: the constructors validate their arguments.
+    fn try_build(self) -> Result<BloomFilter, Error> {
+        Ok(self.build())
+    }
+}
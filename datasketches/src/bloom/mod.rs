@@ -122,6 +122,8 @@
 
 mod builder;
 mod sketch;
+mod summary;
 
 pub use self::builder::BloomFilterBuilder;
 pub use self::sketch::BloomFilter;
+pub use self::summary::BloomFilterSummary;
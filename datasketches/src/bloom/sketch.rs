@@ -15,21 +15,38 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use std::fmt;
 use std::hash::Hash;
 use std::hash::Hasher;
-
+use std::ops::BitAnd;
+use std::ops::BitAndAssign;
+use std::ops::BitOr;
+use std::ops::BitOrAssign;
+
+#[cfg(feature = "rayon")]
+use rayon::iter::IntoParallelIterator;
+#[cfg(feature = "rayon")]
+use rayon::iter::ParallelIterator;
+
+#[cfg(feature = "rayon")]
+use super::BloomFilterBuilder;
+use super::BloomFilterSummary;
 use crate::codec::SketchBytes;
 use crate::codec::SketchSlice;
 use crate::codec::assert::ensure_preamble_longs_in_range;
-use crate::codec::assert::ensure_serial_version_is;
 use crate::codec::assert::insufficient_data;
 use crate::codec::family::Family;
+use crate::codec::format;
+use crate::common::BitArray;
+use crate::common::MemoryUsage;
 use crate::error::Error;
 use crate::hash::XxHash64;
 
 // Serialization constants
-const SERIAL_VERSION: u8 = 1;
-const EMPTY_FLAG_MASK: u8 = 1 << 2;
+const SERIAL_VERSION: u8 = format::bloom::SERIAL_VERSION;
+const EMPTY_FLAG_MASK: u8 = format::bloom::FLAGS_IS_EMPTY;
+// Not part of the DataSketches format: set bits are stored as varint-encoded gaps.
+const COMPRESSED_FLAG_MASK: u8 = 1 << 3;
 
 /// A Bloom filter for probabilistic set membership testing.
 ///
@@ -39,16 +56,19 @@ const EMPTY_FLAG_MASK: u8 = 1 << 2;
 /// * Constant space usage
 ///
 /// Use [`super::BloomFilterBuilder`] to construct instances.
-#[derive(Debug, Clone, PartialEq)]
+///
+/// Filters are equal if they have the same seed, number of hashes and bits, whether or not they
+/// are [derived](Self::is_derived).
+#[derive(Debug, Clone)]
 pub struct BloomFilter {
     /// Hash seed for all hash functions
     pub(super) seed: u64,
     /// Number of hash functions to use (k)
     pub(super) num_hashes: u16,
-    /// Count of bits set to 1 (for statistics)
-    pub(super) num_bits_set: u64,
-    /// Bit array packed into u64 words
-    pub(super) bit_array: Box<[u64]>,
+    /// Bit array packed into u64 words, tracking the count of bits set
+    pub(super) bit_array: BitArray,
+    /// Whether the bits come from an intersection or inversion rather than insertions
+    pub(super) derived: bool,
 }
 
 impl BloomFilter {
@@ -104,6 +124,8 @@ impl BloomFilter {
     /// Inserts an item into the filter.
     ///
     /// After insertion, `contains(item)` will always return `true`.
+    /// Wrap items with [`canonical::from_value`](crate::hash_value::canonical::from_value) to
+    /// hash them the way other datasketches implementations do.
     ///
     /// # Examples
     ///
@@ -139,14 +161,15 @@ impl BloomFilter {
     /// assert!(!filter.contains(&"apple"));
     /// ```
     pub fn reset(&mut self) {
-        self.bit_array.fill(0);
-        self.num_bits_set = 0
+        self.bit_array.reset();
+        self.derived = false;
     }
 
     /// Merges another filter into this one via bitwise OR (union).
     ///
     /// After merging, this filter will recognize items from either filter
-    /// (plus any false positives from either).
+    /// (plus any false positives from either). The result is
+    /// [derived](Self::is_derived) if either filter is.
     ///
     /// # Panics
     ///
@@ -177,19 +200,16 @@ impl BloomFilter {
             "Cannot union incompatible Bloom filters"
         );
 
-        // Count bits during union operation (single pass)
-        let mut num_bits_set = 0;
-        for (word, other_word) in self.bit_array.iter_mut().zip(&other.bit_array) {
-            *word |= *other_word;
-            num_bits_set += word.count_ones() as u64;
-        }
-        self.num_bits_set = num_bits_set;
+        self.bit_array.union(&other.bit_array);
+        self.derived |= other.derived;
     }
 
     /// Intersects this filter with another via bitwise AND.
     ///
     /// After intersection, this filter will recognize only items present in both
-    /// filters (plus false positives).
+    /// filters (plus false positives), and it is [derived](Self::is_derived): bits set by
+    /// different items in each filter survive the intersection, so the saturation metrics
+    /// describe the bit array rather than the items present in both filters.
     ///
     /// # Panics
     ///
@@ -221,19 +241,15 @@ impl BloomFilter {
             "Cannot intersect incompatible Bloom filters"
         );
 
-        // Count bits during intersect operation (single pass)
-        let mut num_bits_set = 0;
-        for (word, other_word) in self.bit_array.iter_mut().zip(&other.bit_array) {
-            *word &= *other_word;
-            num_bits_set += word.count_ones() as u64;
-        }
-        self.num_bits_set = num_bits_set;
+        self.bit_array.intersect(&other.bit_array);
+        self.derived = true;
     }
 
     /// Inverts all bits in the filter.
     ///
     /// This approximately inverts the notion of set membership, though the false
-    /// positive guarantees no longer hold in a well-defined way.
+    /// positive guarantees no longer hold in a well-defined way. The filter becomes
+    /// [derived](Self::is_derived), and stays derived when inverted again.
     ///
     /// # Examples
     ///
@@ -246,27 +262,64 @@ impl BloomFilter {
     /// // Now "apple" probably returns false, and most other items return true
     /// ```
     pub fn invert(&mut self) {
-        for word in &mut self.bit_array {
-            *word = !*word;
-        }
-        self.num_bits_set = self.capacity() as u64 - self.num_bits_set;
+        self.bit_array.invert();
+        self.derived = true;
     }
 
     /// Returns whether the filter is empty (no items inserted).
     pub fn is_empty(&self) -> bool {
-        self.num_bits_set == 0
+        self.bit_array.is_empty()
+    }
+
+    /// Returns whether the bits of this filter come from an [intersection](Self::intersect) or
+    /// an [inversion](Self::invert) rather than from insertions and unions alone.
+    ///
+    /// The saturation metrics of a derived filter describe its bit array, not a set of inserted
+    /// items:
+    /// * [`estimated_fpp`](Self::estimated_fpp) still estimates how often `contains` returns `true`
+    ///   for an item missing from an intersection, but has no false positive meaning after an
+    ///   inversion.
+    /// * [`estimated_num_items`](Self::estimated_num_items) overestimates the size of an
+    ///   intersection, since bits set by different items in each filter survive it, and is
+    ///   meaningless after an inversion.
+    ///
+    /// Only [`reset`](Self::reset) clears the flag: inverting a filter twice restores its bits but
+    /// leaves it derived. The flag is not serialized, so a deserialized filter is never derived,
+    /// and it is ignored by equality.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::bloom::BloomFilterBuilder;
+    /// let builder = BloomFilterBuilder::with_accuracy(100, 0.01);
+    /// let mut f1 = builder.clone().build();
+    /// let mut f2 = builder.build();
+    /// f1.insert("a");
+    /// f2.insert("b");
+    ///
+    /// f1.union(&f2);
+    /// assert!(!f1.is_derived());
+    /// f1.intersect(&f2);
+    /// assert!(f1.is_derived());
+    /// f1.reset();
+    /// assert!(!f1.is_derived());
+    /// ```
+    pub fn is_derived(&self) -> bool {
+        self.derived
     }
 
     /// Returns the number of bits set to 1.
     ///
-    /// Useful for monitoring filter saturation.
+    /// Useful for monitoring filter saturation. Filters deserialized from images whose bit count
+    /// is marked dirty, as the Java implementation may write them, count their bits on the first
+    /// call and cache the count.
     pub fn bits_used(&self) -> u64 {
-        self.num_bits_set
+        self.bit_array.num_bits_set()
     }
 
     /// Returns the total number of bits in the filter (capacity).
     pub fn capacity(&self) -> usize {
-        self.bit_array.len() * 64
+        self.bit_array.capacity()
     }
 
     /// Returns the number of hash functions used.
@@ -284,7 +337,7 @@ impl BloomFilter {
     /// Values near 0.5 indicate the filter is approaching saturation.
     /// Values above 0.5 indicate degraded false positive rates.
     pub fn load_factor(&self) -> f64 {
-        self.num_bits_set as f64 / self.capacity() as f64
+        self.bits_used() as f64 / self.capacity() as f64
     }
 
     /// Estimates the current false positive probability.
@@ -295,7 +348,8 @@ impl BloomFilter {
     /// * k = num_hashes
     ///
     /// This assumes uniform bit distribution and is more accurate than
-    /// trying to estimate insertion count from the load factor.
+    /// trying to estimate insertion count from the load factor. See
+    /// [`is_derived`](Self::is_derived) for its meaning after an intersection or inversion.
     pub fn estimated_fpp(&self) -> f64 {
         let k = self.num_hashes as f64;
         let load = self.load_factor();
@@ -305,6 +359,106 @@ impl BloomFilter {
         load.powf(k)
     }
 
+    /// Estimates the number of distinct items inserted into the filter.
+    ///
+    /// Uses the Swamidass-Baldi estimate `-(m / k) * ln(1 - X / m)`, where `m` is the capacity,
+    /// `k` the number of hashes and `X` the number of bits set. Returns infinity once every bit
+    /// is set. The estimate only counts inserted items if the filter is not
+    /// [derived](Self::is_derived).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::bloom::BloomFilterBuilder;
+    /// let mut filter = BloomFilterBuilder::with_accuracy(10_000, 0.01).build();
+    /// for i in 0..5_000 {
+    ///     filter.insert(i);
+    /// }
+    /// let estimate = filter.estimated_num_items();
+    /// assert!((estimate - 5_000.0).abs() < 250.0);
+    /// ```
+    pub fn estimated_num_items(&self) -> f64 {
+        self.estimate_from_bits_set(self.bits_used())
+    }
+
+    /// Estimates the number of distinct items inserted into either this filter or `other`,
+    /// without building their union.
+    ///
+    /// This is the [`estimated_num_items`](Self::estimated_num_items) of `self | other`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the filters are not [compatible](Self::is_compatible).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::bloom::BloomFilterBuilder;
+    /// let builder = BloomFilterBuilder::with_accuracy(10_000, 0.01);
+    /// let mut f1 = builder.clone().build();
+    /// let mut f2 = builder.build();
+    /// for i in 0..3_000 {
+    ///     f1.insert(i);
+    ///     f2.insert(i + 2_000);
+    /// }
+    /// let estimate = f1.estimated_union_size(&f2);
+    /// assert!((estimate - 5_000.0).abs() < 250.0);
+    /// ```
+    pub fn estimated_union_size(&self, other: &BloomFilter) -> f64 {
+        assert!(
+            self.is_compatible(other),
+            "Cannot estimate the union of incompatible Bloom filters"
+        );
+
+        let bits_set = self
+            .bit_array
+            .words()
+            .iter()
+            .zip(other.bit_array.words())
+            .map(|(a, b)| (a | b).count_ones() as u64)
+            .sum();
+        self.estimate_from_bits_set(bits_set)
+    }
+
+    fn estimate_from_bits_set(&self, bits_set: u64) -> f64 {
+        let m = self.capacity() as f64;
+        let k = self.num_hashes as f64;
+        -(m / k) * (-(bits_set as f64) / m).ln_1p()
+    }
+
+    /// Returns whether the [estimated false positive probability](Self::estimated_fpp) has
+    /// exceeded `target_fpp`.
+    ///
+    /// Long-lived filters can check this after inserting to rotate to a fresh filter before the
+    /// false positive rate degrades beyond its target.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `target_fpp` is not in (0.0, 1.0].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::bloom::BloomFilterBuilder;
+    /// let mut filter = BloomFilterBuilder::with_accuracy(1_000, 0.01).build();
+    /// for i in 0..1_000 {
+    ///     filter.insert(i);
+    /// }
+    /// assert!(!filter.is_saturated(0.02));
+    ///
+    /// for i in 1_000..2_000 {
+    ///     filter.insert(i);
+    /// }
+    /// assert!(filter.is_saturated(0.02));
+    /// ```
+    pub fn is_saturated(&self, target_fpp: f64) -> bool {
+        assert!(
+            target_fpp > 0.0 && target_fpp <= 1.0,
+            "target_fpp must be between 0.0 and 1.0 (inclusive of 1.0)"
+        );
+        self.estimated_fpp() > target_fpp
+    }
+
     /// Checks if two filters are compatible for merging.
     ///
     /// Filters are compatible if they have the same:
@@ -312,14 +466,94 @@ impl BloomFilter {
     /// * Number of hash functions
     /// * Seed
     pub fn is_compatible(&self, other: &Self) -> bool {
-        self.bit_array.len() == other.bit_array.len()
+        self.bit_array.capacity() == other.bit_array.capacity()
             && self.num_hashes == other.num_hashes
             && self.seed == other.seed
     }
 
+    /// Returns a summary of the configuration and saturation of the filter.
+    ///
+    /// The summary is also the [`Display`](fmt::Display) output of the filter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::bloom::BloomFilterBuilder;
+    /// let mut filter = BloomFilterBuilder::with_size(1024, 3).build();
+    /// filter.insert("apple");
+    ///
+    /// let summary = filter.summary();
+    /// assert_eq!(summary.capacity(), 1024);
+    /// assert_eq!(summary.num_hashes(), 3);
+    /// assert_eq!(summary.bits_used(), filter.bits_used());
+    /// assert_eq!(summary.load_factor(), filter.load_factor());
+    /// assert_eq!(summary.estimated_fpp(), filter.estimated_fpp());
+    /// assert_eq!(summary.to_string(), filter.to_string());
+    /// ```
+    pub fn summary(&self) -> BloomFilterSummary {
+        BloomFilterSummary {
+            capacity: self.capacity(),
+            num_hashes: self.num_hashes,
+            bits_used: self.bits_used(),
+            load_factor: self.load_factor(),
+            estimated_fpp: self.estimated_fpp(),
+            seed: self.seed,
+            derived: self.derived,
+        }
+    }
+
+    /// Returns a histogram of the saturation of the 64-bit words of the filter.
+    ///
+    /// Entry `i` is the number of words with exactly `i` bits set, so the entries sum to
+    /// `capacity / 64`. A healthy filter has its words clustered around the mean of
+    /// `64 * load_factor` bits set, while a skewed histogram points at a poorly distributed input
+    /// or at a filter [derived](Self::is_derived) from set operations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::bloom::BloomFilterBuilder;
+    /// let mut filter = BloomFilterBuilder::with_size(1024, 3).build();
+    /// assert_eq!(filter.word_saturation_histogram()[0], 16);
+    ///
+    /// filter.insert("apple");
+    /// let histogram = filter.word_saturation_histogram();
+    /// assert_eq!(histogram.iter().sum::<u64>(), 16);
+    /// let bits_set: u64 = (0..).zip(histogram).map(|(bits, words)| bits * words).sum();
+    /// assert_eq!(bits_set, filter.bits_used());
+    /// ```
+    pub fn word_saturation_histogram(&self) -> [u64; 65] {
+        let mut histogram = [0; 65];
+        for word in self.bit_array.words() {
+            histogram[word.count_ones() as usize] += 1;
+        }
+        histogram
+    }
+
     /// Serializes the filter to a byte vector.
     ///
-    /// The format is compatible with other Apache DataSketches implementations.
+    /// The format is the one of `BloomFilter` in datasketches-java and `bloom_filter` in
+    /// datasketches-cpp, which share the same layout (all integers little-endian):
+    ///
+    /// ```text
+    /// Bytes  | Field
+    /// -------|-------------------------------------------------------------
+    /// 0      | preamble longs: 3 if empty, 4 otherwise
+    /// 1      | serial version, 1
+    /// 2      | family ID, 21
+    /// 3      | flags: 0x04 if empty
+    /// 4..6   | number of hash functions, as i16
+    /// 6..8   | reserved
+    /// 8..16  | hash seed
+    /// 16..20 | bit array length in 64-bit words, as i32
+    /// 20..24 | reserved
+    /// 24..32 | number of bits set, or -1 if not known (non-empty only)
+    /// 32..   | bit array words (non-empty only)
+    /// ```
+    ///
+    /// Reserved bytes are written as zero and ignored when reading. Java writes a bit count of -1
+    /// when it has not kept the count up to date; such images are read lazily and written back
+    /// unchanged, see [`BitArray::is_dirty`].
     ///
     /// # Examples
     ///
@@ -333,46 +567,158 @@ impl BloomFilter {
     /// assert!(restored.contains(&"test"));
     /// ```
     pub fn serialize(&self) -> Vec<u8> {
-        let is_empty = self.is_empty();
-        let preamble_longs = if is_empty {
+        let mut bytes = SketchBytes::with_capacity(self.serialized_size_bytes());
+        self.write_to(&mut bytes);
+        bytes.into_bytes()
+    }
+
+    /// Serializes the filter into `buf`, returning the number of bytes written.
+    ///
+    /// Writes the same bytes as [`serialize`](Self::serialize) without allocating.
+    /// [`serialized_size_bytes`](Self::serialized_size_bytes) gives the exact length required.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `buf` is too short to hold the serialized filter. The filter is
+    /// written up to the end of `buf` before the length is checked, so the contents of `buf`
+    /// are unspecified when an error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::bloom::{BloomFilter, BloomFilterBuilder};
+    /// let mut filter = BloomFilterBuilder::with_accuracy(100, 0.01).build();
+    /// filter.insert("test");
+    ///
+    /// let mut buf = vec![0u8; filter.serialized_size_bytes()];
+    /// let len = filter.serialize_into_slice(&mut buf).unwrap();
+    /// assert_eq!(&buf[..len], filter.serialize().as_slice());
+    /// ```
+    pub fn serialize_into_slice(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let mut bytes = SketchBytes::from_slice(buf);
+        self.write_to(&mut bytes);
+        bytes.finish()
+    }
+
+    /// Returns the number of bytes produced by [`serialize`](Self::serialize).
+    pub fn serialized_size_bytes(&self) -> usize {
+        // The bit count that follows the first three preamble longs is part of the bit array.
+        let preamble_size = 8 * Family::BLOOMFILTER.min_pre_longs as usize;
+        if self.is_empty() {
+            preamble_size
+        } else {
+            preamble_size + self.bit_array.serialized_size_bytes()
+        }
+    }
+
+    /// Serializes the filter, storing the set bits compactly if that is smaller.
+    ///
+    /// A mostly empty filter serializes its whole bit array with [`serialize`](Self::serialize).
+    /// This method instead stores the positions of the set bits as run lengths of cleared bits,
+    /// and falls back to the regular format when that would not be smaller, which happens once
+    /// roughly one bit in eight is set.
+    ///
+    /// The compressed form is marked by a header flag that only this library understands, so
+    /// use [`serialize`](Self::serialize) for images read by other DataSketches implementations.
+    /// [`deserialize`](Self::deserialize) reads both forms.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::bloom::{BloomFilter, BloomFilterBuilder};
+    /// let mut filter = BloomFilterBuilder::with_accuracy(1_000_000, 0.01).build();
+    /// filter.insert("test");
+    ///
+    /// let bytes = filter.serialize_compressed();
+    /// assert!(bytes.len() < filter.serialize().len());
+    /// assert_eq!(BloomFilter::deserialize(&bytes).unwrap(), filter);
+    /// ```
+    pub fn serialize_compressed(&self) -> Vec<u8> {
+        let mut bytes = SketchBytes::with_capacity(self.serialized_compressed_size_bytes());
+        self.write_compressed_to(&mut bytes);
+        bytes.into_bytes()
+    }
+
+    /// Serializes the filter in compressed form if applicable into `buf`, returning the number
+    /// of bytes written.
+    ///
+    /// Writes the same bytes as [`serialize_compressed`](Self::serialize_compressed) without
+    /// allocating. [`serialized_compressed_size_bytes`](Self::serialized_compressed_size_bytes)
+    /// gives the exact length required.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `buf` is too short to hold the serialized filter. The filter is
+    /// written up to the end of `buf` before the length is checked, so the contents of `buf`
+    /// are unspecified when an error is returned.
+    pub fn serialize_compressed_into_slice(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let mut bytes = SketchBytes::from_slice(buf);
+        self.write_compressed_to(&mut bytes);
+        bytes.finish()
+    }
+
+    /// Returns the number of bytes produced by
+    /// [`serialize_compressed`](Self::serialize_compressed).
+    ///
+    /// This scans the bit array.
+    pub fn serialized_compressed_size_bytes(&self) -> usize {
+        match self.compressed_bits_size_bytes() {
+            Some(bits_size) => 8 * Family::BLOOMFILTER.min_pre_longs as usize + bits_size,
+            None => self.serialized_size_bytes(),
+        }
+    }
+
+    /// Returns the size of the compressed bits, or `None` if compression does not pay off.
+    fn compressed_bits_size_bytes(&self) -> Option<usize> {
+        if self.is_empty() {
+            return None;
+        }
+        let size = size_of::<u64>() + set_bit_gaps(&self.bit_array).map(varint_len).sum::<usize>();
+        (size < self.bit_array.serialized_size_bytes()).then_some(size)
+    }
+
+    fn write_to(&self, bytes: &mut SketchBytes<'_>) {
+        if self.is_empty() {
+            self.write_preamble_to(bytes, EMPTY_FLAG_MASK);
+        } else {
+            self.write_preamble_to(bytes, 0);
+            // Bits set followed by the bit array
+            self.bit_array.write_to(bytes);
+        }
+    }
+
+    fn write_compressed_to(&self, bytes: &mut SketchBytes<'_>) {
+        if self.compressed_bits_size_bytes().is_none() {
+            return self.write_to(bytes);
+        }
+
+        self.write_preamble_to(bytes, COMPRESSED_FLAG_MASK);
+        bytes.write_u64_le(self.bit_array.num_bits_set());
+        for gap in set_bit_gaps(&self.bit_array) {
+            write_varint(bytes, gap);
+        }
+    }
+
+    fn write_preamble_to(&self, bytes: &mut SketchBytes<'_>, flags: u8) {
+        let preamble_longs = if flags & EMPTY_FLAG_MASK != 0 {
             Family::BLOOMFILTER.min_pre_longs
         } else {
             Family::BLOOMFILTER.max_pre_longs
         };
 
-        let capacity = 8 * preamble_longs as usize
-            + if is_empty {
-                0
-            } else {
-                self.bit_array.len() * 8
-            };
-        let mut bytes = SketchBytes::with_capacity(capacity);
-
-        // Preamble
         bytes.write_u8(preamble_longs); // Byte 0
         bytes.write_u8(SERIAL_VERSION); // Byte 1
         bytes.write_u8(Family::BLOOMFILTER.id); // Byte 2
-        bytes.write_u8(if is_empty { EMPTY_FLAG_MASK } else { 0 }); // Byte 3: flags
+        bytes.write_u8(flags); // Byte 3: flags
         bytes.write_u16_le(self.num_hashes); // Bytes 4-5
         bytes.write_u16_le(0); // Bytes 6-7: unused
 
         bytes.write_u64_le(self.seed);
 
         // Bit array capacity is stored as number of 64-bit words (int32) + unused padding (uint32).
-        let num_longs = self.bit_array.len() as i32;
+        let num_longs = self.bit_array.words().len() as i32;
         bytes.write_i32_le(num_longs);
         bytes.write_u32_le(0); // unused
-
-        if !is_empty {
-            bytes.write_u64_le(self.num_bits_set);
-
-            // Bit array
-            for &word in &self.bit_array {
-                bytes.write_u64_le(word);
-            }
-        }
-
-        bytes.into_bytes()
     }
 
     /// Deserializes a filter from bytes.
@@ -411,7 +757,7 @@ impl BloomFilter {
 
         // Validate
         Family::BLOOMFILTER.validate_id(family_id)?;
-        ensure_serial_version_is(SERIAL_VERSION, serial_version)?;
+        Family::BLOOMFILTER.validate_serial_version(serial_version)?;
         ensure_preamble_longs_in_range(
             Family::BLOOMFILTER.min_pre_longs..=Family::BLOOMFILTER.max_pre_longs,
             preamble_longs,
@@ -450,44 +796,20 @@ impl BloomFilter {
         }
 
         let num_words = num_longs as usize;
-        let mut bit_array = vec![0u64; num_words].into_boxed_slice();
-        let num_bits_set;
-
-        if is_empty {
-            num_bits_set = 0;
+        let bit_array = if is_empty {
+            BitArray::new(num_words as u64 * 64)
+        } else if (flags & COMPRESSED_FLAG_MASK) != 0 {
+            read_compressed_bits(&mut cursor, num_words)?
         } else {
-            let raw_num_bits_set = cursor
-                .read_u64_le()
-                .map_err(insufficient_data("num_bits_set"))?;
-
-            for word in &mut bit_array {
-                *word = cursor
-                    .read_u64_le()
-                    .map_err(insufficient_data("bit_array"))?;
-            }
-
-            // Handle "dirty" state: 0xFFFFFFFFFFFFFFFF indicates bits need recounting
-            const DIRTY_BITS_VALUE: u64 = 0xFFFFFFFFFFFFFFFF;
-            if raw_num_bits_set == DIRTY_BITS_VALUE {
-                num_bits_set = bit_array.iter().map(|w| w.count_ones() as u64).sum();
-            } else {
-                let raw_num_words_set = raw_num_bits_set.div_ceil(64) as usize;
-                if raw_num_words_set > num_words {
-                    return Err(Error::deserial(format!(
-                        "invalid num_bits_set: expected <= {}, got {}",
-                        num_words * 64,
-                        raw_num_bits_set
-                    )));
-                }
-                num_bits_set = raw_num_bits_set;
-            }
-        }
+            // A "dirty" bit count is kept and recounted on demand
+            BitArray::read_from(&mut cursor, num_words)?
+        };
 
         Ok(BloomFilter {
             seed,
             num_hashes,
-            num_bits_set,
             bit_array,
+            derived: false,
         })
     }
 
@@ -514,7 +836,7 @@ impl BloomFilter {
     fn check_bits(&self, h0: u64, h1: u64) -> bool {
         for i in 1..=self.num_hashes {
             let bit_index = self.compute_bit_index(h0, h1, i);
-            if !self.get_bit(bit_index) {
+            if !self.bit_array.get(bit_index) {
                 return false;
             }
         }
@@ -525,7 +847,7 @@ impl BloomFilter {
     fn set_bits(&mut self, h0: u64, h1: u64) {
         for i in 1..=self.num_hashes {
             let bit_index = self.compute_bit_index(h0, h1, i);
-            self.set_bit(bit_index);
+            self.bit_array.set(bit_index);
         }
     }
 
@@ -542,29 +864,229 @@ impl BloomFilter {
         (hash >> 1) % self.capacity()
     }
 
-    /// Gets the value of a single bit.
-    fn get_bit(&self, bit_index: usize) -> bool {
-        let word_index = bit_index >> 6; // Equivalent to bit_index / 64
-        let bit_offset = bit_index & 63; // Equivalent to bit_index % 64
-        let mask = 1u64 << bit_offset;
-        (self.bit_array[word_index] & mask) != 0
+    /// Returns the estimated size of the filter in bytes
+    pub fn estimated_size(&self) -> usize {
+        size_of::<Self>() + size_of_val(self.bit_array.words())
     }
+}
 
-    /// Sets a single bit and updates the count if it wasn't already set.
-    fn set_bit(&mut self, bit_index: usize) {
-        let word_index = bit_index >> 6; // Equivalent to bit_index / 64
-        let bit_offset = bit_index & 63; // Equivalent to bit_index % 64
-        let mask = 1u64 << bit_offset;
+/// Yields the number of cleared bits before each set bit, in order.
+fn set_bit_gaps(bit_array: &BitArray) -> impl Iterator<Item = u64> + '_ {
+    let mut next = 0;
+    bit_array
+        .words()
+        .iter()
+        .enumerate()
+        .flat_map(|(i, &word)| {
+            let mut word = word;
+            std::iter::from_fn(move || {
+                (word != 0).then(|| {
+                    let bit = word.trailing_zeros() as u64;
+                    word &= word - 1;
+                    i as u64 * 64 + bit
+                })
+            })
+        })
+        .map(move |position| {
+            let gap = position - next;
+            next = position + 1;
+            gap
+        })
+}
+
+fn read_compressed_bits(cursor: &mut SketchSlice<'_>, num_words: usize) -> Result<BitArray, Error> {
+    let capacity = num_words as u64 * 64;
+    let num_bits_set = cursor
+        .read_u64_le()
+        .map_err(insufficient_data("num_bits_set"))?;
+    if num_bits_set > capacity {
+        return Err(Error::deserial(format!(
+            "invalid num_bits_set: expected at most {capacity}, got {num_bits_set}"
+        )));
+    }
 
-        if (self.bit_array[word_index] & mask) == 0 {
-            self.bit_array[word_index] |= mask;
-            self.num_bits_set += 1;
+    let mut bit_array = BitArray::new(capacity);
+    let mut next = 0u64;
+    for _ in 0..num_bits_set {
+        let position = read_varint(cursor)?
+            .checked_add(next)
+            .filter(|&position| position < capacity)
+            .ok_or_else(|| Error::deserial("set bit position exceeds the bit array capacity"))?;
+        bit_array.set(position as usize);
+        next = position + 1;
+    }
+    Ok(bit_array)
+}
+
+fn varint_len(value: u64) -> usize {
+    let bits = 64 - (value | 1).leading_zeros() as usize;
+    bits.div_ceil(7)
+}
+
+/// Writes `value` as a LEB128 varint: 7 bits per byte, low bits first.
+fn write_varint(bytes: &mut SketchBytes<'_>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.write_u8((value as u8) | 0x80);
+        value >>= 7;
+    }
+    bytes.write_u8(value as u8);
+}
+
+fn read_varint(cursor: &mut SketchSlice<'_>) -> Result<u64, Error> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = cursor.read_u8().map_err(insufficient_data("set_bit_gap"))?;
+        let bits = u64::from(byte & 0x7f);
+        if shift == 63 && bits > 1 {
+            break;
+        }
+        value |= bits << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
         }
     }
+    Err(Error::deserial("invalid set bit gap: varint overflows u64"))
+}
 
-    /// Returns the estimated size of the filter in bytes
-    pub fn estimated_size(&self) -> usize {
-        size_of::<Self>() + self.bit_array.len() * size_of::<u64>()
+impl PartialEq for BloomFilter {
+    fn eq(&self, other: &Self) -> bool {
+        self.seed == other.seed
+            && self.num_hashes == other.num_hashes
+            && self.bit_array == other.bit_array
+    }
+}
+
+impl MemoryUsage for BloomFilter {
+    fn memory_usage(&self) -> usize {
+        self.estimated_size()
+    }
+}
+
+/// `a |= &b` is equivalent to [`a.union(&b)`](BloomFilter::union).
+///
+/// # Panics
+///
+/// Panics if the filters are not compatible.
+impl BitOrAssign<&BloomFilter> for BloomFilter {
+    fn bitor_assign(&mut self, other: &BloomFilter) {
+        self.union(other);
+    }
+}
+
+/// `a | &b` consumes `a` and returns the union of both filters.
+///
+/// # Panics
+///
+/// Panics if the filters are not compatible.
+impl BitOr<&BloomFilter> for BloomFilter {
+    type Output = BloomFilter;
+
+    fn bitor(mut self, other: &BloomFilter) -> BloomFilter {
+        self.union(other);
+        self
+    }
+}
+
+/// `&a | &b` returns the union of both filters as a new filter.
+///
+/// # Panics
+///
+/// Panics if the filters are not compatible.
+impl BitOr<&BloomFilter> for &BloomFilter {
+    type Output = BloomFilter;
+
+    fn bitor(self, other: &BloomFilter) -> BloomFilter {
+        self.clone() | other
+    }
+}
+
+/// `a &= &b` is equivalent to [`a.intersect(&b)`](BloomFilter::intersect).
+///
+/// # Panics
+///
+/// Panics if the filters are not compatible.
+impl BitAndAssign<&BloomFilter> for BloomFilter {
+    fn bitand_assign(&mut self, other: &BloomFilter) {
+        self.intersect(other);
+    }
+}
+
+/// `a & &b` consumes `a` and returns the intersection of both filters.
+///
+/// # Panics
+///
+/// Panics if the filters are not compatible.
+impl BitAnd<&BloomFilter> for BloomFilter {
+    type Output = BloomFilter;
+
+    fn bitand(mut self, other: &BloomFilter) -> BloomFilter {
+        self.intersect(other);
+        self
+    }
+}
+
+/// `&a & &b` returns the intersection of both filters as a new filter.
+///
+/// # Panics
+///
+/// Panics if the filters are not compatible.
+impl BitAnd<&BloomFilter> for &BloomFilter {
+    type Output = BloomFilter;
+
+    fn bitand(self, other: &BloomFilter) -> BloomFilter {
+        self.clone() & other
+    }
+}
+
+impl fmt::Display for BloomFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.summary().fmt(f)
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl BloomFilter {
+    /// Builds a filter configured by `builder` from the items of a parallel iterator.
+    ///
+    /// Every rayon job inserts its share of the items into a partial filter of the same
+    /// configuration, and the partial filters are unioned (bitwise OR) as the jobs complete. The
+    /// result is identical to inserting every item into a single filter.
+    ///
+    /// Each concurrent job holds a filter of the full size, so peak memory grows with the number
+    /// of worker threads.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::bloom::BloomFilter;
+    /// # use datasketches::bloom::BloomFilterBuilder;
+    /// use rayon::prelude::*;
+    ///
+    /// let builder = BloomFilterBuilder::with_accuracy(10_000, 0.01);
+    /// let filter = BloomFilter::from_par_iter(builder, (0..10_000_u64).into_par_iter());
+    /// assert!(filter.contains(&42_u64));
+    /// ```
+    pub fn from_par_iter<T, I>(builder: BloomFilterBuilder, items: I) -> Self
+    where
+        T: Hash + Send,
+        I: IntoParallelIterator<Item = T>,
+    {
+        items
+            .into_par_iter()
+            .fold(
+                || builder.clone().build(),
+                |mut filter, item| {
+                    filter.insert(item);
+                    filter
+                },
+            )
+            .reduce(
+                || builder.clone().build(),
+                |mut filter, other| {
+                    filter.union(&other);
+                    filter
+                },
+            )
     }
 }
 
@@ -662,6 +1184,55 @@ mod tests {
         assert!(f1.contains(&"b"));
     }
 
+    #[test]
+    fn test_is_derived() {
+        let builder = BloomFilterBuilder::with_accuracy(10_000, 0.01);
+        let mut f1 = builder.clone().build();
+        let mut f2 = builder.build();
+        assert!(!f1.is_derived());
+        for i in 0..4_000 {
+            f1.insert(i);
+            f2.insert(i + 4_000);
+        }
+        assert!(!(&f1 | &f2).is_derived());
+
+        // The filters are disjoint, but bits set by different items survive the intersection.
+        let intersection = &f1 & &f2;
+        assert!(intersection.is_derived());
+        assert!(intersection.estimated_num_items() > 0.0);
+        assert!((&f1 | &intersection).is_derived());
+        assert!(
+            !BloomFilter::deserialize(&intersection.serialize())
+                .unwrap()
+                .is_derived()
+        );
+
+        let mut inverted = f1.clone();
+        inverted.invert();
+        assert!(inverted.is_derived());
+        inverted.invert();
+        assert!(inverted.is_derived());
+        assert_eq!(inverted, f1);
+        inverted.reset();
+        assert!(!inverted.is_derived());
+    }
+
+    #[test]
+    fn test_is_saturated() {
+        let mut filter = BloomFilterBuilder::with_accuracy(100, 0.01).build();
+        assert!(!filter.is_saturated(0.01));
+
+        let mut i = 0;
+        while !filter.is_saturated(0.01) {
+            filter.insert(i);
+            i += 1;
+        }
+        // Saturation is reached close to the configured capacity.
+        assert!((80..=150).contains(&i), "saturated after {i} items");
+        assert!(filter.estimated_fpp() > 0.01);
+        assert!(!filter.is_saturated(1.0));
+    }
+
     #[test]
     fn test_serialize_deserialize_empty() {
         let filter = BloomFilterBuilder::with_accuracy(100, 0.01).build();
@@ -671,6 +1242,27 @@ mod tests {
         assert_eq!(filter, restored);
     }
 
+    #[test]
+    fn test_serialize_deserialize_derived() {
+        let builder = BloomFilterBuilder::with_accuracy(100, 0.01);
+        let mut f1 = builder.clone().build();
+        let mut f2 = builder.build();
+        f1.insert("a");
+        f1.insert("b");
+        f2.insert("b");
+
+        let mut intersection = f1.clone();
+        intersection.intersect(&f2);
+        let mut inverted = f1.clone();
+        inverted.invert();
+        for filter in [intersection, inverted] {
+            assert!(filter.is_derived());
+            let restored = BloomFilter::deserialize(&filter.serialize()).unwrap();
+            assert!(!restored.is_derived());
+            assert_eq!(restored, filter);
+        }
+    }
+
     #[test]
     fn test_serialize_deserialize_with_data() {
         let mut filter = BloomFilterBuilder::with_accuracy(100, 0.01).build();
@@ -697,6 +1289,116 @@ mod tests {
         assert!(filter.estimated_fpp() > 0.0);
     }
 
+    #[test]
+    fn test_summary() {
+        let mut filter = BloomFilterBuilder::with_size(1000, 5).seed(7).build();
+        for i in 0..100 {
+            filter.insert(i);
+        }
+        let summary = filter.summary();
+        assert_eq!(summary.capacity(), filter.capacity());
+        assert_eq!(summary.num_hashes(), 5);
+        assert_eq!(summary.seed(), 7);
+        assert_eq!(summary.bits_used(), filter.bits_used());
+        assert_eq!(summary.load_factor(), filter.load_factor());
+        assert_eq!(summary.estimated_fpp(), filter.estimated_fpp());
+
+        let display = filter.to_string();
+        assert!(display.starts_with("### Bloom Filter Summary:\n"));
+        assert!(display.contains(&format!("   capacity      : {}\n", filter.capacity())));
+        assert!(display.contains("   num_hashes    : 5\n"));
+        assert!(display.contains("   seed          : 7\n"));
+        assert!(display.contains(&format!("   bits_used     : {}\n", filter.bits_used())));
+        assert!(display.contains("   derived       : false\n"));
+        assert!(display.ends_with("### End filter summary\n"));
+
+        assert!(!summary.is_derived());
+        filter.invert();
+        assert!(filter.summary().is_derived());
+        assert!(filter.to_string().contains("   derived       : true\n"));
+    }
+
+    #[test]
+    fn test_word_saturation_histogram() {
+        let mut filter = BloomFilterBuilder::with_size(4096, 3).build();
+        let num_words = (filter.capacity() / 64) as u64;
+        let mut expected = [0; 65];
+        expected[0] = num_words;
+        assert_eq!(filter.word_saturation_histogram(), expected);
+
+        for i in 0..500 {
+            filter.insert(i);
+        }
+        let histogram = filter.word_saturation_histogram();
+        assert_eq!(histogram.iter().sum::<u64>(), num_words);
+        let bits_set: u64 = (0..).zip(histogram).map(|(bits, words)| bits * words).sum();
+        assert_eq!(bits_set, filter.bits_used());
+
+        filter.reset();
+        filter.invert();
+        let mut expected = [0; 65];
+        expected[64] = num_words;
+        assert_eq!(filter.word_saturation_histogram(), expected);
+    }
+
+    #[test]
+    fn test_estimated_num_items() {
+        let mut filter = BloomFilterBuilder::with_accuracy(10_000, 0.01).build();
+        assert_eq!(filter.estimated_num_items(), 0.0);
+        filter.insert("test");
+        assert!((filter.estimated_num_items() - 1.0).abs() < 0.01);
+
+        let mut other = BloomFilterBuilder::with_accuracy(10_000, 0.01).build();
+        for i in 0..4_000 {
+            filter.insert(i);
+            other.insert(i + 3_000);
+        }
+        let union = &filter | &other;
+        assert_eq!(
+            filter.estimated_union_size(&other),
+            union.estimated_num_items()
+        );
+        assert!((union.estimated_num_items() - 7_001.0).abs() < 350.0);
+
+        filter.invert();
+        filter.union(&union);
+        assert_eq!(filter.estimated_num_items(), f64::INFINITY);
+    }
+
+    #[test]
+    fn test_dirty_bit_count() {
+        let mut filter = BloomFilterBuilder::with_size(1_024, 3).build();
+        for i in 0..100 {
+            filter.insert(i);
+        }
+        let bits_used = filter.bits_used();
+
+        // Java writes a dirty bit count as -1.
+        let mut bytes = filter.serialize();
+        bytes[24..32].copy_from_slice(&(-1_i64).to_le_bytes());
+        let mut restored = BloomFilter::deserialize(&bytes).unwrap();
+        assert_eq!(restored, filter);
+        assert_eq!(restored.bits_used(), bits_used);
+        assert_eq!(restored.serialize(), bytes);
+        assert_eq!(
+            BloomFilter::deserialize(&restored.serialize_compressed()).unwrap(),
+            filter
+        );
+
+        restored.insert(1_000);
+        filter.insert(1_000);
+        assert_eq!(restored.bits_used(), filter.bits_used());
+        assert_eq!(restored.serialize(), filter.serialize());
+
+        bytes[32..].fill(0);
+        let empty = BloomFilter::deserialize(&bytes).unwrap();
+        assert!(empty.is_empty());
+        assert_eq!(
+            empty.serialize(),
+            BloomFilterBuilder::with_size(1_024, 3).build().serialize()
+        );
+    }
+
     #[test]
     fn test_is_compatible() {
         let f1 = BloomFilterBuilder::with_accuracy(100, 0.01)
@@ -724,4 +1426,144 @@ mod tests {
     fn test_invalid_fpp() {
         BloomFilterBuilder::with_accuracy(100, 1.5);
     }
+
+    #[test]
+    fn test_serialize_into_slice() {
+        let mut filter = BloomFilterBuilder::with_accuracy(100, 0.01).build();
+        for i in 0..50 {
+            filter.insert(i);
+        }
+        let expected = filter.serialize();
+        let mut buf = vec![0u8; expected.len()];
+        assert_eq!(
+            filter.serialize_into_slice(&mut buf).unwrap(),
+            expected.len()
+        );
+        assert_eq!(buf, expected);
+        assert!(filter.serialize_into_slice(&mut buf[..16]).is_err());
+    }
+
+    #[test]
+    fn test_serialized_size_bytes() {
+        let mut filter = BloomFilterBuilder::with_accuracy(100, 0.01).build();
+        assert_eq!(filter.serialized_size_bytes(), filter.serialize().len());
+        filter.insert("test");
+        assert_eq!(filter.serialized_size_bytes(), filter.serialize().len());
+    }
+
+    #[test]
+    fn test_serialize_compressed_sparse() {
+        let mut filter = BloomFilterBuilder::with_accuracy(100_000, 0.01).build();
+        for i in 0..1_000 {
+            filter.insert(i);
+        }
+
+        let bytes = filter.serialize_compressed();
+        assert_eq!(bytes.len(), filter.serialized_compressed_size_bytes());
+        assert!(
+            bytes.len() * 10 < filter.serialize().len(),
+            "{}",
+            bytes.len()
+        );
+        assert_eq!(BloomFilter::deserialize(&bytes).unwrap(), filter);
+
+        let mut buf = vec![0u8; bytes.len()];
+        assert_eq!(
+            filter.serialize_compressed_into_slice(&mut buf).unwrap(),
+            bytes.len()
+        );
+        assert_eq!(buf, bytes);
+        assert!(
+            filter
+                .serialize_compressed_into_slice(&mut buf[..bytes.len() - 1])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_serialize_compressed_falls_back_when_not_smaller() {
+        let empty = BloomFilterBuilder::with_accuracy(1_000, 0.01).build();
+        assert_eq!(empty.serialize_compressed(), empty.serialize());
+
+        let mut dense = BloomFilterBuilder::with_accuracy(1_000, 0.01).build();
+        for i in 0..1_000 {
+            dense.insert(i);
+        }
+        assert_eq!(dense.serialize_compressed(), dense.serialize());
+        assert_eq!(
+            dense.serialized_compressed_size_bytes(),
+            dense.serialized_size_bytes()
+        );
+    }
+
+    #[test]
+    fn test_serialize_compressed_edge_bits() {
+        let mut filter = BloomFilterBuilder::with_size(1_000, 3).build();
+        let capacity = filter.capacity();
+        filter.bit_array.set(0);
+        filter.bit_array.set(capacity - 1);
+
+        let bytes = filter.serialize_compressed();
+        assert!(bytes.len() < filter.serialize().len());
+        assert_eq!(BloomFilter::deserialize(&bytes).unwrap(), filter);
+    }
+
+    #[test]
+    fn test_deserialize_compressed_rejects_corrupt_bits() {
+        let mut filter = BloomFilterBuilder::with_size(1_000, 3).build();
+        filter.insert("test");
+        let bytes = filter.serialize_compressed();
+
+        for len in 24..bytes.len() {
+            assert!(
+                BloomFilter::deserialize(&bytes[..len]).is_err(),
+                "len={len}"
+            );
+        }
+
+        // A gap that runs past the end of the bit array
+        let mut out_of_range = bytes[..32].to_vec();
+        out_of_range[24..32].copy_from_slice(&1u64.to_le_bytes());
+        out_of_range.extend_from_slice(&[0xff, 0x7f]);
+        assert!(BloomFilter::deserialize(&out_of_range).is_err());
+
+        // More set bits than the capacity
+        let mut too_many = bytes.clone();
+        too_many[24..32].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(BloomFilter::deserialize(&too_many).is_err());
+    }
+
+    #[test]
+    fn test_bit_operators() {
+        let mut f1 = BloomFilterBuilder::with_accuracy(100, 0.01).build();
+        let mut f2 = BloomFilterBuilder::with_accuracy(100, 0.01).build();
+        f1.insert("a");
+        f1.insert("b");
+        f2.insert("b");
+        f2.insert("c");
+
+        let mut expected_union = f1.clone();
+        expected_union.union(&f2);
+        let mut expected_intersection = f1.clone();
+        expected_intersection.intersect(&f2);
+
+        assert_eq!(&f1 | &f2, expected_union);
+        assert_eq!(&f1 & &f2, expected_intersection);
+        assert_eq!(f1.clone() | &f2, expected_union);
+        assert_eq!(f1.clone() & &f2, expected_intersection);
+
+        let mut f = f1.clone();
+        f |= &f2;
+        assert_eq!(f, expected_union);
+        f1 &= &f2;
+        assert_eq!(f1, expected_intersection);
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot union incompatible Bloom filters")]
+    fn test_bitor_incompatible() {
+        let f1 = BloomFilterBuilder::with_accuracy(100, 0.01).seed(1).build();
+        let f2 = BloomFilterBuilder::with_accuracy(100, 0.01).seed(2).build();
+        let _ = &f1 | &f2;
+    }
 }
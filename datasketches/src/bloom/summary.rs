@@ -0,0 +1,85 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::fmt;
+
+/// A snapshot of the configuration and saturation of a [`BloomFilter`](super::BloomFilter).
+///
+/// Returned by [`BloomFilter::summary`](super::BloomFilter::summary). Its [`Display`](fmt::Display)
+/// output is the one of the filter.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BloomFilterSummary {
+    pub(super) capacity: usize,
+    pub(super) num_hashes: u16,
+    pub(super) bits_used: u64,
+    pub(super) load_factor: f64,
+    pub(super) estimated_fpp: f64,
+    pub(super) seed: u64,
+    pub(super) derived: bool,
+}
+
+impl BloomFilterSummary {
+    /// Returns the total number of bits in the filter.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of hash functions (k).
+    pub fn num_hashes(&self) -> u16 {
+        self.num_hashes
+    }
+
+    /// Returns the number of bits set to 1.
+    pub fn bits_used(&self) -> u64 {
+        self.bits_used
+    }
+
+    /// Returns the fraction of bits set.
+    pub fn load_factor(&self) -> f64 {
+        self.load_factor
+    }
+
+    /// Returns the estimated false positive probability.
+    pub fn estimated_fpp(&self) -> f64 {
+        self.estimated_fpp
+    }
+
+    /// Returns the hash seed.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Returns whether the filter is [derived](super::BloomFilter::is_derived), in which case the
+    /// saturation values describe its bit array rather than a set of inserted items.
+    pub fn is_derived(&self) -> bool {
+        self.derived
+    }
+}
+
+impl fmt::Display for BloomFilterSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "### Bloom Filter Summary:")?;
+        writeln!(f, "   capacity      : {}", self.capacity)?;
+        writeln!(f, "   num_hashes    : {}", self.num_hashes)?;
+        writeln!(f, "   seed          : {}", self.seed)?;
+        writeln!(f, "   bits_used     : {}", self.bits_used)?;
+        writeln!(f, "   load_factor   : {:.6}", self.load_factor)?;
+        writeln!(f, "   estimated_fpp : {:.6e}", self.estimated_fpp)?;
+        writeln!(f, "   derived       : {}", self.derived)?;
+        writeln!(f, "### End filter summary")
+    }
+}
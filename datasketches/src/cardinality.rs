@@ -0,0 +1,171 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Distinct count estimates read from serialized sketches of any cardinality family.
+//!
+//! Storage and query layers often hold "a distinct count sketch" without knowing which family
+//! wrote it. [`estimate_from_bytes`] detects the family from the preamble, deserializes the
+//! image and returns its estimate and bounds, so such a column can be read generically.
+//!
+//! HLL, CPC, compact Theta and updatable Theta images are supported, for each family whose
+//! feature is enabled.
+//!
+//! # Examples
+//!
+//! ```
+//! # #[cfg(all(feature = "hll", feature = "theta"))]
+//! # {
+//! # use datasketches::hll::HllSketch;
+//! # use datasketches::hll::HllType;
+//! # use datasketches::theta::ThetaSketchBuilder;
+//! use datasketches::cardinality::estimate_from_bytes;
+//! use datasketches::common::NumStdDev;
+//!
+//! let mut hll = HllSketch::new(12, HllType::Hll4);
+//! let mut theta = ThetaSketchBuilder::default().build();
+//! for i in 0..100 {
+//!     hll.update(i);
+//!     theta.update(i);
+//! }
+//!
+//! for bytes in [hll.serialize(), theta.compact(true).serialize()] {
+//!     let estimate = estimate_from_bytes(&bytes, NumStdDev::Two).unwrap();
+//!     assert!((estimate.estimate() - 100.0).abs() < 1.0);
+//!     assert!(estimate.lower_bound() <= estimate.estimate());
+//! }
+//! # }
+//! ```
+
+use crate::codec::family::Family;
+use crate::codec::format::PreambleHeader;
+use crate::common::CardinalityEstimate;
+use crate::common::NumStdDev;
+use crate::error::Error;
+use crate::hash::DEFAULT_UPDATE_SEED;
+
+/// Distinct count estimate of a serialized sketch, returned by [`estimate_from_bytes`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DistinctCountEstimate {
+    family_name: &'static str,
+    lower_bound: f64,
+    estimate: f64,
+    upper_bound: f64,
+}
+
+impl DistinctCountEstimate {
+    /// Returns the name of the sketch family that wrote the image, e.g. `"HLL"` or `"CPC"`.
+    pub fn family_name(&self) -> &'static str {
+        self.family_name
+    }
+
+    /// Returns the approximate lower error bound of the distinct count.
+    pub fn lower_bound(&self) -> f64 {
+        self.lower_bound
+    }
+
+    /// Returns the distinct count estimate.
+    pub fn estimate(&self) -> f64 {
+        self.estimate
+    }
+
+    /// Returns the approximate upper error bound of the distinct count.
+    pub fn upper_bound(&self) -> f64 {
+        self.upper_bound
+    }
+
+    /// Returns the bounds and the estimate as `(lower_bound, estimate, upper_bound)`.
+    pub fn to_tuple(&self) -> (f64, f64, f64) {
+        (self.lower_bound, self.estimate, self.upper_bound)
+    }
+}
+
+/// Estimates the distinct count of a serialized HLL, CPC or Theta sketch, built with the default
+/// seed.
+///
+/// The bounds are computed at `num_std_dev` standard deviations. See
+/// [`estimate_from_bytes_with_seed`] for sketches built with another seed.
+///
+/// # Errors
+///
+/// Returns an error if the image is not a valid sketch, if it belongs to a family that does not
+/// estimate distinct counts (including Tuple sketches, whose summaries cannot be read
+/// generically) or whose feature is disabled, or if its seed hash does not match the default
+/// seed.
+pub fn estimate_from_bytes(
+    bytes: &[u8],
+    num_std_dev: NumStdDev,
+) -> Result<DistinctCountEstimate, Error> {
+    estimate_from_bytes_with_seed(bytes, num_std_dev, DEFAULT_UPDATE_SEED)
+}
+
+/// Estimates the distinct count of a serialized HLL, CPC or Theta sketch, built with `seed`.
+///
+/// CPC and Theta images store a seed hash, which is checked against `seed`. HLL images do not,
+/// so any seed is accepted for them.
+///
+/// # Errors
+///
+/// See [`estimate_from_bytes`].
+pub fn estimate_from_bytes_with_seed(
+    bytes: &[u8],
+    num_std_dev: NumStdDev,
+    seed: u64,
+) -> Result<DistinctCountEstimate, Error> {
+    let family_id = PreambleHeader::read(bytes)?.family_id();
+    match family_id {
+        #[cfg(feature = "hll")]
+        id if id == Family::HLL.id => Ok(estimate_of(
+            Family::HLL,
+            &crate::hll::HllSketch::deserialize_with_seed(bytes, seed)?,
+            num_std_dev,
+        )),
+        #[cfg(feature = "cpc")]
+        id if id == Family::CPC.id => Ok(estimate_of(
+            Family::CPC,
+            &crate::cpc::CpcSketch::deserialize_with_seed(bytes, seed)?,
+            num_std_dev,
+        )),
+        #[cfg(feature = "theta")]
+        id if id == Family::THETA.id => Ok(estimate_of(
+            Family::THETA,
+            &crate::theta::CompactThetaSketch::deserialize_with_seed(bytes, seed)?,
+            num_std_dev,
+        )),
+        #[cfg(feature = "theta")]
+        id if id == Family::QUICKSELECT.id => Ok(estimate_of(
+            Family::QUICKSELECT,
+            &crate::theta::ThetaSketch::deserialize_with_seed(bytes, seed)?,
+            num_std_dev,
+        )),
+        _ => Err(Error::deserial(format!(
+            "family id {family_id} is not a supported distinct count sketch"
+        ))),
+    }
+}
+
+fn estimate_of<S: CardinalityEstimate>(
+    family: Family,
+    sketch: &S,
+    num_std_dev: NumStdDev,
+) -> DistinctCountEstimate {
+    DistinctCountEstimate {
+        family_name: family.name,
+        lower_bound: sketch.lower_bound(num_std_dev),
+        estimate: sketch.estimate(),
+        upper_bound: sketch.upper_bound(num_std_dev),
+    }
+}
@@ -24,16 +24,6 @@ pub(crate) fn insufficient_data(tag: &'static str) -> impl FnOnce(std::io::Error
     move |_| Error::insufficient_data(tag)
 }
 
-pub(crate) fn ensure_serial_version_is(expected: u8, actual: u8) -> Result<(), Error> {
-    if expected == actual {
-        Ok(())
-    } else {
-        Err(Error::deserial(format!(
-            "unsupported serial version: expected {expected}, got {actual}"
-        )))
-    }
-}
-
 pub(crate) fn ensure_preamble_longs_in(expected: &[u8], actual: u8) -> Result<(), Error> {
     if expected.contains(&actual) {
         Ok(())
@@ -15,37 +15,109 @@
 // specific language governing permissions and limitations
 // under the License.
 
-/// A simple wrapper around a `Vec<u8>` that provides methods for writing various types of data.
-pub struct SketchBytes {
-    bytes: Vec<u8>,
+use crate::error::Error;
+
+/// A simple wrapper around a byte buffer that provides methods for writing various types of data.
+///
+/// The buffer is either a `Vec<u8>` owned by the `SketchBytes`, which grows as needed, or a
+/// caller-provided slice (see [`from_slice`](Self::from_slice)), which allows serializing without
+/// allocating.
+pub struct SketchBytes<'a> {
+    sink: Sink<'a>,
 }
 
-impl SketchBytes {
+enum Sink<'a> {
+    Owned(Vec<u8>),
+    Borrowed { buf: &'a mut [u8], len: usize },
+}
+
+impl SketchBytes<'static> {
     /// Constructs an empty `SketchBytes` with at least the specified capacity.
     pub fn with_capacity(capacity: usize) -> Self {
         Self {
-            bytes: Vec::with_capacity(capacity),
+            sink: Sink::Owned(Vec::with_capacity(capacity)),
+        }
+    }
+}
+
+impl<'a> SketchBytes<'a> {
+    /// Constructs a `SketchBytes` that writes into `buf`, starting at its beginning.
+    ///
+    /// Bytes written past the end of `buf` are discarded but still counted, so that
+    /// [`finish`](Self::finish) can report how large the buffer needed to be.
+    pub fn from_slice(buf: &'a mut [u8]) -> Self {
+        Self {
+            sink: Sink::Borrowed { buf, len: 0 },
+        }
+    }
+
+    /// Returns the number of bytes written so far, including any that did not fit in the slice.
+    pub fn len(&self) -> usize {
+        match &self.sink {
+            Sink::Owned(bytes) => bytes.len(),
+            Sink::Borrowed { len, .. } => *len,
+        }
+    }
+
+    /// Returns true if no bytes have been written.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Reserves capacity for at least `additional` more bytes. Does nothing for a slice.
+    pub fn reserve(&mut self, additional: usize) {
+        if let Sink::Owned(bytes) = &mut self.sink {
+            bytes.reserve(additional);
+        }
+    }
+
+    /// Consumes the `SketchBytes` and returns the number of bytes written.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `SketchBytes` writes into a slice that is too short to hold every
+    /// byte written. The bytes that fit have already been copied into the slice by then.
+    pub fn finish(self) -> Result<usize, Error> {
+        match self.sink {
+            Sink::Owned(bytes) => Ok(bytes.len()),
+            Sink::Borrowed { buf, len } if len > buf.len() => Err(Error::invalid_argument(
+                format!("buffer too small: need {len} bytes, got {}", buf.len()),
+            )),
+            Sink::Borrowed { len, .. } => Ok(len),
         }
     }
 
     /// Consumes the `SketchBytes` and returns the underlying `Vec<u8>`.
+    ///
+    /// For a `SketchBytes` writing into a slice, returns a copy of the bytes that fit in it.
     pub fn into_bytes(self) -> Vec<u8> {
-        self.bytes
+        match self.sink {
+            Sink::Owned(bytes) => bytes,
+            Sink::Borrowed { buf, len } => buf[..len.min(buf.len())].to_vec(),
+        }
     }
 
     /// Writes the given byte slice to the `SketchBytes`.
-    pub fn write(&mut self, buf: &[u8]) {
-        self.bytes.extend_from_slice(buf);
+    pub fn write(&mut self, data: &[u8]) {
+        match &mut self.sink {
+            Sink::Owned(bytes) => bytes.extend_from_slice(data),
+            Sink::Borrowed { buf, len } => {
+                let start = (*len).min(buf.len());
+                let end = (*len + data.len()).min(buf.len());
+                buf[start..end].copy_from_slice(&data[..end - start]);
+                *len += data.len();
+            }
+        }
     }
 
     /// Writes a single byte to the `SketchBytes`.
     pub fn write_u8(&mut self, n: u8) {
-        self.bytes.push(n);
+        self.write(&[n]);
     }
 
     /// Writes a single byte to the `SketchBytes`.
     pub fn write_i8(&mut self, n: i8) {
-        self.bytes.push(n as u8);
+        self.write(&[n as u8]);
     }
 
     /// Writes a 16-bit unsigned integer to the `SketchBytes` in little-endian byte order.
@@ -128,3 +200,33 @@ impl SketchBytes {
         self.write(&n.to_be_bytes());
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorKind;
+
+    #[test]
+    fn slice_holds_exact_fit() {
+        let mut buf = [0u8; 6];
+        let mut bytes = SketchBytes::from_slice(&mut buf);
+        bytes.write_u16_le(0x0201);
+        bytes.write_u32_be(0x03040506);
+        assert_eq!(bytes.len(), 6);
+        assert_eq!(bytes.finish().unwrap(), 6);
+        assert_eq!(buf, [1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn slice_overflow_reports_required_length() {
+        let mut buf = [0u8; 3];
+        let mut bytes = SketchBytes::from_slice(&mut buf);
+        bytes.write_u8(9);
+        bytes.write_u64_le(u64::MAX);
+        assert_eq!(bytes.len(), 9);
+        let err = bytes.finish().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidArgument);
+        assert!(err.to_string().contains("need 9 bytes, got 3"), "{err}");
+        assert_eq!(buf, [9, 0xFF, 0xFF]);
+    }
+}
@@ -0,0 +1,252 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Versioned envelope for checkpointed aggregation state.
+
+use crate::codec::SketchBytes;
+use crate::codec::SketchSlice;
+use crate::codec::assert::insufficient_data;
+use crate::codec::family::Family;
+use crate::error::Error;
+
+const MAGIC: [u8; 4] = *b"DSST";
+const FORMAT_VERSION: u8 = 1;
+const HEADER_SIZE_BYTES: usize = 16;
+
+const CRATE_VERSION: (u16, u16, u16) = (
+    parse_u16(env!("CARGO_PKG_VERSION_MAJOR")),
+    parse_u16(env!("CARGO_PKG_VERSION_MINOR")),
+    parse_u16(env!("CARGO_PKG_VERSION_PATCH")),
+);
+
+const fn parse_u16(s: &str) -> u16 {
+    let bytes = s.as_bytes();
+    let mut n = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        n = n * 10 + (bytes[i] - b'0') as u16;
+        i += 1;
+    }
+    n
+}
+
+/// A decoded checkpoint envelope.
+///
+/// Unions and other stateful operators save their partial state with `save_state` and restore it
+/// with `load_state`. The state is wrapped in an envelope so that restoring the wrong kind of
+/// state, or state written by an incompatible release, fails with an error instead of producing a
+/// silently wrong aggregate.
+///
+/// The envelope layout (all integers little-endian):
+///
+/// ```text
+/// Bytes  | Field
+/// -------|-------------------------------------------------
+/// 0..4   | magic, b"DSST"
+/// 4      | envelope format version, currently 1
+/// 5      | family ID of the state
+/// 6..12  | crate version that wrote the state: major, minor, patch as u16
+/// 12..16 | payload length in bytes, as u32
+/// 16..   | payload
+/// ```
+///
+/// The payload is specific to the family; it embeds the regular serialized sketch where there is
+/// one, so it is as stable across releases as the sketch formats themselves. The crate version is
+/// informational: use [`StateEnvelope::crate_version`] to detect version skew on restore.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StateEnvelope<'a> {
+    family_id: u8,
+    crate_version: (u16, u16, u16),
+    payload: &'a [u8],
+}
+
+impl<'a> StateEnvelope<'a> {
+    /// Decodes the envelope of a saved state without interpreting its payload.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bytes do not start with the envelope magic, if the envelope format
+    /// version is not supported, or if the payload is truncated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "hll")]
+    /// # {
+    /// # use datasketches::codec::StateEnvelope;
+    /// # use datasketches::hll::HllUnion;
+    /// let state = HllUnion::new(12).save_state();
+    /// let envelope = StateEnvelope::decode(&state).unwrap();
+    /// let (major, minor, patch) = envelope.crate_version();
+    /// assert_eq!(
+    ///     format!("{major}.{minor}.{patch}"),
+    ///     env!("CARGO_PKG_VERSION")
+    /// );
+    /// # }
+    /// ```
+    pub fn decode(bytes: &'a [u8]) -> Result<Self, Error> {
+        let mut cursor = SketchSlice::new(bytes);
+        let mut magic = [0; 4];
+        cursor
+            .read_exact(&mut magic)
+            .map_err(insufficient_data("magic"))?;
+        if magic != MAGIC {
+            return Err(Error::deserial(format!(
+                "invalid state envelope magic: expected {MAGIC:?}, got {magic:?}"
+            )));
+        }
+        let format_version = cursor
+            .read_u8()
+            .map_err(insufficient_data("format_version"))?;
+        if format_version != FORMAT_VERSION {
+            return Err(Error::unsupported_version(
+                "STATE_ENVELOPE",
+                format_version,
+                &[FORMAT_VERSION],
+            ));
+        }
+        let family_id = cursor.read_u8().map_err(insufficient_data("family_id"))?;
+        let major = cursor.read_u16_le().map_err(insufficient_data("major"))?;
+        let minor = cursor.read_u16_le().map_err(insufficient_data("minor"))?;
+        let patch = cursor.read_u16_le().map_err(insufficient_data("patch"))?;
+        let payload_len = cursor
+            .read_u32_le()
+            .map_err(insufficient_data("payload_len"))? as usize;
+
+        // The header is fully read, so the payload starts right after it.
+        let remaining = &bytes[HEADER_SIZE_BYTES..];
+        if remaining.len() < payload_len {
+            return Err(Error::insufficient_data(format!(
+                "payload: expected {payload_len} bytes, got {}",
+                remaining.len()
+            )));
+        }
+
+        Ok(Self {
+            family_id,
+            crate_version: (major, minor, patch),
+            payload: &remaining[..payload_len],
+        })
+    }
+
+    /// Returns the family ID of the saved state.
+    pub fn family_id(&self) -> u8 {
+        self.family_id
+    }
+
+    /// Returns the `(major, minor, patch)` version of the crate that wrote the state.
+    pub fn crate_version(&self) -> (u16, u16, u16) {
+        self.crate_version
+    }
+
+    /// Returns the family specific payload.
+    pub fn payload(&self) -> &'a [u8] {
+        self.payload
+    }
+
+    /// Decodes the envelope and checks that it holds state of the given family.
+    pub(crate) fn decode_family(bytes: &'a [u8], family: &Family) -> Result<Self, Error> {
+        let envelope = Self::decode(bytes)?;
+        family.validate_id(envelope.family_id).map_err(|err| {
+            let (major, minor, patch) = envelope.crate_version;
+            err.with_context("written_by", format!("{major}.{minor}.{patch}"))
+        })?;
+        Ok(envelope)
+    }
+}
+
+/// Wraps a payload written by `write_payload` in an envelope of the given family.
+pub(crate) fn encode_state(
+    family: &Family,
+    payload_size_bytes: usize,
+    write_payload: impl FnOnce(&mut SketchBytes),
+) -> Vec<u8> {
+    let mut bytes = SketchBytes::with_capacity(HEADER_SIZE_BYTES + payload_size_bytes);
+    bytes.write(&MAGIC);
+    bytes.write_u8(FORMAT_VERSION);
+    bytes.write_u8(family.id);
+    bytes.write_u16_le(CRATE_VERSION.0);
+    bytes.write_u16_le(CRATE_VERSION.1);
+    bytes.write_u16_le(CRATE_VERSION.2);
+    // Backfilled once the payload is written.
+    bytes.write_u32_le(0);
+    write_payload(&mut bytes);
+
+    let mut bytes = bytes.into_bytes();
+    let payload_len = (bytes.len() - HEADER_SIZE_BYTES) as u32;
+    bytes[HEADER_SIZE_BYTES - 4..HEADER_SIZE_BYTES].copy_from_slice(&payload_len.to_le_bytes());
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorKind;
+
+    #[cfg(feature = "hll")]
+    fn family() -> &'static Family {
+        &Family::HLL
+    }
+
+    #[cfg(not(feature = "hll"))]
+    fn family() -> &'static Family {
+        &Family::UNION
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let bytes = encode_state(family(), 3, |bytes| bytes.write(&[1, 2, 3]));
+        assert_eq!(bytes.len(), HEADER_SIZE_BYTES + 3);
+
+        let envelope = StateEnvelope::decode_family(&bytes, family()).unwrap();
+        assert_eq!(envelope.family_id(), family().id);
+        assert_eq!(envelope.crate_version(), CRATE_VERSION);
+        assert_eq!(envelope.payload(), &[1, 2, 3]);
+    }
+
+    #[test]
+    fn test_crate_version() {
+        let (major, minor, patch) = CRATE_VERSION;
+        assert_eq!(
+            format!("{major}.{minor}.{patch}"),
+            env!("CARGO_PKG_VERSION")
+        );
+    }
+
+    #[test]
+    fn test_rejects_invalid_envelopes() {
+        let bytes = encode_state(family(), 1, |bytes| bytes.write_u8(42));
+
+        let mut bad_magic = bytes.clone();
+        bad_magic[0] = b'X';
+        assert!(StateEnvelope::decode(&bad_magic).is_err());
+
+        let mut bad_version = bytes.clone();
+        bad_version[4] = FORMAT_VERSION + 1;
+        let err = StateEnvelope::decode(&bad_version).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnsupportedVersion);
+
+        let mut bad_family = bytes.clone();
+        bad_family[5] = 0;
+        assert!(StateEnvelope::decode(&bad_family).is_ok());
+        assert!(StateEnvelope::decode_family(&bad_family, family()).is_err());
+
+        for len in 0..bytes.len() {
+            assert!(StateEnvelope::decode(&bytes[..len]).is_err(), "len {len}");
+        }
+    }
+}
@@ -15,6 +15,7 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use crate::codec::format;
 use crate::error::Error;
 
 /// Defines the various families of sketch and set operation classes.
@@ -32,79 +33,110 @@ pub struct Family {
     /// The maximum preamble size for this family in longs (8-bytes integer).
     #[allow(dead_code)] // only some sketches need to check this field
     pub max_pre_longs: u8,
+    /// The serial versions of this family that can be decoded, in ascending order.
+    pub serial_versions: &'static [u8],
 }
 
 impl Family {
+    /// Updatable Theta Sketch, serialized as its QuickSelect hash table.
+    #[cfg(feature = "theta")]
+    pub const QUICKSELECT: Family = Family {
+        id: format::theta::UPDATABLE_FAMILY_ID,
+        name: "QUICKSELECT",
+        min_pre_longs: 3,
+        max_pre_longs: 3,
+        serial_versions: &[3],
+    };
+
     /// Theta Sketch for cardinality estimation.
     #[cfg(feature = "theta")]
     pub const THETA: Family = Family {
-        id: 3,
+        id: format::theta::FAMILY_ID,
         name: "THETA",
         min_pre_longs: 1,
         max_pre_longs: 3,
+        serial_versions: &[1, 2, 3, 4],
+    };
+
+    /// Theta Union operation.
+    #[cfg(feature = "theta")]
+    pub const UNION: Family = Family {
+        id: format::theta::UNION_FAMILY_ID,
+        name: "UNION",
+        min_pre_longs: 4,
+        max_pre_longs: 4,
+        // Union images are not decoded; the union state has its own envelope.
+        serial_versions: &[],
     };
 
     /// The HLL family of sketches.
     #[cfg(feature = "hll")]
     pub const HLL: Family = Family {
-        id: 7,
+        id: format::hll::FAMILY_ID,
         name: "HLL",
         min_pre_longs: 1,
         max_pre_longs: 1,
+        serial_versions: &[1],
     };
 
     /// Tuple Sketch for cardinality estimation with per-key summaries.
     #[cfg(feature = "tuple")]
     pub const TUPLE: Family = Family {
-        id: 9,
+        id: format::tuple::FAMILY_ID,
         name: "TUPLE",
         min_pre_longs: 1,
         max_pre_longs: 3,
+        serial_versions: &[1, 3],
     };
 
     /// The Frequency family of sketches.
     #[cfg(feature = "frequencies")]
     pub const FREQUENCY: Family = Family {
-        id: 10,
+        id: format::frequencies::FAMILY_ID,
         name: "FREQUENCY",
         min_pre_longs: 1,
         max_pre_longs: 4,
+        serial_versions: &[1],
     };
 
     /// Compressed Probabilistic Counting (CPC) Sketch.
     #[cfg(feature = "cpc")]
     pub const CPC: Family = Family {
-        id: 16,
+        id: format::cpc::FAMILY_ID,
         name: "CPC",
         min_pre_longs: 1,
         max_pre_longs: 5,
+        serial_versions: &[1],
     };
 
     /// CountMin Sketch
     #[cfg(feature = "countmin")]
     pub const COUNTMIN: Family = Family {
-        id: 18,
+        id: format::countmin::FAMILY_ID,
         name: "COUNTMIN",
         min_pre_longs: 2,
         max_pre_longs: 2,
+        serial_versions: &[1],
     };
 
     /// T-Digest for estimating quantiles and ranks.
     #[cfg(feature = "tdigest")]
     pub const TDIGEST: Family = Family {
-        id: 20,
+        id: format::tdigest::FAMILY_ID,
         name: "TDIGEST",
         min_pre_longs: 1,
         max_pre_longs: 2,
+        serial_versions: &[1],
     };
 
     /// Bloom Filter.
     #[cfg(feature = "bloom")]
     pub const BLOOMFILTER: Family = Family {
-        id: 21,
+        id: format::bloom::FAMILY_ID,
         name: "BLOOMFILTER",
-        min_pre_longs: 3,
-        max_pre_longs: 4,
+        min_pre_longs: format::bloom::PREAMBLE_LONGS_EMPTY,
+        max_pre_longs: format::bloom::PREAMBLE_LONGS_NONEMPTY,
+        serial_versions: &[1],
     };
 }
 
@@ -116,4 +148,21 @@ impl Family {
             Ok(())
         }
     }
+
+    /// Checks that `serial_version` is in the decode table of this family.
+    ///
+    /// The serial version is a single byte with no minor component, so a version newer than the
+    /// latest one in the table is never assumed to be readable: it may change the layout of any
+    /// field.
+    pub fn validate_serial_version(&self, serial_version: u8) -> Result<(), Error> {
+        if self.serial_versions.contains(&serial_version) {
+            Ok(())
+        } else {
+            Err(Error::unsupported_version(
+                self.name,
+                serial_version,
+                self.serial_versions,
+            ))
+        }
+    }
 }
@@ -0,0 +1,278 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Serialization format constants of every sketch family.
+//!
+//! These constants describe the preamble of serialized sketches without depending on the sketch
+//! types, so external validators and storage systems can check headers (for example, reject a
+//! blob of the wrong family before storing it) even when the family's feature is not enabled.
+//!
+//! Every serialized sketch starts with the same three bytes, read by [`PreambleHeader`]:
+//!
+//! ```text
+//! Byte | Field
+//! -----|-----------------------------------------------------------------
+//! 0    | preamble size in longs or ints (family specific), in the low 6 bits
+//! 1    | serial version
+//! 2    | family ID
+//! ```
+//!
+//! The family modules list the family ID, the serial version written by this crate, the position
+//! of the flags byte and its masks, and the preamble sizes. Layouts beyond the flags byte are
+//! documented on the `serialize` method of each sketch.
+//!
+//! # Examples
+//!
+//! ```
+//! # use datasketches::codec::format;
+//! # use datasketches::codec::format::PreambleHeader;
+//! // An empty `HllSketch::new(12, HllType::Hll8)`, serialized in List mode.
+//! let bytes = [2, 1, 7, 12, 3, 12, 0, 8];
+//! let header = PreambleHeader::read(&bytes).unwrap();
+//! assert_eq!(header.family_id(), format::hll::FAMILY_ID);
+//! assert_eq!(header.serial_version(), format::hll::SERIAL_VERSION);
+//! assert_ne!(
+//!     bytes[format::hll::FLAGS_BYTE] & format::hll::FLAGS_IS_EMPTY,
+//!     0
+//! );
+//! ```
+
+use crate::error::Error;
+
+/// Offset of the preamble size byte.
+pub const PREAMBLE_SIZE_BYTE: usize = 0;
+/// Offset of the serial version byte.
+pub const SERIAL_VERSION_BYTE: usize = 1;
+/// Offset of the family ID byte.
+pub const FAMILY_ID_BYTE: usize = 2;
+/// Mask of the preamble size in the first byte. Theta sketches keep the resize factor in the upper
+/// two bits.
+pub const PREAMBLE_SIZE_MASK: u8 = 0x3F;
+
+/// The leading bytes shared by the serialized images of every family.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PreambleHeader {
+    preamble_size: u8,
+    serial_version: u8,
+    family_id: u8,
+}
+
+impl PreambleHeader {
+    /// Reads the header from the start of a serialized sketch.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `bytes` is shorter than three bytes.
+    pub fn read(bytes: &[u8]) -> Result<Self, Error> {
+        match bytes {
+            [first, serial_version, family_id, ..] => Ok(Self {
+                preamble_size: first & PREAMBLE_SIZE_MASK,
+                serial_version: *serial_version,
+                family_id: *family_id,
+            }),
+            _ => Err(Error::insufficient_data(format!(
+                "preamble header: expected 3 bytes, got {}",
+                bytes.len()
+            ))),
+        }
+    }
+
+    /// Returns the preamble size, in longs or ints depending on the family.
+    pub fn preamble_size(&self) -> u8 {
+        self.preamble_size
+    }
+
+    /// Returns the serial version.
+    pub fn serial_version(&self) -> u8 {
+        self.serial_version
+    }
+
+    /// Returns the family ID.
+    pub fn family_id(&self) -> u8 {
+        self.family_id
+    }
+}
+
+/// Bloom filter format. The preamble size is in longs.
+pub mod bloom {
+    /// Family ID.
+    pub const FAMILY_ID: u8 = 21;
+    /// Serial version written by this crate.
+    pub const SERIAL_VERSION: u8 = 1;
+    /// Offset of the flags byte.
+    pub const FLAGS_BYTE: usize = 3;
+    /// Flags byte bit: the filter is empty.
+    pub const FLAGS_IS_EMPTY: u8 = 1 << 2;
+    /// Preamble longs of an empty filter.
+    pub const PREAMBLE_LONGS_EMPTY: u8 = 3;
+    /// Preamble longs of a non-empty filter.
+    pub const PREAMBLE_LONGS_NONEMPTY: u8 = 4;
+}
+
+/// Count-Min sketch format. The preamble size is in longs.
+pub mod countmin {
+    /// Family ID.
+    pub const FAMILY_ID: u8 = 18;
+    /// Serial version written by this crate.
+    pub const SERIAL_VERSION: u8 = 1;
+    /// Offset of the flags byte.
+    pub const FLAGS_BYTE: usize = 3;
+    /// Flags byte bit: the sketch is empty.
+    pub const FLAGS_IS_EMPTY: u8 = 1 << 0;
+    /// Preamble longs.
+    pub const PREAMBLE_LONGS: u8 = 2;
+}
+
+/// CPC sketch format. The preamble size is in ints.
+pub mod cpc {
+    /// Family ID.
+    pub const FAMILY_ID: u8 = 16;
+    /// Serial version written by this crate.
+    pub const SERIAL_VERSION: u8 = 1;
+    /// Offset of the lg_k byte.
+    pub const LG_K_BYTE: usize = 3;
+    /// Offset of the flags byte.
+    pub const FLAGS_BYTE: usize = 5;
+    /// Flags byte bit: the sketch is compressed. Always set.
+    pub const FLAGS_IS_COMPRESSED: u8 = 1 << 1;
+    /// Flags byte bit: the preamble holds the HIP estimator state.
+    pub const FLAGS_HAS_HIP: u8 = 1 << 2;
+    /// Flags byte bit: the image holds a compressed surprising-value table.
+    pub const FLAGS_HAS_TABLE: u8 = 1 << 3;
+    /// Flags byte bit: the image holds a compressed sliding window.
+    pub const FLAGS_HAS_WINDOW: u8 = 1 << 4;
+}
+
+/// Frequent items sketch format. The preamble size is in longs.
+pub mod frequencies {
+    /// Family ID.
+    pub const FAMILY_ID: u8 = 10;
+    /// Serial version written by this crate.
+    pub const SERIAL_VERSION: u8 = 1;
+    /// Offset of the flags byte.
+    pub const FLAGS_BYTE: usize = 5;
+    /// Flags byte bits: the sketch is empty. Both bits are set for compatibility, so test either.
+    pub const FLAGS_IS_EMPTY: u8 = 5;
+    /// Preamble longs of an empty sketch.
+    pub const PREAMBLE_LONGS_EMPTY: u8 = 1;
+    /// Preamble longs of a non-empty sketch.
+    pub const PREAMBLE_LONGS_NONEMPTY: u8 = 4;
+}
+
+/// HLL sketch format. The preamble size is in ints.
+pub mod hll {
+    /// Family ID.
+    pub const FAMILY_ID: u8 = 7;
+    /// Serial version written by this crate.
+    pub const SERIAL_VERSION: u8 = 1;
+    /// Offset of the lg_k byte.
+    pub const LG_K_BYTE: usize = 3;
+    /// Offset of the flags byte.
+    pub const FLAGS_BYTE: usize = 5;
+    /// Offset of the mode byte: the current mode in bits 0-1 and the target type in bits 2-3.
+    pub const MODE_BYTE: usize = 7;
+    /// Flags byte bit: the sketch is empty.
+    pub const FLAGS_IS_EMPTY: u8 = 1 << 2;
+    /// Flags byte bit: List and Set mode coupons are stored without empty slots.
+    pub const FLAGS_IS_COMPACT: u8 = 1 << 3;
+    /// Flags byte bit: the sketch is out of order and its HIP accumulator is not valid.
+    pub const FLAGS_IS_OUT_OF_ORDER: u8 = 1 << 4;
+    /// Preamble ints in List mode.
+    pub const LIST_PREAMBLE_INTS: u8 = 2;
+    /// Preamble ints in Set mode.
+    pub const SET_PREAMBLE_INTS: u8 = 3;
+    /// Preamble ints in HLL mode.
+    pub const HLL_PREAMBLE_INTS: u8 = 10;
+}
+
+/// t-digest format. The preamble size is in longs.
+pub mod tdigest {
+    /// Family ID.
+    pub const FAMILY_ID: u8 = 20;
+    /// Serial version written by this crate.
+    pub const SERIAL_VERSION: u8 = 1;
+    /// Offset of the flags byte.
+    pub const FLAGS_BYTE: usize = 5;
+    /// Flags byte bit: the digest is empty.
+    pub const FLAGS_IS_EMPTY: u8 = 1 << 0;
+    /// Flags byte bit: the digest holds a single value, stored in place of the centroids.
+    pub const FLAGS_IS_SINGLE_VALUE: u8 = 1 << 1;
+    /// Flags byte bit: the next compression merges in reverse order.
+    pub const FLAGS_REVERSE_MERGE: u8 = 1 << 2;
+    /// Preamble longs of an empty or single-value digest.
+    pub const PREAMBLE_LONGS_EMPTY_OR_SINGLE: u8 = 1;
+    /// Preamble longs of a digest with centroids.
+    pub const PREAMBLE_LONGS_MULTIPLE: u8 = 2;
+}
+
+/// Theta sketch format, whose flags byte is shared by Tuple sketches. The preamble size is in
+/// longs.
+pub mod theta {
+    /// Family ID of compact sketches.
+    pub const FAMILY_ID: u8 = 3;
+    /// Family ID of updatable (QuickSelect) sketches.
+    pub const UPDATABLE_FAMILY_ID: u8 = 2;
+    /// Family ID of unions.
+    pub const UNION_FAMILY_ID: u8 = 4;
+    /// Serial version of uncompressed images.
+    pub const SERIAL_VERSION: u8 = 3;
+    /// Serial version of compressed compact images.
+    pub const COMPRESSED_SERIAL_VERSION: u8 = 4;
+    /// Offset of the flags byte.
+    pub const FLAGS_BYTE: usize = 5;
+    /// Flags byte bit: the sketch is read-only.
+    pub const FLAGS_IS_READ_ONLY: u8 = 1 << 1;
+    /// Flags byte bit: the sketch is logically empty.
+    pub const FLAGS_IS_EMPTY: u8 = 1 << 2;
+    /// Flags byte bit: the sketch is in compact form.
+    pub const FLAGS_IS_COMPACT: u8 = 1 << 3;
+    /// Flags byte bit: retained entries are ordered by ascending hash.
+    pub const FLAGS_IS_ORDERED: u8 = 1 << 4;
+    /// Preamble longs of updatable sketches.
+    pub const UPDATABLE_PREAMBLE_LONGS: u8 = 3;
+}
+
+/// Tuple sketch format. The preamble size is in longs.
+pub mod tuple {
+    pub use super::theta::FLAGS_BYTE;
+    pub use super::theta::FLAGS_IS_COMPACT;
+    pub use super::theta::FLAGS_IS_EMPTY;
+    pub use super::theta::FLAGS_IS_ORDERED;
+    pub use super::theta::FLAGS_IS_READ_ONLY;
+
+    /// Family ID.
+    pub const FAMILY_ID: u8 = 9;
+    /// Serial version written by this crate.
+    pub const SERIAL_VERSION: u8 = 3;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_header() {
+        let header = PreambleHeader::read(&[0xC3, 3, 2, 12]).unwrap();
+        assert_eq!(header.preamble_size(), 3);
+        assert_eq!(header.serial_version(), theta::SERIAL_VERSION);
+        assert_eq!(header.family_id(), theta::UPDATABLE_FAMILY_ID);
+
+        for len in 0..3 {
+            assert!(PreambleHeader::read(&[1, 1, 1][..len]).is_err());
+        }
+    }
+}
@@ -19,8 +19,15 @@
 
 mod decode;
 mod encode;
+#[cfg(any(feature = "hll", feature = "theta"))]
+mod envelope;
+pub mod format;
 pub use self::decode::SketchSlice;
 pub use self::encode::SketchBytes;
+#[cfg(any(feature = "hll", feature = "theta"))]
+pub use self::envelope::StateEnvelope;
+#[cfg(any(feature = "hll", feature = "theta"))]
+pub(crate) use self::envelope::encode_state;
 
 #[cfg(any(
     feature = "bloom",
@@ -0,0 +1,662 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Fixed-size bit arrays packed into 64-bit words.
+//!
+//! [`BitArray`] is the storage behind [`BloomFilter`](crate::bloom::BloomFilter) and is shared by
+//! any filter that needs a packed bit set with a cached population count. [`BitArrayRef`] reads
+//! the same serialized layout in place without allocating.
+//!
+//! The serialized layout is the number of set bits as a little-endian `u64`, followed by the
+//! words in little-endian order. A bit count of `u64::MAX` marks the count as "dirty", as in the
+//! Java implementation: the count is not known and is counted from the words once when needed.
+
+use std::sync::OnceLock;
+
+use crate::codec::SketchBytes;
+use crate::codec::SketchSlice;
+use crate::error::Error;
+
+/// Serialized bit count indicating that the bits must be recounted on read.
+const DIRTY_BITS_VALUE: u64 = u64::MAX;
+
+/// A fixed-size array of bits packed into `u64` words.
+///
+/// The number of set bits is tracked incrementally so that [`num_bits_set`](Self::num_bits_set)
+/// is `O(1)`. Bits set with [`set_uncounted`](Self::set_uncounted), and arrays read from images
+/// with a dirty count, do not track their count: it is counted from the words on the first
+/// demand and cached until the next mutation, which tracks it again.
+///
+/// # Examples
+///
+/// ```
+/// # use datasketches::common::BitArray;
+/// let mut bits = BitArray::new(100);
+/// assert_eq!(bits.capacity(), 128);
+///
+/// assert!(bits.set(7));
+/// assert!(!bits.set(7)); // already set
+/// assert!(bits.get(7));
+/// assert_eq!(bits.num_bits_set(), 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct BitArray {
+    /// Number of set bits, valid unless the count is dirty
+    num_bits_set: u64,
+    /// Whether the count is not tracked, and is serialized as [`DIRTY_BITS_VALUE`]
+    dirty: bool,
+    /// Number of set bits counted on demand while the count is dirty
+    recount: OnceLock<u64>,
+    words: Box<[u64]>,
+}
+
+impl BitArray {
+    /// Creates a bit array holding at least `num_bits` bits, all cleared.
+    ///
+    /// The capacity is rounded up to a multiple of 64.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num_bits` is 0.
+    pub fn new(num_bits: u64) -> Self {
+        assert!(num_bits > 0, "num_bits must be greater than 0");
+        let num_words = num_bits.div_ceil(64) as usize;
+        Self::from_parts(0, vec![0u64; num_words].into_boxed_slice())
+    }
+
+    /// Creates a bit array from packed words, counting the bits that are set.
+    ///
+    /// Bit `i` is stored in word `i / 64` at bit offset `i % 64`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `words` is empty.
+    pub fn from_words(words: impl Into<Box<[u64]>>) -> Self {
+        let words = words.into();
+        assert!(!words.is_empty(), "words must not be empty");
+        let num_bits_set = count_ones(words.iter().copied());
+        Self::from_parts(num_bits_set, words)
+    }
+
+    /// Returns the value of the bit at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not less than [`capacity`](Self::capacity).
+    pub fn get(&self, index: usize) -> bool {
+        (self.words[index >> 6] & bit_mask(index)) != 0
+    }
+
+    /// Sets the bit at `index`, returning whether it was previously cleared.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not less than [`capacity`](Self::capacity).
+    pub fn set(&mut self, index: usize) -> bool {
+        self.ensure_counted();
+        let word = &mut self.words[index >> 6];
+        let mask = bit_mask(index);
+        if (*word & mask) != 0 {
+            return false;
+        }
+        *word |= mask;
+        self.num_bits_set += 1;
+        true
+    }
+
+    /// Sets the bit at `index` without reading it or updating the bit count.
+    ///
+    /// This is the Java `setBit`: it marks the count as dirty, so that the next
+    /// [`num_bits_set`](Self::num_bits_set) recounts the bits, and serialized images carry the
+    /// dirty count.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not less than [`capacity`](Self::capacity).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::common::BitArray;
+    /// let mut bits = BitArray::new(64);
+    /// bits.set_uncounted(3);
+    /// bits.set_uncounted(3);
+    /// assert!(bits.is_dirty());
+    /// assert_eq!(bits.num_bits_set(), 1);
+    ///
+    /// bits.set(4);
+    /// assert!(!bits.is_dirty());
+    /// assert_eq!(bits.num_bits_set(), 2);
+    /// ```
+    pub fn set_uncounted(&mut self, index: usize) {
+        self.words[index >> 6] |= bit_mask(index);
+        self.mark_dirty();
+    }
+
+    /// Clears the bit at `index`, returning whether it was previously set.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not less than [`capacity`](Self::capacity).
+    pub fn clear(&mut self, index: usize) -> bool {
+        self.ensure_counted();
+        let word = &mut self.words[index >> 6];
+        let mask = bit_mask(index);
+        if (*word & mask) == 0 {
+            return false;
+        }
+        *word &= !mask;
+        self.num_bits_set -= 1;
+        true
+    }
+
+    /// Clears all bits.
+    pub fn reset(&mut self) {
+        self.words.fill(0);
+        self.num_bits_set = 0;
+        self.dirty = false;
+    }
+
+    /// Sets every bit that is set in `other` (bitwise OR).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the arrays have different capacities.
+    pub fn union(&mut self, other: &BitArray) {
+        self.combine(other, |a, b| a | b);
+    }
+
+    /// Clears every bit that is not set in `other` (bitwise AND).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the arrays have different capacities.
+    pub fn intersect(&mut self, other: &BitArray) {
+        self.combine(other, |a, b| a & b);
+    }
+
+    /// Flips every bit.
+    pub fn invert(&mut self) {
+        for word in &mut self.words {
+            *word = !*word;
+        }
+        if self.dirty {
+            self.mark_dirty();
+        } else {
+            self.num_bits_set = self.capacity() as u64 - self.num_bits_set;
+        }
+    }
+
+    /// Returns whether no bits are set.
+    pub fn is_empty(&self) -> bool {
+        self.num_bits_set() == 0
+    }
+
+    /// Returns the number of bits set to 1.
+    ///
+    /// This is `O(1)`, except for the first call after the count became [dirty](Self::is_dirty),
+    /// which counts the bits and caches the result.
+    pub fn num_bits_set(&self) -> u64 {
+        if self.dirty {
+            *self
+                .recount
+                .get_or_init(|| count_ones(self.words.iter().copied()))
+        } else {
+            self.num_bits_set
+        }
+    }
+
+    /// Returns whether the bit count is not tracked by mutations.
+    ///
+    /// A dirty count is counted on demand and cached, but it is still serialized as dirty, so
+    /// that images read with a dirty count are written back unchanged.
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Returns the total number of bits.
+    pub fn capacity(&self) -> usize {
+        self.words.len() * 64
+    }
+
+    /// Returns the packed words.
+    pub fn words(&self) -> &[u64] {
+        &self.words
+    }
+
+    /// Returns the number of bytes produced by [`serialize`](Self::serialize).
+    pub fn serialized_size_bytes(&self) -> usize {
+        (1 + self.words.len()) * size_of::<u64>()
+    }
+
+    /// Serializes the bit array to a byte vector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::common::BitArray;
+    /// let mut bits = BitArray::new(64);
+    /// bits.set(3);
+    ///
+    /// let bytes = bits.serialize();
+    /// assert_eq!(BitArray::deserialize(&bytes).unwrap(), bits);
+    /// ```
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut bytes = SketchBytes::with_capacity(self.serialized_size_bytes());
+        self.write_to(&mut bytes);
+        bytes.into_bytes()
+    }
+
+    /// Writes the bit count followed by the words, as embedded in filter images.
+    ///
+    /// A [dirty](Self::is_dirty) count is written as `u64::MAX`, like the Java implementation
+    /// does. The number of words is not written; callers record it in their own preamble.
+    pub fn write_to(&self, bytes: &mut SketchBytes<'_>) {
+        bytes.write_u64_le(if self.dirty {
+            DIRTY_BITS_VALUE
+        } else {
+            self.num_bits_set
+        });
+        for &word in &self.words {
+            bytes.write_u64_le(word);
+        }
+    }
+
+    /// Deserializes a bit array produced by [`serialize`](Self::serialize).
+    ///
+    /// The number of words is inferred from the length of `bytes`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the length is not a positive number of words after the bit count, or
+    /// if the stored bit count exceeds the capacity.
+    pub fn deserialize(bytes: &[u8]) -> Result<Self, Error> {
+        let num_words = num_words_in(bytes)?;
+        Self::read_from(&mut SketchSlice::new(bytes), num_words)
+    }
+
+    /// Reads a bit count followed by `num_words` words, the counterpart of
+    /// [`write_to`](Self::write_to).
+    ///
+    /// A dirty count stays dirty until the bits are counted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `num_words` is 0, the cursor holds too few bytes, or the stored bit
+    /// count exceeds the capacity.
+    pub fn read_from(cursor: &mut SketchSlice<'_>, num_words: usize) -> Result<Self, Error> {
+        if num_words == 0 {
+            return Err(Error::deserial(
+                "invalid num_words: expected at least 1, got 0",
+            ));
+        }
+        let raw_num_bits_set = cursor
+            .read_u64_le()
+            .map_err(|_| Error::insufficient_data("num_bits_set"))?;
+        let mut words = vec![0u64; num_words].into_boxed_slice();
+        for word in &mut words {
+            *word = cursor
+                .read_u64_le()
+                .map_err(|_| Error::insufficient_data("bit_array"))?;
+        }
+        let num_bits_set = validate_num_bits_set(raw_num_bits_set, num_words)?;
+        Ok(Self::from_parts(num_bits_set, words))
+    }
+
+    /// Creates a bit array from a bit count, which is dirty if it is [`DIRTY_BITS_VALUE`].
+    fn from_parts(num_bits_set: u64, words: Box<[u64]>) -> Self {
+        let mut bits = Self {
+            num_bits_set,
+            dirty: false,
+            recount: OnceLock::new(),
+            words,
+        };
+        if num_bits_set == DIRTY_BITS_VALUE {
+            bits.mark_dirty();
+        }
+        bits
+    }
+
+    /// Stops tracking the bit count, dropping any cached count.
+    fn mark_dirty(&mut self) {
+        self.dirty = true;
+        self.recount = OnceLock::new();
+    }
+
+    /// Counts the bits if the count is dirty, so that mutations can track it again.
+    fn ensure_counted(&mut self) {
+        if self.dirty {
+            self.num_bits_set = self.num_bits_set();
+            self.dirty = false;
+        }
+    }
+
+    fn combine(&mut self, other: &BitArray, op: impl Fn(u64, u64) -> u64) {
+        assert_eq!(
+            self.words.len(),
+            other.words.len(),
+            "bit arrays must have the same capacity"
+        );
+        // Count bits while combining (single pass)
+        let mut num_bits_set = 0;
+        for (word, &other_word) in self.words.iter_mut().zip(other.words.iter()) {
+            *word = op(*word, other_word);
+            num_bits_set += word.count_ones() as u64;
+        }
+        self.num_bits_set = num_bits_set;
+        self.dirty = false;
+    }
+}
+
+/// Bit arrays are equal if they hold the same bits, whether or not their counts are dirty.
+impl PartialEq for BitArray {
+    fn eq(&self, other: &Self) -> bool {
+        self.words == other.words
+    }
+}
+
+impl Eq for BitArray {}
+
+/// A read-only view over a serialized [`BitArray`].
+///
+/// Wrapping validates the layout once; queries then read the words straight from the borrowed
+/// bytes without allocating.
+///
+/// # Examples
+///
+/// ```
+/// # use datasketches::common::{BitArray, BitArrayRef};
+/// let mut bits = BitArray::new(128);
+/// bits.set(100);
+/// let bytes = bits.serialize();
+///
+/// let view = BitArrayRef::wrap(&bytes).unwrap();
+/// assert!(view.get(100));
+/// assert!(!view.get(99));
+/// assert_eq!(view.num_bits_set(), 1);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct BitArrayRef<'a> {
+    num_bits_set: u64,
+    words: &'a [u8],
+}
+
+impl<'a> BitArrayRef<'a> {
+    /// Wraps bytes produced by [`BitArray::serialize`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as [`BitArray::deserialize`].
+    pub fn wrap(bytes: &'a [u8]) -> Result<Self, Error> {
+        let num_words = num_words_in(bytes)?;
+        let (count, words) = bytes.split_at(size_of::<u64>());
+        let raw_num_bits_set = u64::from_le_bytes(count.try_into().unwrap());
+        let num_bits_set = validate_num_bits_set(raw_num_bits_set, num_words)?;
+        Ok(Self {
+            num_bits_set,
+            words,
+        })
+    }
+
+    /// Returns the value of the bit at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not less than [`capacity`](Self::capacity).
+    pub fn get(&self, index: usize) -> bool {
+        (self.word(index >> 6) & bit_mask(index)) != 0
+    }
+
+    /// Returns whether no bits are set.
+    pub fn is_empty(&self) -> bool {
+        if self.is_dirty() {
+            self.words.iter().all(|&byte| byte == 0)
+        } else {
+            self.num_bits_set == 0
+        }
+    }
+
+    /// Returns the number of bits set to 1.
+    ///
+    /// This is `O(1)`, unless the serialized count is [dirty](Self::is_dirty) and must be
+    /// recounted.
+    pub fn num_bits_set(&self) -> u64 {
+        if self.is_dirty() {
+            count_ones((0..self.words.len() / size_of::<u64>()).map(|i| self.word(i)))
+        } else {
+            self.num_bits_set
+        }
+    }
+
+    /// Returns whether the serialized bit count is dirty.
+    pub fn is_dirty(&self) -> bool {
+        self.num_bits_set == DIRTY_BITS_VALUE
+    }
+
+    /// Returns the total number of bits.
+    pub fn capacity(&self) -> usize {
+        self.words.len() * 8
+    }
+
+    /// Copies the view into an owned [`BitArray`].
+    pub fn to_bit_array(&self) -> BitArray {
+        let words = (0..self.words.len() / size_of::<u64>())
+            .map(|i| self.word(i))
+            .collect();
+        BitArray::from_parts(self.num_bits_set, words)
+    }
+
+    fn word(&self, word_index: usize) -> u64 {
+        let offset = word_index * size_of::<u64>();
+        u64::from_le_bytes(self.words[offset..offset + 8].try_into().unwrap())
+    }
+}
+
+fn bit_mask(index: usize) -> u64 {
+    1u64 << (index & 63)
+}
+
+fn count_ones(words: impl Iterator<Item = u64>) -> u64 {
+    words.map(|w| w.count_ones() as u64).sum()
+}
+
+fn num_words_in(bytes: &[u8]) -> Result<usize, Error> {
+    let len = bytes.len();
+    if len < 2 * size_of::<u64>() || len % size_of::<u64>() != 0 {
+        return Err(Error::insufficient_data(format!(
+            "bit array length must be a multiple of 8 and at least 16 bytes, got {len}"
+        )));
+    }
+    Ok(len / size_of::<u64>() - 1)
+}
+
+/// Checks that a serialized bit count fits in `num_words` words, unless it is dirty.
+fn validate_num_bits_set(raw_num_bits_set: u64, num_words: usize) -> Result<u64, Error> {
+    if raw_num_bits_set == DIRTY_BITS_VALUE {
+        return Ok(raw_num_bits_set);
+    }
+    if raw_num_bits_set.div_ceil(64) > num_words as u64 {
+        return Err(Error::deserial(format!(
+            "invalid num_bits_set: expected <= {}, got {}",
+            num_words * 64,
+            raw_num_bits_set
+        )));
+    }
+    Ok(raw_num_bits_set)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorKind;
+
+    #[test]
+    fn test_set_get_clear() {
+        let mut bits = BitArray::new(130);
+        assert_eq!(bits.capacity(), 192);
+        assert!(bits.is_empty());
+
+        assert!(bits.set(0));
+        assert!(bits.set(129));
+        assert!(!bits.set(129));
+        assert!(bits.get(0));
+        assert!(bits.get(129));
+        assert!(!bits.get(128));
+        assert_eq!(bits.num_bits_set(), 2);
+
+        assert!(bits.clear(0));
+        assert!(!bits.clear(0));
+        assert_eq!(bits.num_bits_set(), 1);
+
+        bits.reset();
+        assert!(bits.is_empty());
+        assert!(!bits.get(129));
+    }
+
+    #[test]
+    fn test_union_intersect_invert() {
+        let mut a = BitArray::from_words(vec![0b1100, 0]);
+        let b = BitArray::from_words(vec![0b1010, 1]);
+
+        let mut union = a.clone();
+        union.union(&b);
+        assert_eq!(union.words(), &[0b1110, 1]);
+        assert_eq!(union.num_bits_set(), 4);
+
+        a.intersect(&b);
+        assert_eq!(a.words(), &[0b1000, 0]);
+        assert_eq!(a.num_bits_set(), 1);
+
+        a.invert();
+        assert_eq!(a.words(), &[!0b1000, u64::MAX]);
+        assert_eq!(a.num_bits_set(), 127);
+    }
+
+    #[test]
+    #[should_panic(expected = "bit arrays must have the same capacity")]
+    fn test_union_mismatched_capacity() {
+        let mut a = BitArray::new(64);
+        a.union(&BitArray::new(128));
+    }
+
+    #[test]
+    fn test_serialize_round_trip() {
+        let mut bits = BitArray::new(256);
+        for i in (0..256).step_by(7) {
+            bits.set(i);
+        }
+        let bytes = bits.serialize();
+        assert_eq!(bytes.len(), bits.serialized_size_bytes());
+        assert_eq!(BitArray::deserialize(&bytes).unwrap(), bits);
+
+        let view = BitArrayRef::wrap(&bytes).unwrap();
+        assert_eq!(view.capacity(), 256);
+        assert_eq!(view.num_bits_set(), bits.num_bits_set());
+        for i in 0..256 {
+            assert_eq!(view.get(i), bits.get(i), "bit {i}");
+        }
+        assert_eq!(view.to_bit_array(), bits);
+    }
+
+    #[test]
+    fn test_dirty_count_is_recomputed() {
+        let bits = BitArray::from_words(vec![0xFF, 0x1]);
+        let mut bytes = bits.serialize();
+        bytes[..8].copy_from_slice(&DIRTY_BITS_VALUE.to_le_bytes());
+
+        let view = BitArrayRef::wrap(&bytes).unwrap();
+        assert!(view.is_dirty());
+        assert_eq!(view.num_bits_set(), 9);
+        assert!(view.to_bit_array().is_dirty());
+
+        let mut bits = BitArray::deserialize(&bytes).unwrap();
+        assert!(bits.is_dirty());
+        assert_eq!(bits.num_bits_set(), 9);
+        // The dirty count survives a round trip, as in Java.
+        assert_eq!(bits.serialize(), bytes);
+
+        assert!(!bits.set(0));
+        assert!(!bits.is_dirty());
+        assert_eq!(bits.num_bits_set(), 9);
+    }
+
+    #[test]
+    fn test_dirty_count_is_cached() {
+        let mut bits = BitArray::new(128);
+        bits.set_uncounted(3);
+        assert_eq!(bits.num_bits_set(), 1);
+        assert_eq!(bits.recount.get(), Some(&1));
+
+        // Mutations that do not track the count drop the cached one.
+        bits.set_uncounted(4);
+        assert_eq!(bits.recount.get(), None);
+        assert_eq!(bits.num_bits_set(), 2);
+        bits.invert();
+        assert!(bits.is_dirty());
+        assert_eq!(bits.num_bits_set(), 126);
+        assert_eq!(bits.clone().num_bits_set(), 126);
+        assert_eq!(bits.serialize()[..8], DIRTY_BITS_VALUE.to_le_bytes());
+    }
+
+    #[test]
+    fn test_set_uncounted() {
+        let mut bits = BitArray::new(128);
+        assert!(bits.is_empty());
+        bits.set_uncounted(70);
+        bits.set_uncounted(70);
+        assert!(bits.is_dirty());
+        assert!(!bits.is_empty());
+        assert!(bits.get(70));
+        assert_eq!(bits.num_bits_set(), 1);
+        assert_eq!(bits, BitArray::from_words(vec![0, 1 << 6]));
+
+        let bytes = bits.serialize();
+        assert_eq!(bytes[..8], DIRTY_BITS_VALUE.to_le_bytes());
+
+        let mut inverted = bits.clone();
+        inverted.invert();
+        assert!(inverted.is_dirty());
+        assert_eq!(inverted.num_bits_set(), 127);
+
+        assert!(bits.clear(70));
+        assert!(!bits.is_dirty());
+        assert!(bits.is_empty());
+
+        bits.set_uncounted(1);
+        bits.union(&BitArray::from_words(vec![0b110, 0]));
+        assert!(!bits.is_dirty());
+        assert_eq!(bits.num_bits_set(), 2);
+
+        bits.set_uncounted(3);
+        bits.reset();
+        assert!(!bits.is_dirty());
+        assert!(bits.is_empty());
+    }
+
+    #[test]
+    fn test_deserialize_rejects_malformed_input() {
+        let err = BitArray::deserialize(&[0u8; 8]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        let err = BitArrayRef::wrap(&[0u8; 20]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+
+        let mut bytes = BitArray::new(64).serialize();
+        bytes[..8].copy_from_slice(&65u64.to_le_bytes());
+        assert!(BitArray::deserialize(&bytes).is_err());
+        assert!(BitArrayRef::wrap(&bytes).is_err());
+    }
+}
@@ -0,0 +1,63 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Builder trait shared by all sketches.
+
+use crate::error::Error;
+
+/// Builds a sketch from its configuration.
+///
+/// Every sketch family has a builder, returned by the `builder()` function of its sketch type or
+/// constructed directly (`ThetaSketchBuilder::default`, `TupleSketchBuilder::new`, and
+/// `BloomFilterBuilder::with_accuracy` or `with_size`), and every builder implements this trait,
+/// so that code constructing sketches from configuration can be written once for all of them.
+/// Besides `try_build`, each builder has an inherent `build` method that panics where `try_build`
+/// returns an error.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "hll")]
+/// # {
+/// # use datasketches::common::SketchBuilder;
+/// # use datasketches::error::Error;
+/// # use datasketches::hll::HllSketch;
+/// fn build_all<B: SketchBuilder>(builders: Vec<B>) -> Result<Vec<B::Sketch>, Error> {
+///     builders.into_iter().map(SketchBuilder::try_build).collect()
+/// }
+///
+/// let sketches = build_all(vec![
+///     HllSketch::builder().lg_config_k(10),
+///     HllSketch::builder().lg_config_k(12),
+/// ])
+/// .unwrap();
+/// assert_eq!(sketches[1].lg_config_k(), 12);
+///
+/// assert!(HllSketch::builder().lg_config_k(30).try_build().is_err());
+/// # }
+/// ```
+pub trait SketchBuilder {
+    /// The type of the built sketch.
+    type Sketch;
+
+    /// Builds the sketch.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the configuration is invalid or incomplete.
+    fn try_build(self) -> Result<Self::Sketch, Error>;
+}
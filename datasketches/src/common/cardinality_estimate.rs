@@ -0,0 +1,80 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Cardinality estimates shared by the distinct counting sketches.
+
+use crate::common::NumStdDev;
+
+/// A sketch that estimates a number of distinct items with confidence bounds.
+///
+/// It is implemented by `CpcSketch`, `HllSketch`, `HllUnion`, `ThetaSketch` and
+/// `CompactThetaSketch`, whichever of their features are enabled, so that code reporting distinct
+/// counts can be written once for all of them.
+///
+/// # Examples
+///
+/// ```
+/// # use datasketches::common::CardinalityEstimate;
+/// # use datasketches::common::NumStdDev;
+/// fn report<S: CardinalityEstimate>(sketch: &S) -> String {
+///     format!(
+///         "{} [{}, {}]",
+///         sketch.estimate(),
+///         sketch.lower_bound(NumStdDev::Two),
+///         sketch.upper_bound(NumStdDev::Two)
+///     )
+/// }
+/// ```
+pub trait CardinalityEstimate {
+    /// Returns the cardinality estimate.
+    fn estimate(&self) -> f64;
+
+    /// Returns the lower confidence bound for the given number of standard deviations.
+    fn lower_bound(&self, num_std_dev: NumStdDev) -> f64;
+
+    /// Returns the upper confidence bound for the given number of standard deviations.
+    fn upper_bound(&self, num_std_dev: NumStdDev) -> f64;
+}
+
+macro_rules! impl_cardinality_estimate {
+    ($feature:literal, $($ty:ty),+) => {
+        $(
+            #[cfg(feature = $feature)]
+            impl CardinalityEstimate for $ty {
+                fn estimate(&self) -> f64 {
+                    <$ty>::estimate(self)
+                }
+
+                fn lower_bound(&self, num_std_dev: NumStdDev) -> f64 {
+                    <$ty>::lower_bound(self, num_std_dev)
+                }
+
+                fn upper_bound(&self, num_std_dev: NumStdDev) -> f64 {
+                    <$ty>::upper_bound(self, num_std_dev)
+                }
+            }
+        )+
+    };
+}
+
+impl_cardinality_estimate!("cpc", crate::cpc::CpcSketch);
+impl_cardinality_estimate!("hll", crate::hll::HllSketch, crate::hll::HllUnion);
+impl_cardinality_estimate!(
+    "theta",
+    crate::theta::ThetaSketch,
+    crate::theta::CompactThetaSketch
+);
@@ -0,0 +1,41 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+//! Memory usage introspection shared by all sketches.
+
+/// Reports the in-memory footprint of a sketch.
+///
+/// The reported size includes the sketch struct itself plus all heap allocations it owns (hash
+/// tables, register arrays, auxiliary maps, centroid buffers and so on), measured by allocated
+/// capacity rather than by the number of live entries. Heap memory owned by user-provided items
+/// (for example the bytes of a `String` item) is not included.
+///
+/// This is intended for monitoring caches that hold many sketches; the value is an estimate and
+/// does not account for allocator overhead.
+///
+/// # Examples
+///
+/// ```
+/// # use datasketches::common::MemoryUsage;
+/// fn total_memory<S: MemoryUsage>(sketches: &[S]) -> usize {
+///     sketches.iter().map(MemoryUsage::memory_usage).sum()
+/// }
+/// ```
+pub trait MemoryUsage {
+    /// Returns the estimated number of bytes used by this sketch, including owned heap
+    /// allocations.
+    fn memory_usage(&self) -> usize;
+}
@@ -17,10 +17,21 @@
 
 //! Data structures and functions that may be used across all the sketch families.
 
+mod bit_array;
+mod builder;
+mod cardinality_estimate;
+mod memory_usage;
 mod num_std_dev;
 mod resize;
+pub use self::bit_array::BitArray;
+pub use self::bit_array::BitArrayRef;
+pub use self::builder::SketchBuilder;
+pub use self::cardinality_estimate::CardinalityEstimate;
+pub use self::memory_usage::MemoryUsage;
 pub use self::num_std_dev::NumStdDev;
 pub use self::resize::ResizeFactor;
 
 #[cfg(any(feature = "cpc", feature = "hll"))]
 pub(crate) mod inv_pow2;
+#[cfg(feature = "hll")]
+pub(crate) mod strict_math;
@@ -20,6 +20,8 @@
 //! This module provides types for specifying confidence levels when computing
 //! upper and lower bounds for sketch estimates.
 
+use crate::error::Error;
+
 #[allow(clippy::excessive_precision)]
 static DELTA_OF_NUM_STD_DEVS: [f64; 4] = [
     0.5000000000000000000, // = 0.5 (1 + erf(0))
@@ -55,4 +57,41 @@ impl NumStdDev {
     pub const fn as_u8(&self) -> u8 {
         *self as u8
     }
+
+    /// Returns the number of standard deviations as an `f64`, for scaling a standard error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::common::NumStdDev;
+    /// let rse = 0.01;
+    /// assert_eq!(NumStdDev::Two.as_f64() * rse, 0.02);
+    /// ```
+    pub const fn as_f64(&self) -> f64 {
+        *self as u8 as f64
+    }
+}
+
+/// Converts a number of standard deviations in `[1, 3]`.
+///
+/// # Examples
+///
+/// ```
+/// # use datasketches::common::NumStdDev;
+/// assert_eq!(NumStdDev::try_from(2).unwrap(), NumStdDev::Two);
+/// assert!(NumStdDev::try_from(4).is_err());
+/// ```
+impl TryFrom<u8> for NumStdDev {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(NumStdDev::One),
+            2 => Ok(NumStdDev::Two),
+            3 => Ok(NumStdDev::Three),
+            _ => Err(Error::invalid_argument(format!(
+                "number of standard deviations must be in [1, 3], got {value}"
+            ))),
+        }
+    }
 }
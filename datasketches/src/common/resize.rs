@@ -64,6 +64,17 @@ impl ResizeFactor {
         }
     }
 
+    /// Returns the Resize Factor for the given Log-base 2, using only its low two bits.
+    #[cfg(feature = "theta")]
+    pub(crate) fn from_lg_value(lg_value: u8) -> Self {
+        match lg_value & 0x3 {
+            0 => ResizeFactor::X1,
+            1 => ResizeFactor::X2,
+            2 => ResizeFactor::X4,
+            _ => ResizeFactor::X8,
+        }
+    }
+
     /// Returns the Resize Factor.
     pub fn value(self) -> usize {
         // 1 << lg_value
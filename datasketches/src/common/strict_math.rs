@@ -0,0 +1,150 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Platform-independent floating point functions.
+//!
+//! The transcendental functions of the standard library call into the platform's libm, whose
+//! results may differ in the last bit between targets. The functions here are built only from
+//! IEEE 754 basic operations, which Rust evaluates with correct rounding and never contracts into
+//! fused multiply-adds, so they return bit-identical results on every target.
+
+const LN2_HI: f64 = f64::from_bits(0x3fe6_2e42_fee0_0000);
+const LN2_LO: f64 = f64::from_bits(0x3dea_39ef_3579_3c76);
+const TWO54: f64 = f64::from_bits(0x4350_0000_0000_0000);
+const LG1: f64 = f64::from_bits(0x3fe5_5555_5555_5593);
+const LG2: f64 = f64::from_bits(0x3fd9_9999_9997_fa04);
+const LG3: f64 = f64::from_bits(0x3fd2_4924_9422_9359);
+const LG4: f64 = f64::from_bits(0x3fcc_71c5_1d8e_78af);
+const LG5: f64 = f64::from_bits(0x3fc7_4664_96cb_03de);
+const LG6: f64 = f64::from_bits(0x3fc3_9a09_d078_c69f);
+const LG7: f64 = f64::from_bits(0x3fc2_f112_df3e_5244);
+
+/// Natural logarithm, ported from fdlibm's `__ieee754_log`.
+///
+/// This is the algorithm behind Java's `StrictMath.log`, with an error below 1 ulp.
+pub(crate) fn ln(x: f64) -> f64 {
+    let mut x = x;
+    let mut hx = (x.to_bits() >> 32) as u32 as i32;
+    let lx = x.to_bits() as u32;
+
+    let mut k: i32 = 0;
+    if hx < 0x0010_0000 {
+        // x < 2^-1022
+        if ((hx & 0x7fff_ffff) as u32 | lx) == 0 {
+            return f64::NEG_INFINITY; // ln(+-0) = -inf
+        }
+        if hx < 0 {
+            return f64::NAN; // ln(-x) = NaN
+        }
+        // subnormal, scale up x
+        k -= 54;
+        x *= TWO54;
+        hx = (x.to_bits() >> 32) as u32 as i32;
+    }
+    if hx >= 0x7ff0_0000 {
+        return x + x;
+    }
+    k += (hx >> 20) - 1023;
+    hx &= 0x000f_ffff;
+    let i = (hx + 0x95f64) & 0x0010_0000;
+    // normalize x or x/2
+    x = with_high_word(x, (hx | (i ^ 0x3ff0_0000)) as u32);
+    k += i >> 20;
+    let f = x - 1.0;
+
+    if (0x000f_ffff & (2 + hx)) < 3 {
+        // |f| < 2^-20
+        if f == 0.0 {
+            if k == 0 {
+                return 0.0;
+            }
+            let dk = k as f64;
+            return dk * LN2_HI + dk * LN2_LO;
+        }
+        let r = f * f * (0.5 - (1.0 / 3.0) * f);
+        if k == 0 {
+            return f - r;
+        }
+        let dk = k as f64;
+        return dk * LN2_HI - ((r - dk * LN2_LO) - f);
+    }
+
+    let s = f / (2.0 + f);
+    let dk = k as f64;
+    let z = s * s;
+    let w = z * z;
+    let t1 = w * (LG2 + w * (LG4 + w * LG6));
+    let t2 = z * (LG1 + w * (LG3 + w * (LG5 + w * LG7)));
+    let r = t2 + t1;
+    if ((hx - 0x6147a) | (0x6b851 - hx)) > 0 {
+        let hfsq = 0.5 * f * f;
+        if k == 0 {
+            f - (hfsq - s * (hfsq + r))
+        } else {
+            dk * LN2_HI - ((hfsq - (s * (hfsq + r) + dk * LN2_LO)) - f)
+        }
+    } else if k == 0 {
+        f - s * (f - r)
+    } else {
+        dk * LN2_HI - ((s * (f - r) - dk * LN2_LO) - f)
+    }
+}
+
+fn with_high_word(x: f64, hi: u32) -> f64 {
+    f64::from_bits(((hi as u64) << 32) | (x.to_bits() & 0xffff_ffff))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ln_special_values() {
+        assert_eq!(ln(1.0), 0.0);
+        assert_eq!(ln(2.0), std::f64::consts::LN_2);
+        assert_eq!(ln(std::f64::consts::E), 1.0);
+        assert_eq!(ln(0.0), f64::NEG_INFINITY);
+        assert_eq!(ln(f64::INFINITY), f64::INFINITY);
+        assert!(ln(-1.0).is_nan());
+        assert!(ln(f64::NAN).is_nan());
+        assert_eq!(ln(f64::MIN_POSITIVE / 4.0), (f64::MIN_POSITIVE / 4.0).ln());
+    }
+
+    #[test]
+    fn ln_within_one_ulp_of_std() {
+        let mut x = 1e-300;
+        while x < 1e300 {
+            for v in [x, x * 1.000_001, x * 1.5, x * 1.999_999] {
+                let diff = ln(v).to_bits().abs_diff(v.ln().to_bits());
+                assert!(diff <= 1, "ln({v}) = {}, std gives {}", ln(v), v.ln());
+            }
+            x *= 1.7;
+        }
+        for n in 1..=(1 << 12) {
+            let v = n as f64;
+            let diff = ln(v).to_bits().abs_diff(v.ln().to_bits());
+            assert!(diff <= 1, "ln({v}) = {}, std gives {}", ln(v), v.ln());
+        }
+    }
+
+    #[test]
+    fn ln_reference_bits() {
+        // Correctly rounded reference values, as returned by Java's StrictMath.log.
+        assert_eq!(ln(10.0).to_bits(), 0x4002_6bb1_bbb5_5516);
+        assert_eq!(ln(0.5).to_bits(), 0xbfe6_2e42_fefa_39ef);
+    }
+}
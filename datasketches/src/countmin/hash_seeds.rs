@@ -0,0 +1,186 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Per-row hash seeds, derived as datasketches-cpp derives them.
+//!
+//! The C++ sketch draws one value per row from `std::uniform_int_distribution<uint64_t>` over a
+//! `std::default_random_engine` seeded with the sketch seed, and adds the sketch seed to it. Both
+//! are implementation-defined in C++; this module reproduces libstdc++, where the engine is
+//! `minstd_rand0`, a multiplicative linear congruential generator.
+
+/// Modulus of `minstd_rand0`, `2^31 - 1`.
+const MODULUS: u64 = 0x7fff_ffff;
+/// Multiplier of `minstd_rand0`.
+const MULTIPLIER: u64 = 16_807;
+/// Smallest value `minstd_rand0` yields.
+const ENGINE_MIN: u64 = 1;
+/// Width of the range of values `minstd_rand0` yields, minus one.
+const ENGINE_RANGE: u64 = MODULUS - 1 - ENGINE_MIN;
+
+/// Returns the hash seeds of the `num_hashes` rows of a Count-Min sketch with the given seed.
+///
+/// Row `i` hashes items with MurmurHash3 seeded with the `i`-th seed. The seeds are derived as in
+/// datasketches-cpp built with libstdc++: `minstd_rand0` (`x = 16807 * x mod (2^31 - 1)`) is
+/// seeded with `seed`, 64-bit values are drawn from it the way libstdc++'s
+/// `std::uniform_int_distribution<uint64_t>` composes several draws, and `seed` is added to each
+/// value. Sketches with the same seed and shape therefore update the same buckets as the C++
+/// library, so their serialized counters can be merged across languages.
+///
+/// # Examples
+///
+/// ```
+/// # use datasketches::countmin::hash_seeds;
+/// let seeds = hash_seeds(9001, 3);
+/// assert_eq!(
+///     seeds,
+///     [0x3e2f341aa14fc70c, 0x5c069e43fce813ce, 0x9d8635c136501eb7]
+/// );
+/// // Rows share the seeds of the sketches with fewer rows.
+/// assert_eq!(hash_seeds(9001, 2), seeds[..2]);
+/// ```
+pub fn hash_seeds(seed: u64, num_hashes: u8) -> Vec<u64> {
+    let mut engine = MinStdRand0::new(seed);
+    (0..num_hashes)
+        .map(|_| engine.uniform(u64::MAX).wrapping_add(seed))
+        .collect()
+}
+
+/// The `minstd_rand0` engine of the C++ standard library.
+struct MinStdRand0 {
+    state: u64,
+}
+
+impl MinStdRand0 {
+    fn new(seed: u64) -> Self {
+        // A zero state would be a fixed point, so libstdc++ replaces it with 1.
+        let state = match seed % MODULUS {
+            0 => 1,
+            state => state,
+        };
+        Self { state }
+    }
+
+    fn next(&mut self) -> u64 {
+        self.state = MULTIPLIER * self.state % MODULUS;
+        self.state
+    }
+
+    /// Draws a value in `[0, range]` like libstdc++'s `uniform_int_distribution`.
+    fn uniform(&mut self, range: u64) -> u64 {
+        if ENGINE_RANGE > range {
+            // Downscaling: reject the draws past the largest multiple of the range.
+            let range = range + 1;
+            let scaling = ENGINE_RANGE / range;
+            let past = range * scaling;
+            loop {
+                let value = self.next() - ENGINE_MIN;
+                if value < past {
+                    return value / scaling;
+                }
+            }
+        } else if ENGINE_RANGE < range {
+            // Upscaling: combine a draw for the high part with one for the low part, retrying
+            // when the sum overflows the range.
+            loop {
+                let high =
+                    (ENGINE_RANGE + 1).wrapping_mul(self.uniform(range / (ENGINE_RANGE + 1)));
+                let value = high.wrapping_add(self.next() - ENGINE_MIN);
+                if value <= range && value >= high {
+                    return value;
+                }
+            }
+        } else {
+            self.next() - ENGINE_MIN
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Seeds printed by datasketches-cpp's derivation compiled with g++ 12 (libstdc++).
+    #[test]
+    fn test_hash_seeds_match_libstdcxx() {
+        let cases: [(u64, [u64; 5]); 6] = [
+            (
+                9001,
+                [
+                    0x3e2f341aa14fc70c,
+                    0x5c069e43fce813ce,
+                    0x9d8635c136501eb7,
+                    0x39bda2e83b2ef264,
+                    0x8bbf8f2feba771ec,
+                ],
+            ),
+            (
+                0,
+                [
+                    0x086b1d783f0b36f8,
+                    0xa218dbbc13a36bcd,
+                    0x2b7284fe29291b48,
+                    0xb52e8940afb2b6d3,
+                    0x21e69ba2ce4dca8a,
+                ],
+            ),
+            (
+                1,
+                [
+                    0x086b1d783f0b36f9,
+                    0xa218dbbc13a36bce,
+                    0x2b7284fe29291b49,
+                    0xb52e8940afb2b6d4,
+                    0x21e69ba2ce4dca8b,
+                ],
+            ),
+            (
+                42,
+                [
+                    0x2192d5c6d7d704c6,
+                    0x58140db238cfae34,
+                    0x90745a5fcbe39fa1,
+                    0x8fb472ab60d9472f,
+                    0x14b156643a78bd97,
+                ],
+            ),
+            (
+                MODULUS,
+                [
+                    0x086b1d78bf0b36f7,
+                    0xa218dbbc93a36bcc,
+                    0x2b7284fea9291b47,
+                    0xb52e89412fb2b6d2,
+                    0x21e69ba34e4dca89,
+                ],
+            ),
+            (
+                u64::MAX,
+                [
+                    0x19415868bd21a4e7,
+                    0x664a9340baea434e,
+                    0x02578efd7b7b51d3,
+                    0x9f8b9bce0f182462,
+                    0x25b3d2e9eae95f9b,
+                ],
+            ),
+        ];
+        for (seed, expected) in cases {
+            assert_eq!(hash_seeds(seed, 5), expected, "seed {seed}");
+        }
+        assert!(hash_seeds(9001, 0).is_empty());
+    }
+}
@@ -34,16 +34,20 @@
 //!
 //! ```
 //! # use datasketches::countmin::CountMinSketch;
-//! let buckets = CountMinSketch::<i64>::suggest_num_buckets(0.01);
-//! let hashes = CountMinSketch::<i64>::suggest_num_hashes(0.99);
+//! let buckets = CountMinSketch::<i64>::suggest_num_buckets(0.01).unwrap();
+//! let hashes = CountMinSketch::<i64>::suggest_num_hashes(0.99).unwrap();
 //! let sketch = CountMinSketch::<i64>::new(hashes, buckets);
 //! assert_eq!(sketch.estimate("apple"), 0);
 //! ```
 
-mod serialization;
+mod hash_seeds;
+pub use self::hash_seeds::hash_seeds;
+
+pub(crate) mod serialization;
 
 mod sketch;
 pub use self::sketch::CountMinSketch;
+pub use self::sketch::CountMinSketchBuilder;
 
 mod value;
 pub use self::value::CountMinValue;
@@ -15,7 +15,9 @@
 // specific language governing permissions and limitations
 // under the License.
 
-pub(super) const PREAMBLE_LONGS_SHORT: u8 = 2;
-pub(super) const SERIAL_VERSION: u8 = 1;
-pub(super) const FLAGS_IS_EMPTY: u8 = 1 << 0;
+use crate::codec::format;
+
+pub(super) const PREAMBLE_LONGS_SHORT: u8 = format::countmin::PREAMBLE_LONGS;
+pub(super) const SERIAL_VERSION: u8 = format::countmin::SERIAL_VERSION;
+pub(crate) const FLAGS_IS_EMPTY: u8 = format::countmin::FLAGS_IS_EMPTY;
 pub(super) const LONG_SIZE_BYTES: usize = 8;
@@ -16,16 +16,23 @@
 // under the License.
 
 use std::hash::Hash;
-use std::hash::Hasher;
+use std::marker::PhantomData;
+
+#[cfg(feature = "rayon")]
+use rayon::iter::IntoParallelIterator;
+#[cfg(feature = "rayon")]
+use rayon::iter::ParallelIterator;
 
 use crate::codec::SketchBytes;
 use crate::codec::SketchSlice;
 use crate::codec::assert::ensure_preamble_longs_in;
-use crate::codec::assert::ensure_serial_version_is;
 use crate::codec::assert::insufficient_data;
 use crate::codec::family::Family;
+use crate::common::MemoryUsage;
+use crate::common::SketchBuilder;
 use crate::countmin::CountMinValue;
 use crate::countmin::UnsignedCountMinValue;
+use crate::countmin::hash_seeds;
 use crate::countmin::serialization::FLAGS_IS_EMPTY;
 use crate::countmin::serialization::LONG_SIZE_BYTES;
 use crate::countmin::serialization::PREAMBLE_LONGS_SHORT;
@@ -34,8 +41,11 @@ use crate::error::Error;
 use crate::hash::DEFAULT_UPDATE_SEED;
 use crate::hash::MurmurHash3X64128;
 use crate::hash::compute_seed_hash;
+use crate::hash::named_seed_hash;
 
 const MAX_TABLE_ENTRIES: usize = 1 << 30;
+/// Number of pairs that `Extend` applies to one row of counters before moving to the next row.
+const EXTEND_BATCH_SIZE: usize = 256;
 
 /// Count-Min sketch for estimating item frequencies.
 ///
@@ -53,6 +63,32 @@ pub struct CountMinSketch<T: CountMinValue> {
 }
 
 impl<T: CountMinValue> CountMinSketch<T> {
+    /// Returns a builder with the default seed.
+    ///
+    /// The number of hashes and the number of buckets have no defaults and must be set, for
+    /// example from [`suggest_num_hashes`](Self::suggest_num_hashes) and
+    /// [`suggest_num_buckets`](Self::suggest_num_buckets).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::countmin::CountMinSketch;
+    /// let sketch = CountMinSketch::<i64>::builder()
+    ///     .num_hashes(CountMinSketch::<i64>::suggest_num_hashes(0.95).unwrap())
+    ///     .num_buckets(CountMinSketch::<i64>::suggest_num_buckets(0.01).unwrap())
+    ///     .build();
+    /// assert_eq!(sketch.num_hashes(), 3);
+    /// assert_eq!(sketch.num_buckets(), 272);
+    /// ```
+    pub fn builder() -> CountMinSketchBuilder<T> {
+        CountMinSketchBuilder {
+            num_hashes: None,
+            num_buckets: None,
+            seed: DEFAULT_UPDATE_SEED,
+            _value: PhantomData,
+        }
+    }
+
     /// Creates a new Count-Min sketch with the default seed.
     ///
     /// # Panics
@@ -113,6 +149,44 @@ impl<T: CountMinValue> CountMinSketch<T> {
         self.total_weight
     }
 
+    /// Returns the counter table in row-major order.
+    ///
+    /// The table has [`num_hashes`](Self::num_hashes) rows of [`num_buckets`](Self::num_buckets)
+    /// counters each, so the counter of bucket `b` in row `r` is at `r * num_buckets + b`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::countmin::CountMinSketch;
+    /// let mut sketch = CountMinSketch::<i64>::new(4, 128);
+    /// sketch.update_with_weight("apple", 3);
+    ///
+    /// let counts = sketch.counts();
+    /// assert_eq!(counts.len(), 4 * 128);
+    /// assert_eq!(counts.iter().sum::<i64>(), 4 * 3);
+    /// ```
+    pub fn counts(&self) -> &[T] {
+        &self.counts
+    }
+
+    /// Returns an iterator over the rows of the counter table, one per hash function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::countmin::CountMinSketch;
+    /// let mut sketch = CountMinSketch::<i64>::new(4, 128);
+    /// sketch.update_with_weight("apple", 3);
+    ///
+    /// for row in sketch.rows() {
+    ///     assert_eq!(row.len(), 128);
+    ///     assert_eq!(row.iter().sum::<i64>(), 3);
+    /// }
+    /// ```
+    pub fn rows(&self) -> impl ExactSizeIterator<Item = &[T]> + '_ {
+        self.counts.chunks_exact(self.num_buckets as usize)
+    }
+
     /// Returns the relative error (epsilon) implied by the number of buckets.
     pub fn relative_error(&self) -> f64 {
         std::f64::consts::E / self.num_buckets as f64
@@ -125,33 +199,91 @@ impl<T: CountMinValue> CountMinSketch<T> {
 
     /// Suggests the number of buckets to achieve the given relative error.
     ///
-    /// # Panics
+    /// # Errors
     ///
-    /// Panics if `relative_error` is negative.
-    pub fn suggest_num_buckets(relative_error: f64) -> u32 {
-        assert!(relative_error >= 0.0, "relative_error must be at least 0");
-        (std::f64::consts::E / relative_error).ceil() as u32
+    /// Returns an [`InvalidArgument`](crate::error::ErrorKind::InvalidArgument) error if
+    /// `relative_error` is not a positive finite number, or if the suggested number of buckets
+    /// is less than 3 or does not fit in a `u32`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::countmin::CountMinSketch;
+    /// assert_eq!(
+    ///     CountMinSketch::<i64>::suggest_num_buckets(0.01).unwrap(),
+    ///     272
+    /// );
+    /// assert!(CountMinSketch::<i64>::suggest_num_buckets(0.0).is_err());
+    /// ```
+    pub fn suggest_num_buckets(relative_error: f64) -> Result<u32, Error> {
+        if !(relative_error > 0.0 && relative_error.is_finite()) {
+            return Err(Error::invalid_argument(format!(
+                "relative_error must be a positive finite number, got {relative_error}"
+            )));
+        }
+        let buckets = (std::f64::consts::E / relative_error).ceil();
+        if !(3.0..=u32::MAX as f64).contains(&buckets) {
+            return Err(Error::invalid_argument(format!(
+                "relative_error {relative_error} needs {buckets} buckets, which is outside [3, {}]",
+                u32::MAX
+            )));
+        }
+        Ok(buckets as u32)
     }
 
     /// Suggests the number of hashes to achieve the given confidence.
     ///
-    /// # Panics
+    /// # Errors
+    ///
+    /// Returns an [`InvalidArgument`](crate::error::ErrorKind::InvalidArgument) error if
+    /// `confidence` is not in `(0, 1)`.
+    ///
+    /// # Examples
     ///
-    /// Panics if `confidence` is not in `[0, 1]`.
-    pub fn suggest_num_hashes(confidence: f64) -> u8 {
-        assert!(
-            (0.0..=1.0).contains(&confidence),
-            "confidence must be between 0 and 1.0 (inclusive)"
-        );
-        if confidence == 1.0 {
-            return 127;
+    /// ```
+    /// # use datasketches::countmin::CountMinSketch;
+    /// assert_eq!(CountMinSketch::<i64>::suggest_num_hashes(0.95).unwrap(), 3);
+    /// assert!(CountMinSketch::<i64>::suggest_num_hashes(1.0).is_err());
+    /// ```
+    pub fn suggest_num_hashes(confidence: f64) -> Result<u8, Error> {
+        if !(confidence > 0.0 && confidence < 1.0) {
+            return Err(Error::invalid_argument(format!(
+                "confidence must be in (0, 1), got {confidence}"
+            )));
         }
+        // Confidences below 1 give at most ln(2^53) < 37 hashes, and confidences too small to
+        // change `1 - confidence` still need one.
         let hashes = (1.0 / (1.0 - confidence)).ln().ceil();
-        hashes.min(127.0) as u8
+        Ok((hashes as u8).max(1))
+    }
+
+    /// Returns the in-memory size in bytes of a sketch with the given configuration, as
+    /// [`estimated_size`](Self::estimated_size) would report it once built.
+    ///
+    /// This lets planners size a sketch before allocating it. The configuration is not
+    /// validated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::countmin::CountMinSketch;
+    /// let planned = CountMinSketch::<i64>::estimated_memory_bytes(4, 128);
+    /// assert_eq!(planned, CountMinSketch::<i64>::new(4, 128).estimated_size());
+    /// ```
+    pub fn estimated_memory_bytes(num_hashes: u8, num_buckets: u32) -> usize {
+        let counts = (num_hashes as usize)
+            .saturating_mul(num_buckets as usize)
+            .saturating_mul(size_of::<T>());
+        size_of::<Self>()
+            .saturating_add(counts)
+            .saturating_add(num_hashes as usize * size_of::<u64>())
     }
 
     /// Updates the sketch with a single occurrence of the item.
     ///
+    /// Wrap items with [`canonical::from_value`](crate::hash_value::canonical::from_value) to
+    /// hash them the way other datasketches implementations do.
+    ///
     /// # Examples
     ///
     /// ```
@@ -258,6 +390,13 @@ impl<T: CountMinValue> CountMinSketch<T> {
         self.total_weight = self.total_weight + other.total_weight;
     }
 
+    /// Returns the estimated size of the sketch in bytes.
+    pub fn estimated_size(&self) -> usize {
+        size_of::<Self>()
+            + self.counts.capacity() * size_of::<T>()
+            + self.hash_seeds.capacity() * size_of::<u64>()
+    }
+
     /// Serializes this sketch into the DataSketches Count-Min format.
     ///
     /// # Examples
@@ -271,6 +410,39 @@ impl<T: CountMinValue> CountMinSketch<T> {
     /// assert!(decoded.estimate("apple") >= 1);
     /// ```
     pub fn serialize(&self) -> Vec<u8> {
+        let mut bytes = SketchBytes::with_capacity(0);
+        self.write_to(&mut bytes);
+        bytes.into_bytes()
+    }
+
+    /// Serializes this sketch into `buf`, returning the number of bytes written.
+    ///
+    /// Writes the same bytes as [`serialize`](Self::serialize) without allocating.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `buf` is too short to hold the serialized sketch. The sketch is
+    /// written up to the end of `buf` before the length is checked, so the contents of `buf`
+    /// are unspecified when an error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::countmin::CountMinSketch;
+    /// # let mut sketch = CountMinSketch::<i64>::new(4, 128);
+    /// # sketch.update("apple");
+    /// let mut buf = vec![0u8; 8192];
+    /// let len = sketch.serialize_into_slice(&mut buf).unwrap();
+    /// let decoded = CountMinSketch::<i64>::deserialize(&buf[..len]).unwrap();
+    /// assert!(decoded.estimate("apple") >= 1);
+    /// ```
+    pub fn serialize_into_slice(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let mut bytes = SketchBytes::from_slice(buf);
+        self.write_to(&mut bytes);
+        bytes.finish()
+    }
+
+    fn write_to(&self, bytes: &mut SketchBytes<'_>) {
         let header_size = PREAMBLE_LONGS_SHORT as usize * LONG_SIZE_BYTES;
         let value_size = LONG_SIZE_BYTES;
         let payload_size = if self.is_empty() {
@@ -278,7 +450,7 @@ impl<T: CountMinValue> CountMinSketch<T> {
         } else {
             value_size + (self.counts.len() * value_size)
         };
-        let mut bytes = SketchBytes::with_capacity(header_size + payload_size);
+        bytes.reserve(header_size + payload_size);
 
         bytes.write_u8(PREAMBLE_LONGS_SHORT);
         bytes.write_u8(SERIAL_VERSION);
@@ -293,14 +465,13 @@ impl<T: CountMinValue> CountMinSketch<T> {
         bytes.write_u8(0);
 
         if self.is_empty() {
-            return bytes.into_bytes();
+            return;
         }
 
         bytes.write(&self.total_weight.to_bytes());
         for count in &self.counts {
             bytes.write(&count.to_bytes());
         }
-        bytes.into_bytes()
     }
 
     /// Deserializes a sketch from bytes using the default seed.
@@ -355,7 +526,7 @@ impl<T: CountMinValue> CountMinSketch<T> {
             .map_err(insufficient_data("<unused>"))?;
 
         Family::COUNTMIN.validate_id(family_id)?;
-        ensure_serial_version_is(SERIAL_VERSION, serial_version)?;
+        Family::COUNTMIN.validate_serial_version(serial_version)?;
         ensure_preamble_longs_in(&[PREAMBLE_LONGS_SHORT], preamble_longs)?;
 
         let num_buckets = cursor
@@ -370,11 +541,13 @@ impl<T: CountMinValue> CountMinSketch<T> {
         let expected_seed_hash = compute_seed_hash(seed);
         if seed_hash != expected_seed_hash {
             return Err(Error::deserial(format!(
-                "incompatible seed hash: expected {expected_seed_hash}, got {seed_hash}",
+                "incompatible seed hash: expected {}, got {}",
+                named_seed_hash(expected_seed_hash),
+                named_seed_hash(seed_hash)
             )));
         }
 
-        let entries = entries_for_config_checked(num_hashes, num_buckets)?;
+        let entries = entries_for_config_checked(num_hashes, num_buckets, Error::deserial)?;
         let mut sketch = Self::make(num_hashes, num_buckets, seed, entries);
         if (flags & FLAGS_IS_EMPTY) != 0 {
             return Ok(sketch);
@@ -390,7 +563,7 @@ impl<T: CountMinValue> CountMinSketch<T> {
     fn make(num_hashes: u8, num_buckets: u32, seed: u64, entries: usize) -> Self {
         let counts = vec![T::ZERO; entries];
         let seed_hash = compute_seed_hash(seed);
-        let hash_seeds = make_hash_seeds(seed, num_hashes);
+        let hash_seeds = hash_seeds(seed, num_hashes);
         CountMinSketch {
             num_hashes,
             num_buckets,
@@ -410,6 +583,170 @@ impl<T: CountMinValue> CountMinSketch<T> {
     }
 }
 
+/// Updates the sketch with every `(item, weight)` pair of the iterator.
+///
+/// The result is the same as calling [`update_with_weight`](CountMinSketch::update_with_weight)
+/// for each pair. The pairs are applied in batches: each batch updates one row of counters at a
+/// time, which keeps memory accesses local in wide sketches, and adds its weight to the total
+/// once.
+///
+/// # Examples
+///
+/// ```
+/// # use datasketches::countmin::CountMinSketch;
+/// let mut sketch = CountMinSketch::<i64>::new(4, 128);
+/// sketch.extend([("apple", 3), ("banana", 1), ("apple", 2)]);
+/// assert!(sketch.estimate("apple") >= 5);
+/// assert_eq!(sketch.total_weight(), 6);
+/// ```
+impl<I: Hash, T: CountMinValue> Extend<(I, T)> for CountMinSketch<T> {
+    fn extend<It: IntoIterator<Item = (I, T)>>(&mut self, iter: It) {
+        let num_buckets = self.num_buckets as usize;
+        let mut iter = iter.into_iter().filter(|(_, weight)| *weight != T::ZERO);
+        let mut batch = Vec::with_capacity(EXTEND_BATCH_SIZE);
+        loop {
+            batch.clear();
+            batch.extend(iter.by_ref().take(EXTEND_BATCH_SIZE));
+            if batch.is_empty() {
+                break;
+            }
+            for (row, &seed) in self.hash_seeds.iter().enumerate() {
+                for (item, weight) in &batch {
+                    let index = row * num_buckets + self.bucket_index(item, seed);
+                    self.counts[index] = self.counts[index] + *weight;
+                }
+            }
+            for (_, weight) in &batch {
+                self.total_weight = self.total_weight + weight.abs();
+            }
+        }
+    }
+}
+
+/// Builder for [`CountMinSketch`].
+///
+/// Created by [`CountMinSketch::builder`]. The parameters are validated when the sketch is built.
+#[derive(Debug, Clone)]
+pub struct CountMinSketchBuilder<T: CountMinValue> {
+    num_hashes: Option<u8>,
+    num_buckets: Option<u32>,
+    seed: u64,
+    _value: PhantomData<T>,
+}
+
+impl<T: CountMinValue> CountMinSketchBuilder<T> {
+    /// Set the number of hash functions, which is the number of rows of the counter table.
+    pub fn num_hashes(mut self, num_hashes: u8) -> Self {
+        self.num_hashes = Some(num_hashes);
+        self
+    }
+
+    /// Set the number of buckets per hash function.
+    pub fn num_buckets(mut self, num_buckets: u32) -> Self {
+        self.num_buckets = Some(num_buckets);
+        self
+    }
+
+    /// Set the hash seed.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Build the CountMinSketch.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of hashes or buckets is not set, or if the configuration is rejected
+    /// by [`CountMinSketch::with_seed`].
+    pub fn build(self) -> CountMinSketch<T> {
+        let num_hashes = self.num_hashes.expect("num_hashes must be set");
+        let num_buckets = self.num_buckets.expect("num_buckets must be set");
+        CountMinSketch::with_seed(num_hashes, num_buckets, self.seed)
+    }
+}
+
+impl<T: CountMinValue> SketchBuilder for CountMinSketchBuilder<T> {
+    type Sketch = CountMinSketch<T>;
+
+    fn try_build(self) -> Result<CountMinSketch<T>, Error> {
+        let num_hashes = self
+            .num_hashes
+            .ok_or_else(|| Error::invalid_argument("num_hashes must be set"))?;
+        let num_buckets = self
+            .num_buckets
+            .ok_or_else(|| Error::invalid_argument("num_buckets must be set"))?;
+        let entries = entries_for_config_checked(num_hashes, num_buckets, Error::invalid_argument)?;
+        Ok(CountMinSketch::make(
+            num_hashes,
+            num_buckets,
+            self.seed,
+            entries,
+        ))
+    }
+}
+
+impl<T: CountMinValue> MemoryUsage for CountMinSketch<T> {
+    fn memory_usage(&self) -> usize {
+        self.estimated_size()
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<T: CountMinValue + Send + Sync> CountMinSketch<T> {
+    /// Builds a sketch configured by `builder` from the `(item, weight)` pairs of a parallel
+    /// iterator.
+    ///
+    /// Every rayon job updates a partial sketch of the same configuration, and the partial
+    /// sketches are merged (elementwise addition of the counters) as the jobs complete. The result
+    /// is identical to calling [`update_with_weight`](Self::update_with_weight) for every pair on
+    /// a single sketch.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as [`CountMinSketchBuilder::build`], before any item is
+    /// consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::countmin::CountMinSketch;
+    /// use rayon::prelude::*;
+    ///
+    /// let builder = CountMinSketch::<u64>::builder()
+    ///     .num_hashes(4)
+    ///     .num_buckets(128)
+    ///     .seed(9001);
+    /// let items = (0..10_000_u64).into_par_iter().map(|i| (i % 100, 1));
+    /// let sketch = CountMinSketch::from_par_iter(builder, items);
+    /// assert!(sketch.estimate(42_u64) >= 100);
+    /// assert_eq!(sketch.total_weight(), 10_000);
+    /// ```
+    pub fn from_par_iter<I, It>(builder: CountMinSketchBuilder<T>, items: It) -> Self
+    where
+        I: Hash,
+        It: IntoParallelIterator<Item = (I, T)>,
+    {
+        let template = builder.build();
+        items
+            .into_par_iter()
+            .fold(
+                || template.clone(),
+                |mut sketch, (item, weight)| {
+                    sketch.update_with_weight(item, weight);
+                    sketch
+                },
+            )
+            .reduce(
+                || template.clone(),
+                |mut sketch, other| {
+                    sketch.merge(&other);
+                    sketch
+                },
+            )
+    }
+}
+
 impl<T: UnsignedCountMinValue> CountMinSketch<T> {
     /// Divides every counter by two, truncating toward zero.
     ///
@@ -472,32 +809,25 @@ fn entries_for_config(num_hashes: u8, num_buckets: u32) -> usize {
     entries
 }
 
-fn entries_for_config_checked(num_hashes: u8, num_buckets: u32) -> Result<usize, Error> {
+/// Like [`entries_for_config`], but reports an invalid configuration with `error`.
+fn entries_for_config_checked(
+    num_hashes: u8,
+    num_buckets: u32,
+    error: fn(String) -> Error,
+) -> Result<usize, Error> {
     if num_hashes == 0 {
-        return Err(Error::deserial("num_hashes must be at least 1"));
+        return Err(error("num_hashes must be at least 1".to_string()));
     }
     if num_buckets < 3 {
-        return Err(Error::deserial("num_buckets must be at least 3"));
+        return Err(error("num_buckets must be at least 3".to_string()));
     }
     let entries = (num_hashes as usize)
         .checked_mul(num_buckets as usize)
-        .ok_or_else(|| Error::deserial("num_hashes * num_buckets overflows usize"))?;
+        .ok_or_else(|| error("num_hashes * num_buckets overflows usize".to_string()))?;
     if entries >= MAX_TABLE_ENTRIES {
-        return Err(Error::deserial(format!(
+        return Err(error(format!(
             "num_hashes * num_buckets must be < {MAX_TABLE_ENTRIES}",
         )));
     }
     Ok(entries)
 }
-
-fn make_hash_seeds(seed: u64, num_hashes: u8) -> Vec<u64> {
-    let mut seeds = Vec::with_capacity(num_hashes as usize);
-    for i in 0..num_hashes {
-        // Derive per-row hash seeds deterministically from the sketch seed.
-        let mut hasher = MurmurHash3X64128::with_seed(seed);
-        hasher.write(&u64::from(i).to_le_bytes());
-        let (h1, _) = hasher.finish128();
-        seeds.push(h1);
-    }
-    seeds
-}
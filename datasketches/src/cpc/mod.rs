@@ -46,6 +46,7 @@ mod union;
 mod wrapper;
 
 pub use self::sketch::CpcSketch;
+pub use self::sketch::CpcSketchBuilder;
 pub use self::union::CpcUnion;
 pub use self::wrapper::CpcWrapper;
 
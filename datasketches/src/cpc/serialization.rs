@@ -15,7 +15,9 @@
 // specific language governing permissions and limitations
 // under the License.
 
-pub(super) const SERIAL_VERSION: u8 = 1;
+use crate::codec::format;
+
+pub(super) const SERIAL_VERSION: u8 = format::cpc::SERIAL_VERSION;
 pub(super) const FLAG_COMPRESSED: u8 = 1;
 pub(super) const FLAG_HAS_HIP: u8 = 2;
 pub(super) const FLAG_HAS_TABLE: u8 = 3;
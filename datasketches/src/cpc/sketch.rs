@@ -20,10 +20,11 @@ use std::hash::Hash;
 use crate::codec::SketchBytes;
 use crate::codec::SketchSlice;
 use crate::codec::assert::ensure_preamble_longs_in;
-use crate::codec::assert::ensure_serial_version_is;
 use crate::codec::assert::insufficient_data;
 use crate::codec::family::Family;
+use crate::common::MemoryUsage;
 use crate::common::NumStdDev;
+use crate::common::SketchBuilder;
 use crate::common::inv_pow2::inv_pow2;
 use crate::cpc::DEFAULT_LG_K;
 use crate::cpc::Flavor;
@@ -49,6 +50,7 @@ use crate::error::ErrorKind;
 use crate::hash::DEFAULT_UPDATE_SEED;
 use crate::hash::MurmurHash3X64128;
 use crate::hash::compute_seed_hash;
+use crate::hash::named_seed_hash;
 
 /// A Compressed Probabilistic Counting sketch.
 ///
@@ -92,6 +94,19 @@ impl Default for CpcSketch {
 }
 
 impl CpcSketch {
+    /// Returns a builder with lg_k 11 and the default seed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::cpc::CpcSketch;
+    /// let sketch = CpcSketch::builder().lg_k(10).seed(7).build();
+    /// assert_eq!(sketch.lg_k(), 10);
+    /// ```
+    pub fn builder() -> CpcSketchBuilder {
+        CpcSketchBuilder::default()
+    }
+
     /// Creates a new `CpcSketch` with the given `lg_k` and default seed.
     ///
     /// # Panics
@@ -464,11 +479,93 @@ impl CpcSketch {
     }
 }
 
+/// Builder for [`CpcSketch`].
+///
+/// Created by [`CpcSketch::builder`]. The parameters are validated when the sketch is built.
+#[derive(Debug, Clone)]
+pub struct CpcSketchBuilder {
+    lg_k: u8,
+    seed: u64,
+}
+
+impl Default for CpcSketchBuilder {
+    fn default() -> Self {
+        Self {
+            lg_k: DEFAULT_LG_K,
+            seed: DEFAULT_UPDATE_SEED,
+        }
+    }
+}
+
+impl CpcSketchBuilder {
+    /// Set lg_k, the log2 of the nominal size k. Must be in `[4, 26]`.
+    pub fn lg_k(mut self, lg_k: u8) -> Self {
+        self.lg_k = lg_k;
+        self
+    }
+
+    /// Set the hash seed.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Build the CpcSketch.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lg_k` is not in the range `[4, 26]`, or the computed seed hash is zero.
+    pub fn build(self) -> CpcSketch {
+        CpcSketch::with_seed(self.lg_k, self.seed)
+    }
+}
+
+impl SketchBuilder for CpcSketchBuilder {
+    type Sketch = CpcSketch;
+
+    fn try_build(self) -> Result<CpcSketch, Error> {
+        if !(MIN_LG_K..=MAX_LG_K).contains(&self.lg_k) {
+            return Err(Error::invalid_argument(format!(
+                "lg_k must be in [{MIN_LG_K}, {MAX_LG_K}], got {}",
+                self.lg_k
+            )));
+        }
+        Ok(self.build())
+    }
+}
+
+impl MemoryUsage for CpcSketch {
+    fn memory_usage(&self) -> usize {
+        self.estimated_size()
+    }
+}
+
 impl CpcSketch {
     /// Serializes this CpcSketch to bytes.
     pub fn serialize(&self) -> Vec<u8> {
         let mut bytes = SketchBytes::with_capacity(256);
+        self.write_to(&mut bytes);
+        bytes.into_bytes()
+    }
 
+    /// Serializes this CpcSketch into `buf`, returning the number of bytes written.
+    ///
+    /// Writes the same bytes as [`serialize`](Self::serialize) without allocating the output.
+    /// [`max_serialized_bytes`](Self::max_serialized_bytes) gives a buffer size that fits almost
+    /// every sketch of a given lg_k.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `buf` is too short to hold the serialized sketch. The sketch is
+    /// written up to the end of `buf` before the length is checked, so the contents of `buf`
+    /// are unspecified when an error is returned.
+    pub fn serialize_into_slice(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let mut bytes = SketchBytes::from_slice(buf);
+        self.write_to(&mut bytes);
+        bytes.finish()
+    }
+
+    fn write_to(&self, bytes: &mut SketchBytes<'_>) {
         let mut compressed = CompressedState::default();
         compressed.compress(self);
 
@@ -496,7 +593,7 @@ impl CpcSketch {
                 // HIP values can be in two different places in the sequence of fields
                 // this is the first HIP decision point
                 if has_hip {
-                    self.write_hip(&mut bytes);
+                    self.write_hip(bytes);
                 }
             }
             if has_table {
@@ -509,7 +606,7 @@ impl CpcSketch {
             }
             // this is the second HIP decision point
             if has_hip && !(has_table && has_window) {
-                self.write_hip(&mut bytes);
+                self.write_hip(bytes);
             }
             if has_window {
                 for i in 0..compressed.window_data_words {
@@ -522,7 +619,6 @@ impl CpcSketch {
                 }
             }
         }
-        bytes.into_bytes()
     }
 
     /// Deserializes a CpcSketch from bytes.
@@ -541,7 +637,7 @@ impl CpcSketch {
             .map_err(insufficient_data("serial_version"))?;
         let family_id = cursor.read_u8().map_err(insufficient_data("family_id"))?;
         Family::CPC.validate_id(family_id)?;
-        ensure_serial_version_is(SERIAL_VERSION, serial_version)?;
+        Family::CPC.validate_serial_version(serial_version)?;
 
         let lg_k = cursor.read_u8().map_err(insufficient_data("lg_k"))?;
         let first_interesting_column = cursor
@@ -630,8 +726,8 @@ impl CpcSketch {
                 ErrorKind::InvalidData,
                 format!(
                     "seed hash mismatch: expected {}, got {}",
-                    compute_seed_hash(seed),
-                    seed_hash
+                    named_seed_hash(compute_seed_hash(seed)),
+                    named_seed_hash(seed_hash)
                 ),
             ));
         }
@@ -664,7 +760,7 @@ impl CpcSketch {
         })
     }
 
-    fn write_hip(&self, bytes: &mut SketchBytes) {
+    fn write_hip(&self, bytes: &mut SketchBytes<'_>) {
         bytes.write_f64_le(self.kxp);
         bytes.write_f64_le(self.hip_est_accum);
     }
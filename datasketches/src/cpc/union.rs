@@ -61,6 +61,7 @@
 //! which requires doing some extra work to figure out the values of num_coupons, offset,
 //! first_interesting_column, and kxp.
 
+use crate::common::MemoryUsage;
 use crate::cpc::CpcSketch;
 use crate::cpc::DEFAULT_LG_K;
 use crate::cpc::Flavor;
@@ -337,6 +338,27 @@ impl CpcUnion {
     }
 }
 
+impl CpcUnion {
+    /// Returns the estimated size of the union in bytes.
+    pub fn estimated_size(&self) -> usize {
+        match &self.state {
+            // The accumulator is stored inline, so only its heap allocations are added on top.
+            UnionState::Accumulator(sketch) => {
+                size_of::<Self>() - size_of::<CpcSketch>() + sketch.estimated_size()
+            }
+            UnionState::BitMatrix(matrix) => {
+                size_of::<Self>() + matrix.capacity() * size_of::<u64>()
+            }
+        }
+    }
+}
+
+impl MemoryUsage for CpcUnion {
+    fn memory_usage(&self) -> usize {
+        self.estimated_size()
+    }
+}
+
 // testing methods
 impl CpcUnion {
     /// Returns the number of coupons in the union.
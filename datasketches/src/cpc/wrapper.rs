@@ -17,7 +17,6 @@
 
 use crate::codec::SketchSlice;
 use crate::codec::assert::ensure_preamble_longs_in;
-use crate::codec::assert::ensure_serial_version_is;
 use crate::codec::assert::insufficient_data;
 use crate::codec::family::Family;
 use crate::common::NumStdDev;
@@ -30,7 +29,6 @@ use crate::cpc::serialization::FLAG_COMPRESSED;
 use crate::cpc::serialization::FLAG_HAS_HIP;
 use crate::cpc::serialization::FLAG_HAS_TABLE;
 use crate::cpc::serialization::FLAG_HAS_WINDOW;
-use crate::cpc::serialization::SERIAL_VERSION;
 use crate::cpc::serialization::make_preamble_ints;
 use crate::error::Error;
 use crate::error::ErrorKind;
@@ -56,7 +54,7 @@ impl CpcWrapper {
             .map_err(insufficient_data("serial_version"))?;
         let family_id = cursor.read_u8().map_err(insufficient_data("family_id"))?;
         Family::CPC.validate_id(family_id)?;
-        ensure_serial_version_is(SERIAL_VERSION, serial_version)?;
+        Family::CPC.validate_serial_version(serial_version)?;
 
         let lg_k = cursor.read_u8().map_err(insufficient_data("lg_k"))?;
         let first_interesting_column = cursor
@@ -179,4 +177,9 @@ impl CpcWrapper {
     pub fn is_empty(&self) -> bool {
         self.num_coupons == 0
     }
+
+    /// Returns the number of coupons in the sketch.
+    pub fn num_coupons(&self) -> u32 {
+        self.num_coupons
+    }
 }
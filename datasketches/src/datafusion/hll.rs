@@ -0,0 +1,273 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::any::Any;
+use std::hash::Hash;
+
+use datafusion_common::Result;
+use datafusion_common::ScalarValue;
+use datafusion_common::arrow::array::ArrayRef;
+use datafusion_common::arrow::datatypes::DataType;
+use datafusion_common::arrow::datatypes::FieldRef;
+use datafusion_expr::Accumulator;
+use datafusion_expr::AggregateUDFImpl;
+use datafusion_expr::Signature;
+use datafusion_expr::Volatility;
+use datafusion_expr::function::AccumulatorArgs;
+use datafusion_expr::function::StateFieldsArgs;
+
+use super::common::SKETCH_TYPES;
+use super::common::external;
+use super::common::for_each_sketch;
+use super::common::sketch_state_fields;
+use super::values::UpdateSketch;
+use super::values::VALUE_TYPES;
+use super::values::update_from_array;
+use crate::hll::DEFAULT_LG_K;
+use crate::hll::HllSketch;
+use crate::hll::HllType;
+use crate::hll::HllUnion;
+use crate::hll::MAX_LG_K;
+use crate::hll::MIN_LG_K;
+
+fn assert_lg_k(lg_k: u8) {
+    assert!(
+        (MIN_LG_K..=MAX_LG_K).contains(&lg_k),
+        "lg_k must be in [{MIN_LG_K}, {MAX_LG_K}], got {lg_k}"
+    );
+}
+
+/// Merges every sketch of the union into `union`.
+fn union_sketches(union: &mut HllUnion, array: &ArrayRef) -> Result<()> {
+    for_each_sketch(array, |bytes| {
+        let sketch = HllSketch::deserialize(bytes).map_err(external)?;
+        union.update(&sketch);
+        Ok(())
+    })
+}
+
+/// Partial state: the union gadget is always `Hll8`, so serialize it as such.
+fn union_state(union: &HllUnion) -> Vec<ScalarValue> {
+    let sketch = union.to_sketch(HllType::Hll8);
+    vec![ScalarValue::Binary(Some(sketch.serialize()))]
+}
+
+impl UpdateSketch for HllUnion {
+    fn update_value<T: Hash>(&mut self, value: T) {
+        HllUnion::update_value(self, value);
+    }
+}
+
+/// `hll_union(sketch)`: unions serialized HLL sketches into a serialized HLL sketch.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HllUnionUdaf {
+    signature: Signature,
+    lg_max_k: u8,
+    hll_type: HllType,
+}
+
+impl Default for HllUnionUdaf {
+    fn default() -> Self {
+        Self::new(DEFAULT_LG_K, HllType::Hll4)
+    }
+}
+
+impl HllUnionUdaf {
+    /// Creates the aggregate with the given maximum lg_k and result type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lg_max_k` is not in `[4, 21]`.
+    pub fn new(lg_max_k: u8, hll_type: HllType) -> Self {
+        assert_lg_k(lg_max_k);
+        Self {
+            signature: Signature::uniform(1, SKETCH_TYPES.to_vec(), Volatility::Immutable),
+            lg_max_k,
+            hll_type,
+        }
+    }
+}
+
+impl AggregateUDFImpl for HllUnionUdaf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "hll_union"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Binary)
+    }
+
+    fn accumulator(&self, _args: AccumulatorArgs) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(HllUnionAccumulator::new(
+            self.lg_max_k,
+            self.hll_type,
+        )))
+    }
+
+    fn state_fields(&self, args: StateFieldsArgs) -> Result<Vec<FieldRef>> {
+        Ok(sketch_state_fields(args.name))
+    }
+}
+
+/// Accumulator of [`HllUnionUdaf`].
+#[derive(Debug)]
+pub struct HllUnionAccumulator {
+    union: HllUnion,
+    hll_type: HllType,
+}
+
+impl HllUnionAccumulator {
+    /// Creates an empty accumulator with the given maximum lg_k and result type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lg_max_k` is not in `[4, 21]`.
+    pub fn new(lg_max_k: u8, hll_type: HllType) -> Self {
+        assert_lg_k(lg_max_k);
+        Self {
+            union: HllUnion::new(lg_max_k),
+            hll_type,
+        }
+    }
+}
+
+impl Accumulator for HllUnionAccumulator {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        union_sketches(&mut self.union, &values[0])
+    }
+
+    fn evaluate(&mut self) -> Result<ScalarValue> {
+        let sketch = self.union.to_sketch(self.hll_type);
+        Ok(ScalarValue::Binary(Some(sketch.serialize())))
+    }
+
+    fn size(&self) -> usize {
+        size_of::<Self>() + self.union.estimated_size()
+    }
+
+    fn state(&mut self) -> Result<Vec<ScalarValue>> {
+        Ok(union_state(&self.union))
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        union_sketches(&mut self.union, &states[0])
+    }
+}
+
+/// `hll_count_distinct(value)`: estimates the number of distinct values with an HLL sketch.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HllCountDistinctUdaf {
+    signature: Signature,
+    lg_k: u8,
+}
+
+impl Default for HllCountDistinctUdaf {
+    fn default() -> Self {
+        Self::new(DEFAULT_LG_K)
+    }
+}
+
+impl HllCountDistinctUdaf {
+    /// Creates the aggregate with the given lg_k.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lg_k` is not in `[4, 21]`.
+    pub fn new(lg_k: u8) -> Self {
+        assert_lg_k(lg_k);
+        Self {
+            signature: Signature::uniform(1, VALUE_TYPES.to_vec(), Volatility::Immutable),
+            lg_k,
+        }
+    }
+}
+
+impl AggregateUDFImpl for HllCountDistinctUdaf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "hll_count_distinct"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Float64)
+    }
+
+    fn accumulator(&self, _args: AccumulatorArgs) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(HllCountDistinctAccumulator::new(self.lg_k)))
+    }
+
+    fn state_fields(&self, args: StateFieldsArgs) -> Result<Vec<FieldRef>> {
+        Ok(sketch_state_fields(args.name))
+    }
+}
+
+/// Accumulator of [`HllCountDistinctUdaf`].
+#[derive(Debug)]
+pub struct HllCountDistinctAccumulator {
+    union: HllUnion,
+}
+
+impl HllCountDistinctAccumulator {
+    /// Creates an empty accumulator with the given lg_k.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lg_k` is not in `[4, 21]`.
+    pub fn new(lg_k: u8) -> Self {
+        assert_lg_k(lg_k);
+        Self {
+            union: HllUnion::new(lg_k),
+        }
+    }
+}
+
+impl Accumulator for HllCountDistinctAccumulator {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        update_from_array(&mut self.union, &values[0])
+    }
+
+    fn evaluate(&mut self) -> Result<ScalarValue> {
+        Ok(ScalarValue::Float64(Some(self.union.estimate())))
+    }
+
+    fn size(&self) -> usize {
+        size_of::<Self>() + self.union.estimated_size()
+    }
+
+    fn state(&mut self) -> Result<Vec<ScalarValue>> {
+        Ok(union_state(&self.union))
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        union_sketches(&mut self.union, &states[0])
+    }
+}
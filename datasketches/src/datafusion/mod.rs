@@ -0,0 +1,236 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [DataFusion] aggregate functions over sketches.
+//!
+//! Each aggregate is an [`AggregateUDFImpl`](datafusion_expr::AggregateUDFImpl) backed by an
+//! [`Accumulator`](datafusion_expr::Accumulator) that is also public, for engines that wire
+//! accumulators up themselves. Only aggregates for the enabled sketch features are available.
+//!
+//! | Function               | Feature    | Input                         | Output                 |
+//! |------------------------|------------|-------------------------------|------------------------|
+//! | `hll_union`            | `hll`      | serialized HLL sketches       | serialized HLL sketch  |
+//! | `hll_count_distinct`   | `hll`      | values                        | `Float64` estimate     |
+//! | `theta_union`          | `theta`    | serialized compact sketches   | serialized sketch      |
+//! | `theta_count_distinct` | `theta`    | values                        | `Float64` estimate     |
+//! | `tdigest_merge`        | `tdigest`  | serialized t-digests          | serialized t-digest    |
+//!
+//! Sketch arguments are `Binary`, `LargeBinary` or `BinaryView` columns in the same format as
+//! the sketch's `serialize`, so they interoperate with sketches built outside the query engine.
+//! The partial aggregate state of every function is a serialized sketch.
+//!
+//! The count distinct functions accept integer, floating-point, string and binary columns and hash
+//! them the way the other DataSketches implementations do: integers as 64-bit values, floats
+//! through [`canonical_float`](crate::hash_value::canonical_float), and strings and binaries as
+//! [`raw_bytes`](crate::hash_value::raw_bytes), skipping empty ones. Nulls are ignored by every
+//! function.
+//!
+//! Register all of them with a session in one line:
+//!
+//! ```ignore
+//! datasketches::datafusion::udafs()
+//!     .into_iter()
+//!     .for_each(|udaf| ctx.register_udaf(udaf));
+//! ```
+//!
+//! [DataFusion]: https://datafusion.apache.org
+
+#[cfg(feature = "hll")]
+mod hll;
+#[cfg(feature = "tdigest")]
+mod tdigest;
+#[cfg(feature = "theta")]
+mod theta;
+
+use datafusion_expr::AggregateUDF;
+
+#[cfg(feature = "hll")]
+pub use self::hll::HllCountDistinctAccumulator;
+#[cfg(feature = "hll")]
+pub use self::hll::HllCountDistinctUdaf;
+#[cfg(feature = "hll")]
+pub use self::hll::HllUnionAccumulator;
+#[cfg(feature = "hll")]
+pub use self::hll::HllUnionUdaf;
+#[cfg(feature = "tdigest")]
+pub use self::tdigest::TDigestMergeAccumulator;
+#[cfg(feature = "tdigest")]
+pub use self::tdigest::TDigestMergeUdaf;
+#[cfg(feature = "theta")]
+pub use self::theta::ThetaCountDistinctAccumulator;
+#[cfg(feature = "theta")]
+pub use self::theta::ThetaCountDistinctUdaf;
+#[cfg(feature = "theta")]
+pub use self::theta::ThetaUnionAccumulator;
+#[cfg(feature = "theta")]
+pub use self::theta::ThetaUnionUdaf;
+
+/// Returns every aggregate function of the enabled sketch features, with default parameters.
+pub fn udafs() -> Vec<AggregateUDF> {
+    vec![
+        #[cfg(feature = "hll")]
+        AggregateUDF::new_from_impl(HllUnionUdaf::default()),
+        #[cfg(feature = "hll")]
+        AggregateUDF::new_from_impl(HllCountDistinctUdaf::default()),
+        #[cfg(feature = "theta")]
+        AggregateUDF::new_from_impl(ThetaUnionUdaf::default()),
+        #[cfg(feature = "theta")]
+        AggregateUDF::new_from_impl(ThetaCountDistinctUdaf::default()),
+        #[cfg(feature = "tdigest")]
+        AggregateUDF::new_from_impl(TDigestMergeUdaf::default()),
+    ]
+}
+
+#[cfg(any(feature = "hll", feature = "tdigest", feature = "theta"))]
+mod common {
+    use datafusion_common::DataFusionError;
+    use datafusion_common::Result;
+    use datafusion_common::arrow::array::Array;
+    use datafusion_common::arrow::array::ArrayRef;
+    use datafusion_common::arrow::array::AsArray;
+    use datafusion_common::arrow::datatypes::DataType;
+    use datafusion_common::arrow::datatypes::Field;
+    use datafusion_common::arrow::datatypes::FieldRef;
+    use datafusion_common::exec_err;
+    use datafusion_expr::utils::format_state_name;
+
+    use crate::error::Error;
+
+    /// Column types accepted as serialized sketches.
+    pub(super) const SKETCH_TYPES: [DataType; 3] = [
+        DataType::Binary,
+        DataType::LargeBinary,
+        DataType::BinaryView,
+    ];
+
+    pub(super) fn external(err: Error) -> DataFusionError {
+        DataFusionError::External(Box::new(err))
+    }
+
+    /// The single state field shared by all aggregates: a serialized sketch.
+    pub(super) fn sketch_state_fields(name: &str) -> Vec<FieldRef> {
+        vec![Field::new(format_state_name(name, "sketch"), DataType::Binary, true).into()]
+    }
+
+    /// Calls `f` with every non-null value of a binary column.
+    pub(super) fn for_each_sketch(
+        array: &ArrayRef,
+        f: impl FnMut(&[u8]) -> Result<()>,
+    ) -> Result<()> {
+        match array.data_type() {
+            DataType::Binary => array.as_binary::<i32>().iter().flatten().try_for_each(f),
+            DataType::LargeBinary => array.as_binary::<i64>().iter().flatten().try_for_each(f),
+            DataType::BinaryView => array.as_binary_view().iter().flatten().try_for_each(f),
+            other => exec_err!("expected a binary sketch column, got {other}"),
+        }
+    }
+}
+
+#[cfg(any(feature = "hll", feature = "theta"))]
+mod values {
+    use std::hash::Hash;
+
+    use datafusion_common::Result;
+    use datafusion_common::arrow::array::Array;
+    use datafusion_common::arrow::array::ArrayRef;
+    use datafusion_common::arrow::array::AsArray;
+    use datafusion_common::arrow::datatypes::DataType;
+    use datafusion_common::arrow::datatypes::Float32Type;
+    use datafusion_common::arrow::datatypes::Float64Type;
+    use datafusion_common::arrow::datatypes::Int8Type;
+    use datafusion_common::arrow::datatypes::Int16Type;
+    use datafusion_common::arrow::datatypes::Int32Type;
+    use datafusion_common::arrow::datatypes::Int64Type;
+    use datafusion_common::arrow::datatypes::UInt8Type;
+    use datafusion_common::arrow::datatypes::UInt16Type;
+    use datafusion_common::arrow::datatypes::UInt32Type;
+    use datafusion_common::arrow::datatypes::UInt64Type;
+    use datafusion_common::exec_err;
+
+    use crate::hash_value::canonical_float;
+    use crate::hash_value::raw_bytes;
+    use crate::hash_value::sign_extend;
+
+    /// Column types accepted by the count distinct aggregates.
+    pub(super) const VALUE_TYPES: [DataType; 16] = [
+        DataType::Int8,
+        DataType::Int16,
+        DataType::Int32,
+        DataType::Int64,
+        DataType::UInt8,
+        DataType::UInt16,
+        DataType::UInt32,
+        DataType::UInt64,
+        DataType::Float32,
+        DataType::Float64,
+        DataType::Utf8,
+        DataType::LargeUtf8,
+        DataType::Utf8View,
+        DataType::Binary,
+        DataType::LargeBinary,
+        DataType::BinaryView,
+    ];
+
+    /// A sketch that can be updated with hashable values.
+    pub(super) trait UpdateSketch {
+        fn update_value<T: Hash>(&mut self, value: T);
+    }
+
+    /// Updates `sketch` with every non-null, non-empty value of `array`.
+    pub(super) fn update_from_array(
+        sketch: &mut impl UpdateSketch,
+        array: &ArrayRef,
+    ) -> Result<()> {
+        macro_rules! primitive {
+            ($ty:ty, $wrap:expr) => {
+                for value in array.as_primitive::<$ty>().iter().flatten() {
+                    sketch.update_value($wrap(value));
+                }
+            };
+        }
+        macro_rules! bytes {
+            ($values:expr, $wrap:expr) => {
+                for value in $values.iter().flatten() {
+                    if !value.is_empty() {
+                        sketch.update_value($wrap(value));
+                    }
+                }
+            };
+        }
+
+        match array.data_type() {
+            DataType::Int8 => primitive!(Int8Type, i64::from),
+            DataType::Int16 => primitive!(Int16Type, i64::from),
+            DataType::Int32 => primitive!(Int32Type, i64::from),
+            DataType::Int64 => primitive!(Int64Type, i64::from),
+            DataType::UInt8 => primitive!(UInt8Type, sign_extend::from_u8),
+            DataType::UInt16 => primitive!(UInt16Type, sign_extend::from_u16),
+            DataType::UInt32 => primitive!(UInt32Type, sign_extend::from_u32),
+            DataType::UInt64 => primitive!(UInt64Type, u64::from),
+            DataType::Float32 => primitive!(Float32Type, canonical_float::from_f32),
+            DataType::Float64 => primitive!(Float64Type, canonical_float::from_f64),
+            DataType::Utf8 => bytes!(array.as_string::<i32>(), raw_bytes::from_str),
+            DataType::LargeUtf8 => bytes!(array.as_string::<i64>(), raw_bytes::from_str),
+            DataType::Utf8View => bytes!(array.as_string_view(), raw_bytes::from_str),
+            DataType::Binary => bytes!(array.as_binary::<i32>(), raw_bytes::from_slice),
+            DataType::LargeBinary => bytes!(array.as_binary::<i64>(), raw_bytes::from_slice),
+            DataType::BinaryView => bytes!(array.as_binary_view(), raw_bytes::from_slice),
+            other => return exec_err!("unsupported value type for count distinct: {other}"),
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,144 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::any::Any;
+
+use datafusion_common::Result;
+use datafusion_common::ScalarValue;
+use datafusion_common::arrow::array::ArrayRef;
+use datafusion_common::arrow::datatypes::DataType;
+use datafusion_common::arrow::datatypes::FieldRef;
+use datafusion_expr::Accumulator;
+use datafusion_expr::AggregateUDFImpl;
+use datafusion_expr::Signature;
+use datafusion_expr::Volatility;
+use datafusion_expr::function::AccumulatorArgs;
+use datafusion_expr::function::StateFieldsArgs;
+
+use super::common::SKETCH_TYPES;
+use super::common::external;
+use super::common::for_each_sketch;
+use super::common::sketch_state_fields;
+use crate::tdigest::TDigestMut;
+
+const DEFAULT_K: u16 = 200;
+
+/// `tdigest_merge(sketch)`: merges serialized t-digests into a serialized t-digest.
+///
+/// Input digests must be serialized with double precision values, as [`TDigestMut::serialize`]
+/// does.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TDigestMergeUdaf {
+    signature: Signature,
+    k: u16,
+}
+
+impl Default for TDigestMergeUdaf {
+    fn default() -> Self {
+        Self::new(DEFAULT_K)
+    }
+}
+
+impl TDigestMergeUdaf {
+    /// Creates the aggregate whose result has the given k.
+    ///
+    /// # Panics
+    ///
+    /// Panics if k is less than 10.
+    pub fn new(k: u16) -> Self {
+        assert!(k >= 10, "k must be at least 10, got {k}");
+        Self {
+            signature: Signature::uniform(1, SKETCH_TYPES.to_vec(), Volatility::Immutable),
+            k,
+        }
+    }
+}
+
+impl AggregateUDFImpl for TDigestMergeUdaf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "tdigest_merge"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Binary)
+    }
+
+    fn accumulator(&self, _args: AccumulatorArgs) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(TDigestMergeAccumulator::new(self.k)))
+    }
+
+    fn state_fields(&self, args: StateFieldsArgs) -> Result<Vec<FieldRef>> {
+        Ok(sketch_state_fields(args.name))
+    }
+}
+
+/// Accumulator of [`TDigestMergeUdaf`].
+#[derive(Debug)]
+pub struct TDigestMergeAccumulator {
+    digest: TDigestMut,
+}
+
+impl TDigestMergeAccumulator {
+    /// Creates an empty accumulator whose result has the given k.
+    ///
+    /// # Panics
+    ///
+    /// Panics if k is less than 10.
+    pub fn new(k: u16) -> Self {
+        Self {
+            digest: TDigestMut::new(k),
+        }
+    }
+
+    fn merge_digests(&mut self, array: &ArrayRef) -> Result<()> {
+        for_each_sketch(array, |bytes| {
+            let digest = TDigestMut::deserialize(bytes, false).map_err(external)?;
+            self.digest.merge(&digest);
+            Ok(())
+        })
+    }
+}
+
+impl Accumulator for TDigestMergeAccumulator {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        self.merge_digests(&values[0])
+    }
+
+    fn evaluate(&mut self) -> Result<ScalarValue> {
+        Ok(ScalarValue::Binary(Some(self.digest.serialize())))
+    }
+
+    fn size(&self) -> usize {
+        size_of::<Self>() + self.digest.estimated_size()
+    }
+
+    fn state(&mut self) -> Result<Vec<ScalarValue>> {
+        Ok(vec![ScalarValue::Binary(Some(self.digest.serialize()))])
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        self.merge_digests(&states[0])
+    }
+}
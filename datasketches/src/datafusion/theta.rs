@@ -0,0 +1,296 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::any::Any;
+use std::hash::Hash;
+
+use datafusion_common::Result;
+use datafusion_common::ScalarValue;
+use datafusion_common::arrow::array::ArrayRef;
+use datafusion_common::arrow::datatypes::DataType;
+use datafusion_common::arrow::datatypes::FieldRef;
+use datafusion_expr::Accumulator;
+use datafusion_expr::AggregateUDFImpl;
+use datafusion_expr::Signature;
+use datafusion_expr::Volatility;
+use datafusion_expr::function::AccumulatorArgs;
+use datafusion_expr::function::StateFieldsArgs;
+
+use super::common::SKETCH_TYPES;
+use super::common::external;
+use super::common::for_each_sketch;
+use super::common::sketch_state_fields;
+use super::values::UpdateSketch;
+use super::values::VALUE_TYPES;
+use super::values::update_from_array;
+use crate::hash::DEFAULT_UPDATE_SEED;
+use crate::theta::CompactThetaSketch;
+use crate::theta::ThetaSketch;
+use crate::theta::ThetaSketchBuilder;
+use crate::theta::ThetaUnion;
+use crate::theta::ThetaUnionBuilder;
+use crate::thetacommon::constants::DEFAULT_LG_K;
+use crate::thetacommon::constants::MAX_LG_K;
+use crate::thetacommon::constants::MIN_LG_K;
+
+fn assert_lg_k(lg_k: u8) {
+    assert!(
+        (MIN_LG_K..=MAX_LG_K).contains(&lg_k),
+        "lg_k must be in [{MIN_LG_K}, {MAX_LG_K}], got {lg_k}"
+    );
+}
+
+fn new_union(lg_k: u8, seed: u64) -> ThetaUnion {
+    ThetaUnionBuilder::default().lg_k(lg_k).seed(seed).build()
+}
+
+/// Merges every compact sketch of the array into `union`.
+fn union_sketches(union: &mut ThetaUnion, seed: u64, array: &ArrayRef) -> Result<()> {
+    for_each_sketch(array, |bytes| {
+        let sketch = CompactThetaSketch::deserialize_with_seed(bytes, seed).map_err(external)?;
+        union.update(&sketch).map_err(external)
+    })
+}
+
+fn union_state(union: &ThetaUnion) -> Vec<ScalarValue> {
+    vec![ScalarValue::Binary(Some(union.to_sketch(true).serialize()))]
+}
+
+impl UpdateSketch for ThetaSketch {
+    fn update_value<T: Hash>(&mut self, value: T) {
+        self.update(value);
+    }
+}
+
+/// `theta_union(sketch)`: unions serialized compact Theta sketches into a serialized ordered
+/// compact sketch.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ThetaUnionUdaf {
+    signature: Signature,
+    lg_k: u8,
+    seed: u64,
+}
+
+impl Default for ThetaUnionUdaf {
+    fn default() -> Self {
+        Self::new(DEFAULT_LG_K, DEFAULT_UPDATE_SEED)
+    }
+}
+
+impl ThetaUnionUdaf {
+    /// Creates the aggregate with the given lg_k, for sketches built with the given seed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lg_k` is not in `[5, 26]`.
+    pub fn new(lg_k: u8, seed: u64) -> Self {
+        assert_lg_k(lg_k);
+        Self {
+            signature: Signature::uniform(1, SKETCH_TYPES.to_vec(), Volatility::Immutable),
+            lg_k,
+            seed,
+        }
+    }
+}
+
+impl AggregateUDFImpl for ThetaUnionUdaf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "theta_union"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Binary)
+    }
+
+    fn accumulator(&self, _args: AccumulatorArgs) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(ThetaUnionAccumulator::new(self.lg_k, self.seed)))
+    }
+
+    fn state_fields(&self, args: StateFieldsArgs) -> Result<Vec<FieldRef>> {
+        Ok(sketch_state_fields(args.name))
+    }
+}
+
+/// Accumulator of [`ThetaUnionUdaf`].
+#[derive(Debug)]
+pub struct ThetaUnionAccumulator {
+    union: ThetaUnion,
+    seed: u64,
+}
+
+impl ThetaUnionAccumulator {
+    /// Creates an empty accumulator with the given lg_k, for sketches built with the given seed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lg_k` is not in `[5, 26]`.
+    pub fn new(lg_k: u8, seed: u64) -> Self {
+        assert_lg_k(lg_k);
+        Self {
+            union: new_union(lg_k, seed),
+            seed,
+        }
+    }
+}
+
+impl Accumulator for ThetaUnionAccumulator {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        union_sketches(&mut self.union, self.seed, &values[0])
+    }
+
+    fn evaluate(&mut self) -> Result<ScalarValue> {
+        Ok(union_state(&self.union).remove(0))
+    }
+
+    fn size(&self) -> usize {
+        size_of::<Self>() + self.union.estimated_size()
+    }
+
+    fn state(&mut self) -> Result<Vec<ScalarValue>> {
+        Ok(union_state(&self.union))
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        union_sketches(&mut self.union, self.seed, &states[0])
+    }
+}
+
+/// `theta_count_distinct(value)`: estimates the number of distinct values with a Theta sketch.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ThetaCountDistinctUdaf {
+    signature: Signature,
+    lg_k: u8,
+    seed: u64,
+}
+
+impl Default for ThetaCountDistinctUdaf {
+    fn default() -> Self {
+        Self::new(DEFAULT_LG_K, DEFAULT_UPDATE_SEED)
+    }
+}
+
+impl ThetaCountDistinctUdaf {
+    /// Creates the aggregate with the given lg_k and hash seed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lg_k` is not in `[5, 26]`.
+    pub fn new(lg_k: u8, seed: u64) -> Self {
+        assert_lg_k(lg_k);
+        Self {
+            signature: Signature::uniform(1, VALUE_TYPES.to_vec(), Volatility::Immutable),
+            lg_k,
+            seed,
+        }
+    }
+}
+
+impl AggregateUDFImpl for ThetaCountDistinctUdaf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "theta_count_distinct"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Float64)
+    }
+
+    fn accumulator(&self, _args: AccumulatorArgs) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(ThetaCountDistinctAccumulator::new(
+            self.lg_k, self.seed,
+        )))
+    }
+
+    fn state_fields(&self, args: StateFieldsArgs) -> Result<Vec<FieldRef>> {
+        Ok(sketch_state_fields(args.name))
+    }
+}
+
+/// Accumulator of [`ThetaCountDistinctUdaf`].
+///
+/// Raw values go into an update sketch and partial states into a union; the sketch is folded
+/// into the union whenever a result is needed.
+#[derive(Debug)]
+pub struct ThetaCountDistinctAccumulator {
+    sketch: ThetaSketch,
+    union: ThetaUnion,
+    seed: u64,
+}
+
+impl ThetaCountDistinctAccumulator {
+    /// Creates an empty accumulator with the given lg_k and hash seed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lg_k` is not in `[5, 26]`.
+    pub fn new(lg_k: u8, seed: u64) -> Self {
+        assert_lg_k(lg_k);
+        Self {
+            sketch: ThetaSketchBuilder::default().lg_k(lg_k).seed(seed).build(),
+            union: new_union(lg_k, seed),
+            seed,
+        }
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        if !self.sketch.is_empty() {
+            self.union.update(&self.sketch).map_err(external)?;
+            self.sketch.reset();
+        }
+        Ok(())
+    }
+}
+
+impl Accumulator for ThetaCountDistinctAccumulator {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        update_from_array(&mut self.sketch, &values[0])
+    }
+
+    fn evaluate(&mut self) -> Result<ScalarValue> {
+        self.flush()?;
+        let estimate = self.union.to_sketch(false).estimate();
+        Ok(ScalarValue::Float64(Some(estimate)))
+    }
+
+    fn size(&self) -> usize {
+        size_of::<Self>() + self.sketch.estimated_size() + self.union.estimated_size()
+    }
+
+    fn state(&mut self) -> Result<Vec<ScalarValue>> {
+        self.flush()?;
+        Ok(union_state(&self.union))
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        union_sketches(&mut self.union, self.seed, &states[0])
+    }
+}
@@ -0,0 +1,533 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Family-agnostic description of serialized sketches.
+//!
+//! [`inspect`] reads the family id of a serialized sketch and decodes as much of the image as is
+//! needed to report its configuration, retained count and estimate. It is meant for debugging
+//! and tooling that must make sense of stored blobs without knowing their type up front.
+//!
+//! Only the families enabled through crate features are recognized.
+
+use crate::codec::SketchSlice;
+use crate::error::Error;
+
+/// Description of a serialized sketch, as returned by [`inspect`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SketchDescriptor {
+    family_id: u8,
+    family_name: &'static str,
+    serial_version: u8,
+    is_empty: bool,
+    parameters: Vec<(&'static str, String)>,
+    num_retained: Option<u64>,
+    estimate: Option<f64>,
+}
+
+impl SketchDescriptor {
+    #[allow(dead_code)] // unused when no sketch family is enabled
+    fn new(family_id: u8, family_name: &'static str, serial_version: u8, is_empty: bool) -> Self {
+        Self {
+            family_id,
+            family_name,
+            serial_version,
+            is_empty,
+            parameters: vec![],
+            num_retained: None,
+            estimate: None,
+        }
+    }
+
+    #[allow(dead_code)] // unused when no sketch family is enabled
+    fn with_parameter(mut self, name: &'static str, value: impl ToString) -> Self {
+        self.parameters.push((name, value.to_string()));
+        self
+    }
+
+    /// Returns the family id stored in the preamble.
+    pub fn family_id(&self) -> u8 {
+        self.family_id
+    }
+
+    /// Returns the name of the sketch family, e.g. `"HLL"` or `"THETA"`.
+    pub fn family_name(&self) -> &'static str {
+        self.family_name
+    }
+
+    /// Returns the serialization version stored in the preamble.
+    pub fn serial_version(&self) -> u8 {
+        self.serial_version
+    }
+
+    /// Returns true if the sketch is empty.
+    pub fn is_empty(&self) -> bool {
+        self.is_empty
+    }
+
+    /// Returns the family-specific configuration parameters as `(name, value)` pairs.
+    ///
+    /// Parameters appear in a stable order for each family; names are lower snake case, such as
+    /// `lg_k` or `num_hashes`.
+    pub fn parameters(&self) -> &[(&'static str, String)] {
+        &self.parameters
+    }
+
+    /// Returns the value of the named parameter, if the family reports it.
+    pub fn parameter(&self, name: &str) -> Option<&str> {
+        self.parameters
+            .iter()
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns the number of entries retained by the sketch, if the family has such a notion.
+    ///
+    /// This counts hashes for Theta and Tuple sketches, coupons for HLL (in List or Set mode) and
+    /// CPC sketches, active items for frequent items sketches and centroids plus buffered values
+    /// for t-digests.
+    pub fn num_retained(&self) -> Option<u64> {
+        self.num_retained
+    }
+
+    /// Returns the distinct count estimate, if it can be computed from the serialized image.
+    ///
+    /// This is only available for the cardinality families (Theta, Tuple, HLL and CPC).
+    pub fn estimate(&self) -> Option<f64> {
+        self.estimate
+    }
+}
+
+/// Describes a serialized sketch of any family enabled in this build.
+///
+/// Seeds are not checked, so sketches built with any seed can be described. Families whose items
+/// or summaries have a caller-chosen type (frequent items, Tuple and CountMin sketches) are only
+/// described from their preamble.
+///
+/// # Errors
+///
+/// Returns an error if the bytes are too short, the family id is unknown or its feature is not
+/// enabled, or the image is malformed.
+///
+/// # Examples
+///
+/// ```
+/// # use datasketches::hll::HllSketch;
+/// # use datasketches::hll::HllType;
+/// let mut sketch = HllSketch::new(12, HllType::Hll8);
+/// sketch.update("apple");
+///
+/// let descriptor = datasketches::inspect(&sketch.serialize()).unwrap();
+/// assert_eq!(descriptor.family_name(), "HLL");
+/// assert_eq!(descriptor.parameter("lg_k"), Some("12"));
+/// assert_eq!(descriptor.num_retained(), Some(1));
+/// ```
+pub fn inspect(bytes: &[u8]) -> Result<SketchDescriptor, Error> {
+    let mut cursor = SketchSlice::new(bytes);
+    cursor
+        .read_u8()
+        .map_err(|_| Error::insufficient_data("preamble"))?;
+    cursor
+        .read_u8()
+        .map_err(|_| Error::insufficient_data("serial_version"))?;
+    let family_id = cursor
+        .read_u8()
+        .map_err(|_| Error::insufficient_data("family_id"))?;
+
+    match family_id {
+        #[cfg(feature = "theta")]
+        id if id == crate::codec::family::Family::THETA.id => theta::describe(bytes),
+        #[cfg(feature = "theta")]
+        id if id == crate::codec::family::Family::QUICKSELECT.id => {
+            theta::describe_updatable(bytes)
+        }
+        #[cfg(feature = "hll")]
+        id if id == crate::codec::family::Family::HLL.id => hll::describe(bytes),
+        #[cfg(feature = "tuple")]
+        id if id == crate::codec::family::Family::TUPLE.id => tuple::describe(bytes),
+        #[cfg(feature = "frequencies")]
+        id if id == crate::codec::family::Family::FREQUENCY.id => frequencies::describe(bytes),
+        #[cfg(feature = "cpc")]
+        id if id == crate::codec::family::Family::CPC.id => cpc::describe(bytes),
+        #[cfg(feature = "countmin")]
+        id if id == crate::codec::family::Family::COUNTMIN.id => countmin::describe(bytes),
+        #[cfg(feature = "tdigest")]
+        id if id == crate::codec::family::Family::TDIGEST.id => tdigest::describe(bytes),
+        #[cfg(feature = "bloom")]
+        id if id == crate::codec::family::Family::BLOOMFILTER.id => bloom::describe(bytes),
+        _ => Err(Error::deserial(format!(
+            "unknown or unsupported family id: {family_id}"
+        ))),
+    }
+}
+
+#[cfg(feature = "theta")]
+mod theta {
+    use super::SketchDescriptor;
+    use crate::codec::SketchSlice;
+    use crate::codec::assert::insufficient_data;
+    use crate::codec::family::Family;
+    use crate::error::Error;
+    use crate::theta::CompactThetaSketch;
+    use crate::thetacommon::constants::FLAGS_IS_EMPTY;
+    use crate::thetacommon::constants::MAX_THETA;
+
+    pub(super) fn describe(bytes: &[u8]) -> Result<SketchDescriptor, Error> {
+        let sketch = CompactThetaSketch::deserialize_unchecked_seed(bytes)?;
+        let mut descriptor = SketchDescriptor::new(
+            Family::THETA.id,
+            Family::THETA.name,
+            bytes[1],
+            sketch.is_empty(),
+        )
+        .with_parameter("seed_hash", sketch.seed_hash())
+        .with_parameter("theta", sketch.theta())
+        .with_parameter("ordered", sketch.is_ordered());
+        descriptor.num_retained = Some(sketch.num_retained() as u64);
+        descriptor.estimate = Some(sketch.estimate());
+        Ok(descriptor)
+    }
+
+    pub(super) fn describe_updatable(bytes: &[u8]) -> Result<SketchDescriptor, Error> {
+        let mut cursor = SketchSlice::new(bytes);
+        let first_byte = cursor
+            .read_u8()
+            .map_err(insufficient_data("preamble_longs"))?;
+        let ser_ver = cursor
+            .read_u8()
+            .map_err(insufficient_data("serial_version"))?;
+        cursor.read_u8().map_err(insufficient_data("family_id"))?;
+        let lg_k = cursor.read_u8().map_err(insufficient_data("lg_nom_size"))?;
+        cursor.read_u8().map_err(insufficient_data("lg_cur_size"))?;
+        let flags = cursor.read_u8().map_err(insufficient_data("flags"))?;
+        let seed_hash = cursor
+            .read_u16_le()
+            .map_err(insufficient_data("seed_hash"))?;
+        let num_retained = cursor
+            .read_u32_le()
+            .map_err(insufficient_data("num_retained"))?;
+        let sampling_probability = cursor
+            .read_f32_le()
+            .map_err(insufficient_data("sampling_probability"))?;
+        let theta = cursor.read_u64_le().map_err(insufficient_data("theta"))?;
+
+        let empty = (flags & FLAGS_IS_EMPTY) != 0;
+        let theta = theta as f64 / MAX_THETA as f64;
+        let mut descriptor = SketchDescriptor::new(
+            Family::QUICKSELECT.id,
+            Family::QUICKSELECT.name,
+            ser_ver,
+            empty,
+        )
+        .with_parameter("lg_k", lg_k)
+        .with_parameter("resize_factor", 1u8 << (first_byte >> 6))
+        .with_parameter("sampling_probability", sampling_probability)
+        .with_parameter("seed_hash", seed_hash)
+        .with_parameter("theta", theta);
+        descriptor.num_retained = Some(num_retained as u64);
+        descriptor.estimate = Some(if empty {
+            0.0
+        } else {
+            num_retained as f64 / theta
+        });
+        Ok(descriptor)
+    }
+}
+
+#[cfg(feature = "hll")]
+mod hll {
+    use super::SketchDescriptor;
+    use crate::codec::family::Family;
+    use crate::error::Error;
+    use crate::hll::HllSketch;
+
+    pub(super) fn describe(bytes: &[u8]) -> Result<SketchDescriptor, Error> {
+        let info = crate::hll::inspect(bytes)?;
+        let sketch = HllSketch::deserialize(bytes)?;
+        let mut descriptor = SketchDescriptor::new(
+            Family::HLL.id,
+            Family::HLL.name,
+            info.serial_version(),
+            info.is_empty(),
+        )
+        .with_parameter("lg_k", info.lg_config_k())
+        .with_parameter("target_type", format!("{:?}", info.target_type()))
+        .with_parameter("mode", format!("{:?}", info.mode()));
+        descriptor.num_retained = info.coupon_count().map(u64::from);
+        descriptor.estimate = Some(sketch.estimate());
+        Ok(descriptor)
+    }
+}
+
+#[cfg(feature = "tuple")]
+mod tuple {
+    use super::SketchDescriptor;
+    use crate::codec::SketchSlice;
+    use crate::codec::assert::insufficient_data;
+    use crate::codec::family::Family;
+    use crate::error::Error;
+    use crate::thetacommon::constants::FLAGS_IS_EMPTY;
+    use crate::thetacommon::constants::FLAGS_IS_ORDERED;
+    use crate::thetacommon::constants::MAX_THETA;
+
+    pub(super) fn describe(bytes: &[u8]) -> Result<SketchDescriptor, Error> {
+        let mut cursor = SketchSlice::new(bytes);
+        let pre_longs = cursor
+            .read_u8()
+            .map_err(insufficient_data("preamble_longs"))?;
+        let ser_ver = cursor
+            .read_u8()
+            .map_err(insufficient_data("serial_version"))?;
+        cursor.read_u8().map_err(insufficient_data("family_id"))?;
+        cursor.read_u8().map_err(insufficient_data("sketch_type"))?;
+        cursor.read_u8().map_err(insufficient_data("<unused>"))?;
+        let flags = cursor.read_u8().map_err(insufficient_data("flags"))?;
+        let seed_hash = cursor
+            .read_u16_le()
+            .map_err(insufficient_data("seed_hash"))?;
+
+        let empty = (flags & FLAGS_IS_EMPTY) != 0;
+        let mut theta = MAX_THETA;
+        let num_retained = if empty {
+            0
+        } else if pre_longs == 1 {
+            1
+        } else {
+            let n = cursor
+                .read_u32_le()
+                .map_err(insufficient_data("num_entries"))?;
+            cursor
+                .read_u32_le()
+                .map_err(insufficient_data("<unused_u32>"))?;
+            if pre_longs > 2 {
+                theta = cursor.read_u64_le().map_err(insufficient_data("theta"))?;
+            }
+            n
+        };
+
+        let theta = theta as f64 / MAX_THETA as f64;
+        let mut descriptor =
+            SketchDescriptor::new(Family::TUPLE.id, Family::TUPLE.name, ser_ver, empty)
+                .with_parameter("seed_hash", seed_hash)
+                .with_parameter("theta", theta)
+                .with_parameter("ordered", (flags & FLAGS_IS_ORDERED) != 0);
+        descriptor.num_retained = Some(num_retained as u64);
+        descriptor.estimate = Some(num_retained as f64 / theta);
+        Ok(descriptor)
+    }
+}
+
+#[cfg(feature = "frequencies")]
+mod frequencies {
+    use super::SketchDescriptor;
+    use crate::codec::SketchSlice;
+    use crate::codec::assert::insufficient_data;
+    use crate::codec::family::Family;
+    use crate::error::Error;
+    use crate::frequencies::serialization::EMPTY_FLAG_MASK;
+
+    pub(super) fn describe(bytes: &[u8]) -> Result<SketchDescriptor, Error> {
+        let mut cursor = SketchSlice::new(bytes);
+        cursor
+            .read_u8()
+            .map_err(insufficient_data("preamble_longs"))?;
+        let serial_version = cursor
+            .read_u8()
+            .map_err(insufficient_data("serial_version"))?;
+        cursor.read_u8().map_err(insufficient_data("family_id"))?;
+        let lg_max = cursor
+            .read_u8()
+            .map_err(insufficient_data("lg_max_map_size"))?;
+        let lg_cur = cursor
+            .read_u8()
+            .map_err(insufficient_data("lg_cur_map_size"))?;
+        let flags = cursor.read_u8().map_err(insufficient_data("flags"))?;
+        let serde_id = cursor
+            .read_u16_le()
+            .map_err(insufficient_data("serde_id"))?;
+
+        let empty = (flags & EMPTY_FLAG_MASK) != 0;
+        let (active_items, stream_weight, offset) = if empty {
+            (0, 0, 0)
+        } else {
+            let active_items = cursor
+                .read_u32_le()
+                .map_err(insufficient_data("active_items"))?;
+            cursor
+                .read_u32_le()
+                .map_err(insufficient_data("<unused>"))?;
+            let stream_weight = cursor
+                .read_u64_le()
+                .map_err(insufficient_data("stream_weight"))?;
+            let offset = cursor.read_u64_le().map_err(insufficient_data("offset"))?;
+            (active_items, stream_weight, offset)
+        };
+
+        let mut descriptor = SketchDescriptor::new(
+            Family::FREQUENCY.id,
+            Family::FREQUENCY.name,
+            serial_version,
+            empty,
+        )
+        .with_parameter("lg_max_map_size", lg_max)
+        .with_parameter("lg_cur_map_size", lg_cur)
+        .with_parameter("total_weight", stream_weight)
+        .with_parameter("offset", offset)
+        .with_parameter("serde_id", serde_id);
+        descriptor.num_retained = Some(active_items as u64);
+        Ok(descriptor)
+    }
+}
+
+#[cfg(feature = "cpc")]
+mod cpc {
+    use super::SketchDescriptor;
+    use crate::codec::family::Family;
+    use crate::cpc::CpcWrapper;
+    use crate::error::Error;
+
+    pub(super) fn describe(bytes: &[u8]) -> Result<SketchDescriptor, Error> {
+        let wrapper = CpcWrapper::new(bytes)?;
+        let seed_hash = u16::from_le_bytes([bytes[6], bytes[7]]);
+        let mut descriptor = SketchDescriptor::new(
+            Family::CPC.id,
+            Family::CPC.name,
+            bytes[1],
+            wrapper.is_empty(),
+        )
+        .with_parameter("lg_k", wrapper.lg_k())
+        .with_parameter("seed_hash", seed_hash);
+        descriptor.num_retained = Some(u64::from(wrapper.num_coupons()));
+        descriptor.estimate = Some(wrapper.estimate());
+        Ok(descriptor)
+    }
+}
+
+#[cfg(feature = "countmin")]
+mod countmin {
+    use super::SketchDescriptor;
+    use crate::codec::SketchSlice;
+    use crate::codec::assert::insufficient_data;
+    use crate::codec::family::Family;
+    use crate::countmin::serialization::FLAGS_IS_EMPTY;
+    use crate::error::Error;
+
+    pub(super) fn describe(bytes: &[u8]) -> Result<SketchDescriptor, Error> {
+        let mut cursor = SketchSlice::new(bytes);
+        cursor
+            .read_u8()
+            .map_err(insufficient_data("preamble_longs"))?;
+        let serial_version = cursor
+            .read_u8()
+            .map_err(insufficient_data("serial_version"))?;
+        cursor.read_u8().map_err(insufficient_data("family_id"))?;
+        let flags = cursor.read_u8().map_err(insufficient_data("flags"))?;
+        cursor
+            .read_u32_le()
+            .map_err(insufficient_data("<unused>"))?;
+        let num_buckets = cursor
+            .read_u32_le()
+            .map_err(insufficient_data("num_buckets"))?;
+        let num_hashes = cursor.read_u8().map_err(insufficient_data("num_hashes"))?;
+        let seed_hash = cursor
+            .read_u16_le()
+            .map_err(insufficient_data("seed_hash"))?;
+
+        Ok(SketchDescriptor::new(
+            Family::COUNTMIN.id,
+            Family::COUNTMIN.name,
+            serial_version,
+            (flags & FLAGS_IS_EMPTY) != 0,
+        )
+        .with_parameter("num_hashes", num_hashes)
+        .with_parameter("num_buckets", num_buckets)
+        .with_parameter("seed_hash", seed_hash))
+    }
+}
+
+#[cfg(feature = "tdigest")]
+mod tdigest {
+    use super::SketchDescriptor;
+    use crate::codec::SketchSlice;
+    use crate::codec::assert::insufficient_data;
+    use crate::codec::family::Family;
+    use crate::error::Error;
+    use crate::tdigest::serialization::FLAGS_IS_EMPTY;
+    use crate::tdigest::serialization::FLAGS_IS_SINGLE_VALUE;
+
+    pub(super) fn describe(bytes: &[u8]) -> Result<SketchDescriptor, Error> {
+        let mut cursor = SketchSlice::new(bytes);
+        cursor
+            .read_u8()
+            .map_err(insufficient_data("preamble_longs"))?;
+        let serial_version = cursor
+            .read_u8()
+            .map_err(insufficient_data("serial_version"))?;
+        cursor.read_u8().map_err(insufficient_data("family_id"))?;
+        let k = cursor.read_u16_le().map_err(insufficient_data("k"))?;
+        let flags = cursor.read_u8().map_err(insufficient_data("flags"))?;
+        cursor
+            .read_u16_le()
+            .map_err(insufficient_data("<unused>"))?;
+
+        let empty = (flags & FLAGS_IS_EMPTY) != 0;
+        let num_retained = if empty {
+            0
+        } else if (flags & FLAGS_IS_SINGLE_VALUE) != 0 {
+            1
+        } else {
+            let num_centroids = cursor
+                .read_u32_le()
+                .map_err(insufficient_data("num_centroids"))?;
+            let num_buffered = cursor
+                .read_u32_le()
+                .map_err(insufficient_data("num_buffered"))?;
+            u64::from(num_centroids) + u64::from(num_buffered)
+        };
+
+        let mut descriptor = SketchDescriptor::new(
+            Family::TDIGEST.id,
+            Family::TDIGEST.name,
+            serial_version,
+            empty,
+        )
+        .with_parameter("k", k);
+        descriptor.num_retained = Some(num_retained);
+        Ok(descriptor)
+    }
+}
+
+#[cfg(feature = "bloom")]
+mod bloom {
+    use super::SketchDescriptor;
+    use crate::bloom::BloomFilter;
+    use crate::codec::family::Family;
+    use crate::error::Error;
+
+    pub(super) fn describe(bytes: &[u8]) -> Result<SketchDescriptor, Error> {
+        let filter = BloomFilter::deserialize(bytes)?;
+        Ok(SketchDescriptor::new(
+            Family::BLOOMFILTER.id,
+            Family::BLOOMFILTER.name,
+            bytes[1],
+            filter.is_empty(),
+        )
+        .with_parameter("num_hashes", filter.num_hashes())
+        .with_parameter("capacity", filter.capacity())
+        .with_parameter("bits_used", filter.bits_used())
+        .with_parameter("seed", filter.seed()))
+    }
+}
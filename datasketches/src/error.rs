@@ -27,6 +27,8 @@ pub enum ErrorKind {
     InvalidArgument,
     /// The sketch data deserializing is malformed.
     InvalidData,
+    /// The sketch data was written with a serial version that this release cannot read.
+    UnsupportedVersion,
 }
 
 impl ErrorKind {
@@ -35,6 +37,7 @@ impl ErrorKind {
         match self {
             ErrorKind::InvalidArgument => "InvalidArgument",
             ErrorKind::InvalidData => "InvalidData",
+            ErrorKind::UnsupportedVersion => "UnsupportedVersion",
         }
     }
 }
@@ -113,6 +116,21 @@ impl Error {
         ))
     }
 
+    pub(crate) fn unsupported_version(family: &'static str, found: u8, supported: &[u8]) -> Self {
+        let max_supported = supported.iter().copied().max().unwrap_or_default();
+        let message = if found > max_supported {
+            format!(
+                "unsupported serial version: {found} is newer than the latest supported version {max_supported}"
+            )
+        } else {
+            format!("unsupported serial version: expected one of {supported:?}, got {found}")
+        };
+        Self::new(ErrorKind::UnsupportedVersion, message)
+            .with_context("family", family)
+            .with_context("found", found)
+            .with_context("max_supported", max_supported)
+    }
+
     pub(crate) fn invalid_preamble_longs(expected: &[u8], actual: u8) -> Self {
         Error::deserial(format!(
             "invalid preamble longs: expected {expected:?}, got {actual}"
@@ -186,6 +204,15 @@ mod tests {
         assert_snapshot!(err, @"InvalidArgument => something went wrong",);
     }
 
+    #[test]
+    fn test_format_unsupported_version() {
+        let err = Error::unsupported_version("HLL", 2, &[1]);
+        assert_snapshot!(err, @"UnsupportedVersion, context: { family: HLL, found: 2, max_supported: 1 } => unsupported serial version: 2 is newer than the latest supported version 1");
+
+        let err = Error::unsupported_version("TUPLE", 2, &[1, 3]);
+        assert_snapshot!(err, @"UnsupportedVersion, context: { family: TUPLE, found: 2, max_supported: 3 } => unsupported serial version: expected one of [1, 3], got 2");
+    }
+
     #[test]
     fn test_format_with_multiple_contexts() {
         let err = Error::new(ErrorKind::InvalidData, "parsing failed")
@@ -86,6 +86,29 @@
 //! assert!(rows.iter().any(|row| *row.item() == 1));
 //! ```
 //!
+//! # Shared string items
+//!
+//! Every item tracked by the sketch is stored once, in its hash map. When the items are long
+//! strings that the application already holds elsewhere, such as interned keys, use
+//! `FrequentItemsSketch<Arc<str>>` so that the sketch shares them instead of keeping its own
+//! copies; cloning an item for [`FrequentItemsSketch::frequent_items`] or a merge is then a
+//! reference count increment. `Arc<str>` items serialize exactly like `String` items, so images
+//! can be read back with either item type.
+//!
+//! ```
+//! # use std::sync::Arc;
+//! # use datasketches::frequencies::FrequentItemsSketch;
+//! let key: Arc<str> = Arc::from("a long and frequently repeated key");
+//! let mut sketch = FrequentItemsSketch::<Arc<str>>::new(64);
+//! for _ in 0..3 {
+//!     sketch.update(Arc::clone(&key));
+//! }
+//! assert_eq!(sketch.estimate(&key), 3);
+//!
+//! let decoded = FrequentItemsSketch::<String>::deserialize(&sketch.serialize()).unwrap();
+//! assert_eq!(decoded.estimate(&*key), 3);
+//! ```
+//!
 //! # Serialization
 //!
 //! ```
@@ -99,10 +122,12 @@
 //! ```
 
 mod reverse_purge_item_hash_map;
-mod serialization;
+pub(crate) mod serialization;
 mod sketch;
 
 pub use self::serialization::FrequentItemValue;
 pub use self::sketch::ErrorType;
+pub use self::sketch::FrequentItemsIter;
 pub use self::sketch::FrequentItemsSketch;
+pub use self::sketch::FrequentItemsSketchBuilder;
 pub use self::sketch::Row;
@@ -16,38 +16,51 @@
 // under the License.
 
 use std::hash::Hash;
+use std::sync::Arc;
 
 use crate::codec::SketchBytes;
 use crate::codec::SketchSlice;
+use crate::codec::format;
 use crate::error::Error;
 
 /// Serialization version.
-pub const SERIAL_VERSION: u8 = 1;
+pub const SERIAL_VERSION: u8 = format::frequencies::SERIAL_VERSION;
 
 /// Preamble longs for empty sketch.
-pub const PREAMBLE_LONGS_EMPTY: u8 = 1;
+pub const PREAMBLE_LONGS_EMPTY: u8 = format::frequencies::PREAMBLE_LONGS_EMPTY;
 /// Preamble longs for non-empty sketch.
-pub const PREAMBLE_LONGS_NONEMPTY: u8 = 4;
+pub const PREAMBLE_LONGS_NONEMPTY: u8 = format::frequencies::PREAMBLE_LONGS_NONEMPTY;
 
 /// Empty flag mask (both bits for compatibility).
-pub const EMPTY_FLAG_MASK: u8 = 5;
+pub const EMPTY_FLAG_MASK: u8 = format::frequencies::FLAGS_IS_EMPTY;
 
 /// Trait for serializing and deserializing frequent item values.
 pub trait FrequentItemValue: Sized + Eq + Hash {
+    /// Identifier of the item encoding, stored in bytes 6-7 of the serialized preamble.
+    ///
+    /// Deserializing an image whose stored id differs from the reader's id fails with an error
+    /// instead of decoding the items with the wrong encoding (e.g. reading `i64` items as
+    /// `String`s). `0` means unspecified and disables the check; it is the default, and it is
+    /// what Java and C++ write, since they leave these bytes unused. Ids `1..=255` are reserved
+    /// for the built-in implementations.
+    const SERDE_ID: u16 = 0;
+
     /// Returns the size in bytes required to serialize the given item.
     fn serialize_size(item: &Self) -> usize;
     /// Serializes the item into the given byte buffer.
-    fn serialize_value(&self, bytes: &mut SketchBytes);
+    fn serialize_value(&self, bytes: &mut SketchBytes<'_>);
     /// Deserializes an item from the given byte cursor.
     fn deserialize_value(cursor: &mut SketchSlice<'_>) -> Result<Self, Error>;
 }
 
 impl FrequentItemValue for String {
+    const SERDE_ID: u16 = 3;
+
     fn serialize_size(item: &Self) -> usize {
         size_of::<u32>() + item.len()
     }
 
-    fn serialize_value(&self, bytes: &mut SketchBytes) {
+    fn serialize_value(&self, bytes: &mut SketchBytes<'_>) {
         let bs = self.as_bytes();
         bytes.write_u32_le(bs.len() as u32);
         bytes.write(bs);
@@ -68,14 +81,36 @@ impl FrequentItemValue for String {
     }
 }
 
+/// Shared strings use the same encoding and serde id as [`String`], so images are
+/// interchangeable between `FrequentItemsSketch<String>` and `FrequentItemsSketch<Arc<str>>`.
+impl FrequentItemValue for Arc<str> {
+    const SERDE_ID: u16 = String::SERDE_ID;
+
+    fn serialize_size(item: &Self) -> usize {
+        size_of::<u32>() + item.len()
+    }
+
+    fn serialize_value(&self, bytes: &mut SketchBytes<'_>) {
+        let bs = self.as_bytes();
+        bytes.write_u32_le(bs.len() as u32);
+        bytes.write(bs);
+    }
+
+    fn deserialize_value(cursor: &mut SketchSlice<'_>) -> Result<Self, Error> {
+        String::deserialize_value(cursor).map(Arc::from)
+    }
+}
+
 macro_rules! impl_primitive {
-    ($name:ty, $read:ident, $write:ident) => {
+    ($name:ty, $serde_id:expr, $read:ident, $write:ident) => {
         impl FrequentItemValue for $name {
+            const SERDE_ID: u16 = $serde_id;
+
             fn serialize_size(_item: &Self) -> usize {
                 size_of::<$name>()
             }
 
-            fn serialize_value(&self, bytes: &mut SketchBytes) {
+            fn serialize_value(&self, bytes: &mut SketchBytes<'_>) {
                 bytes.$write(*self);
             }
 
@@ -90,5 +125,5 @@ macro_rules! impl_primitive {
     };
 }
 
-impl_primitive!(i64, read_i64_le, write_i64_le);
-impl_primitive!(u64, read_u64_le, write_u64_le);
+impl_primitive!(i64, 1, read_i64_le, write_i64_le);
+impl_primitive!(u64, 2, read_u64_le, write_u64_le);
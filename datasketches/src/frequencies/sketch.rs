@@ -19,26 +19,31 @@
 
 use std::borrow::Borrow;
 use std::hash::Hash;
+use std::marker::PhantomData;
 
 use crate::codec::SketchBytes;
 use crate::codec::SketchSlice;
 use crate::codec::assert::ensure_preamble_longs_in;
-use crate::codec::assert::ensure_serial_version_is;
 use crate::codec::assert::insufficient_data;
 use crate::codec::family::Family;
+use crate::common::MemoryUsage;
+use crate::common::SketchBuilder;
 use crate::error::Error;
 use crate::frequencies::FrequentItemValue;
 use crate::frequencies::reverse_purge_item_hash_map::ReversePurgeItemHashMap;
+use crate::frequencies::reverse_purge_item_hash_map::ReversePurgeItemIter;
 use crate::frequencies::serialization::EMPTY_FLAG_MASK;
 use crate::frequencies::serialization::PREAMBLE_LONGS_EMPTY;
 use crate::frequencies::serialization::PREAMBLE_LONGS_NONEMPTY;
 use crate::frequencies::serialization::SERIAL_VERSION;
 
 type CountSerializeSize<T> = fn(&T) -> usize;
-type SerializeItem<T> = fn(&mut SketchBytes, &T);
+type SerializeItem<T> = fn(&mut SketchBytes<'_>, &T);
 type DeserializeItems<T> = fn(SketchSlice<'_>, usize) -> Result<Vec<T>, Error>;
 
 const LG_MIN_MAP_SIZE: u8 = 3;
+/// Largest lg_max_map_size accepted on deserialization, so that the map capacity fits in `usize`.
+const MAX_LG_MAP_SIZE: u8 = usize::BITS as u8 - 2;
 const SAMPLE_SIZE: usize = 1024;
 const EPSILON_FACTOR: f64 = 3.5;
 const LOAD_FACTOR_NUMERATOR: usize = 3;
@@ -86,6 +91,53 @@ impl<T> Row<T> {
     }
 }
 
+impl<T: Clone> Row<&T> {
+    /// Returns a row that owns a clone of the item.
+    pub fn cloned(&self) -> Row<T> {
+        Row {
+            item: self.item.clone(),
+            estimate: self.estimate,
+            upper_bound: self.upper_bound,
+            lower_bound: self.lower_bound,
+        }
+    }
+}
+
+/// Iterator over the rows of a frequent items query.
+///
+/// Created by [`FrequentItemsSketch::iter_frequent_items`] and
+/// [`FrequentItemsSketch::iter_frequent_items_with_threshold`].
+pub struct FrequentItemsIter<'a, T> {
+    entries: ReversePurgeItemIter<'a, T>,
+    error_type: ErrorType,
+    threshold: u64,
+    offset: u64,
+}
+
+impl<'a, T> Iterator for FrequentItemsIter<'a, T> {
+    type Item = Row<&'a T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for (item, count) in self.entries.by_ref() {
+            let lower = count;
+            let upper = count + self.offset;
+            let include = match self.error_type {
+                ErrorType::NoFalseNegatives => upper > self.threshold,
+                ErrorType::NoFalsePositives => lower > self.threshold,
+            };
+            if include {
+                return Some(Row {
+                    item,
+                    estimate: upper,
+                    upper_bound: upper,
+                    lower_bound: lower,
+                });
+            }
+        }
+        None
+    }
+}
+
 /// Frequent items sketch for generic item types.
 ///
 /// The sketch tracks approximate item frequencies and can return estimates with
@@ -99,10 +151,31 @@ pub struct FrequentItemsSketch<T> {
     offset: u64,
     stream_weight: u64,
     sample_size: usize,
+    num_purges: u32,
     hash_map: ReversePurgeItemHashMap<T>,
 }
 
 impl<T: Eq + Hash> FrequentItemsSketch<T> {
+    /// Returns a builder.
+    ///
+    /// The maximum map size has no default and must be set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::frequencies::FrequentItemsSketch;
+    /// let sketch = FrequentItemsSketch::<i64>::builder()
+    ///     .max_map_size(64)
+    ///     .build();
+    /// assert!(sketch.is_empty());
+    /// ```
+    pub fn builder() -> FrequentItemsSketchBuilder<T> {
+        FrequentItemsSketchBuilder {
+            max_map_size: None,
+            _item: PhantomData,
+        }
+    }
+
     /// Creates a new sketch with the given maximum map size (power of two).
     ///
     /// The maximum map capacity is `0.75 * max_map_size`, and the internal map grows
@@ -201,6 +274,32 @@ impl<T: Eq + Hash> FrequentItemsSketch<T> {
         self.offset
     }
 
+    /// Returns the number of purges that contributed to this sketch.
+    ///
+    /// A purge happens each time the map is full at its maximum size, and is what makes the
+    /// [`maximum_error`](Self::maximum_error) grow. The count includes the purges of merged
+    /// sketches and saturates at `u32::MAX`. It is diagnostic only and never affects estimates.
+    ///
+    /// Non-empty sketches persist the count in bytes 12-15 of the serialized preamble, which Java
+    /// and C++ leave unused; empty images have no room for it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::frequencies::FrequentItemsSketch;
+    /// let mut sketch = FrequentItemsSketch::<i64>::new(8);
+    /// sketch.update(1);
+    /// assert_eq!(sketch.num_purges(), 0);
+    ///
+    /// for i in 0..100 {
+    ///     sketch.update(i);
+    /// }
+    /// assert!(sketch.num_purges() > 0);
+    /// ```
+    pub fn num_purges(&self) -> u32 {
+        self.num_purges
+    }
+
     /// Returns epsilon for this sketch.
     pub fn epsilon(&self) -> f64 {
         Self::epsilon_for_lg(self.lg_max_map_size)
@@ -258,6 +357,12 @@ impl<T: Eq + Hash> FrequentItemsSketch<T> {
     ///
     /// A count of zero is a no-op.
     ///
+    /// The fallible version of this method is [`FrequentItemsSketch::try_update_with_count`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the total weight of the stream would overflow `u64`.
+    ///
     /// # Examples
     ///
     /// ```
@@ -270,12 +375,41 @@ impl<T: Eq + Hash> FrequentItemsSketch<T> {
         if count == 0 {
             return;
         }
-        assert!(count > 0, "count may not be negative");
+        self.assert_stream_weight_fits(count);
         self.stream_weight += count;
         self.hash_map.adjust_or_put_value(item, count);
         self.maybe_resize_or_purge();
     }
 
+    /// Updates the sketch with an item and count.
+    ///
+    /// A count of zero is a no-op. The panicking version of this method is
+    /// [`FrequentItemsSketch::update_with_count`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the total weight of the stream would overflow `u64`. The sketch is left
+    /// unchanged in that case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::frequencies::FrequentItemsSketch;
+    /// let mut sketch = FrequentItemsSketch::<i64>::new(64);
+    /// sketch.try_update_with_count(10, u64::MAX).unwrap();
+    /// assert!(sketch.try_update_with_count(11, 1).is_err());
+    /// assert_eq!(sketch.total_weight(), u64::MAX);
+    /// ```
+    pub fn try_update_with_count(&mut self, item: T, count: u64) -> Result<(), Error> {
+        if count == 0 {
+            return Ok(());
+        }
+        self.stream_weight = self.checked_stream_weight(count)?;
+        self.hash_map.adjust_or_put_value(item, count);
+        self.maybe_resize_or_purge();
+        Ok(())
+    }
+
     /// Updates the sketch with a borrowed item and a count of one.
     ///
     /// Equivalent to [`update`](Self::update) but takes the item by reference and
@@ -305,6 +439,10 @@ impl<T: Eq + Hash> FrequentItemsSketch<T> {
     /// item by reference and only allocates an owned item when it is newly
     /// inserted. A count of zero is a no-op.
     ///
+    /// # Panics
+    ///
+    /// Panics if the total weight of the stream would overflow `u64`.
+    ///
     /// # Examples
     ///
     /// ```
@@ -321,7 +459,7 @@ impl<T: Eq + Hash> FrequentItemsSketch<T> {
         if count == 0 {
             return;
         }
-        assert!(count > 0, "count may not be negative");
+        self.assert_stream_weight_fits(count);
         self.stream_weight += count;
         self.hash_map.adjust_or_put_value_ref(item, count);
         self.maybe_resize_or_purge();
@@ -332,6 +470,12 @@ impl<T: Eq + Hash> FrequentItemsSketch<T> {
     /// The other sketch may have a different map size. The merged sketch respects the
     /// larger error tolerance of the inputs.
     ///
+    /// The fallible version of this method is [`FrequentItemsSketch::try_merge`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the combined total weight of the two sketches would overflow `u64`.
+    ///
     /// # Examples
     ///
     /// ```
@@ -350,11 +494,52 @@ impl<T: Eq + Hash> FrequentItemsSketch<T> {
         if other.is_empty() {
             return;
         }
-        let merged_total = self.stream_weight + other.stream_weight;
+        self.assert_stream_weight_fits(other.stream_weight);
+        self.merge_items(other, self.stream_weight + other.stream_weight);
+    }
+
+    /// Merges another sketch into this one.
+    ///
+    /// The panicking version of this method is [`FrequentItemsSketch::merge`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the combined total weight of the two sketches would overflow `u64`.
+    /// The sketch is left unchanged in that case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::frequencies::FrequentItemsSketch;
+    /// let mut left = FrequentItemsSketch::<i64>::new(64);
+    /// let mut right = FrequentItemsSketch::<i64>::new(64);
+    /// left.update_with_count(1, u64::MAX - 1);
+    /// right.update_with_count(2, 2);
+    /// assert!(left.try_merge(&right).is_err());
+    /// assert_eq!(left.total_weight(), u64::MAX - 1);
+    /// ```
+    pub fn try_merge(&mut self, other: &Self) -> Result<(), Error>
+    where
+        T: Clone,
+    {
+        if other.is_empty() {
+            return Ok(());
+        }
+        let merged_total = self.checked_stream_weight(other.stream_weight)?;
+        self.merge_items(other, merged_total);
+        Ok(())
+    }
+
+    /// Merges the items of `other`, then sets the stream weight to `merged_total`.
+    fn merge_items(&mut self, other: &Self, merged_total: u64)
+    where
+        T: Clone,
+    {
         for (item, count) in other.hash_map.iter() {
             self.update_with_count_ref(item, count);
         }
         self.offset += other.offset;
+        self.num_purges = self.num_purges.saturating_add(other.num_purges);
         self.stream_weight = merged_total;
     }
 
@@ -363,6 +548,13 @@ impl<T: Eq + Hash> FrequentItemsSketch<T> {
         *self = Self::with_lg_map_sizes(self.lg_max_map_size, LG_MIN_MAP_SIZE);
     }
 
+    /// Returns the estimated size of the sketch in bytes.
+    ///
+    /// Heap memory owned by the items themselves (e.g. the bytes of a `String`) is not included.
+    pub fn estimated_size(&self) -> usize {
+        size_of::<Self>() + self.hash_map.estimated_size()
+    }
+
     /// Returns frequent items using the sketch maximum error as threshold.
     ///
     /// This is equivalent to `frequent_items_with_threshold(error_type, self.maximum_error())`.
@@ -411,36 +603,151 @@ impl<T: Eq + Hash> FrequentItemsSketch<T> {
     where
         T: Clone,
     {
-        let threshold = threshold.max(self.offset);
-        let mut rows = vec![];
-        for (item, count) in self.hash_map.iter() {
-            let lower = count;
-            let upper = count + self.offset;
-            let include = match error_type {
-                ErrorType::NoFalseNegatives => upper > threshold,
-                ErrorType::NoFalsePositives => lower > threshold,
-            };
-            if include {
-                rows.push(Row {
-                    item: item.clone(),
-                    estimate: upper,
-                    upper_bound: upper,
-                    lower_bound: lower,
-                });
-            }
-        }
+        let mut rows = self
+            .iter_frequent_items_with_threshold(error_type, threshold)
+            .map(|row| row.cloned())
+            .collect::<Vec<_>>();
         rows.sort_by_key(|row| std::cmp::Reverse(row.estimate));
         rows
     }
 
+    /// Returns an iterator over the frequent items, using the sketch maximum error as threshold.
+    ///
+    /// This is the lazy counterpart of [`frequent_items`](Self::frequent_items): rows borrow their
+    /// items and are computed as the iterator advances, in no particular order. Filtering the
+    /// iterator therefore runs before anything is cloned or collected, which is cheaper than
+    /// materializing every row of a large map when only a few of them are wanted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::frequencies::ErrorType;
+    /// # use datasketches::frequencies::FrequentItemsSketch;
+    /// let mut sketch = FrequentItemsSketch::<String>::new(64);
+    /// sketch.update_with_count("apple".to_string(), 50);
+    /// sketch.update_with_count("banana".to_string(), 5);
+    /// sketch.update("cherry".to_string());
+    /// let heavy = sketch
+    ///     .iter_frequent_items(ErrorType::NoFalsePositives)
+    ///     .filter(|row| row.lower_bound() >= 10)
+    ///     .map(|row| row.item().to_string())
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(heavy, ["apple"]);
+    /// ```
+    pub fn iter_frequent_items(&self, error_type: ErrorType) -> FrequentItemsIter<'_, T> {
+        self.iter_frequent_items_with_threshold(error_type, self.offset)
+    }
+
+    /// Returns an iterator over the frequent items, using a custom threshold.
+    ///
+    /// Items are selected exactly as in
+    /// [`frequent_items_with_threshold`](Self::frequent_items_with_threshold), but the rows are
+    /// computed lazily and yielded in no particular order.
+    pub fn iter_frequent_items_with_threshold(
+        &self,
+        error_type: ErrorType,
+        threshold: u64,
+    ) -> FrequentItemsIter<'_, T> {
+        FrequentItemsIter {
+            entries: self.hash_map.iter(),
+            error_type,
+            threshold: threshold.max(self.offset),
+            offset: self.offset,
+        }
+    }
+
+    /// Returns the number of frequent items above a custom threshold.
+    ///
+    /// Items are counted exactly as
+    /// [`frequent_items_with_threshold`](Self::frequent_items_with_threshold) selects them, but no
+    /// rows are cloned or collected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::frequencies::ErrorType;
+    /// # use datasketches::frequencies::FrequentItemsSketch;
+    /// let mut sketch = FrequentItemsSketch::<i64>::new(64);
+    /// sketch.update_with_count(1, 50);
+    /// sketch.update_with_count(2, 20);
+    /// sketch.update(3);
+    /// assert_eq!(
+    ///     sketch.count_frequent_items(ErrorType::NoFalsePositives, 10),
+    ///     2
+    /// );
+    /// ```
+    pub fn count_frequent_items(&self, error_type: ErrorType, threshold: u64) -> usize {
+        self.iter_frequent_items_with_threshold(error_type, threshold)
+            .count()
+    }
+
+    /// Returns true if the item is frequent above a custom threshold, without false positives.
+    ///
+    /// This is true when the item would be returned by
+    /// [`frequent_items_with_threshold`](Self::frequent_items_with_threshold) with
+    /// [`ErrorType::NoFalsePositives`], that is when its `lower_bound` exceeds `threshold`. If
+    /// `threshold` is less than `maximum_error`, `maximum_error` is used instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::frequencies::FrequentItemsSketch;
+    /// let mut sketch = FrequentItemsSketch::<String>::new(64);
+    /// sketch.update_with_count("apple".to_string(), 50);
+    /// sketch.update("banana".to_string());
+    /// assert!(sketch.contains_frequent("apple", 10));
+    /// assert!(!sketch.contains_frequent("banana", 10));
+    /// assert!(!sketch.contains_frequent("cherry", 10));
+    /// ```
+    pub fn contains_frequent<Q>(&self, item: &Q, threshold: u64) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.lower_bound(item) > threshold.max(self.offset)
+    }
+
+    #[track_caller]
+    fn assert_stream_weight_fits(&self, count: u64) {
+        assert!(
+            count <= u64::MAX - self.stream_weight,
+            "total stream weight overflows u64: {} + {}",
+            self.stream_weight,
+            count
+        );
+    }
+
+    fn checked_stream_weight(&self, count: u64) -> Result<u64, Error> {
+        self.stream_weight.checked_add(count).ok_or_else(|| {
+            Error::invalid_argument(format!(
+                "total stream weight overflows u64: {} + {}",
+                self.stream_weight, count
+            ))
+        })
+    }
+
     fn maybe_resize_or_purge(&mut self) {
         if self.hash_map.num_active() > self.cur_map_cap {
             if self.hash_map.lg_length() < self.lg_max_map_size {
                 self.hash_map.resize(self.hash_map.len() * 2);
                 self.cur_map_cap = self.hash_map.capacity();
+                #[cfg(feature = "tracing")]
+                tracing::debug!(
+                    lg_map_size = self.hash_map.lg_length(),
+                    num_active = self.hash_map.num_active(),
+                    "resized frequent items map"
+                );
             } else {
                 let delta = self.hash_map.purge(self.sample_size);
                 self.offset += delta;
+                self.num_purges = self.num_purges.saturating_add(1);
+                #[cfg(feature = "tracing")]
+                tracing::debug!(
+                    num_active = self.hash_map.num_active(),
+                    offset = self.offset,
+                    num_purges = self.num_purges,
+                    "purged frequent items map"
+                );
                 if self.hash_map.num_active() > self.maximum_map_capacity() {
                     panic!("purge did not reduce number of active items");
                 }
@@ -465,31 +772,34 @@ impl<T: Eq + Hash> FrequentItemsSketch<T> {
             offset: 0,
             stream_weight: 0,
             sample_size,
+            num_purges: 0,
             hash_map: map,
         }
     }
 
     fn serialize_inner(
         &self,
+        bytes: &mut SketchBytes<'_>,
+        serde_id: u16,
         count_serialize_size: CountSerializeSize<T>,
         serialize_item: SerializeItem<T>,
-    ) -> Vec<u8> {
+    ) {
         if self.is_empty() {
-            let mut bytes = SketchBytes::with_capacity(PREAMBLE_LONGS_EMPTY as usize * 8);
+            bytes.reserve(PREAMBLE_LONGS_EMPTY as usize * 8);
             bytes.write_u8(PREAMBLE_LONGS_EMPTY);
             bytes.write_u8(SERIAL_VERSION);
             bytes.write_u8(Family::FREQUENCY.id);
             bytes.write_u8(self.lg_max_map_size);
             bytes.write_u8(self.hash_map.lg_length());
             bytes.write_u8(EMPTY_FLAG_MASK);
-            bytes.write_u16_le(0); // unused
-            return bytes.into_bytes();
+            bytes.write_u16_le(serde_id);
+            return;
         }
 
         let active_items = self.num_active_items();
         let active_entries = self.hash_map.active_entries();
 
-        let mut bytes = SketchBytes::with_capacity({
+        bytes.reserve({
             let mut total_bytes = 0;
             total_bytes += PREAMBLE_LONGS_NONEMPTY as usize * 8;
             total_bytes += active_items * 8;
@@ -504,10 +814,10 @@ impl<T: Eq + Hash> FrequentItemsSketch<T> {
         bytes.write_u8(self.lg_max_map_size);
         bytes.write_u8(self.hash_map.lg_length());
         bytes.write_u8(0); // flags
-        bytes.write_u16_le(0); // unused
+        bytes.write_u16_le(serde_id);
 
         bytes.write_u32_le(active_items as u32);
-        bytes.write_u32_le(0); // unused
+        bytes.write_u32_le(self.num_purges);
         bytes.write_u64_le(self.stream_weight);
         bytes.write_u64_le(self.offset);
 
@@ -515,14 +825,13 @@ impl<T: Eq + Hash> FrequentItemsSketch<T> {
             bytes.write_u64_le(*v);
         }
         for (k, _) in &active_entries {
-            serialize_item(&mut bytes, k);
+            serialize_item(bytes, k);
         }
-
-        bytes.into_bytes()
     }
 
     fn deserialize_inner(
         bytes: &[u8],
+        serde_id: u16,
         deserialize_items: DeserializeItems<T>,
     ) -> Result<Self, Error> {
         let mut cursor = SketchSlice::new(bytes);
@@ -539,12 +848,22 @@ impl<T: Eq + Hash> FrequentItemsSketch<T> {
             .read_u8()
             .map_err(insufficient_data("lg_cur_map_size"))?;
         let flags = cursor.read_u8().map_err(insufficient_data("flags"))?;
-        cursor
+        let stored_serde_id = cursor
             .read_u16_le()
-            .map_err(insufficient_data("<unused>"))?;
+            .map_err(insufficient_data("serde_id"))?;
 
         Family::FREQUENCY.validate_id(family)?;
-        ensure_serial_version_is(SERIAL_VERSION, serial_version)?;
+        Family::FREQUENCY.validate_serial_version(serial_version)?;
+        if stored_serde_id != 0 && serde_id != 0 && stored_serde_id != serde_id {
+            return Err(Error::deserial(format!(
+                "item serde id mismatch: serialized with {stored_serde_id}, reading with {serde_id}"
+            )));
+        }
+        if lg_max > MAX_LG_MAP_SIZE {
+            return Err(Error::deserial(format!(
+                "lg_max_map_size must be at most {MAX_LG_MAP_SIZE}, got {lg_max}"
+            )));
+        }
         if lg_cur > lg_max {
             return Err(Error::deserial("lg_cur_map_size exceeds lg_max_map_size"));
         }
@@ -560,9 +879,10 @@ impl<T: Eq + Hash> FrequentItemsSketch<T> {
             .read_u32_le()
             .map_err(insufficient_data("active_items"))?;
         let active_items = active_items as usize;
-        cursor
+        // Unused by Java and C++, which normally write zeros here.
+        let num_purges = cursor
             .read_u32_le()
-            .map_err(insufficient_data("<unused>"))?;
+            .map_err(insufficient_data("num_purges"))?;
         let stream_weight = cursor
             .read_u64_le()
             .map_err(insufficient_data("stream_weight"))?;
@@ -590,10 +910,105 @@ impl<T: Eq + Hash> FrequentItemsSketch<T> {
         }
         sketch.stream_weight = stream_weight;
         sketch.offset = offset_val;
+        sketch.num_purges = num_purges;
         Ok(sketch)
     }
 }
 
+/// Updates the sketch with every `(item, count)` pair of the iterator.
+///
+/// The result is the same as calling
+/// [`update_with_count`](FrequentItemsSketch::update_with_count) for each pair, so pairs with a
+/// zero count are skipped. Since only new items can fill the map, the pairs are applied in runs
+/// as long as the free capacity of the map, and the map is checked for a resize or purge once per
+/// run rather than after every pair.
+///
+/// # Panics
+///
+/// Panics if the total weight of the stream would overflow `u64`.
+///
+/// # Examples
+///
+/// ```
+/// # use datasketches::frequencies::FrequentItemsSketch;
+/// let mut sketch = FrequentItemsSketch::<i64>::new(64);
+/// sketch.extend([(1, 3), (2, 1), (1, 2)]);
+/// assert_eq!(sketch.estimate(&1), 5);
+/// assert_eq!(sketch.total_weight(), 6);
+/// ```
+impl<T: Eq + Hash> Extend<(T, u64)> for FrequentItemsSketch<T> {
+    #[track_caller]
+    fn extend<I: IntoIterator<Item = (T, u64)>>(&mut self, iter: I) {
+        let mut iter = iter.into_iter().filter(|&(_, count)| count > 0);
+        loop {
+            // The map holds at most cur_map_cap active items between checks, and a check is due
+            // once it holds one more.
+            let run_len = self.cur_map_cap + 1 - self.hash_map.num_active();
+            let mut updated = false;
+            for (item, count) in iter.by_ref().take(run_len) {
+                self.assert_stream_weight_fits(count);
+                self.stream_weight += count;
+                self.hash_map.adjust_or_put_value(item, count);
+                updated = true;
+            }
+            if !updated {
+                break;
+            }
+            self.maybe_resize_or_purge();
+        }
+    }
+}
+
+/// Builder for [`FrequentItemsSketch`].
+///
+/// Created by [`FrequentItemsSketch::builder`]. The parameters are validated when the sketch is
+/// built.
+#[derive(Debug, Clone)]
+pub struct FrequentItemsSketchBuilder<T> {
+    max_map_size: Option<usize>,
+    _item: PhantomData<fn() -> T>,
+}
+
+impl<T: Eq + Hash> FrequentItemsSketchBuilder<T> {
+    /// Set the maximum map size, which must be a power of two.
+    ///
+    /// The maximum map capacity is `0.75 * max_map_size`.
+    pub fn max_map_size(mut self, max_map_size: usize) -> Self {
+        self.max_map_size = Some(max_map_size);
+        self
+    }
+
+    /// Build the FrequentItemsSketch.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the maximum map size is not set or is not a power of two.
+    pub fn build(self) -> FrequentItemsSketch<T> {
+        let max_map_size = self.max_map_size.expect("max_map_size must be set");
+        FrequentItemsSketch::new(max_map_size)
+    }
+}
+
+impl<T: Eq + Hash> SketchBuilder for FrequentItemsSketchBuilder<T> {
+    type Sketch = FrequentItemsSketch<T>;
+
+    fn try_build(self) -> Result<FrequentItemsSketch<T>, Error> {
+        match self.max_map_size {
+            None => Err(Error::invalid_argument("max_map_size must be set")),
+            Some(max_map_size) if !max_map_size.is_power_of_two() => Err(Error::invalid_argument(
+                format!("max_map_size must be power of 2, got {max_map_size}"),
+            )),
+            Some(_) => Ok(self.build()),
+        }
+    }
+}
+
+impl<T: Eq + Hash> MemoryUsage for FrequentItemsSketch<T> {
+    fn memory_usage(&self) -> usize {
+        self.estimated_size()
+    }
+}
+
 impl<T: FrequentItemValue> FrequentItemsSketch<T> {
     /// Serializes this sketch into a byte vector.
     ///
@@ -622,11 +1037,49 @@ impl<T: FrequentItemValue> FrequentItemsSketch<T> {
     /// assert!(decoded.estimate(&apple) >= 2);
     /// ```
     pub fn serialize(&self) -> Vec<u8> {
-        self.serialize_inner(T::serialize_size, |bytes, item| item.serialize_value(bytes))
+        let mut bytes = SketchBytes::with_capacity(0);
+        self.serialize_inner(&mut bytes, T::SERDE_ID, T::serialize_size, |bytes, item| {
+            item.serialize_value(bytes)
+        });
+        bytes.into_bytes()
+    }
+
+    /// Serializes this sketch into `buf`, returning the number of bytes written.
+    ///
+    /// Writes the same bytes as [`serialize`](Self::serialize) without allocating the output.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `buf` is too short to hold the serialized sketch. The sketch is
+    /// written up to the end of `buf` before the length is checked, so the contents of `buf`
+    /// are unspecified when an error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::frequencies::FrequentItemsSketch;
+    /// # let mut sketch = FrequentItemsSketch::<i64>::new(64);
+    /// # sketch.update_with_count(7, 2);
+    /// let mut buf = vec![0u8; 1024];
+    /// let len = sketch.serialize_into_slice(&mut buf).unwrap();
+    /// let decoded = FrequentItemsSketch::<i64>::deserialize(&buf[..len]).unwrap();
+    /// assert!(decoded.estimate(&7) >= 2);
+    /// ```
+    pub fn serialize_into_slice(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let mut bytes = SketchBytes::from_slice(buf);
+        self.serialize_inner(&mut bytes, T::SERDE_ID, T::serialize_size, |bytes, item| {
+            item.serialize_value(bytes)
+        });
+        bytes.finish()
     }
 
     /// Deserializes a sketch from bytes.
     ///
+    /// # Errors
+    ///
+    /// Returns an error if the bytes are not a valid frequent items sketch image, or if the image
+    /// records a different [`FrequentItemValue::SERDE_ID`] than `T`.
+    ///
     /// # Examples
     ///
     /// Built-in support for `i64`:
@@ -652,7 +1105,7 @@ impl<T: FrequentItemValue> FrequentItemsSketch<T> {
     /// assert!(decoded.estimate(&apple) >= 2);
     /// ```
     pub fn deserialize(bytes: &[u8]) -> Result<Self, Error> {
-        Self::deserialize_inner(bytes, |mut cursor, num_items| {
+        Self::deserialize_inner(bytes, T::SERDE_ID, |mut cursor, num_items| {
             let mut items = Vec::with_capacity(num_items);
             for i in 0..num_items {
                 let item = T::deserialize_value(&mut cursor).map_err(|_| {
@@ -15,24 +15,90 @@
 // specific language governing permissions and limitations
 // under the License.
 
-#[cfg(any(
-    feature = "countmin",
-    feature = "cpc",
-    feature = "frequencies",
-    feature = "hll",
-    feature = "theta",
-    feature = "tuple",
-))]
+//! Hash functions shared with the other DataSketches implementations.
+//!
+//! Sketches other than the Bloom filter hash their items with [`MurmurHash3X64128`], the 128-bit
+//! x64 variant of MurmurHash3, seeded with [`DEFAULT_UPDATE_SEED`] unless configured otherwise.
+//! Exposing the hasher lets external systems precompute hashes that agree with the sketches, and
+//! check that they agree with the Java and C++ libraries.
+//!
+//! # Byte order
+//!
+//! The hash is a function of the bytes written into the hasher:
+//!
+//! * [`Hasher::write`](std::hash::Hasher::write) appends the given bytes as-is, so splitting the
+//!   input over several writes does not change the hash.
+//! * The integer methods, such as `write_u64` and `write_i32`, append the little-endian bytes of
+//!   the value, on every target. `usize` and `isize` are always written as 8 bytes.
+//! * [`MurmurHash3X64128::finish128`] returns `(h1, h2)`, the two 64-bit halves in the order the
+//!   Java `MurmurHash3.hash` methods return them, and [`Hasher::finish`](std::hash::Hasher::finish)
+//!   returns `h1`.
+//!
+//! Hashing a `long[]` in Java is therefore the same as writing each value with `write_i64`, and
+//! hashing a `byte[]` or a UTF-8 string is the same as a single `write` of its bytes. Note that
+//! Rust's [`Hash`](std::hash::Hash) implementations of strings and slices write a length prefix
+//! or terminator too; see [`crate::hash_value`] for wrappers that hash raw bytes instead.
+//!
+//! # Examples
+//!
+//! ```
+//! # use std::hash::Hasher;
+//! # use datasketches::hash::DEFAULT_UPDATE_SEED;
+//! # use datasketches::hash::MurmurHash3X64128;
+//! let mut hasher = MurmurHash3X64128::with_seed(DEFAULT_UPDATE_SEED);
+//! hasher.write_u64(42);
+//! let (h1, h2) = hasher.finish128();
+//!
+//! // Integers are written as their little-endian bytes
+//! let mut bytes = MurmurHash3X64128::with_seed(DEFAULT_UPDATE_SEED);
+//! bytes.write(&42_u64.to_le_bytes());
+//! assert_eq!(bytes.finish128(), (h1, h2));
+//! ```
+
+use std::collections::BTreeMap;
+use std::sync::PoisonError;
+use std::sync::RwLock;
+
+/// Implements the integer methods of [`Hasher`](std::hash::Hasher) by writing little-endian
+/// bytes, so that hashes of integer values do not depend on the target's endianness or pointer
+/// width. `usize` and `isize` are always written as 8 bytes.
+macro_rules! impl_hasher_write_integers_le {
+    () => {
+        impl_hasher_write_integers_le!(
+            write_u8(u8),
+            write_u16(u16),
+            write_u32(u32),
+            write_u64(u64),
+            write_u128(u128),
+            write_i8(i8),
+            write_i16(i16),
+            write_i32(i32),
+            write_i64(i64),
+            write_i128(i128),
+        );
+
+        #[inline]
+        fn write_usize(&mut self, i: usize) {
+            self.write_u64(i as u64);
+        }
+
+        #[inline]
+        fn write_isize(&mut self, i: isize) {
+            self.write_i64(i as i64);
+        }
+    };
+    ($($method:ident($ty:ty)),* $(,)?) => {
+        $(
+            #[inline]
+            fn $method(&mut self, i: $ty) {
+                self.write(&i.to_le_bytes());
+            }
+        )*
+    };
+}
+
 mod murmurhash;
-#[cfg(any(
-    feature = "countmin",
-    feature = "cpc",
-    feature = "frequencies",
-    feature = "hll",
-    feature = "theta",
-    feature = "tuple",
-))]
-pub(crate) use self::murmurhash::MurmurHash3X64128;
+pub use self::murmurhash::MurmurHash3X64128;
 
 #[cfg(feature = "bloom")]
 mod xxhash;
@@ -52,32 +118,29 @@ pub(crate) use self::xxhash::XxHash64;
 /// and seed are identical for both sketches, otherwise the assumed 1:1 relationship between the
 /// original source key value and the hashed bit string would be violated. Once you have developed
 /// a history of stored sketches you are stuck with it.
-#[cfg(any(
-    feature = "bloom",
-    feature = "countmin",
-    feature = "cpc",
-    feature = "frequencies",
-    feature = "hll",
-    feature = "theta",
-    feature = "tuple",
-))]
-pub(crate) const DEFAULT_UPDATE_SEED: u64 = 9001;
+pub const DEFAULT_UPDATE_SEED: u64 = 9001;
 
 /// Computes and checks the 16-bit seed hash from the given long seed.
 ///
+/// The seed hash is the low 16 bits of `h1` of the [`MurmurHash3X64128`] hash, with seed 0, of
+/// the 8 little-endian bytes of `seed`, like `computeSeedHash` in Java. Serialized sketches store
+/// it so that sketches built with different seeds are not merged by mistake.
+///
 /// The computed seed hash must not be zero in order to maintain compatibility with older
 /// serialized versions that did not have this concept.
 ///
 /// # Panics
 ///
 /// Panics if the computed seed hash is zero.
-#[cfg(any(
-    feature = "countmin",
-    feature = "cpc",
-    feature = "theta",
-    feature = "tuple",
-))]
-pub(crate) fn compute_seed_hash(seed: u64) -> u16 {
+///
+/// # Examples
+///
+/// ```
+/// # use datasketches::hash::DEFAULT_UPDATE_SEED;
+/// # use datasketches::hash::compute_seed_hash;
+/// assert_eq!(compute_seed_hash(DEFAULT_UPDATE_SEED), 0x93cc);
+/// ```
+pub fn compute_seed_hash(seed: u64) -> u16 {
     use std::hash::Hasher;
 
     let mut hasher = MurmurHash3X64128::with_seed(0);
@@ -88,20 +151,105 @@ pub(crate) fn compute_seed_hash(seed: u64) -> u16 {
     seed_hash
 }
 
-/// Reads an u64 from a byte slice in little-endian order.
+/// Derives a seed from a phrase, such as a team or dataset name.
+///
+/// The seed is `h1` of the [`MurmurHash3X64128`] hash, with seed 0, of the UTF-8 bytes of
+/// `phrase`; in Java, `MurmurHash3.hash(phrase.getBytes(UTF_8), 0)[0]`. Deriving seeds from
+/// phrases makes them easy to share between teams that keep separate sketch populations, and
+/// [`register_seed_name`] can name them in seed hash mismatch errors.
+///
+/// As with any seed, sketches built with a phrase seed can only be merged with sketches built
+/// with the same seed.
+///
+/// # Examples
+///
+/// ```
+/// # use datasketches::hash::compute_seed_hash;
+/// # use datasketches::hash::seed_from_str;
+/// let seed = seed_from_str("teamA-2024");
+/// assert_eq!(seed, seed_from_str("teamA-2024"));
+/// assert_ne!(seed, seed_from_str("teamB-2024"));
+/// assert_ne!(compute_seed_hash(seed), 0);
+/// ```
+pub fn seed_from_str(phrase: &str) -> u64 {
+    use std::hash::Hasher;
+
+    let mut hasher = MurmurHash3X64128::with_seed(0);
+    hasher.write(phrase.as_bytes());
+    hasher.finish()
+}
+
+/// Names of seed hashes registered with [`register_seed_name`].
+static SEED_NAMES: RwLock<BTreeMap<u16, String>> = RwLock::new(BTreeMap::new());
+
+/// Registers a name for `seed`, shown next to its seed hash in seed hash mismatch errors.
+///
+/// Errors about sketches built with different seeds only know the 16-bit seed hashes stored in
+/// the sketches. Registering the seeds in use, for example at startup, makes such errors name the
+/// seeds involved. The registry is process-wide; registering another name for the same seed hash
+/// replaces the previous name.
 ///
 /// # Panics
 ///
-/// Panics if `bytes.len()` is greater than 8.
+/// Panics if the seed hash of `seed` is zero, like [`compute_seed_hash`].
+///
+/// # Examples
+///
+/// ```
+/// # use datasketches::hash::DEFAULT_UPDATE_SEED;
+/// # use datasketches::hash::compute_seed_hash;
+/// # use datasketches::hash::register_seed_name;
+/// # use datasketches::hash::seed_from_str;
+/// # use datasketches::hash::seed_name;
+/// register_seed_name(seed_from_str("teamA-2024"), "teamA-2024");
+/// register_seed_name(DEFAULT_UPDATE_SEED, "default");
+/// assert_eq!(
+///     seed_name(compute_seed_hash(DEFAULT_UPDATE_SEED)).as_deref(),
+///     Some("default")
+/// );
+/// ```
+pub fn register_seed_name(seed: u64, name: impl Into<String>) {
+    let seed_hash = compute_seed_hash(seed);
+    let mut names = SEED_NAMES.write().unwrap_or_else(PoisonError::into_inner);
+    names.insert(seed_hash, name.into());
+}
+
+/// Returns the name registered for `seed_hash` with [`register_seed_name`], if any.
+pub fn seed_name(seed_hash: u16) -> Option<String> {
+    let names = SEED_NAMES.read().unwrap_or_else(PoisonError::into_inner);
+    names.get(&seed_hash).cloned()
+}
+
+/// Formats a seed hash for error messages, followed by its registered name if any.
 #[cfg(any(
-    feature = "bloom",
     feature = "countmin",
     feature = "cpc",
-    feature = "frequencies",
-    feature = "hll",
     feature = "theta",
-    feature = "tuple",
+    feature = "tuple"
 ))]
+pub(crate) fn named_seed_hash(seed_hash: u16) -> impl std::fmt::Display {
+    use std::fmt;
+
+    struct NamedSeedHash(u16);
+
+    impl fmt::Display for NamedSeedHash {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", self.0)?;
+            match seed_name(self.0) {
+                Some(name) => write!(f, " ({name:?})"),
+                None => Ok(()),
+            }
+        }
+    }
+
+    NamedSeedHash(seed_hash)
+}
+
+/// Reads an u64 from a byte slice in little-endian order.
+///
+/// # Panics
+///
+/// Panics if `bytes.len()` is greater than 8.
 fn read_u64_le(bytes: &[u8]) -> u64 {
     let mut buf = [0u8; 8];
     buf[..bytes.len()].copy_from_slice(bytes);
@@ -24,6 +24,9 @@ const C2: u64 = 0x4cf5ad432745937f;
 
 /// The MurmurHash3 is a fast, non-cryptographic, 128-bit hash function that has
 /// excellent avalanche and 2-way bit independence properties.
+///
+/// This is the x64 128-bit variant with a 64-bit seed, as used by the Java and C++ libraries. See
+/// the [module level documentation](super) for how values are turned into bytes.
 #[derive(Debug)]
 pub struct MurmurHash3X64128 {
     h1: u64,
@@ -34,6 +37,7 @@ pub struct MurmurHash3X64128 {
 }
 
 impl MurmurHash3X64128 {
+    /// Creates a hasher with the given seed.
     pub fn with_seed(seed: u64) -> Self {
         MurmurHash3X64128 {
             h1: seed,
@@ -44,18 +48,55 @@ impl MurmurHash3X64128 {
         }
     }
 
+    /// Returns the 128-bit hash of `bytes` with the given seed, as `(h1, h2)`.
+    ///
+    /// This is the same as a single [`write`](Hasher::write) of `bytes` into a hasher created with
+    /// [`with_seed`](Self::with_seed), followed by [`finish128`](Self::finish128), but reads the
+    /// blocks straight from `bytes` instead of going through the hasher's buffer. It is the
+    /// `MurmurHash3.hash(byte[], seed)` function of the Java library.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::hash::Hasher;
+    /// # use datasketches::hash::MurmurHash3X64128;
+    /// let mut hasher = MurmurHash3X64128::with_seed(9001);
+    /// hasher.write(b"apple");
+    /// assert_eq!(
+    ///     MurmurHash3X64128::hash_bytes(b"apple", 9001),
+    ///     hasher.finish128()
+    /// );
+    /// ```
+    pub fn hash_bytes(bytes: &[u8], seed: u64) -> (u64, u64) {
+        let mut hasher = Self::with_seed(seed);
+        let mut blocks = bytes.chunks_exact(16);
+        for block in &mut blocks {
+            let k1 = super::read_u64_le(&block[0..8]);
+            let k2 = super::read_u64_le(&block[8..16]);
+            hasher.update(k1, k2);
+        }
+        hasher.finish_with_tail(blocks.remainder())
+    }
+
+    /// Returns the 128-bit hash of the bytes written so far, as `(h1, h2)`.
     pub fn finish128(&self) -> (u64, u64) {
+        self.finish_with_tail(&self.buf[..self.buf_len])
+    }
+
+    /// Finishes the hash of the full blocks processed so far followed by `tail`, which must be
+    /// shorter than a block.
+    fn finish_with_tail(&self, tail: &[u8]) -> (u64, u64) {
         let mut h1 = self.h1;
         let mut h2 = self.h2;
 
-        let total = self.total + self.buf_len as u64;
-        let rem = self.buf_len;
+        let total = self.total + tail.len() as u64;
+        let rem = tail.len();
 
         // tail
         if rem > 0 {
             if rem > 8 {
                 // read k2 little endian
-                let mut k2 = super::read_u64_le(&self.buf[8..rem]);
+                let mut k2 = super::read_u64_le(&tail[8..rem]);
                 // mix k2
                 k2 = k2.wrapping_mul(C2);
                 k2 = k2.rotate_left(33);
@@ -65,7 +106,7 @@ impl MurmurHash3X64128 {
 
             // read k1 little endian
             let k1_len = rem.min(8);
-            let mut k1 = super::read_u64_le(&self.buf[..k1_len]);
+            let mut k1 = super::read_u64_le(&tail[..k1_len]);
             // mix k1
             k1 = k1.wrapping_mul(C1);
             k1 = k1.rotate_left(31);
@@ -124,6 +165,8 @@ impl Hasher for MurmurHash3X64128 {
         self.finish128().0
     }
 
+    impl_hasher_write_integers_le!();
+
     fn write(&mut self, mut bytes: &[u8]) {
         if self.buf_len + bytes.len() < 16 {
             self.buf[self.buf_len..self.buf_len + bytes.len()].copy_from_slice(bytes);
@@ -229,4 +272,51 @@ mod tests {
         assert_eq!(h1, 0xe88abda785929c9e);
         assert_eq!(h2, 0x96b98587cacc83d6);
     }
+
+    #[test]
+    fn test_hash_bytes_matches_hasher() {
+        let bytes = (0..100u8).collect::<Vec<_>>();
+        for len in 0..bytes.len() {
+            let key = &bytes[..len];
+            let expected = murmurhash3_x64_128(key, DEFAULT_UPDATE_SEED);
+            assert_eq!(
+                MurmurHash3X64128::hash_bytes(key, DEFAULT_UPDATE_SEED),
+                expected,
+                "len {len}"
+            );
+
+            // Splitting the input over several writes does not change the hash either.
+            let mut hasher = MurmurHash3X64128::default();
+            for chunk in key.chunks(7) {
+                hasher.write(chunk);
+            }
+            assert_eq!(hasher.finish128(), expected, "len {len}");
+        }
+    }
+
+    #[test]
+    fn test_integers_hash_as_little_endian_bytes() {
+        let hash_bytes = |bytes: &[u8]| murmurhash3_x64_128(bytes, DEFAULT_UPDATE_SEED);
+        let hash_with = |f: &dyn Fn(&mut MurmurHash3X64128)| {
+            let mut hasher = MurmurHash3X64128::default();
+            f(&mut hasher);
+            hasher.finish128()
+        };
+
+        let v = 0x0102_0304_0506_0708_u64;
+        assert_eq!(hash_with(&|h| h.write_u64(v)), hash_bytes(&v.to_le_bytes()));
+        assert_eq!(
+            hash_with(&|h| h.write_i32(-2)),
+            hash_bytes(&(-2i32).to_le_bytes())
+        );
+        assert_eq!(
+            hash_with(&|h| h.write_u16(0x0102)),
+            hash_bytes(&[0x02, 0x01])
+        );
+        // usize is always hashed as 8 bytes, regardless of pointer width.
+        assert_eq!(
+            hash_with(&|h| h.write_usize(7)),
+            hash_bytes(&7u64.to_le_bytes())
+        );
+    }
 }
@@ -134,6 +134,8 @@ impl Hasher for XxHash64 {
         self.finish64()
     }
 
+    impl_hasher_write_integers_le!();
+
     fn write(&mut self, bytes: &[u8]) {
         self.total_len = self.total_len.wrapping_add(bytes.len() as u64);
 
@@ -244,4 +246,25 @@ mod tests {
         let hash2 = hasher.finish64();
         assert_eq!(hash2, hash1);
     }
+
+    #[test]
+    fn test_integers_hash_as_little_endian_bytes() {
+        let hash_bytes = |bytes: &[u8]| {
+            let mut hasher = XxHash64::with_seed(0);
+            hasher.write(bytes);
+            hasher.finish64()
+        };
+        let hash_with = |f: &dyn Fn(&mut XxHash64)| {
+            let mut hasher = XxHash64::with_seed(0);
+            f(&mut hasher);
+            hasher.finish64()
+        };
+
+        let v = 0x0102_0304_0506_0708_u64;
+        assert_eq!(hash_with(&|h| h.write_u64(v)), hash_bytes(&v.to_le_bytes()));
+        assert_eq!(
+            hash_with(&|h| h.write_isize(-1)),
+            hash_bytes(&(-1i64).to_le_bytes())
+        );
+    }
 }
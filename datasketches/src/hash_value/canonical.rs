@@ -0,0 +1,264 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Cross-language stable hash value wrappers.
+//!
+//! [`UpdateValue`] is implemented by the value types whose hashing contract is shared with other
+//! datasketches implementations, and [`Canonical`] wraps such a value so that it can be passed to
+//! any sketch update method:
+//!
+//! * `str` and `[u8]` (and their owned forms) hash their raw bytes, like [`super::raw_bytes`].
+//! * `u64` and `i64` hash their 8 little-endian bytes.
+//! * `f64` hashes its canonical bit pattern, like [`super::canonical_float`].
+//! * Every other value wrapper of this module hashes with its own strategy.
+//!
+//! [`ValueRef`] erases the type of such a value instead, for callers that only know the type of
+//! their values at runtime.
+//!
+//! A sketch updated through [`Canonical`] values can be merged with sketches built by the Java and
+//! C++ libraries from the same items. Types that only implement [`Hash`] are still accepted by the
+//! update methods, but their hash follows Rust's [`Hash`] implementation, which is not stable
+//! across languages and, for strings and slices, includes a length prefix or terminator.
+//!
+//! The generic update methods hash every value they are given, including empty strings and byte
+//! strings, which the Java and C++ libraries ignore. Skip such values before updating, for example
+//! with [`ValueRef::is_empty`], or update through a method that skips them, such as
+//! `update_str`, `update_bytes` or `update_value_ref` of the HLL and Theta sketches.
+
+use std::hash::Hash;
+use std::hash::Hasher;
+
+use super::value::HashStrategy;
+use super::value::Value;
+
+/// A value with a cross-language stable hash.
+///
+/// Empty strings and byte strings hash zero bytes, whereas other implementations ignore them. See
+/// the [module level documentation](self) for more.
+pub trait UpdateValue {
+    /// Feeds the canonical representation of this value into `state`.
+    fn hash_canonical<H: Hasher>(&self, state: &mut H);
+}
+
+/// A value wrapper that hashes its [`UpdateValue`] canonical representation.
+///
+/// See the [module level documentation](self) for more.
+pub type Canonical<T> = Value<T, CanonicalStrategy>;
+
+/// Hashing strategy for [`Canonical`].
+#[doc(hidden)]
+pub struct CanonicalStrategy;
+
+/// Create a hashable value from any [`UpdateValue`].
+///
+/// # Examples
+///
+/// ```
+/// # use datasketches::hash_value::calculate_hash;
+/// # use datasketches::hash_value::canonical::from_value;
+/// # use datasketches::hash_value::canonical_float;
+/// # use datasketches::hash_value::raw_bytes;
+/// assert_eq!(
+///     calculate_hash(from_value("abc")),
+///     calculate_hash(raw_bytes::from_str("abc"))
+/// );
+/// assert_eq!(
+///     calculate_hash(from_value("abc")),
+///     calculate_hash(from_value(b"abc".as_slice()))
+/// );
+/// assert_eq!(
+///     calculate_hash(from_value(-0.0)),
+///     calculate_hash(canonical_float::from_f64(0.0))
+/// );
+/// assert_eq!(calculate_hash(from_value(42_u64)), calculate_hash(42_u64));
+/// ```
+#[inline(always)]
+pub fn from_value<T: UpdateValue>(v: T) -> Canonical<T> {
+    Canonical::new(v)
+}
+
+/// A borrowed value of one of the types with a cross-language stable hash.
+///
+/// Query engines that bind columns at runtime can convert each cell to a `ValueRef` and update
+/// sketches through one non-generic method, such as `ThetaSketch::update_value_ref` or
+/// `HllSketch::update_value_ref`, instead of instantiating the generic update methods per column
+/// type. A `ValueRef` hashes exactly like the value it holds wrapped in [`Canonical`], and it
+/// implements [`Hash`] so that it can be passed to any sketch update method.
+///
+/// # Examples
+///
+/// ```
+/// # use datasketches::hash_value::calculate_hash;
+/// # use datasketches::hash_value::canonical::from_value;
+/// # use datasketches::hash_value::ValueRef;
+/// assert_eq!(
+///     calculate_hash(ValueRef::Str("abc")),
+///     calculate_hash(from_value("abc"))
+/// );
+/// assert_eq!(
+///     calculate_hash(ValueRef::from(b"abc".as_slice())),
+///     calculate_hash(ValueRef::Str("abc"))
+/// );
+/// assert_eq!(
+///     calculate_hash(ValueRef::F64(-0.0)),
+///     calculate_hash(from_value(0.0))
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ValueRef<'a> {
+    /// A string, hashed as its UTF-8 bytes.
+    Str(&'a str),
+    /// A byte string, hashed as is.
+    Bytes(&'a [u8]),
+    /// An unsigned integer, hashed as its 8 little-endian bytes.
+    U64(u64),
+    /// A signed integer, hashed as its 8 little-endian bytes.
+    I64(i64),
+    /// A float, hashed as its canonical `f64` bit pattern.
+    F64(f64),
+}
+
+impl ValueRef<'_> {
+    /// Returns true if this is an empty string or byte string.
+    ///
+    /// Other datasketches implementations ignore such values instead of hashing them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::hash_value::ValueRef;
+    /// assert!(ValueRef::Str("").is_empty());
+    /// assert!(ValueRef::Bytes(&[]).is_empty());
+    /// assert!(!ValueRef::Str("abc").is_empty());
+    /// assert!(!ValueRef::U64(0).is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        match self {
+            ValueRef::Str(v) => v.is_empty(),
+            ValueRef::Bytes(v) => v.is_empty(),
+            ValueRef::U64(_) | ValueRef::I64(_) | ValueRef::F64(_) => false,
+        }
+    }
+}
+
+impl UpdateValue for ValueRef<'_> {
+    fn hash_canonical<H: Hasher>(&self, state: &mut H) {
+        match self {
+            ValueRef::Str(v) => v.hash_canonical(state),
+            ValueRef::Bytes(v) => v.hash_canonical(state),
+            ValueRef::U64(v) => v.hash_canonical(state),
+            ValueRef::I64(v) => v.hash_canonical(state),
+            ValueRef::F64(v) => v.hash_canonical(state),
+        }
+    }
+}
+
+impl Hash for ValueRef<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.hash_canonical(state);
+    }
+}
+
+impl<'a> From<&'a str> for ValueRef<'a> {
+    fn from(value: &'a str) -> Self {
+        ValueRef::Str(value)
+    }
+}
+
+impl<'a> From<&'a [u8]> for ValueRef<'a> {
+    fn from(value: &'a [u8]) -> Self {
+        ValueRef::Bytes(value)
+    }
+}
+
+impl From<u64> for ValueRef<'_> {
+    fn from(value: u64) -> Self {
+        ValueRef::U64(value)
+    }
+}
+
+impl From<i64> for ValueRef<'_> {
+    fn from(value: i64) -> Self {
+        ValueRef::I64(value)
+    }
+}
+
+impl From<f64> for ValueRef<'_> {
+    fn from(value: f64) -> Self {
+        ValueRef::F64(value)
+    }
+}
+
+impl<T: UpdateValue> HashStrategy<T> for CanonicalStrategy {
+    fn hash<H: Hasher>(value: &T, state: &mut H) {
+        value.hash_canonical(state);
+    }
+}
+
+impl UpdateValue for str {
+    fn hash_canonical<H: Hasher>(&self, state: &mut H) {
+        state.write(self.as_bytes());
+    }
+}
+
+impl UpdateValue for String {
+    fn hash_canonical<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash_canonical(state);
+    }
+}
+
+impl UpdateValue for [u8] {
+    fn hash_canonical<H: Hasher>(&self, state: &mut H) {
+        state.write(self);
+    }
+}
+
+impl UpdateValue for Vec<u8> {
+    fn hash_canonical<H: Hasher>(&self, state: &mut H) {
+        self.as_slice().hash_canonical(state);
+    }
+}
+
+impl UpdateValue for u64 {
+    fn hash_canonical<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(*self);
+    }
+}
+
+impl UpdateValue for i64 {
+    fn hash_canonical<H: Hasher>(&self, state: &mut H) {
+        state.write_i64(*self);
+    }
+}
+
+impl UpdateValue for f64 {
+    fn hash_canonical<H: Hasher>(&self, state: &mut H) {
+        super::canonical_float::from_f64(*self).hash(state);
+    }
+}
+
+impl<T: UpdateValue + ?Sized> UpdateValue for &T {
+    fn hash_canonical<H: Hasher>(&self, state: &mut H) {
+        (**self).hash_canonical(state);
+    }
+}
+
+impl<T, S: HashStrategy<T>> UpdateValue for Value<T, S> {
+    fn hash_canonical<H: Hasher>(&self, state: &mut H) {
+        S::hash(self, state);
+    }
+}
@@ -21,6 +21,14 @@
 //! passing the value directly is sufficient. This module provides value wrappers for
 //! cases where the default implementation does not match a sketch's compatibility rules.
 //!
+//! ## Cross-language Stable Values
+//!
+//! [`UpdateValue`] makes the hashing contract shared with other datasketches implementations
+//! explicit: it is implemented for strings, byte slices, `u64`, `i64` and `f64` with the
+//! canonicalization described below, and for every value wrapper of this module.
+//! [`canonical::from_value`] wraps any such value for the sketch update methods, and [`ValueRef`]
+//! holds any of them behind a single type for values only known at runtime.
+//!
 //! ## Floating-point Numbers
 //!
 //! [`canonical_float::CanonicalFloat`] maps `f32` and `f64` values through a canonical `f64` bit
@@ -72,6 +80,7 @@
 //! * [`raw_bytes::from_slice`]
 //! * [`raw_bytes::from_str`]
 
+pub mod canonical;
 pub mod canonical_float;
 pub mod natural_extend;
 pub mod raw_bytes;
@@ -81,6 +90,9 @@ pub mod value;
 use std::hash::Hash;
 use std::hash::Hasher;
 
+pub use self::canonical::UpdateValue;
+pub use self::canonical::ValueRef;
+
 #[doc(hidden)] // for doctest
 pub fn calculate_hash<T: Hash>(t: T) -> u64 {
     use std::hash::DefaultHasher;
@@ -20,7 +20,9 @@
 //! Array4 stores HLL register values using 4 bits per slot (2 slots per byte).
 //! When values exceed 4 bits after cur_min offset, they're stored in an auxiliary hash map.
 
+use super::array8::Array8;
 use super::aux_map::AuxMap;
+use super::aux_map::lg_aux_arr_ints;
 use crate::codec::SketchBytes;
 use crate::codec::SketchSlice;
 use crate::codec::assert::insufficient_data;
@@ -72,6 +74,35 @@ impl Array4 {
         }
     }
 
+    /// Convert an Array8 to an Array4 holding the same register values
+    ///
+    /// Like Java's `convertToHll4`, cur_min and num_at_cur_min are computed upfront so that every
+    /// register is written once, and the HIP accumulator, KxQ registers and out-of-order flag are
+    /// copied from the source instead of being accumulated again in slot order.
+    pub(super) fn from_array8(src: &Array8, lg_config_k: u8) -> Self {
+        let values = src.values();
+        let cur_min = values.iter().copied().min().unwrap_or(0);
+        let num_at_cur_min = values.iter().filter(|&&value| value == cur_min).count() as u32;
+
+        let mut array = Self::new(lg_config_k);
+        for (slot, &value) in values.iter().enumerate() {
+            let shifted = value - cur_min;
+            if shifted >= AUX_TOKEN {
+                array.put_raw(slot as u32, AUX_TOKEN);
+                array
+                    .aux_map
+                    .get_or_insert_with(|| AuxMap::new(lg_config_k))
+                    .insert(slot as u32, value);
+            } else {
+                array.put_raw(slot as u32, shifted);
+            }
+        }
+        array.cur_min = cur_min;
+        array.num_at_cur_min = num_at_cur_min;
+        array.estimator = src.estimator().clone();
+        array
+    }
+
     /// Get raw 4-bit value from slot (not adjusted for cur_min)
     #[inline]
     fn get_raw(&self, slot: u32) -> u8 {
@@ -261,6 +292,17 @@ impl Array4 {
             .estimate(self.lg_config_k, self.cur_min, self.num_at_cur_min)
     }
 
+    /// Get the HIP estimate, or `None` if the sketch is out of order
+    pub fn hip_estimate(&self) -> Option<f64> {
+        self.estimator.hip_estimate()
+    }
+
+    /// Get the composite (raw HLL and linear counting) estimate
+    pub fn composite_estimate(&self) -> f64 {
+        self.estimator
+            .get_composite_estimate(self.lg_config_k, self.cur_min, self.num_at_cur_min)
+    }
+
     /// Get upper bound for cardinality estimate
     pub fn upper_bound(&self, num_std_dev: NumStdDev) -> f64 {
         self.estimator.upper_bound(
@@ -300,6 +342,7 @@ impl Array4 {
         mut cursor: SketchSlice,
         cur_min: u8,
         lg_config_k: u8,
+        lg_aux_arr: u8,
         compact: bool,
         ooo: bool,
     ) -> Result<Self, Error> {
@@ -321,29 +364,56 @@ impl Array4 {
             .map_err(insufficient_data("aux_count"))?;
 
         // Read packed 4-bit byte array
+        // The register array is always present: the compact flag only concerns the aux map
         let mut data = vec![0u8; num_bytes];
-        if !compact {
-            cursor
-                .read_exact(&mut data)
-                .map_err(insufficient_data("data"))?;
-        } else {
-            cursor.advance(num_bytes as u64);
-        }
+        cursor
+            .read_exact(&mut data)
+            .map_err(insufficient_data("data"))?;
 
         // Read aux map if present
+        // A compact image lists the aux coupons sequentially; an updatable one stores the whole
+        // aux hash table of 2^lg_aux_arr slots, with empty slots in between, which is adopted as
+        // is. Like Java, lg_aux_arr is only trusted for updatable images.
         let mut aux_map = None;
         if aux_count > 0 {
-            let mut aux = AuxMap::new(lg_config_k);
-            for i in 0..aux_count {
+            let num_entries = if compact {
+                aux_count
+            } else if lg_aux_arr <= lg_config_k + 1 {
+                1u32 << lg_aux_arr
+            } else {
+                return Err(Error::deserial(format!(
+                    "invalid lg_aux_arr: expected at most {}, got {lg_aux_arr}",
+                    lg_config_k + 1
+                )));
+            };
+            let mut coupons = Vec::with_capacity(num_entries.min(1 << lg_config_k) as usize);
+            for i in 0..num_entries {
                 let coupon = cursor.read_u32_le().map_err(|_| {
                     Error::insufficient_data(format!(
-                        "expected {aux_count} aux coupons, failed at index {i}",
+                        "expected {num_entries} aux coupons, failed at index {i}",
                     ))
                 })?;
-                let coupon = Coupon(coupon);
-                let slot = coupon.slot() & ((1 << lg_config_k) - 1);
-                let value = coupon.value();
-                aux.insert(slot, value);
+                coupons.push(Coupon(coupon));
+            }
+
+            let aux = if compact {
+                let mut aux = AuxMap::new(lg_config_k);
+                for coupon in coupons.into_iter().filter(|c| !c.is_empty()) {
+                    let slot = coupon.slot() & ((1 << lg_config_k) - 1);
+                    if aux.get(slot).is_some() {
+                        return Err(Error::deserial(format!("duplicate aux slot {slot}")));
+                    }
+                    aux.insert(slot, coupon.value());
+                }
+                aux
+            } else {
+                AuxMap::from_table(lg_config_k, coupons.into_boxed_slice())?
+            };
+            if aux.count() != aux_count as usize {
+                return Err(Error::deserial(format!(
+                    "aux count mismatch: expected {aux_count}, got {}",
+                    aux.count()
+                )));
             }
             aux_map = Some(aux);
         }
@@ -365,34 +435,42 @@ impl Array4 {
         })
     }
 
-    /// Serialize Array4 to bytes
-    ///
-    /// Produces full HLL preamble (40 bytes) followed by packed 4-bit data and optional aux map.
-    pub fn serialize(&self, lg_config_k: u8) -> Vec<u8> {
-        let num_bytes = 1 << (lg_config_k - 1); // k/2 bytes for 4-bit packing
-
-        // Collect aux map entries if present
-        let aux_entries: Vec<(u32, u8)> = if let Some(aux) = &self.aux_map {
-            aux.iter().collect()
-        } else {
-            vec![]
+    /// Returns the number of bytes [`serialize_to`](Self::serialize_to) writes.
+    pub fn serialized_size_bytes(&self, compact: bool) -> usize {
+        let aux_ints = match &self.aux_map {
+            None if compact => 0,
+            None => 1 << lg_aux_arr_ints(self.lg_config_k),
+            Some(aux) if compact => aux.count(),
+            Some(aux) => aux.entries().len(),
         };
+        HLL_PREAMBLE_SIZE + self.bytes.len() + aux_ints * COUPON_SIZE_BYTES
+    }
 
-        let aux_count = aux_entries.len() as u32;
-        let total_size = HLL_PREAMBLE_SIZE + num_bytes + (aux_count as usize * COUPON_SIZE_BYTES);
-        let mut bytes = SketchBytes::with_capacity(total_size);
+    /// Serialize Array4 into `bytes`
+    ///
+    /// Produces full HLL preamble (40 bytes) followed by packed 4-bit data and optional aux map.
+    /// The aux map is written as a sequential list of its entries if compact, or as its whole
+    /// hash table otherwise. Like Java, an updatable image without aux map still reserves an empty
+    /// table of the initial size, so that readers mapping the image in place can grow into it.
+    pub fn serialize_to(&self, bytes: &mut SketchBytes<'_>, lg_config_k: u8, compact: bool) {
+        let aux_count = self.aux_map.as_ref().map_or(0, AuxMap::count) as u32;
+        bytes.reserve(self.serialized_size_bytes(compact));
 
         // Write standard header
         bytes.write_u8(HLL_PREINTS);
         bytes.write_u8(SERIAL_VERSION);
         bytes.write_u8(Family::HLL.id);
         bytes.write_u8(lg_config_k);
-        bytes.write_u8(0); // unused for HLL mode
-
-        // Write flags.
-        // COMPACT_FLAG_MASK is always set: aux map entries are written as a compact sequential
-        // list of populated entries only.
-        let mut flags = COMPACT_FLAG_MASK;
+        // lg_aux_arr: written whenever there is an aux map, as Java does, but only needed to read
+        // back an updatable aux hash table
+        let lg_aux_arr = self.aux_map.as_ref().map_or(0, AuxMap::lg_size);
+        bytes.write_u8(lg_aux_arr);
+
+        // Write flags
+        let mut flags = 0;
+        if compact {
+            flags |= COMPACT_FLAG_MASK;
+        }
         if self.estimator.is_out_of_order() {
             flags |= OUT_OF_ORDER_FLAG_MASK;
         }
@@ -419,11 +497,25 @@ impl Array4 {
         bytes.write(&self.bytes);
 
         // Write aux map entries if present
-        for (slot, value) in aux_entries.iter().copied() {
-            bytes.write_u32_le(Coupon::pack(slot, value).raw());
+        match &self.aux_map {
+            Some(aux) if compact => {
+                for (slot, value) in aux.iter() {
+                    bytes.write_u32_le(Coupon::pack(slot, value).raw());
+                }
+            }
+            Some(aux) => {
+                for coupon in aux.entries() {
+                    bytes.write_u32_le(coupon.raw());
+                }
+            }
+            None if compact => {}
+            None => {
+                let num_ints = 1 << lg_aux_arr_ints(self.lg_config_k);
+                for _ in 0..num_ints {
+                    bytes.write_u32_le(Coupon::EMPTY.raw());
+                }
+            }
         }
-
-        bytes.into_bytes()
     }
 
     /// Returns the estimated size of the heap allocations in bytes
@@ -541,4 +633,150 @@ mod tests {
             assert_eq!(arr.get(slot), 1);
         }
     }
+
+    #[test]
+    fn test_from_array8_matches_updates() {
+        let coupons = (0..16).map(|slot| Coupon::pack(slot, 3)).chain([
+            Coupon::pack(0, 20),
+            Coupon::pack(5, 30),
+            Coupon::pack(7, 9),
+        ]);
+        let mut src = Array8::new(4);
+        let mut expected = Array4::new(4);
+        for coupon in coupons {
+            src.update(coupon);
+            expected.update(coupon);
+        }
+
+        let arr = Array4::from_array8(&src, 4);
+        assert_eq!(arr.cur_min, expected.cur_min);
+        assert_eq!(arr.num_at_cur_min, expected.num_at_cur_min);
+        assert_eq!(arr.aux_map.as_ref().map(AuxMap::count), Some(2));
+        for slot in 0..16 {
+            assert_eq!(arr.get_raw(slot), expected.get_raw(slot), "slot {slot}");
+            assert_eq!(arr.get(slot), src.get(slot), "slot {slot}");
+        }
+        assert_eq!(&arr.estimator, src.estimator());
+        assert_eq!(arr.estimate(), src.estimate());
+    }
+
+    /// 16 slots with cur_min 3, two aux exceptions (slots 0 and 5) and one in-range value.
+    fn array_with_exceptions() -> Array4 {
+        let mut arr = Array4::new(4);
+        for slot in 0..16 {
+            arr.update(Coupon::pack(slot, 3));
+        }
+        arr.update(Coupon::pack(0, 20));
+        arr.update(Coupon::pack(5, 30));
+        arr.update(Coupon::pack(7, 9));
+        assert_eq!(arr.cur_min, 3);
+        assert_eq!(arr.aux_map.as_ref().map(AuxMap::count), Some(2));
+        arr
+    }
+
+    fn serialize(arr: &Array4) -> Vec<u8> {
+        let mut bytes = SketchBytes::with_capacity(0);
+        arr.serialize_to(&mut bytes, arr.lg_config_k, true);
+        bytes.into_bytes()
+    }
+
+    #[test]
+    fn test_compact_round_trip_preserves_cur_min_and_aux() {
+        let arr = array_with_exceptions();
+        let bytes = serialize(&arr);
+        assert_eq!(bytes.len(), arr.serialized_size_bytes(true));
+
+        let cur_min = bytes[6];
+        let cursor = SketchSlice::new(&bytes[8..]);
+        let restored = Array4::deserialize(cursor, cur_min, 4, 0, true, false).unwrap();
+        assert_eq!(restored, arr);
+        assert_eq!(restored.get(0), 20);
+        assert_eq!(restored.get(5), 30);
+        assert_eq!(restored.get(7), 9);
+        assert_eq!(restored.get(1), 3);
+        assert_eq!(serialize(&restored), bytes);
+    }
+
+    #[test]
+    fn test_updatable_aux_table_round_trip() {
+        let arr = array_with_exceptions();
+        let compact = serialize(&arr);
+        let aux_start = HLL_PREAMBLE_SIZE + arr.bytes.len();
+        let with_table = |table: &[u32]| {
+            let mut bytes = compact[..aux_start].to_vec();
+            for entry in table {
+                bytes.extend_from_slice(&entry.to_le_bytes());
+            }
+            bytes
+        };
+
+        // Replace the compact aux list with a sparse 2^4 slot hash table
+        let lg_aux_arr = 4;
+        let mut table = [0u32; 16];
+        table[5] = Coupon::pack(5, 30).raw();
+        table[0] = Coupon::pack(0, 20).raw();
+        let bytes = with_table(&table);
+
+        let cursor = SketchSlice::new(&bytes[8..]);
+        let restored = Array4::deserialize(cursor, bytes[6], 4, lg_aux_arr, false, false).unwrap();
+        assert_eq!(restored, arr);
+        // The table is kept as is, so only the lg_aux_arr byte differs
+        let mut reserialized = serialize(&restored);
+        assert_eq!(reserialized[4], lg_aux_arr);
+        reserialized[4] = compact[4];
+        assert_eq!(reserialized, compact);
+
+        // The aux count in the preamble must match the populated table entries
+        table[0] = 0;
+        let bytes = with_table(&table);
+        let cursor = SketchSlice::new(&bytes[8..]);
+        assert!(Array4::deserialize(cursor, bytes[6], 4, lg_aux_arr, false, false).is_err());
+
+        // Entries must sit where probing from their slot finds them
+        table[0] = Coupon::pack(0, 20).raw();
+        table.swap(5, 3);
+        let bytes = with_table(&table);
+        let cursor = SketchSlice::new(&bytes[8..]);
+        assert!(Array4::deserialize(cursor, bytes[6], 4, lg_aux_arr, false, false).is_err());
+    }
+
+    #[test]
+    fn test_updatable_round_trip() {
+        let arr = array_with_exceptions();
+        let mut bytes = SketchBytes::with_capacity(0);
+        arr.serialize_to(&mut bytes, arr.lg_config_k, false);
+        let bytes = bytes.into_bytes();
+        assert_eq!(bytes.len(), arr.serialized_size_bytes(false));
+        assert!(bytes.len() > arr.serialized_size_bytes(true));
+        assert_eq!(bytes[5] & COMPACT_FLAG_MASK, 0);
+
+        let cursor = SketchSlice::new(&bytes[8..]);
+        let restored = Array4::deserialize(cursor, bytes[6], 4, bytes[4], false, false).unwrap();
+        assert_eq!(restored, arr);
+
+        let mut reserialized = SketchBytes::with_capacity(0);
+        restored.serialize_to(&mut reserialized, restored.lg_config_k, false);
+        assert_eq!(reserialized.into_bytes(), bytes);
+    }
+
+    #[test]
+    fn test_updatable_without_aux_reserves_table() {
+        let mut arr = Array4::new(12);
+        arr.update(Coupon::pack(3, 2));
+        assert!(arr.aux_map.is_none());
+
+        let mut bytes = SketchBytes::with_capacity(0);
+        arr.serialize_to(&mut bytes, arr.lg_config_k, false);
+        let bytes = bytes.into_bytes();
+        // Java reserves 2^lg_aux_arr_ints(12) = 32 empty aux ints
+        let aux_start = HLL_PREAMBLE_SIZE + arr.bytes.len();
+        assert_eq!(bytes.len(), aux_start + 32 * COUPON_SIZE_BYTES);
+        assert_eq!(bytes.len(), arr.serialized_size_bytes(false));
+        assert_eq!(bytes[4], 0);
+        assert!(bytes[aux_start..].iter().all(|&b| b == 0));
+
+        let cursor = SketchSlice::new(&bytes[8..]);
+        let restored = Array4::deserialize(cursor, bytes[6], 12, bytes[4], false, false).unwrap();
+        assert_eq!(restored, arr);
+    }
 }
@@ -21,6 +21,7 @@
 //! This is sufficient for most HLL use cases without needing exception handling or
 //! cur_min optimization like Array4.
 
+use super::array8::Array8;
 use crate::codec::SketchBytes;
 use crate::codec::SketchSlice;
 use crate::codec::assert::insufficient_data;
@@ -64,6 +65,22 @@ impl Array6 {
         }
     }
 
+    /// Convert an Array8 to an Array6 holding the same register values
+    ///
+    /// Like Java's `convertToHll6`, the HIP accumulator, KxQ registers and out-of-order flag are
+    /// copied from the source instead of being accumulated again in slot order.
+    pub(super) fn from_array8(src: &Array8, lg_config_k: u8) -> Self {
+        let mut array = Self::new(lg_config_k);
+        for (slot, &value) in src.values().iter().enumerate() {
+            if value > 0 {
+                array.put_raw(slot as u32, value);
+                array.num_zeros -= 1;
+            }
+        }
+        array.estimator = src.estimator().clone();
+        array
+    }
+
     /// Get value from a slot (6-bit value)
     ///
     /// Uses 16-bit window reads to handle values crossing byte boundaries.
@@ -151,6 +168,17 @@ impl Array6 {
         self.estimator.estimate(self.lg_config_k, 0, self.num_zeros)
     }
 
+    /// Get the HIP estimate, or `None` if the sketch is out of order
+    pub fn hip_estimate(&self) -> Option<f64> {
+        self.estimator.hip_estimate()
+    }
+
+    /// Get the composite (raw HLL and linear counting) estimate
+    pub fn composite_estimate(&self) -> f64 {
+        self.estimator
+            .get_composite_estimate(self.lg_config_k, 0, self.num_zeros)
+    }
+
     /// Get upper bound for cardinality estimate
     pub fn upper_bound(&self, num_std_dev: NumStdDev) -> f64 {
         self.estimator
@@ -178,12 +206,7 @@ impl Array6 {
     /// Deserialize Array6 from HLL mode bytes
     ///
     /// Expects full HLL preamble (40 bytes) followed by packed 6-bit data.
-    pub fn deserialize(
-        mut cursor: SketchSlice,
-        lg_config_k: u8,
-        compact: bool,
-        ooo: bool,
-    ) -> Result<Self, Error> {
+    pub fn deserialize(mut cursor: SketchSlice, lg_config_k: u8, ooo: bool) -> Result<Self, Error> {
         let k = 1 << lg_config_k;
         let num_bytes = num_bytes_for_k(k);
 
@@ -203,14 +226,11 @@ impl Array6 {
             .map_err(insufficient_data("aux_count"))?; // always 0
 
         // Read packed byte array from offset HLL_BYTE_ARR_START
+        // The register array is always present: the compact flag only concerns the aux map
         let mut data = vec![0u8; num_bytes];
-        if !compact {
-            cursor
-                .read_exact(&mut data)
-                .map_err(insufficient_data("data"))?;
-        } else {
-            cursor.advance(num_bytes as u64);
-        }
+        cursor
+            .read_exact(&mut data)
+            .map_err(insufficient_data("data"))?;
 
         // Create estimator and restore state
         let mut estimator = HipEstimator::new(lg_config_k);
@@ -227,14 +247,19 @@ impl Array6 {
         })
     }
 
-    /// Serialize Array6 to bytes
+    /// Returns the number of bytes [`serialize_to`](Self::serialize_to) writes.
+    pub fn serialized_size_bytes(&self) -> usize {
+        HLL_PREAMBLE_SIZE + self.bytes.len()
+    }
+
+    /// Serialize Array6 into `bytes`
     ///
     /// Produces full HLL preamble (40 bytes) followed by packed 6-bit data.
-    pub fn serialize(&self, lg_config_k: u8) -> Vec<u8> {
+    pub fn serialize_to(&self, bytes: &mut SketchBytes<'_>, lg_config_k: u8) {
         let k = 1 << lg_config_k;
         let num_bytes = num_bytes_for_k(k);
         let total_size = HLL_PREAMBLE_SIZE + num_bytes;
-        let mut bytes = SketchBytes::with_capacity(total_size);
+        bytes.reserve(total_size);
 
         // Write standard header
         bytes.write_u8(HLL_PREINTS);
@@ -269,8 +294,6 @@ impl Array6 {
 
         // Write packed byte array
         bytes.write(&self.bytes);
-
-        bytes.into_bytes()
     }
 
     /// Returns the estimated size of the heap allocations in bytes
@@ -389,6 +412,23 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_from_array8_matches_updates() {
+        let mut src = Array8::new(8);
+        let mut expected = Array6::new(8);
+        for i in 0..1_000u32 {
+            let coupon = Coupon::from_hash(i);
+            src.update(coupon);
+            expected.update(coupon);
+        }
+
+        let arr = Array6::from_array8(&src, 8);
+        assert_eq!(arr.bytes, expected.bytes);
+        assert_eq!(arr.num_zeros, expected.num_zeros);
+        assert_eq!(&arr.estimator, src.estimator());
+        assert_eq!(arr.estimate(), src.estimate());
+    }
+
     #[test]
     fn test_kxq_register_split() {
         let mut arr = Array6::new(8); // 256 buckets
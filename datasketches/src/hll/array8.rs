@@ -60,6 +60,26 @@ impl Array8 {
         }
     }
 
+    /// Takes `spare` if it holds an array of `lg_config_k`, or allocates a new one otherwise.
+    ///
+    /// `spare` must have been [cleared](Self::clear).
+    pub(super) fn take_or_new(spare: &mut Option<Array8>, lg_config_k: u8) -> Self {
+        match spare.take() {
+            Some(array) if array.lg_config_k == lg_config_k => array,
+            other => {
+                *spare = other;
+                Self::new(lg_config_k)
+            }
+        }
+    }
+
+    /// Resets every register and the estimator, keeping the register allocation.
+    pub(super) fn clear(&mut self) {
+        self.bytes.fill(0);
+        self.num_zeros = 1 << self.lg_config_k;
+        self.estimator = HipEstimator::new(self.lg_config_k);
+    }
+
     /// Get value from a slot
     ///
     /// Direct array access - no bit manipulation required.
@@ -105,6 +125,17 @@ impl Array8 {
         self.estimator.estimate(self.lg_config_k, 0, self.num_zeros)
     }
 
+    /// Get the HIP estimate, or `None` if the sketch is out of order
+    pub fn hip_estimate(&self) -> Option<f64> {
+        self.estimator.hip_estimate()
+    }
+
+    /// Get the composite (raw HLL and linear counting) estimate
+    pub fn composite_estimate(&self) -> f64 {
+        self.estimator
+            .get_composite_estimate(self.lg_config_k, 0, self.num_zeros)
+    }
+
     /// Get upper bound for cardinality estimate
     pub fn upper_bound(&self, num_std_dev: NumStdDev) -> f64 {
         self.estimator
@@ -144,6 +175,11 @@ impl Array8 {
         self.estimator.hip_accum()
     }
 
+    /// Get the HIP estimator state (HIP accumulator, KxQ registers and out-of-order flag)
+    pub(super) fn estimator(&self) -> &HipEstimator {
+        &self.estimator
+    }
+
     /// Directly set a register value
     ///
     /// This bypasses the normal update path and directly modifies the register.
@@ -252,12 +288,7 @@ impl Array8 {
     /// Deserialize Array8 from HLL mode bytes
     ///
     /// Expects full HLL preamble (40 bytes) followed by k bytes of data.
-    pub fn deserialize(
-        mut cursor: SketchSlice,
-        lg_config_k: u8,
-        compact: bool,
-        ooo: bool,
-    ) -> Result<Self, Error> {
+    pub fn deserialize(mut cursor: SketchSlice, lg_config_k: u8, ooo: bool) -> Result<Self, Error> {
         let k = 1usize << lg_config_k;
 
         // Read HIP estimator values from preamble
@@ -276,14 +307,11 @@ impl Array8 {
             .map_err(insufficient_data("aux_count"))?; // always 0
 
         // Read byte array from offset HLL_BYTE_ARR_START
+        // The register array is always present: the compact flag only concerns the aux map
         let mut data = vec![0u8; k];
-        if !compact {
-            cursor
-                .read_exact(&mut data)
-                .map_err(insufficient_data("data"))?;
-        } else {
-            cursor.advance(k as u64);
-        }
+        cursor
+            .read_exact(&mut data)
+            .map_err(insufficient_data("data"))?;
 
         // Create estimator and restore state
         let mut estimator = HipEstimator::new(lg_config_k);
@@ -300,13 +328,18 @@ impl Array8 {
         })
     }
 
-    /// Serialize Array8 to bytes
+    /// Returns the number of bytes [`serialize_to`](Self::serialize_to) writes.
+    pub fn serialized_size_bytes(&self) -> usize {
+        HLL_PREAMBLE_SIZE + self.bytes.len()
+    }
+
+    /// Serialize Array8 into `bytes`
     ///
     /// Produces full HLL preamble (40 bytes) followed by k bytes of data.
-    pub fn serialize(&self, lg_config_k: u8) -> Vec<u8> {
+    pub fn serialize_to(&self, bytes: &mut SketchBytes<'_>, lg_config_k: u8) {
         let k = 1 << lg_config_k;
         let total_size = HLL_PREAMBLE_SIZE + k as usize;
-        let mut bytes = SketchBytes::with_capacity(total_size);
+        bytes.reserve(total_size);
 
         // Write standard header
         bytes.write_u8(HLL_PREINTS);
@@ -341,8 +374,6 @@ impl Array8 {
 
         // Write byte array
         bytes.write(&self.bytes);
-
-        bytes.into_bytes()
     }
 
     /// Returns the estimated size of the heap allocations in bytes
@@ -20,6 +20,7 @@
 //! Stores slot-value pairs for values that don't fit in the 4-bit main array.
 //! Uses open addressing with stride-based probing for collision resolution.
 
+use crate::error::Error;
 use crate::hll::Coupon;
 use crate::hll::RESIZE_DENOMINATOR;
 use crate::hll::RESIZE_NUMERATOR;
@@ -72,7 +73,7 @@ impl PartialEq for AuxMap {
 ///
 /// This determines the initial size of the auxiliary hash map
 /// based on the sketch size.
-fn lg_aux_arr_ints(lg_config_k: u8) -> u8 {
+pub(super) fn lg_aux_arr_ints(lg_config_k: u8) -> u8 {
     static LG_AUX_ARR_INTS: &[u8] = &[
         0, 2, 2, 2, 2, 2, 2, 3, 3, 3, // 0-9
         4, 4, 5, 5, 6, 7, 8, 9, 10, 11, // 10-19
@@ -94,6 +95,78 @@ impl AuxMap {
         }
     }
 
+    /// Adopt a serialized hash table as is, like the Java `AuxHashMap` of an updatable image.
+    ///
+    /// Keeping the table layout, rather than re-inserting its entries, makes updatable images
+    /// round-trip byte for byte.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `entries` is not a power of two in length, is loaded beyond the
+    /// resize threshold, or holds an entry that probing from its slot does not reach (which also
+    /// covers duplicate slots).
+    pub fn from_table(lg_config_k: u8, entries: Box<[Coupon]>) -> Result<Self, Error> {
+        if !entries.len().is_power_of_two() {
+            return Err(Error::deserial(format!(
+                "aux table size must be a power of two, got {}",
+                entries.len()
+            )));
+        }
+        let config_k_mask = (1 << lg_config_k) - 1;
+        let mut map = Self {
+            lg_size: entries.len().trailing_zeros() as u8,
+            lg_config_k,
+            entries,
+            count: 0,
+        };
+        for i in 0..map.entries.len() {
+            let entry = map.entries[i];
+            if entry.is_empty() {
+                continue;
+            }
+            let slot = entry.slot() & config_k_mask;
+            map.entries[i] = Coupon::pack(slot, entry.value());
+            map.count += 1;
+        }
+        let size = map.entries.len() as u32;
+        if RESIZE_DENOMINATOR * map.count > RESIZE_NUMERATOR * size {
+            return Err(Error::deserial(format!(
+                "aux table of {size} slots is overloaded with {} entries",
+                map.count
+            )));
+        }
+        for (i, &entry) in map.entries.iter().enumerate() {
+            if entry.is_empty() {
+                continue;
+            }
+            match map.find(entry.slot()) {
+                FindResult::Found(index) if index == i => {}
+                _ => {
+                    return Err(Error::deserial(format!(
+                        "aux slot {} is duplicated or misplaced at index {i}",
+                        entry.slot()
+                    )));
+                }
+            }
+        }
+        Ok(map)
+    }
+
+    /// Number of slot-value pairs stored in the map
+    pub fn count(&self) -> usize {
+        self.count as usize
+    }
+
+    /// Log2 of the number of hash table slots
+    pub fn lg_size(&self) -> u8 {
+        self.lg_size
+    }
+
+    /// The raw hash table, including empty slots
+    pub fn entries(&self) -> &[Coupon] {
+        &self.entries
+    }
+
     /// Insert a new slot-value pair
     pub fn insert(&mut self, slot: u32, value: u8) {
         let index = self.find(slot);
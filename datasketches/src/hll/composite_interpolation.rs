@@ -21,10 +21,13 @@
 //! cardinality estimates when the HLL sketch is in out-of-order mode
 //! (after deserialization or merging).
 //!
-//! Currently, this module contains tables for common lg_k values (4-12). The full C++
-//! implementation has tables for lg_k 4-21. Additional tables can be found at:
+//! There is one table for every supported lg_k, [`MIN_LG_K`] to [`MAX_LG_K`], taken from the C++
+//! implementation:
 //! <https://github.com/apache/datasketches-cpp/blob/5a055521/hll/include/CompositeInterpolationXTable-internal.hpp>
 
+use crate::hll::MAX_LG_K;
+use crate::hll::MIN_LG_K;
+
 const NUM_X_VALUES: usize = 257;
 
 /// Y stride values for each lg_k (index 0 = lg_k 4, index 17 = lg_k 21)
@@ -38,10 +41,10 @@ static Y_STRIDES: [u32; 18] = [
 ///
 /// Panics if lg_k is not in range `[4, 21]`.
 pub fn get_y_stride(lg_k: u8) -> u32 {
-    if !(4..=21).contains(&lg_k) {
-        panic!("lg_k must be in range [4, 21], got: {}", lg_k);
+    if !(MIN_LG_K..=MAX_LG_K).contains(&lg_k) {
+        panic!("lg_k must be in range [{MIN_LG_K}, {MAX_LG_K}], got: {lg_k}");
     }
-    Y_STRIDES[(lg_k - 4) as usize]
+    Y_STRIDES[(lg_k - MIN_LG_K) as usize]
 }
 
 /// Get X array length (constant for all lg_k)
@@ -57,10 +60,10 @@ pub const fn get_x_arr_length() -> usize {
 ///
 /// Panics if lg_k is not in range `[4, 21]`.
 pub fn get_x_arr(lg_k: u8) -> &'static [f64; NUM_X_VALUES] {
-    if !(4..=21).contains(&lg_k) {
-        panic!("lg_k must be in range [4, 21], got: {}", lg_k);
+    if !(MIN_LG_K..=MAX_LG_K).contains(&lg_k) {
+        panic!("lg_k must be in range [{MIN_LG_K}, {MAX_LG_K}], got: {lg_k}");
     }
-    &ARRAYS[(lg_k - 4) as usize]
+    &ARRAYS[(lg_k - MIN_LG_K) as usize]
 }
 
 /// This is extracted from https://github.com/apache/datasketches-cpp/blob/5a055521/hll/include/CompositeInterpolationXTable-internal.hpp#L54
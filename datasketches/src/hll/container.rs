@@ -46,23 +46,7 @@ impl PartialEq for Container {
             return false;
         }
 
-        let mut coupons1: Vec<Coupon> = self
-            .coupons
-            .iter()
-            .filter(|&&c| !c.is_empty())
-            .copied()
-            .collect();
-        let mut coupons2: Vec<Coupon> = other
-            .coupons
-            .iter()
-            .filter(|&&c| !c.is_empty())
-            .copied()
-            .collect();
-
-        coupons1.sort_unstable();
-        coupons2.sort_unstable();
-
-        coupons1 == coupons2
+        self.sorted_coupons() == other.sorted_coupons()
     }
 }
 
@@ -116,7 +100,7 @@ impl Container {
         let len = self.len as f64;
         let est = using_x_and_y_tables(&X_ARR, &Y_ARR, len);
         // Upper bound: negative RSE means (1 + rse) < 1, so bound > estimate
-        let rse = -(num_std_dev as u8 as f64) * COUPON_RSE;
+        let rse = -num_std_dev.as_f64() * COUPON_RSE;
         let bound = est / (1.0 + rse);
         len.max(bound)
     }
@@ -126,7 +110,7 @@ impl Container {
         let len = self.len as f64;
         let est = using_x_and_y_tables(&X_ARR, &Y_ARR, len);
         // Lower bound: positive RSE means (1 + rse) > 1, so bound < estimate
-        let rse = (num_std_dev as u8 as f64) * COUPON_RSE;
+        let rse = num_std_dev.as_f64() * COUPON_RSE;
         let bound = est / (1.0 + rse);
         len.max(bound)
     }
@@ -136,6 +120,16 @@ impl Container {
         self.coupons.iter().filter(|&&c| !c.is_empty()).copied()
     }
 
+    /// Returns the non-empty coupons in ascending order.
+    ///
+    /// Unlike [`iter`](Self::iter), the order does not depend on the insertion order or the hash
+    /// table layout, so equal containers always yield the same sequence.
+    pub fn sorted_coupons(&self) -> Vec<Coupon> {
+        let mut coupons: Vec<Coupon> = self.iter().collect();
+        coupons.sort_unstable();
+        coupons
+    }
+
     /// Returns the estimated size of the heap allocations in bytes
     pub fn estimated_size(&self) -> usize {
         self.coupons.len() * size_of::<Coupon>()
@@ -24,6 +24,7 @@
 
 use crate::common::NumStdDev;
 use crate::common::inv_pow2::inv_pow2;
+use crate::common::strict_math;
 use crate::hll::composite_interpolation;
 use crate::hll::cubic_interpolation;
 use crate::hll::harmonic_numbers;
@@ -197,7 +198,7 @@ impl HipEstimator {
 
         // Edge case: all buckets hit
         if num_unhit == 0 {
-            return (k as f64) * (k as f64 / 0.5).ln();
+            return (k as f64) * strict_math::ln(k as f64 / 0.5);
         }
 
         let num_hit = k - num_unhit;
@@ -209,7 +210,7 @@ impl HipEstimator {
     /// This is the primary estimator used when in out-of-order mode.
     /// It uses cubic interpolation on raw HLL estimate, then blends
     /// with linear counting for small cardinalities.
-    fn get_composite_estimate(&self, lg_config_k: u8, cur_min: u8, num_at_cur_min: u32) -> f64 {
+    pub fn get_composite_estimate(&self, lg_config_k: u8, cur_min: u8, num_at_cur_min: u32) -> f64 {
         let raw_est = self.get_raw_estimate(lg_config_k);
 
         // Get composite interpolation table
@@ -264,6 +265,11 @@ impl HipEstimator {
         }
     }
 
+    /// Get the HIP estimate, or `None` in out-of-order mode where the accumulator is invalid
+    pub fn hip_estimate(&self) -> Option<f64> {
+        (!self.out_of_order).then_some(self.hip_accum)
+    }
+
     /// Get the HIP accumulator value
     pub fn hip_accum(&self) -> f64 {
         self.hip_accum
@@ -314,6 +320,12 @@ impl HipEstimator {
     }
 }
 
+/// Asymptotic RSE factor of the HIP estimator used by in-order sketches, `sqrt(ln(2))`.
+pub(super) const HLL_HIP_RSE_FACTOR: f64 = 0.8325546;
+/// Asymptotic RSE factor of the composite estimator used by out-of-order sketches (merged or
+/// deserialized), `sqrt(3 * ln(2) - 1)`.
+pub(super) const HLL_NON_HIP_RSE_FACTOR: f64 = 1.03896;
+
 /// Get relative error for HLL estimates
 ///
 /// This matches the implementation in datasketches-cpp HllUtil.hpp and RelativeErrorTables.hpp
@@ -331,19 +343,16 @@ impl HipEstimator {
 fn get_rel_err(lg_config_k: u8, upper_bound: bool, ooo: bool, num_std_dev: NumStdDev) -> f64 {
     // For lg_k > 12, use analytical formula with RSE factors
     if lg_config_k > 12 {
-        // RSE factors from Apache DataSketches C++ implementation
-        // HLL_HIP_RSE_FACTOR = sqrt(ln(2)) ≈ 0.8325546
-        // HLL_NON_HIP_RSE_FACTOR = sqrt((3 * ln(2)) - 1) ≈ 1.03896
         let rse_factor = if ooo {
-            1.03896 // Non-HIP (out-of-order)
+            HLL_NON_HIP_RSE_FACTOR
         } else {
-            0.8325546 // HIP (in-order)
+            HLL_HIP_RSE_FACTOR
         };
 
         let k = (1 << lg_config_k) as f64;
         let sign = if upper_bound { -1.0 } else { 1.0 };
 
-        return sign * (num_std_dev as u8 as f64) * rse_factor / k.sqrt();
+        return sign * num_std_dev.as_f64() * rse_factor / k.sqrt();
     }
 
     // For lg_k <= 12, use empirically measured lookup tables.
@@ -20,6 +20,8 @@
 //! Provides utilities for computing harmonic numbers used in the
 //! HLL bitmap estimator for small cardinalities.
 
+use crate::common::strict_math;
+
 const NUM_EXACT: usize = 25;
 const EULER_MASCHERONI: f64 = 0.577_215_664_901_532_9;
 
@@ -62,7 +64,7 @@ fn harmonic_number(n: usize) -> f64 {
 
     let x = n as f64;
     let inv_sq = 1.0 / (x * x);
-    let mut sum = x.ln() + EULER_MASCHERONI + (1.0 / (2.0 * x));
+    let mut sum = strict_math::ln(x) + EULER_MASCHERONI + (1.0 / (2.0 * x));
 
     // Asymptotic expansion (appropriate for n >= 25)
     let mut pow = inv_sq; // n^-2
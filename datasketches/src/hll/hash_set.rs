@@ -30,6 +30,7 @@ use crate::hll::HllType;
 use crate::hll::KEY_MASK_26;
 use crate::hll::container::Container;
 use crate::hll::serialization::COMPACT_FLAG_MASK;
+use crate::hll::serialization::COUPON_SIZE_BYTES;
 use crate::hll::serialization::CUR_MODE_SET;
 use crate::hll::serialization::HASH_SET_PREINTS;
 use crate::hll::serialization::SERIAL_VERSION;
@@ -142,17 +143,29 @@ impl HashSet {
         }
     }
 
-    /// Serialize a HashSet to bytes
-    pub fn serialize(&self, lg_config_k: u8, hll_type: HllType) -> Vec<u8> {
-        let compact = true; // Always use compact format
+    /// Returns the number of bytes [`serialize_to`](Self::serialize_to) writes: the preamble plus
+    /// one int per stored coupon if compact, or per hash table slot otherwise.
+    pub fn serialized_size_bytes(&self, compact: bool) -> usize {
+        let num_ints = if compact {
+            self.container.len()
+        } else {
+            1 << self.container.lg_size()
+        };
+        SET_PREAMBLE_SIZE + num_ints * COUPON_SIZE_BYTES
+    }
+
+    /// Serialize a HashSet into `bytes`
+    pub fn serialize_to(
+        &self,
+        bytes: &mut SketchBytes<'_>,
+        lg_config_k: u8,
+        hll_type: HllType,
+        compact: bool,
+    ) {
         let coupon_count = self.container.len();
         let lg_arr = self.container.lg_size();
 
-        // Compute size
-        let array_size = if compact { coupon_count } else { 1 << lg_arr };
-        let total_size = SET_PREAMBLE_SIZE + (array_size * 4);
-
-        let mut bytes = SketchBytes::with_capacity(total_size);
+        bytes.reserve(self.serialized_size_bytes(compact));
 
         // Write preamble
         bytes.write_u8(HASH_SET_PREINTS);
@@ -177,9 +190,10 @@ impl HashSet {
         // Write coupon count
         bytes.write_u32_le(coupon_count as u32);
 
-        // Write coupons
+        // Write coupons: sorted if compact, so that equal sets produce identical bytes whatever
+        // their insertion order
         if compact {
-            for coupon in self.container.iter() {
+            for coupon in self.container.sorted_coupons() {
                 bytes.write_u32_le(coupon.raw());
             }
         } else {
@@ -188,7 +202,5 @@ impl HashSet {
                 bytes.write_u32_le(coupon.raw());
             }
         }
-
-        bytes.into_bytes()
     }
 }
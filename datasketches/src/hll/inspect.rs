@@ -0,0 +1,280 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Introspection of serialized HLL sketches.
+//!
+//! [`inspect`] decodes only the preamble of a serialized sketch, which is enough for tooling that
+//! needs to report on stored blobs without paying for a full deserialization.
+
+use crate::codec::SketchSlice;
+use crate::codec::assert::insufficient_data;
+use crate::codec::family::Family;
+use crate::error::Error;
+use crate::hll::HllMode;
+use crate::hll::HllType;
+use crate::hll::check_lg_k;
+use crate::hll::serialization::COMPACT_FLAG_MASK;
+use crate::hll::serialization::CUR_MODE_HLL;
+use crate::hll::serialization::CUR_MODE_LIST;
+use crate::hll::serialization::CUR_MODE_SET;
+use crate::hll::serialization::EMPTY_FLAG_MASK;
+use crate::hll::serialization::HASH_SET_PREINTS;
+use crate::hll::serialization::HLL_PREINTS;
+use crate::hll::serialization::LIST_PREINTS;
+use crate::hll::serialization::OUT_OF_ORDER_FLAG_MASK;
+use crate::hll::serialization::TGT_HLL4;
+use crate::hll::serialization::TGT_HLL6;
+use crate::hll::serialization::TGT_HLL8;
+use crate::hll::serialization::extract_cur_mode;
+use crate::hll::serialization::extract_tgt_hll_type;
+
+/// Preamble fields of a serialized HLL sketch, as returned by [`inspect`].
+///
+/// Fields that only exist in some modes are returned as `Option`: the coupon count is stored in
+/// List and Set mode, while `cur_min`, the HIP registers, `num_at_cur_min` and the aux count are
+/// stored in HLL mode.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SketchInfo {
+    preamble_ints: u8,
+    serial_version: u8,
+    lg_config_k: u8,
+    lg_arr: u8,
+    flags: u8,
+    mode: HllMode,
+    target_type: HllType,
+    coupon_count: Option<u32>,
+    cur_min: Option<u8>,
+    hip_accum: Option<f64>,
+    kxq0: Option<f64>,
+    kxq1: Option<f64>,
+    num_at_cur_min: Option<u32>,
+    aux_count: Option<u32>,
+}
+
+impl SketchInfo {
+    /// Returns the number of 32-bit integers in the preamble.
+    pub fn preamble_ints(&self) -> u8 {
+        self.preamble_ints
+    }
+
+    /// Returns the serialization version.
+    pub fn serial_version(&self) -> u8 {
+        self.serial_version
+    }
+
+    /// Returns the family id, which is always the HLL family id.
+    pub fn family_id(&self) -> u8 {
+        Family::HLL.id
+    }
+
+    /// Returns log2 of the number of buckets (K).
+    pub fn lg_config_k(&self) -> u8 {
+        self.lg_config_k
+    }
+
+    /// Returns log2 of the coupon array size in List and Set mode.
+    pub fn lg_arr(&self) -> u8 {
+        self.lg_arr
+    }
+
+    /// Returns the raw flags byte.
+    pub fn flags(&self) -> u8 {
+        self.flags
+    }
+
+    /// Returns true if the empty flag is set.
+    pub fn is_empty(&self) -> bool {
+        self.flags & EMPTY_FLAG_MASK != 0
+    }
+
+    /// Returns true if the compact flag is set.
+    pub fn is_compact(&self) -> bool {
+        self.flags & COMPACT_FLAG_MASK != 0
+    }
+
+    /// Returns true if the out-of-order flag is set, meaning the HIP estimator is not valid.
+    pub fn is_out_of_order(&self) -> bool {
+        self.flags & OUT_OF_ORDER_FLAG_MASK != 0
+    }
+
+    /// Returns the storage mode the sketch was serialized in.
+    pub fn mode(&self) -> HllMode {
+        self.mode
+    }
+
+    /// Returns the target HLL type of the sketch.
+    pub fn target_type(&self) -> HllType {
+        self.target_type
+    }
+
+    /// Returns the number of coupons in List or Set mode.
+    pub fn coupon_count(&self) -> Option<u32> {
+        self.coupon_count
+    }
+
+    /// Returns the minimum register value in HLL mode.
+    pub fn cur_min(&self) -> Option<u8> {
+        self.cur_min
+    }
+
+    /// Returns the HIP accumulator in HLL mode.
+    pub fn hip_accum(&self) -> Option<f64> {
+        self.hip_accum
+    }
+
+    /// Returns the KxQ register for values below 32 in HLL mode.
+    pub fn kxq0(&self) -> Option<f64> {
+        self.kxq0
+    }
+
+    /// Returns the KxQ register for values of 32 and above in HLL mode.
+    pub fn kxq1(&self) -> Option<f64> {
+        self.kxq1
+    }
+
+    /// Returns the number of registers holding `cur_min` in HLL mode.
+    pub fn num_at_cur_min(&self) -> Option<u32> {
+        self.num_at_cur_min
+    }
+
+    /// Returns the number of auxiliary exception entries in HLL mode.
+    ///
+    /// This is only non-zero for [`HllType::Hll4`] sketches.
+    pub fn aux_count(&self) -> Option<u32> {
+        self.aux_count
+    }
+}
+
+/// Decodes the preamble of a serialized HLL sketch without deserializing the sketch data.
+///
+/// # Errors
+///
+/// Returns an error if the bytes are too short to hold the preamble, or if the family id, serial
+/// version, lg_k, HLL type, mode or preamble size is invalid.
+///
+/// # Examples
+///
+/// ```
+/// # use datasketches::hll;
+/// # use datasketches::hll::HllMode;
+/// # use datasketches::hll::HllSketch;
+/// # use datasketches::hll::HllType;
+/// let mut sketch = HllSketch::new(12, HllType::Hll8);
+/// sketch.update("apple");
+///
+/// let info = hll::inspect(&sketch.serialize()).unwrap();
+/// assert_eq!(info.lg_config_k(), 12);
+/// assert_eq!(info.mode(), HllMode::List);
+/// assert_eq!(info.coupon_count(), Some(1));
+/// ```
+pub fn inspect(bytes: &[u8]) -> Result<SketchInfo, Error> {
+    let mut cursor = SketchSlice::new(bytes);
+    let preamble_ints = cursor
+        .read_u8()
+        .map_err(insufficient_data("preamble_ints"))?;
+    let serial_version = cursor
+        .read_u8()
+        .map_err(insufficient_data("serial_version"))?;
+    let family_id = cursor.read_u8().map_err(insufficient_data("family_id"))?;
+    let lg_config_k = cursor.read_u8().map_err(insufficient_data("lg_config_k"))?;
+    let lg_arr = cursor.read_u8().map_err(insufficient_data("lg_arr"))?;
+    let flags = cursor.read_u8().map_err(insufficient_data("flags"))?;
+    let state = cursor.read_u8().map_err(insufficient_data("state"))?;
+    let mode_byte = cursor.read_u8().map_err(insufficient_data("mode"))?;
+
+    Family::HLL.validate_id(family_id)?;
+    Family::HLL.validate_serial_version(serial_version)?;
+    check_lg_k("lg_config_k", lg_config_k).map_err(Error::deserial)?;
+
+    let target_type = match extract_tgt_hll_type(mode_byte) {
+        TGT_HLL4 => HllType::Hll4,
+        TGT_HLL6 => HllType::Hll6,
+        TGT_HLL8 => HllType::Hll8,
+        hll_type => {
+            return Err(Error::deserial(format!("invalid HLL type: {hll_type}")));
+        }
+    };
+
+    let mut info = SketchInfo {
+        preamble_ints,
+        serial_version,
+        lg_config_k,
+        lg_arr,
+        flags,
+        mode: HllMode::List,
+        target_type,
+        coupon_count: None,
+        cur_min: None,
+        hip_accum: None,
+        kxq0: None,
+        kxq1: None,
+        num_at_cur_min: None,
+        aux_count: None,
+    };
+
+    let (mode_name, expected_preamble_ints) = match extract_cur_mode(mode_byte) {
+        CUR_MODE_LIST => ("LIST", LIST_PREINTS),
+        CUR_MODE_SET => ("SET", HASH_SET_PREINTS),
+        CUR_MODE_HLL => ("HLL", HLL_PREINTS),
+        mode => return Err(Error::deserial(format!("invalid mode: {mode}"))),
+    };
+    if preamble_ints != expected_preamble_ints {
+        return Err(Error::deserial(format!(
+            "{mode_name} mode preamble: expected {expected_preamble_ints}, got {preamble_ints}"
+        )));
+    }
+
+    match extract_cur_mode(mode_byte) {
+        CUR_MODE_LIST => {
+            info.coupon_count = Some(state as u32);
+        }
+        CUR_MODE_SET => {
+            info.mode = HllMode::Set;
+            let coupon_count = cursor
+                .read_u32_le()
+                .map_err(insufficient_data("coupon_count"))?;
+            info.coupon_count = Some(coupon_count);
+        }
+        _ => {
+            info.mode = match target_type {
+                HllType::Hll4 => HllMode::Hll4,
+                HllType::Hll6 => HllMode::Hll6,
+                HllType::Hll8 => HllMode::Hll8,
+            };
+            info.cur_min = Some(state);
+            info.hip_accum = Some(
+                cursor
+                    .read_f64_le()
+                    .map_err(insufficient_data("hip_accum"))?,
+            );
+            info.kxq0 = Some(cursor.read_f64_le().map_err(insufficient_data("kxq0"))?);
+            info.kxq1 = Some(cursor.read_f64_le().map_err(insufficient_data("kxq1"))?);
+            info.num_at_cur_min = Some(
+                cursor
+                    .read_u32_le()
+                    .map_err(insufficient_data("num_at_cur_min"))?,
+            );
+            info.aux_count = Some(
+                cursor
+                    .read_u32_le()
+                    .map_err(insufficient_data("aux_count"))?,
+            );
+        }
+    }
+
+    Ok(info)
+}
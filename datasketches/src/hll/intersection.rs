@@ -0,0 +1,178 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Intersection cardinality estimation for HLL sketches via inclusion-exclusion.
+
+use crate::common::NumStdDev;
+use crate::hll::HllSketch;
+use crate::hll::HllUnion;
+
+/// Relative error above which an [`IntersectionEstimate`] is reported as unreliable.
+const MAX_RELIABLE_RELATIVE_ERROR: f64 = 1.0;
+
+/// Estimate of `|A ∩ B|` computed by [`intersection_estimate`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IntersectionEstimate {
+    lower_bound: f64,
+    estimate: f64,
+    upper_bound: f64,
+}
+
+impl IntersectionEstimate {
+    /// Returns the approximate lower error bound of the intersection size.
+    pub fn lower_bound(&self) -> f64 {
+        self.lower_bound
+    }
+
+    /// Returns the intersection size estimate.
+    pub fn estimate(&self) -> f64 {
+        self.estimate
+    }
+
+    /// Returns the approximate upper error bound of the intersection size.
+    pub fn upper_bound(&self) -> f64 {
+        self.upper_bound
+    }
+
+    /// Returns the bounds and the estimate as `(lower_bound, estimate, upper_bound)`.
+    pub fn to_tuple(&self) -> (f64, f64, f64) {
+        (self.lower_bound, self.estimate, self.upper_bound)
+    }
+
+    /// Returns the half-width of the bounds relative to the estimate.
+    ///
+    /// Returns [`f64::INFINITY`] if the estimate is 0 but the upper bound is not.
+    pub fn relative_error(&self) -> f64 {
+        let half_width = (self.upper_bound - self.lower_bound) / 2.0;
+        if half_width == 0.0 {
+            0.0
+        } else if self.estimate == 0.0 {
+            f64::INFINITY
+        } else {
+            half_width / self.estimate
+        }
+    }
+
+    /// Returns false if the [`relative_error`](Self::relative_error) exceeds 100%.
+    ///
+    /// Inclusion-exclusion subtracts large, noisy quantities, so the error of the result scales
+    /// with the size of the union rather than the intersection. When the intersection is a small
+    /// fraction of the union, the bounds are wider than the estimate itself and the estimate
+    /// should not be trusted; use a Theta or Tuple sketch, which estimate intersections directly.
+    pub fn is_reliable(&self) -> bool {
+        self.relative_error() <= MAX_RELIABLE_RELATIVE_ERROR
+    }
+}
+
+/// Estimates the size of the intersection of two HLL sketches by inclusion-exclusion.
+///
+/// The estimate is `|A| + |B| - |A ∪ B|`, clamped to `[0, min(|A|, |B|)]`, with the union
+/// computed by an [`HllUnion`] at the larger `lg_config_k` of the two sketches. The bounds
+/// propagate the error of each of the three terms at the given number of standard deviations,
+/// combining them in quadrature.
+///
+/// HLL sketches cannot intersect directly, and the error of the result is driven by the union:
+/// check [`IntersectionEstimate::is_reliable`] before using the estimate.
+///
+/// # Panics
+///
+/// Panics if the sketches were built with different seeds.
+///
+/// # Examples
+///
+/// ```
+/// # use datasketches::common::NumStdDev;
+/// # use datasketches::hll::HllSketch;
+/// # use datasketches::hll::HllType;
+/// # use datasketches::hll::intersection_estimate;
+/// let mut a = HllSketch::new(12, HllType::Hll8);
+/// let mut b = HllSketch::new(12, HllType::Hll8);
+/// for i in 0..100_000 {
+///     a.update(i);
+///     b.update(i + 50_000);
+/// }
+///
+/// let result = intersection_estimate(&a, &b, NumStdDev::Two);
+/// let (lb, est, ub) = result.to_tuple();
+/// assert!(lb <= est && est <= ub);
+/// assert!((est - 50_000.0).abs() < 5_000.0);
+/// assert!(result.is_reliable());
+/// ```
+pub fn intersection_estimate(
+    a: &HllSketch,
+    b: &HllSketch,
+    num_std_dev: NumStdDev,
+) -> IntersectionEstimate {
+    let union = HllUnion::of_pair(a, b);
+
+    let terms = [
+        Term::of_sketch(a, num_std_dev),
+        Term::of_sketch(b, num_std_dev),
+    ];
+    let union = Term {
+        estimate: union.estimate(),
+        lower_bound: union.lower_bound(num_std_dev),
+        upper_bound: union.upper_bound(num_std_dev),
+    };
+
+    let max_estimate = terms[0].estimate.min(terms[1].estimate);
+    let max_upper_bound = terms[0].upper_bound.min(terms[1].upper_bound);
+
+    let raw = terms[0].estimate + terms[1].estimate - union.estimate;
+    let estimate = raw.clamp(0.0, max_estimate);
+
+    // The union enters with a negative sign, so its lower deviation widens the upper bound and
+    // vice versa.
+    let lower_deviation = (terms[0].lower_deviation().powi(2)
+        + terms[1].lower_deviation().powi(2)
+        + union.upper_deviation().powi(2))
+    .sqrt();
+    let upper_deviation = (terms[0].upper_deviation().powi(2)
+        + terms[1].upper_deviation().powi(2)
+        + union.lower_deviation().powi(2))
+    .sqrt();
+
+    IntersectionEstimate {
+        lower_bound: (raw - lower_deviation).clamp(0.0, estimate),
+        estimate,
+        upper_bound: (raw + upper_deviation).clamp(estimate, max_upper_bound.max(estimate)),
+    }
+}
+
+struct Term {
+    estimate: f64,
+    lower_bound: f64,
+    upper_bound: f64,
+}
+
+impl Term {
+    fn of_sketch(sketch: &HllSketch, num_std_dev: NumStdDev) -> Self {
+        Self {
+            estimate: sketch.estimate(),
+            lower_bound: sketch.lower_bound(num_std_dev),
+            upper_bound: sketch.upper_bound(num_std_dev),
+        }
+    }
+
+    fn lower_deviation(&self) -> f64 {
+        (self.estimate - self.lower_bound).max(0.0)
+    }
+
+    fn upper_deviation(&self) -> f64 {
+        (self.upper_bound - self.estimate).max(0.0)
+    }
+}
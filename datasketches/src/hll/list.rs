@@ -28,6 +28,7 @@ use crate::hll::Coupon;
 use crate::hll::HllType;
 use crate::hll::container::Container;
 use crate::hll::serialization::COMPACT_FLAG_MASK;
+use crate::hll::serialization::COUPON_SIZE_BYTES;
 use crate::hll::serialization::CUR_MODE_LIST;
 use crate::hll::serialization::EMPTY_FLAG_MASK;
 use crate::hll::serialization::LIST_PREAMBLE_SIZE;
@@ -107,18 +108,32 @@ impl List {
         })
     }
 
-    /// Serialize a List to bytes
-    pub fn serialize(&self, lg_config_k: u8, hll_type: HllType) -> Vec<u8> {
-        let compact = true; // Always use compact format
+    /// Returns the number of bytes [`serialize_to`](Self::serialize_to) writes: the preamble plus
+    /// one int per stored coupon if compact, or per array slot otherwise.
+    pub fn serialized_size_bytes(&self, compact: bool) -> usize {
+        let num_ints = if compact {
+            self.container.len()
+        } else if self.container.is_empty() {
+            0
+        } else {
+            1 << self.container.lg_size()
+        };
+        LIST_PREAMBLE_SIZE + num_ints * COUPON_SIZE_BYTES
+    }
+
+    /// Serialize a List into `bytes`
+    pub fn serialize_to(
+        &self,
+        bytes: &mut SketchBytes<'_>,
+        lg_config_k: u8,
+        hll_type: HllType,
+        compact: bool,
+    ) {
         let empty = self.container.is_empty();
         let coupon_count = self.container.len();
         let lg_arr = self.container.lg_size();
 
-        // Compute size
-        let array_size = if compact { coupon_count } else { 1 << lg_arr };
-        let total_size = LIST_PREAMBLE_SIZE + (array_size * 4);
-
-        let mut bytes = SketchBytes::with_capacity(total_size);
+        bytes.reserve(self.serialized_size_bytes(compact));
 
         // Write preamble
         bytes.write_u8(LIST_PREINTS);
@@ -143,21 +158,18 @@ impl List {
         // Write mode byte: LIST mode with target HLL type
         bytes.write_u8(encode_mode_byte(CUR_MODE_LIST, hll_type as u8));
 
-        // Write coupons (only non-empty ones if compact)
+        // Write coupons: sorted if compact, so that equal lists produce identical bytes, or the
+        // whole array otherwise
         if !empty {
-            let mut write_idx = 0;
-            for coupon in self.container.coupons.iter().copied() {
-                if compact && coupon.is_empty() {
-                    continue; // Skip empty coupons in compact mode
+            if compact {
+                for coupon in self.container.sorted_coupons() {
+                    bytes.write_u32_le(coupon.raw());
                 }
-                bytes.write_u32_le(coupon.raw());
-                write_idx += 1;
-                if write_idx >= array_size {
-                    break;
+            } else {
+                for coupon in self.container.coupons.iter() {
+                    bytes.write_u32_le(coupon.raw());
                 }
             }
         }
-
-        bytes.into_bytes()
     }
 }
@@ -70,7 +70,17 @@
 //! * Out-of-order flag for merged/deserialized sketches
 //!
 //! The serialization format is compatible with Apache DataSketches implementations
-//! in Java and C++, enabling cross-platform sketch exchange.
+//! in Java and C++, enabling cross-platform sketch exchange. The preamble of a serialized
+//! sketch can be examined with [`inspect`] without deserializing the sketch itself.
+//!
+//! # Reproducibility
+//!
+//! Estimates and bounds are bit-identical across platforms for the same sequence of updates. They
+//! are computed with IEEE 754 basic operations only, each correctly rounded to nearest, which Rust
+//! never contracts into fused multiply-adds; the logarithm used by the linear counting estimator
+//! is a portable port of fdlibm's `log` (as used by Java's `StrictMath.log`) rather than the
+//! platform libm. Estimates can still differ from Java and C++ in the last bits where those
+//! implementations call their platform math libraries.
 //!
 //! # Usage
 //!
@@ -105,6 +115,7 @@
 
 use std::hash::Hash;
 
+use crate::hash::DEFAULT_UPDATE_SEED;
 use crate::hash::MurmurHash3X64128;
 
 mod array4;
@@ -118,19 +129,30 @@ mod cubic_interpolation;
 mod estimator;
 mod harmonic_numbers;
 mod hash_set;
+mod inspect;
+mod intersection;
 mod list;
 mod mode;
+mod planning;
 mod serialization;
 mod sketch;
 mod union;
 
+pub use self::inspect::SketchInfo;
+pub use self::inspect::inspect;
+pub use self::intersection::IntersectionEstimate;
+pub use self::intersection::intersection_estimate;
+pub use self::planning::error_for_lg_k;
+pub use self::planning::lg_k_for_error;
 pub use self::sketch::HllSketch;
+pub use self::sketch::HllSketchBuilder;
 pub use self::union::HllUnion;
+pub use self::union::union_estimate;
 
 /// Target HLL type.
 ///
 /// See [module level documentation](self) for more details.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum HllType {
     /// Uses a 4-bit field per HLL bucket and for large counts may require the use of a
     /// small internal auxiliary array for storing statistical exceptions, which are rare.
@@ -148,19 +170,70 @@ pub enum HllType {
     Hll8,
 }
 
+/// Storage mode of an HLL sketch.
+///
+/// Sketches start in [`HllMode::List`], are promoted to [`HllMode::Set`] as more distinct
+/// coupons arrive, and finally to the HLL array mode matching their target [`HllType`].
+/// Use [`HllSketch::current_mode`] to observe the mode of a sketch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HllMode {
+    /// Coupons are kept in a small list.
+    List,
+    /// Coupons are kept in a hash set.
+    Set,
+    /// Registers are kept in a 4-bit array.
+    Hll4,
+    /// Registers are kept in a 6-bit array.
+    Hll6,
+    /// Registers are kept in an 8-bit array.
+    Hll8,
+}
+
+/// Default log2 of the number of registers (K).
+pub const DEFAULT_LG_K: u8 = 12;
+/// Minimum log2 of the number of registers (K).
+pub const MIN_LG_K: u8 = 4;
+/// Maximum log2 of the number of registers (K).
+///
+/// This is the limit of datasketches-java and datasketches-cpp, and it applies to sketches,
+/// unions and serialized images alike. [`Coupon`] slots keep 26 bits of the hash so that the same
+/// coupon can update a sketch of any lg_k, but that is not a larger supported range.
+pub const MAX_LG_K: u8 = 21;
+
+/// Checks that `lg_k` is in `[MIN_LG_K, MAX_LG_K]`, naming the parameter in the error message.
+fn check_lg_k(name: &str, lg_k: u8) -> Result<(), String> {
+    if (MIN_LG_K..=MAX_LG_K).contains(&lg_k) {
+        Ok(())
+    } else {
+        Err(format!(
+            "{name} must be in [{MIN_LG_K}, {MAX_LG_K}], got {lg_k}"
+        ))
+    }
+}
+
 const KEY_BITS_26: u32 = 26;
 const KEY_MASK_26: u32 = (1 << KEY_BITS_26) - 1;
 
 const COUPON_RSE_FACTOR: f64 = 0.409; // At transition point not the asymptote
 const COUPON_RSE: f64 = COUPON_RSE_FACTOR / (1 << 13) as f64;
 
-const RESIZE_NUMERATOR: u32 = 3; // Resize at 3/4 = 75% load factor
-const RESIZE_DENOMINATOR: u32 = 4;
+/// Numerator of the load factor above which coupon hash sets and HLL4 aux maps grow.
+///
+/// A coupon hash set holding more than `RESIZE_NUMERATOR / RESIZE_DENOMINATOR` (75%) of its slots
+/// doubles in size, or is promoted to an HLL array once it has `K / 8` slots. The policy is fixed
+/// because it decides the promotion points and serialized table sizes, which match
+/// datasketches-java and datasketches-cpp.
+pub const RESIZE_NUMERATOR: u32 = 3;
+/// Denominator of the resize load factor; see [`RESIZE_NUMERATOR`].
+pub const RESIZE_DENOMINATOR: u32 = 4;
 
 /// A coupon encodes a (slot, value) pair derived from hashing an input.
 ///
 /// Format: `[value (6 bits) << 26] | [slot (26 bits)]`
 ///
+/// The slot keeps 26 bits of the hash regardless of lg_k; a sketch masks it down to its own
+/// `lg_config_k` bits, which are at most [`MAX_LG_K`].
+///
 /// The slot identifies an HLL register (derived from the lower bits of the hash),
 /// and the value represents the number of leading zeros plus one (from the upper bits).
 ///
@@ -209,18 +282,82 @@ impl Coupon {
     /// You may use [`hash_value`](crate::hash_value) wrappers when matching other datasketches
     /// implementations require a specific value hashing strategy.
     ///
-    /// Hashes `v` using MurmurHash3 128-bit and packs the result into a coupon:
-    /// the low 26 bits of the low hash word become the slot index, and the
-    /// leading-zero count of the high hash word (capped at 62, then plus one)
-    /// becomes the 6-bit register value.
+    /// Hashes `v` using MurmurHash3 128-bit with the default update seed (9001), which is the seed
+    /// every HLL implementation uses, and packs the result as described in
+    /// [`Coupon::from_hash_with_seed`].
     #[inline(always)]
     pub fn from_hash<T: Hash>(v: T) -> Self {
-        let mut hasher = MurmurHash3X64128::default();
+        Self::from_hash_with_seed(v, DEFAULT_UPDATE_SEED)
+    }
+
+    /// Compute the HLL coupon for a hashable value using the given hash seed.
+    ///
+    /// Hashes `v` using MurmurHash3 128-bit and packs the result into a coupon exactly as Java's
+    /// `HllUtil` and C++'s `coupon()` do: the low 26 bits of the first hash word become the slot
+    /// index, and the leading-zero count of the second hash word (capped at 62, then plus one)
+    /// becomes the 6-bit register value.
+    ///
+    /// Coupons computed with a seed other than the default are only comparable with coupons
+    /// computed with the same seed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::hll::Coupon;
+    /// assert_eq!(
+    ///     Coupon::from_hash_with_seed("apple", 9001),
+    ///     Coupon::from_hash("apple")
+    /// );
+    /// assert_ne!(
+    ///     Coupon::from_hash_with_seed("apple", 42),
+    ///     Coupon::from_hash("apple")
+    /// );
+    /// ```
+    #[inline(always)]
+    pub fn from_hash_with_seed<T: Hash>(v: T, seed: u64) -> Self {
+        let mut hasher = MurmurHash3X64128::with_seed(seed);
         v.hash(&mut hasher);
-        let (lo, hi) = hasher.finish128();
+        let (h1, h2) = hasher.finish128();
+        Self::from_hash128(h1, h2)
+    }
+
+    /// Compute the HLL coupon for a byte string, with the default update seed.
+    ///
+    /// The bytes are hashed as-is, without the length prefix that the [`Hash`] implementation of
+    /// slices writes, so the coupon is the one Java and C++ compute for the same `byte[]`, or for
+    /// a string with these UTF-8 bytes. It equals
+    /// `Coupon::from_hash(hash_value::raw_bytes::from_slice(bytes))` but hashes the bytes in one
+    /// shot.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::hash_value::raw_bytes;
+    /// # use datasketches::hll::Coupon;
+    /// assert_eq!(
+    ///     Coupon::from_bytes(b"apple"),
+    ///     Coupon::from_hash(raw_bytes::from_str("apple"))
+    /// );
+    /// ```
+    #[inline(always)]
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        Self::from_bytes_with_seed(bytes, DEFAULT_UPDATE_SEED)
+    }
+
+    /// Compute the HLL coupon for a byte string using the given hash seed.
+    ///
+    /// See [`Coupon::from_bytes`] and [`Coupon::from_hash_with_seed`].
+    #[inline(always)]
+    pub fn from_bytes_with_seed(bytes: &[u8], seed: u64) -> Self {
+        let (h1, h2) = MurmurHash3X64128::hash_bytes(bytes, seed);
+        Self::from_hash128(h1, h2)
+    }
 
-        let addr26 = lo as u32 & KEY_MASK_26;
-        let lz = hi.leading_zeros();
+    /// Pack the two 64-bit words of a MurmurHash3 128-bit hash into a coupon.
+    #[inline(always)]
+    fn from_hash128(h1: u64, h2: u64) -> Self {
+        let addr26 = h1 as u32 & KEY_MASK_26;
+        let lz = h2.leading_zeros();
         let capped = lz.min(62);
         let value = capped + 1;
 
@@ -248,6 +385,7 @@ impl Coupon {
 
 #[cfg(test)]
 mod tests {
+    use crate::hash_value::raw_bytes;
     use crate::hll::Coupon;
 
     #[test]
@@ -258,4 +396,57 @@ mod tests {
         assert_eq!(coupon.slot(), slot);
         assert_eq!(coupon.value(), value);
     }
+
+    // Reference MurmurHash3_x64_128 vectors (seed 0), shared with the hash module tests, and the
+    // coupons Java's HllUtil derives from them.
+    #[test]
+    fn test_coupon_cross_language_vectors() {
+        let vectors: [(&str, u64, u64); 3] = [
+            (
+                "The quick brown fox jumps over the lazy dog",
+                0xe34bbc7bbc071b6c,
+                0x7a433ca9c49a9347,
+            ),
+            (
+                "The quick brown fox jumps over the lazy eog",
+                0x362108102c62d1c9,
+                0x3285cd100292b305,
+            ),
+            (
+                "The quick brown fox jumps over t",
+                0xdf6af91bb29bdacf,
+                0x91a341c58df1f3a6,
+            ),
+        ];
+        let expected = [(0x0007_1b6c, 2), (0x0062_d1c9, 3), (0x029b_dacf, 1)];
+
+        for ((key, h1, h2), (slot, value)) in vectors.into_iter().zip(expected) {
+            let coupon = Coupon::from_hash_with_seed(raw_bytes::from_str(key), 0);
+            assert_eq!(
+                coupon,
+                Coupon::from_bytes_with_seed(key.as_bytes(), 0),
+                "{key}"
+            );
+            assert_eq!(coupon, Coupon::from_hash128(h1, h2), "{key}");
+            assert_eq!(coupon.slot(), slot, "{key}");
+            assert_eq!(coupon.value(), value, "{key}");
+        }
+    }
+
+    #[test]
+    fn test_coupon_value_is_capped() {
+        assert_eq!(Coupon::from_hash128(0, 0).value(), 63);
+        assert_eq!(Coupon::from_hash128(0, 1).value(), 63);
+        assert_eq!(Coupon::from_hash128(0, 2).value(), 63);
+        assert_eq!(Coupon::from_hash128(0, 4).value(), 62);
+        assert_eq!(Coupon::from_hash128(0, u64::MAX).value(), 1);
+        assert_eq!(Coupon::from_hash128(u64::MAX, 0).slot(), (1 << 26) - 1);
+    }
+
+    #[test]
+    fn test_coupon_default_seed() {
+        for i in 0..100u64 {
+            assert_eq!(Coupon::from_hash(i), Coupon::from_hash_with_seed(i, 9001));
+        }
+    }
 }
@@ -20,22 +20,24 @@
 //! This module contains all constants related to the Apache DataSketches
 //! binary serialization format, shared across all sketch modes.
 
+use crate::codec::format;
+
 /// Current serialization version
-pub const SERIAL_VERSION: u8 = 1;
+pub const SERIAL_VERSION: u8 = format::hll::SERIAL_VERSION;
 
 /// Flag indicating sketch is empty (no values inserted)
-pub const EMPTY_FLAG_MASK: u8 = 4;
+pub const EMPTY_FLAG_MASK: u8 = format::hll::FLAGS_IS_EMPTY;
 /// Flag indicating compact serialization (no empty slots stored)
-pub const COMPACT_FLAG_MASK: u8 = 8;
+pub const COMPACT_FLAG_MASK: u8 = format::hll::FLAGS_IS_COMPACT;
 /// Flag indicating out-of-order mode (HIP estimator invalid)
-pub const OUT_OF_ORDER_FLAG_MASK: u8 = 16;
+pub const OUT_OF_ORDER_FLAG_MASK: u8 = format::hll::FLAGS_IS_OUT_OF_ORDER;
 
 /// Preamble size for LIST mode (8 bytes = 2 ints)
-pub const LIST_PREINTS: u8 = 2;
+pub const LIST_PREINTS: u8 = format::hll::LIST_PREAMBLE_INTS;
 /// Preamble size for SET mode (12 bytes = 3 ints)
-pub const HASH_SET_PREINTS: u8 = 3;
+pub const HASH_SET_PREINTS: u8 = format::hll::SET_PREAMBLE_INTS;
 /// Preamble size for HLL mode (40 bytes = 10 ints)
-pub const HLL_PREINTS: u8 = 10;
+pub const HLL_PREINTS: u8 = format::hll::HLL_PREAMBLE_INTS;
 
 /// Total size of LIST preamble in bytes
 pub const LIST_PREAMBLE_SIZE: usize = 8;
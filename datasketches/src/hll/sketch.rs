@@ -22,19 +22,29 @@
 
 use std::hash::Hash;
 
+use crate::codec::SketchBytes;
 use crate::codec::SketchSlice;
-use crate::codec::assert::ensure_serial_version_is;
 use crate::codec::assert::insufficient_data;
 use crate::codec::family::Family;
+use crate::common::MemoryUsage;
 use crate::common::NumStdDev;
+use crate::common::SketchBuilder;
 use crate::error::Error;
+use crate::hash::DEFAULT_UPDATE_SEED;
+use crate::hash_value::ValueRef;
 use crate::hll::Coupon;
+use crate::hll::DEFAULT_LG_K;
+use crate::hll::HllMode;
 use crate::hll::HllType;
+use crate::hll::HllUnion;
+use crate::hll::MAX_LG_K;
+use crate::hll::MIN_LG_K;
 use crate::hll::RESIZE_DENOMINATOR;
 use crate::hll::RESIZE_NUMERATOR;
 use crate::hll::array4::Array4;
 use crate::hll::array6::Array6;
 use crate::hll::array8::Array8;
+use crate::hll::check_lg_k;
 use crate::hll::container::Container;
 use crate::hll::hash_set::HashSet;
 use crate::hll::list::List;
@@ -48,7 +58,6 @@ use crate::hll::serialization::HASH_SET_PREINTS;
 use crate::hll::serialization::HLL_PREINTS;
 use crate::hll::serialization::LIST_PREINTS;
 use crate::hll::serialization::OUT_OF_ORDER_FLAG_MASK;
-use crate::hll::serialization::SERIAL_VERSION;
 use crate::hll::serialization::TGT_HLL4;
 use crate::hll::serialization::TGT_HLL6;
 use crate::hll::serialization::TGT_HLL8;
@@ -62,9 +71,29 @@ use crate::hll::serialization::extract_tgt_hll_type;
 pub struct HllSketch {
     lg_config_k: u8,
     mode: Mode,
+    seed: u64,
 }
 
 impl HllSketch {
+    /// Returns a builder with lg_config_k 12 and [`HllType::Hll4`], the defaults of the Java
+    /// library.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::hll::HllSketch;
+    /// # use datasketches::hll::HllType;
+    /// let sketch = HllSketch::builder()
+    ///     .lg_config_k(10)
+    ///     .hll_type(HllType::Hll8)
+    ///     .build();
+    /// assert_eq!(sketch.lg_config_k(), 10);
+    /// assert_eq!(sketch.target_type(), HllType::Hll8);
+    /// ```
+    pub fn builder() -> HllSketchBuilder {
+        HllSketchBuilder::default()
+    }
+
     /// Create a new HLL sketch
     ///
     /// # Arguments
@@ -88,10 +117,36 @@ impl HllSketch {
     /// assert_eq!(sketch.lg_config_k(), 12);
     /// ```
     pub fn new(lg_config_k: u8, hll_type: HllType) -> Self {
+        Self::with_seed(lg_config_k, hll_type, DEFAULT_UPDATE_SEED)
+    }
+
+    /// Create a new HLL sketch that hashes its inputs with `seed`.
+    ///
+    /// Sketches built with different seeds summarize unrelated hashes, so they cannot be merged:
+    /// [`HllUnion::update`] panics when the seeds differ. The serialized format, shared with the
+    /// Java and C++ libraries, does not record the seed, so sketches built with a non-default seed
+    /// are read back with [`deserialize_with_seed`](Self::deserialize_with_seed).
+    ///
+    /// # Panics
+    ///
+    /// If lg_config_k is not in range `[4, 21]`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::hll::HllSketch;
+    /// # use datasketches::hll::HllType;
+    /// let mut sketch = HllSketch::with_seed(12, HllType::Hll8, 42);
+    /// sketch.update("apple");
+    /// assert_eq!(sketch.seed(), 42);
+    ///
+    /// let decoded = HllSketch::deserialize_with_seed(&sketch.serialize(), 42).unwrap();
+    /// assert_eq!(decoded, sketch);
+    /// ```
+    pub fn with_seed(lg_config_k: u8, hll_type: HllType, seed: u64) -> Self {
         assert!(
-            (4..=21).contains(&lg_config_k),
-            "lg_config_k must be in [4, 21], got {}",
-            lg_config_k
+            (MIN_LG_K..=MAX_LG_K).contains(&lg_config_k),
+            "lg_config_k must be in [{MIN_LG_K}, {MAX_LG_K}], got {lg_config_k}"
         );
 
         let list = List::default();
@@ -99,9 +154,38 @@ impl HllSketch {
         Self {
             lg_config_k,
             mode: Mode::List { list, hll_type },
+            seed,
         }
     }
 
+    /// Resets the sketch to its empty initial state, keeping `lg_config_k`, the target HLL type
+    /// and the seed.
+    ///
+    /// The coupon hash set or HLL array is dropped, so the sketch releases its memory and starts
+    /// again from a small coupon list, as a new sketch does.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::hll::HllMode;
+    /// # use datasketches::hll::HllSketch;
+    /// # use datasketches::hll::HllType;
+    /// let mut sketch = HllSketch::new(12, HllType::Hll6);
+    /// for i in 0..10_000 {
+    ///     sketch.update(i);
+    /// }
+    /// sketch.reset();
+    /// assert!(sketch.is_empty());
+    /// assert_eq!(sketch.current_mode(), HllMode::List);
+    /// assert_eq!(sketch, HllSketch::new(12, HllType::Hll6));
+    /// ```
+    pub fn reset(&mut self) {
+        self.mode = Mode::List {
+            list: List::default(),
+            hll_type: self.target_type(),
+        };
+    }
+
     /// Create an HLL sketch directly from a Mode
     ///
     /// This is used internally (e.g., by union operations) to construct
@@ -111,8 +195,13 @@ impl HllSketch {
     ///
     /// * `lg_config_k`: Log2 of the number of buckets (K)
     /// * `mode`: The mode to initialize the sketch with
-    pub(super) fn from_mode(lg_config_k: u8, mode: Mode) -> Self {
-        Self { lg_config_k, mode }
+    /// * `seed`: The hash seed of the sketch the mode was built from
+    pub(super) fn from_mode(lg_config_k: u8, mode: Mode, seed: u64) -> Self {
+        Self {
+            lg_config_k,
+            mode,
+            seed,
+        }
     }
 
     /// Get the current mode of the sketch
@@ -129,6 +218,11 @@ impl HllSketch {
         &mut self.mode
     }
 
+    /// Consume the sketch and return its mode
+    pub(super) fn into_mode(self) -> Mode {
+        self.mode
+    }
+
     /// Check if the sketch is empty (no values have been added)
     pub fn is_empty(&self) -> bool {
         match &self.mode {
@@ -151,18 +245,97 @@ impl HllSketch {
         }
     }
 
+    /// Get the current storage mode of this sketch.
+    ///
+    /// The mode changes as the sketch is promoted from coupon storage to its HLL array; see
+    /// [`HllMode`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::hll::HllMode;
+    /// # use datasketches::hll::HllSketch;
+    /// # use datasketches::hll::HllType;
+    /// let mut sketch = HllSketch::new(10, HllType::Hll4);
+    /// assert_eq!(sketch.current_mode(), HllMode::List);
+    /// for i in 0..10_000 {
+    ///     sketch.update(i);
+    /// }
+    /// assert_eq!(sketch.current_mode(), HllMode::Hll4);
+    /// ```
+    pub fn current_mode(&self) -> HllMode {
+        match &self.mode {
+            Mode::List { .. } => HllMode::List,
+            Mode::Set { .. } => HllMode::Set,
+            Mode::Array4(_) => HllMode::Hll4,
+            Mode::Array6(_) => HllMode::Hll6,
+            Mode::Array8(_) => HllMode::Hll8,
+        }
+    }
+
+    /// Get the value of HLL register `slot`, in `[0, 64)`.
+    ///
+    /// In HLL4 mode the value resolves the `cur_min` offset and aux map exceptions. In List and
+    /// Set modes the register is the largest value among the retained coupons that map to `slot`,
+    /// which is what it would hold after promotion to an HLL array.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `slot` is not less than `2^lg_config_k`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::hll::HllSketch;
+    /// # use datasketches::hll::HllType;
+    /// let mut sketch = HllSketch::new(10, HllType::Hll4);
+    /// for i in 0..10_000 {
+    ///     sketch.update(i);
+    /// }
+    /// let registers: Vec<u8> = (0..1 << 10)
+    ///     .map(|slot| sketch.register_value(slot))
+    ///     .collect();
+    /// assert!(registers.iter().all(|&v| v > 0));
+    /// ```
+    pub fn register_value(&self, slot: u32) -> u8 {
+        let k = 1u32 << self.lg_config_k;
+        assert!(slot < k, "slot must be less than {k}, got {slot}");
+        let from_coupons = |container: &Container| {
+            container
+                .iter()
+                .filter(|coupon| coupon.slot() & (k - 1) == slot)
+                .map(|coupon| coupon.value())
+                .max()
+                .unwrap_or(0)
+        };
+        match &self.mode {
+            Mode::List { list, .. } => from_coupons(list.container()),
+            Mode::Set { set, .. } => from_coupons(set.container()),
+            Mode::Array4(arr) => arr.get(slot),
+            Mode::Array6(arr) => arr.get(slot),
+            Mode::Array8(arr) => arr.get(slot),
+        }
+    }
+
     /// Get the configured lg_config_k
     pub fn lg_config_k(&self) -> u8 {
         self.lg_config_k
     }
 
+    /// Get the hash seed
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
     /// Update the sketch with a value.
     ///
     /// Accepts any type that implements [`Hash`]. The value is hashed and converted to
     /// an internal coupon, which is then inserted into the sketch.
     ///
     /// You may use [`hash_value`](crate::hash_value) wrappers when matching other datasketches
-    /// implementations require a specific value hashing strategy.
+    /// implementations require a specific value hashing strategy;
+    /// [`canonical::from_value`](crate::hash_value::canonical::from_value) covers strings, bytes,
+    /// `u64`, `i64` and `f64`.
     ///
     /// If you need to insert the same logical value into multiple sketches, consider
     /// pre-computing the coupon with [`Coupon::from_hash`] and calling
@@ -184,7 +357,82 @@ impl HllSketch {
     /// assert!(sketch.estimate() >= 1.0);
     /// ```
     pub fn update<T: Hash>(&mut self, value: T) {
-        self.update_with_coupon(Coupon::from_hash(value));
+        self.update_with_coupon(Coupon::from_hash_with_seed(value, self.seed));
+    }
+
+    /// Update the sketch with a value whose type is only known at runtime.
+    ///
+    /// This is [`update`](Self::update) with a [`ValueRef`], which hashes like the value it holds
+    /// wrapped in [`Canonical`](crate::hash_value::canonical::Canonical). Unlike the generic
+    /// method, it is compiled once for every value type. Empty strings and byte strings are
+    /// ignored, as in [`update_str`](Self::update_str) and [`update_bytes`](Self::update_bytes)
+    /// and the other implementations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::hll::HllSketch;
+    /// # use datasketches::hll::HllType;
+    /// # use datasketches::hash_value::ValueRef;
+    /// # use datasketches::hash_value::canonical::from_value;
+    /// let mut sketch = HllSketch::new(10, HllType::Hll8);
+    /// for value in [
+    ///     ValueRef::Str("apple"),
+    ///     ValueRef::I64(42),
+    ///     ValueRef::F64(1.5),
+    /// ] {
+    ///     sketch.update_value_ref(value);
+    /// }
+    ///
+    /// let mut typed = HllSketch::new(10, HllType::Hll8);
+    /// typed.update(from_value("apple"));
+    /// typed.update(from_value(42_i64));
+    /// typed.update(from_value(1.5));
+    /// assert_eq!(sketch.estimate(), typed.estimate());
+    /// ```
+    pub fn update_value_ref(&mut self, value: ValueRef<'_>) {
+        if !value.is_empty() {
+            self.update(value);
+        }
+    }
+
+    /// Update the sketch with a string, hashed as its UTF-8 bytes.
+    ///
+    /// This is how Java and C++ hash strings, so identical strings produce identical sketches
+    /// across languages; `update(&str)` instead hashes the string as Rust's [`Hash`] does, with a
+    /// terminator byte. Empty strings are ignored, as in the other implementations.
+    ///
+    /// It is equivalent to updating with
+    /// [`raw_bytes::from_str`](crate::hash_value::raw_bytes::from_str) after checking that the
+    /// string is non-empty, but hashes the bytes in one shot instead of through a
+    /// [`Hasher`](std::hash::Hasher).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::hash_value::raw_bytes;
+    /// # use datasketches::hll::HllSketch;
+    /// # use datasketches::hll::HllType;
+    /// let mut sketch = HllSketch::new(10, HllType::Hll8);
+    /// sketch.update_str("apple");
+    /// sketch.update_str("");
+    ///
+    /// let mut expected = HllSketch::new(10, HllType::Hll8);
+    /// expected.update(raw_bytes::from_str("apple"));
+    /// assert_eq!(sketch, expected);
+    /// ```
+    pub fn update_str(&mut self, value: &str) {
+        self.update_bytes(value.as_bytes());
+    }
+
+    /// Update the sketch with a byte string, hashed as-is.
+    ///
+    /// This is how Java and C++ hash `byte[]` values; see [`update_str`](Self::update_str).
+    /// Empty byte strings are ignored.
+    pub fn update_bytes(&mut self, value: &[u8]) {
+        if !value.is_empty() {
+            self.update_with_coupon(Coupon::from_bytes_with_seed(value, self.seed));
+        }
     }
 
     /// Update the sketch with a pre-computed [`Coupon`].
@@ -194,6 +442,9 @@ impl HllSketch {
     /// coupon makes it possible to pay the hashing cost once and fan the result out to
     /// many independent sketches — see [`Coupon`] for a worked example.
     ///
+    /// The coupon must be computed with the seed of this sketch, e.g. with
+    /// [`Coupon::from_hash_with_seed`] for a sketch built with a non-default seed.
+    ///
     /// Handles all internal bookkeeping, including automatic mode transitions
     /// (List → Set → HLL array) and estimator state updates.
     ///
@@ -207,16 +458,33 @@ impl HllSketch {
     /// assert!(sketch.estimate() >= 1.0);
     /// ```
     pub fn update_with_coupon(&mut self, coupon: Coupon) {
+        self.update_with_coupon_reusing(coupon, &mut None);
+    }
+
+    /// Like [`update_with_coupon`](Self::update_with_coupon), but an `Hll8` sketch promoted to
+    /// HLL mode takes its register array from `spare` when it has the right size.
+    pub(super) fn update_with_coupon_reusing(
+        &mut self,
+        coupon: Coupon,
+        spare: &mut Option<Array8>,
+    ) {
         match &mut self.mode {
             Mode::List { list, hll_type } => {
                 list.update(coupon);
                 let should_promote = list.container().is_full();
                 if should_promote {
                     self.mode = if self.lg_config_k < 8 {
-                        promote_container_to_array(list.container(), *hll_type, self.lg_config_k)
+                        promote_container_to_array(
+                            list.container(),
+                            *hll_type,
+                            self.lg_config_k,
+                            spare,
+                        )
                     } else {
                         promote_container_to_set(list.container(), *hll_type)
-                    }
+                    };
+                    #[cfg(feature = "tracing")]
+                    self.trace_promotion(HllMode::List);
                 }
             }
             Mode::Set { set, hll_type } => {
@@ -224,11 +492,20 @@ impl HllSketch {
                 let should_promote = RESIZE_DENOMINATOR as usize * set.container().len()
                     > RESIZE_NUMERATOR as usize * set.container().capacity();
                 if should_promote {
-                    self.mode = if set.container().lg_size() == self.lg_config_k as usize - 3 {
-                        promote_container_to_array(set.container(), *hll_type, self.lg_config_k)
+                    // Sets stop growing at K / 8 slots, as in Java. Images may hold larger sets,
+                    // which are promoted rather than grown further.
+                    self.mode = if set.container().lg_size() >= self.lg_config_k as usize - 3 {
+                        promote_container_to_array(
+                            set.container(),
+                            *hll_type,
+                            self.lg_config_k,
+                            spare,
+                        )
                     } else {
                         grow_set(set, *hll_type)
-                    }
+                    };
+                    #[cfg(feature = "tracing")]
+                    self.trace_promotion(HllMode::Set);
                 }
             }
             Mode::Array4(arr) => arr.update(coupon),
@@ -237,6 +514,26 @@ impl HllSketch {
         }
     }
 
+    #[cfg(feature = "tracing")]
+    fn trace_promotion(&self, from_mode: HllMode) {
+        let to_mode = self.current_mode();
+        if to_mode == from_mode {
+            if let Mode::Set { set, .. } = &self.mode {
+                tracing::debug!(
+                    lg_size = set.container().lg_size(),
+                    "grew HLL coupon hash set"
+                );
+            }
+        } else {
+            tracing::debug!(
+                lg_config_k = self.lg_config_k,
+                ?from_mode,
+                ?to_mode,
+                "promoted HLL sketch"
+            );
+        }
+    }
+
     /// Get the current cardinality estimate
     ///
     /// # Examples
@@ -258,6 +555,64 @@ impl HllSketch {
         }
     }
 
+    /// Returns the HIP (Historical Inverse Probability) estimate, if it is valid.
+    ///
+    /// An HLL-mode sketch that has only seen updates in order keeps a HIP accumulator, which is
+    /// the more accurate of the two estimators and the one [`estimate`](Self::estimate) uses.
+    /// A sketch that was merged, such as the result of an [`HllUnion`], or deserialized from an
+    /// image flagged out of order cannot trust its accumulator and returns `None`; its estimate
+    /// is the [`composite_estimate`](Self::composite_estimate).
+    ///
+    /// In List and Set modes the sketch holds coupons rather than registers, and this returns
+    /// the same coupon estimate as [`estimate`](Self::estimate).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::hll::HllSketch;
+    /// # use datasketches::hll::HllType;
+    /// # use datasketches::hll::HllUnion;
+    /// let mut sketch = HllSketch::new(10, HllType::Hll8);
+    /// for i in 0..10_000 {
+    ///     sketch.update(i);
+    /// }
+    /// assert_eq!(sketch.hip_estimate(), Some(sketch.estimate()));
+    ///
+    /// let mut union = HllUnion::new(10);
+    /// union.update(&sketch);
+    /// let merged = union.to_sketch(HllType::Hll8);
+    /// assert_eq!(merged.hip_estimate(), None);
+    /// assert_eq!(merged.estimate(), merged.composite_estimate());
+    /// ```
+    pub fn hip_estimate(&self) -> Option<f64> {
+        match &self.mode {
+            Mode::List { list, .. } => Some(list.container().estimate()),
+            Mode::Set { set, .. } => Some(set.container().estimate()),
+            Mode::Array4(arr) => arr.hip_estimate(),
+            Mode::Array6(arr) => arr.hip_estimate(),
+            Mode::Array8(arr) => arr.hip_estimate(),
+        }
+    }
+
+    /// Returns the composite estimate, which blends the raw HLL estimate with linear counting.
+    ///
+    /// This estimator only depends on the register values, so it is valid for every sketch and is
+    /// what [`estimate`](Self::estimate) returns once the sketch is out of order (see
+    /// [`hip_estimate`](Self::hip_estimate)). Comparing it with the HIP estimate of an in-order
+    /// sketch shows how far the two estimators diverge.
+    ///
+    /// In List and Set modes this returns the same coupon estimate as
+    /// [`estimate`](Self::estimate).
+    pub fn composite_estimate(&self) -> f64 {
+        match &self.mode {
+            Mode::List { list, .. } => list.container().estimate(),
+            Mode::Set { set, .. } => set.container().estimate(),
+            Mode::Array4(arr) => arr.composite_estimate(),
+            Mode::Array6(arr) => arr.composite_estimate(),
+            Mode::Array8(arr) => arr.composite_estimate(),
+        }
+    }
+
     /// Get upper bound for cardinality estimate
     ///
     /// Returns the upper confidence bound for the cardinality estimate based on
@@ -286,7 +641,53 @@ impl HllSketch {
         }
     }
 
-    /// Deserializes an HLL sketch from bytes
+    /// Returns a copy of this sketch reduced to `target_lg_k`.
+    ///
+    /// Registers are folded into the smaller configuration the same way [`HllUnion`] merges an
+    /// input with a larger lg_k, so the result summarizes the same stream with the precision (and
+    /// the serialized size) of a `target_lg_k` sketch. The target HLL type is preserved.
+    ///
+    /// If `target_lg_k` equals [`lg_config_k`](Self::lg_config_k), an identical copy is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `target_lg_k` is less than 4 or greater than [`lg_config_k`](Self::lg_config_k).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::hll::HllSketch;
+    /// # use datasketches::hll::HllType;
+    /// let mut sketch = HllSketch::new(16, HllType::Hll4);
+    /// for i in 0..100_000 {
+    ///     sketch.update(i);
+    /// }
+    /// let small = sketch.downsample(10);
+    /// assert_eq!(small.lg_config_k(), 10);
+    /// assert_eq!(small.target_type(), HllType::Hll4);
+    /// assert!(small.serialize().len() < sketch.serialize().len());
+    /// ```
+    pub fn downsample(&self, target_lg_k: u8) -> HllSketch {
+        assert!(
+            (MIN_LG_K..=self.lg_config_k).contains(&target_lg_k),
+            "target_lg_k must be in [{MIN_LG_K}, {}], got {}",
+            self.lg_config_k,
+            target_lg_k
+        );
+
+        if target_lg_k == self.lg_config_k {
+            return self.clone();
+        }
+
+        let mut union = HllUnion::with_seed(target_lg_k, self.seed);
+        union.update(self);
+        union.to_sketch(self.target_type())
+    }
+
+    /// Deserializes an HLL sketch from bytes, with the default seed.
+    ///
+    /// See [`deserialize_with_seed`](Self::deserialize_with_seed) for sketches built with another
+    /// seed.
     ///
     /// # Examples
     ///
@@ -300,6 +701,14 @@ impl HllSketch {
     /// assert!(decoded.estimate() >= 1.0);
     /// ```
     pub fn deserialize(bytes: &[u8]) -> Result<HllSketch, Error> {
+        Self::deserialize_with_seed(bytes, DEFAULT_UPDATE_SEED)
+    }
+
+    /// Deserializes an HLL sketch from bytes, built with the given seed.
+    ///
+    /// Unlike Theta and CPC images, HLL images carry no seed hash, so the seed cannot be checked
+    /// here: the caller is responsible for passing the seed the sketch was built with.
+    pub fn deserialize_with_seed(bytes: &[u8], seed: u64) -> Result<HllSketch, Error> {
         let mut cursor = SketchSlice::new(bytes);
 
         // Read and validate preamble
@@ -325,14 +734,9 @@ impl HllSketch {
         Family::HLL.validate_id(family_id)?;
 
         // Verify serialization version
-        ensure_serial_version_is(SERIAL_VERSION, serial_version)?;
+        Family::HLL.validate_serial_version(serial_version)?;
 
-        // Verify lg_k range (4-21 are valid)
-        if !(4..=21).contains(&lg_config_k) {
-            return Err(Error::deserial(format!(
-                "lg_k must be in [4; 21], got {lg_config_k}",
-            )));
-        }
+        check_lg_k("lg_config_k", lg_config_k).map_err(Error::deserial)?;
 
         let hll_type = match extract_tgt_hll_type(mode_byte) {
             TGT_HLL4 => HllType::Hll4,
@@ -348,61 +752,69 @@ impl HllSketch {
         let ooo = (flags & OUT_OF_ORDER_FLAG_MASK) != 0;
 
         // Deserialize based on mode
-        let mode =
-            match extract_cur_mode(mode_byte) {
-                CUR_MODE_LIST => {
-                    if preamble_ints != LIST_PREINTS {
-                        return Err(Error::deserial(format!(
-                            "LIST mode preamble: expected {}, got {}",
-                            LIST_PREINTS, preamble_ints,
-                        )));
-                    }
+        let mode = match extract_cur_mode(mode_byte) {
+            CUR_MODE_LIST => {
+                if preamble_ints != LIST_PREINTS {
+                    return Err(Error::deserial(format!(
+                        "LIST mode preamble: expected {}, got {}",
+                        LIST_PREINTS, preamble_ints,
+                    )));
+                }
 
-                    let lg_arr = lg_arr as usize;
-                    let coupon_count = state as usize;
-                    let list = List::deserialize(cursor, lg_arr, coupon_count, empty, compact)?;
-                    Mode::List { list, hll_type }
+                let lg_arr = lg_arr as usize;
+                let coupon_count = state as usize;
+                let list = List::deserialize(cursor, lg_arr, coupon_count, empty, compact)?;
+                Mode::List { list, hll_type }
+            }
+            CUR_MODE_SET => {
+                if preamble_ints != HASH_SET_PREINTS {
+                    return Err(Error::deserial(format!(
+                        "SET mode preamble: expected {}, got {}",
+                        HASH_SET_PREINTS, preamble_ints
+                    )));
                 }
-                CUR_MODE_SET => {
-                    if preamble_ints != HASH_SET_PREINTS {
-                        return Err(Error::deserial(format!(
-                            "SET mode preamble: expected {}, got {}",
-                            HASH_SET_PREINTS, preamble_ints
-                        )));
-                    }
 
-                    let lg_arr = lg_arr as usize;
-                    let set = HashSet::deserialize(cursor, lg_arr, compact)?;
-                    Mode::Set { set, hll_type }
+                let lg_arr = lg_arr as usize;
+                let set = HashSet::deserialize(cursor, lg_arr, compact)?;
+                Mode::Set { set, hll_type }
+            }
+            CUR_MODE_HLL => {
+                if preamble_ints != HLL_PREINTS {
+                    return Err(Error::deserial(format!(
+                        "HLL mode preamble: expected {}, got {}",
+                        HLL_PREINTS, preamble_ints
+                    )));
                 }
-                CUR_MODE_HLL => {
-                    if preamble_ints != HLL_PREINTS {
-                        return Err(Error::deserial(format!(
-                            "HLL mode preamble: expected {}, got {}",
-                            HLL_PREINTS, preamble_ints
-                        )));
-                    }
 
-                    match hll_type {
-                        HllType::Hll4 => {
-                            let cur_min = state;
-                            Array4::deserialize(cursor, cur_min, lg_config_k, compact, ooo)
-                                .map(Mode::Array4)?
-                        }
-                        HllType::Hll6 => Array6::deserialize(cursor, lg_config_k, compact, ooo)
-                            .map(Mode::Array6)?,
-                        HllType::Hll8 => Array8::deserialize(cursor, lg_config_k, compact, ooo)
-                            .map(Mode::Array8)?,
+                match hll_type {
+                    HllType::Hll4 => {
+                        let cur_min = state;
+                        Array4::deserialize(cursor, cur_min, lg_config_k, lg_arr, compact, ooo)
+                            .map(Mode::Array4)?
+                    }
+                    HllType::Hll6 => {
+                        Array6::deserialize(cursor, lg_config_k, ooo).map(Mode::Array6)?
+                    }
+                    HllType::Hll8 => {
+                        Array8::deserialize(cursor, lg_config_k, ooo).map(Mode::Array8)?
                     }
                 }
-                mode => return Err(Error::deserial(format!("invalid mode: {mode}"))),
-            };
+            }
+            mode => return Err(Error::deserial(format!("invalid mode: {mode}"))),
+        };
 
-        Ok(HllSketch { lg_config_k, mode })
+        Ok(HllSketch {
+            lg_config_k,
+            mode,
+            seed,
+        })
     }
 
     /// Serializes the HLL sketch to bytes
     ///
+    /// List and Set mode images store their coupons in ascending order, so equal sketches
+    /// serialize to identical bytes whatever the order in which they were updated.
+    ///
     /// # Examples
     ///
     /// ```
@@ -415,12 +827,132 @@ impl HllSketch {
     /// assert!(decoded.estimate() >= 1.0);
     /// ```
     pub fn serialize(&self) -> Vec<u8> {
+        let mut bytes = SketchBytes::with_capacity(self.serialized_size_bytes());
+        self.write_to(&mut bytes);
+        bytes.into_bytes()
+    }
+
+    /// Serializes the HLL sketch to bytes in the updatable layout.
+    ///
+    /// Unlike [`serialize`](Self::serialize), List and Set modes store their whole coupon array
+    /// and an `Hll4` sketch stores its whole auxiliary hash table, empty slots included, or an
+    /// empty table of the initial size when it has no exceptions. This is the layout the Java
+    /// and C++ libraries use for updatable images. Both layouts deserialize
+    /// to the same sketch with [`deserialize`](Self::deserialize).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::hll::HllSketch;
+    /// # use datasketches::hll::HllType;
+    /// let mut sketch = HllSketch::new(12, HllType::Hll8);
+    /// sketch.update("apple");
+    ///
+    /// let bytes = sketch.serialize_updatable();
+    /// assert_eq!(bytes.len(), sketch.updatable_serialized_size_bytes());
+    /// let decoded = HllSketch::deserialize(&bytes).unwrap();
+    /// assert_eq!(decoded, sketch);
+    /// ```
+    pub fn serialize_updatable(&self) -> Vec<u8> {
+        let mut bytes = SketchBytes::with_capacity(self.updatable_serialized_size_bytes());
+        self.write_layout_to(&mut bytes, false);
+        bytes.into_bytes()
+    }
+
+    /// Serializes the HLL sketch into `buf`, returning the number of bytes written.
+    ///
+    /// Writes the same bytes as [`serialize`](Self::serialize) without allocating the output.
+    /// [`serialized_size_bytes`](Self::serialized_size_bytes) gives the exact length required.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `buf` is too short to hold the serialized sketch. The sketch is
+    /// written up to the end of `buf` before the length is checked, so the contents of `buf`
+    /// are unspecified when an error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::hll::HllSketch;
+    /// # use datasketches::hll::HllType;
+    /// let mut sketch = HllSketch::new(10, HllType::Hll8);
+    /// sketch.update("apple");
+    ///
+    /// let mut buf = vec![0u8; 4096];
+    /// let len = sketch.serialize_into_slice(&mut buf).unwrap();
+    /// assert_eq!(len, sketch.serialized_size_bytes());
+    /// let decoded = HllSketch::deserialize(&buf[..len]).unwrap();
+    /// assert_eq!(decoded.estimate(), sketch.estimate());
+    /// ```
+    pub fn serialize_into_slice(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let mut bytes = SketchBytes::from_slice(buf);
+        self.write_to(&mut bytes);
+        bytes.finish()
+    }
+
+    pub(super) fn write_to(&self, bytes: &mut SketchBytes<'_>) {
+        self.write_layout_to(bytes, true);
+    }
+
+    fn write_layout_to(&self, bytes: &mut SketchBytes<'_>, compact: bool) {
+        let lg_config_k = self.lg_config_k;
+        match &self.mode {
+            Mode::List { list, hll_type } => {
+                list.serialize_to(bytes, lg_config_k, *hll_type, compact)
+            }
+            Mode::Set { set, hll_type } => set.serialize_to(bytes, lg_config_k, *hll_type, compact),
+            Mode::Array4(arr) => arr.serialize_to(bytes, lg_config_k, compact),
+            Mode::Array6(arr) => arr.serialize_to(bytes, lg_config_k),
+            Mode::Array8(arr) => arr.serialize_to(bytes, lg_config_k),
+        }
+    }
+
+    /// Returns the exact number of bytes [`serialize`](Self::serialize) will produce.
+    ///
+    /// List and Set modes are always serialized in the compact coupon layout, storing one 4-byte
+    /// int per retained coupon rather than the full register array. Small sketches are therefore
+    /// much cheaper to store than their HLL-mode equivalents.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::hll::HllSketch;
+    /// # use datasketches::hll::HllType;
+    /// let mut sketch = HllSketch::new(12, HllType::Hll8);
+    /// sketch.update("apple");
+    /// sketch.update("banana");
+    /// assert_eq!(sketch.serialized_size_bytes(), 8 + 2 * 4);
+    /// assert_eq!(sketch.serialized_size_bytes(), sketch.serialize().len());
+    /// ```
+    pub fn serialized_size_bytes(&self) -> usize {
+        self.layout_size_bytes(true)
+    }
+
+    /// Returns the exact number of bytes [`serialize_updatable`](Self::serialize_updatable) will
+    /// produce.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::hll::HllSketch;
+    /// # use datasketches::hll::HllType;
+    /// let mut sketch = HllSketch::new(12, HllType::Hll8);
+    /// sketch.update("apple");
+    /// // The initial List mode coupon array has 8 slots
+    /// assert_eq!(sketch.updatable_serialized_size_bytes(), 8 + 8 * 4);
+    /// assert!(sketch.updatable_serialized_size_bytes() > sketch.serialized_size_bytes());
+    /// ```
+    pub fn updatable_serialized_size_bytes(&self) -> usize {
+        self.layout_size_bytes(false)
+    }
+
+    fn layout_size_bytes(&self, compact: bool) -> usize {
         match &self.mode {
-            Mode::List { list, hll_type } => list.serialize(self.lg_config_k, *hll_type),
-            Mode::Set { set, hll_type } => set.serialize(self.lg_config_k, *hll_type),
-            Mode::Array4(arr) => arr.serialize(self.lg_config_k),
-            Mode::Array6(arr) => arr.serialize(self.lg_config_k),
-            Mode::Array8(arr) => arr.serialize(self.lg_config_k),
+            Mode::List { list, .. } => list.serialized_size_bytes(compact),
+            Mode::Set { set, .. } => set.serialized_size_bytes(compact),
+            Mode::Array4(arr) => arr.serialized_size_bytes(compact),
+            Mode::Array6(arr) => arr.serialized_size_bytes(),
+            Mode::Array8(arr) => arr.serialized_size_bytes(),
         }
     }
 
@@ -438,6 +970,70 @@ impl HllSketch {
     }
 }
 
+/// Builder for [`HllSketch`].
+///
+/// Created by [`HllSketch::builder`]. The parameters are validated when the sketch is built.
+#[derive(Debug, Clone)]
+pub struct HllSketchBuilder {
+    lg_config_k: u8,
+    hll_type: HllType,
+    seed: u64,
+}
+
+impl Default for HllSketchBuilder {
+    fn default() -> Self {
+        Self {
+            lg_config_k: DEFAULT_LG_K,
+            hll_type: HllType::Hll4,
+            seed: DEFAULT_UPDATE_SEED,
+        }
+    }
+}
+
+impl HllSketchBuilder {
+    /// Set lg_config_k, the log2 of the number of buckets. Must be in `[4, 21]`.
+    pub fn lg_config_k(mut self, lg_config_k: u8) -> Self {
+        self.lg_config_k = lg_config_k;
+        self
+    }
+
+    /// Set the target HLL array type.
+    pub fn hll_type(mut self, hll_type: HllType) -> Self {
+        self.hll_type = hll_type;
+        self
+    }
+
+    /// Set the hash seed. See [`HllSketch::with_seed`].
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Build the HllSketch.
+    ///
+    /// # Panics
+    ///
+    /// If lg_config_k is not in range `[4, 21]`
+    pub fn build(self) -> HllSketch {
+        HllSketch::with_seed(self.lg_config_k, self.hll_type, self.seed)
+    }
+}
+
+impl SketchBuilder for HllSketchBuilder {
+    type Sketch = HllSketch;
+
+    fn try_build(self) -> Result<HllSketch, Error> {
+        check_lg_k("lg_config_k", self.lg_config_k).map_err(Error::invalid_argument)?;
+        Ok(self.build())
+    }
+}
+
+impl MemoryUsage for HllSketch {
+    fn memory_usage(&self) -> usize {
+        self.estimated_size()
+    }
+}
+
 fn promote_container_to_set(container: &Container, hll_type: HllType) -> Mode {
     let mut set = HashSet::default();
     for coupon in container.iter() {
@@ -460,7 +1056,12 @@ fn grow_set(old_set: &HashSet, hll_type: HllType) -> Mode {
     }
 }
 
-fn promote_container_to_array(container: &Container, hll_type: HllType, lg_config_k: u8) -> Mode {
+fn promote_container_to_array(
+    container: &Container,
+    hll_type: HllType,
+    lg_config_k: u8,
+    spare: &mut Option<Array8>,
+) -> Mode {
     match hll_type {
         HllType::Hll4 => {
             let mut array = Array4::new(lg_config_k);
@@ -479,7 +1080,7 @@ fn promote_container_to_array(container: &Container, hll_type: HllType, lg_confi
             Mode::Array6(array)
         }
         HllType::Hll8 => {
-            let mut array = Array8::new(lg_config_k);
+            let mut array = Array8::take_or_new(spare, lg_config_k);
             for coupon in container.iter() {
                 array.update(coupon);
             }
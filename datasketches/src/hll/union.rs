@@ -29,16 +29,33 @@
 //! * Different target HLL types
 
 use std::hash::Hash;
-
+use std::ops::Add;
+use std::ops::AddAssign;
+
+use crate::codec::SketchSlice;
+use crate::codec::StateEnvelope;
+use crate::codec::assert::insufficient_data;
+use crate::codec::encode_state;
+use crate::codec::family::Family;
+use crate::common::MemoryUsage;
 use crate::common::NumStdDev;
+use crate::error::Error;
+use crate::hash::DEFAULT_UPDATE_SEED;
 use crate::hll::Coupon;
+use crate::hll::HllMode;
 use crate::hll::HllSketch;
 use crate::hll::HllType;
+use crate::hll::MAX_LG_K;
+use crate::hll::MIN_LG_K;
 use crate::hll::array4::Array4;
 use crate::hll::array6::Array6;
 use crate::hll::array8::Array8;
+use crate::hll::check_lg_k;
 use crate::hll::mode::Mode;
 
+/// lg_max_k of a union collected from an empty iterator, matching the Java default.
+const DEFAULT_LG_MAX_K: u8 = 12;
+
 /// An HLL Union for combining multiple HLL sketches.
 ///
 /// The union maintains an internal sketch (the "gadget") that accumulates
@@ -52,6 +69,10 @@ pub struct HllUnion {
     lg_max_k: u8,
     /// Internal sketch that accumulates the union
     gadget: HllSketch,
+    /// Cleared register array kept by [`reset`](Self::reset) for the next HLL-mode gadget
+    spare: Option<Array8>,
+    /// Hash seed shared by the union and every sketch it accepts
+    seed: u64,
 }
 
 impl HllUnion {
@@ -78,16 +99,59 @@ impl HllUnion {
     /// assert_eq!(result.estimate(), 1.0);
     /// ```
     pub fn new(lg_max_k: u8) -> Self {
+        Self::with_seed(lg_max_k, DEFAULT_UPDATE_SEED)
+    }
+
+    /// Create a new HLL Union for sketches built with `seed`.
+    ///
+    /// See [`HllSketch::with_seed`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lg_max_k` is not in the range `[4, 21]`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::hll::HllSketch;
+    /// # use datasketches::hll::HllType;
+    /// # use datasketches::hll::HllUnion;
+    /// let mut sketch = HllSketch::with_seed(10, HllType::Hll8, 42);
+    /// sketch.update("apple");
+    ///
+    /// let mut union = HllUnion::with_seed(10, 42);
+    /// union.update(&sketch);
+    /// union.update_value("banana");
+    /// let result = union.to_sketch(HllType::Hll8);
+    /// assert_eq!(result.seed(), 42);
+    /// assert!((result.estimate() - 2.0).abs() < 0.01);
+    /// ```
+    pub fn with_seed(lg_max_k: u8, seed: u64) -> Self {
         assert!(
-            (4..=21).contains(&lg_max_k),
-            "lg_max_k must be in [4, 21], got {}",
-            lg_max_k
+            (MIN_LG_K..=MAX_LG_K).contains(&lg_max_k),
+            "lg_max_k must be in [{MIN_LG_K}, {MAX_LG_K}], got {lg_max_k}"
         );
 
         // Start with an empty gadget at lg_max_k using Hll8
-        let gadget = HllSketch::new(lg_max_k, HllType::Hll8);
+        let gadget = HllSketch::with_seed(lg_max_k, HllType::Hll8, seed);
 
-        Self { lg_max_k, gadget }
+        Self {
+            lg_max_k,
+            gadget,
+            spare: None,
+            seed,
+        }
+    }
+
+    /// Creates a union sized for the larger lg_k of `a` and `b`, updated with both sketches.
+    ///
+    /// Panics if the seeds of `a` and `b` differ.
+    pub(super) fn of_pair(a: &HllSketch, b: &HllSketch) -> Self {
+        let lg_max_k = a.lg_config_k().max(b.lg_config_k());
+        let mut union = HllUnion::with_seed(lg_max_k, a.seed());
+        union.update(a);
+        union.update(b);
+        union
     }
 
     /// Update the union's gadget with a value
@@ -106,7 +170,10 @@ impl HllUnion {
     /// assert_eq!(result.estimate(), 1.0);
     /// ```
     pub fn update_value<T: Hash>(&mut self, value: T) {
-        self.gadget.update(value);
+        self.gadget.update_with_coupon_reusing(
+            Coupon::from_hash_with_seed(value, self.seed),
+            &mut self.spare,
+        );
     }
 
     /// Update the union with another sketch
@@ -116,6 +183,10 @@ impl HllUnion {
     /// * Sketches in different modes (List, Set, Array4/6/8)
     /// * Sketches with different target HLL types
     ///
+    /// # Panics
+    ///
+    /// Panics if the seed of the sketch does not match the seed of this union.
+    ///
     /// # Examples
     ///
     /// ```
@@ -134,6 +205,7 @@ impl HllUnion {
     /// assert!(result.estimate() >= 2.0);
     /// ```
     pub fn update(&mut self, sketch: &HllSketch) {
+        self.check_seed(sketch);
         if sketch.is_empty() {
             return;
         }
@@ -152,6 +224,14 @@ impl HllUnion {
         }
     }
 
+    fn check_seed(&self, sketch: &HllSketch) {
+        assert_eq!(
+            self.seed,
+            sketch.seed(),
+            "sketch seed does not match the union seed"
+        );
+    }
+
     /// Update union from a List or Set mode sketch
     fn update_from_list_or_set(
         &mut self,
@@ -166,11 +246,11 @@ impl HllUnion {
                 sketch.clone()
             } else {
                 // Convert to Hll8 by changing target type
-                convert_coupon_mode_to_hll8(src_mode, src_lg_k)
+                convert_coupon_mode_to_hll8(src_mode, src_lg_k, self.seed)
             };
         } else {
             // Regular path: merge coupons into gadget
-            merge_coupons_into_gadget(&mut self.gadget, src_mode);
+            merge_coupons_into_gadget(&mut self.gadget, src_mode, &mut self.spare);
         }
     }
 
@@ -178,9 +258,9 @@ impl HllUnion {
     fn update_from_array(&mut self, src_mode: &Mode, src_lg_k: u8, dst_lg_k: u8) {
         // Fast path: If gadget is empty, just copy/downsample source
         if self.gadget.is_empty() {
-            let new_array = copy_or_downsample(src_mode, src_lg_k, self.lg_max_k);
+            let new_array = copy_or_downsample(&mut self.spare, src_mode, src_lg_k, self.lg_max_k);
             let final_lg_k = new_array.num_registers().trailing_zeros() as u8;
-            self.gadget = HllSketch::from_mode(final_lg_k, Mode::Array8(new_array));
+            self.gadget = HllSketch::from_mode(final_lg_k, Mode::Array8(new_array), self.seed);
             return;
         }
 
@@ -197,7 +277,7 @@ impl HllUnion {
     fn merge_array_into_array_gadget(&mut self, src_mode: &Mode, src_lg_k: u8, dst_lg_k: u8) {
         if src_lg_k < dst_lg_k {
             // Source has lower precision - must downsize gadget
-            let mut new_array = Array8::new(src_lg_k);
+            let mut new_array = Array8::take_or_new(&mut self.spare, src_lg_k);
 
             match self.gadget.mode() {
                 Mode::Array8(old_gadget) => {
@@ -214,7 +294,7 @@ impl HllUnion {
             }
 
             merge_array_same_lgk(&mut new_array, src_mode);
-            self.gadget = HllSketch::from_mode(src_lg_k, Mode::Array8(new_array));
+            self.gadget = HllSketch::from_mode(src_lg_k, Mode::Array8(new_array), self.seed);
         } else {
             // Standard merge: src_lg_k >= dst_lg_k
             match self.gadget.mode_mut() {
@@ -230,13 +310,13 @@ impl HllUnion {
 
     /// Promote gadget from List/Set to Array and merge array source
     fn promote_gadget_and_merge_array(&mut self, src_mode: &Mode, src_lg_k: u8) {
-        let mut new_array = copy_or_downsample(src_mode, src_lg_k, self.lg_max_k);
+        let mut new_array = copy_or_downsample(&mut self.spare, src_mode, src_lg_k, self.lg_max_k);
 
         let old_gadget_mode = self.gadget.mode();
         merge_coupons_into_mode(&mut new_array, old_gadget_mode);
 
         let final_lg_k = new_array.num_registers().trailing_zeros() as u8;
-        self.gadget = HllSketch::from_mode(final_lg_k, Mode::Array8(new_array));
+        self.gadget = HllSketch::from_mode(final_lg_k, Mode::Array8(new_array), self.seed);
     }
 
     /// Get the union result as a new sketch.
@@ -272,6 +352,7 @@ impl HllUnion {
                     list: list.clone(),
                     hll_type,
                 },
+                self.seed,
             ),
             Mode::Set { set, .. } => HllSketch::from_mode(
                 self.gadget.lg_config_k(),
@@ -279,9 +360,10 @@ impl HllUnion {
                     set: set.clone(),
                     hll_type,
                 },
+                self.seed,
             ),
             Mode::Array8(array8) => {
-                convert_array8_to_type(array8, self.gadget.lg_config_k(), hll_type)
+                convert_array8_to_type(array8, self.gadget.lg_config_k(), hll_type, self.seed)
             }
             Mode::Array4(_) | Mode::Array6(_) => {
                 unreachable!("gadget mode changed unexpectedly; should never be Array4/Array6")
@@ -299,6 +381,11 @@ impl HllUnion {
         self.lg_max_k
     }
 
+    /// Get the hash seed
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
     /// Check if the union is empty
     pub fn is_empty(&self) -> bool {
         self.gadget.is_empty()
@@ -308,8 +395,37 @@ impl HllUnion {
     ///
     /// Clears all data from the internal gadget, allowing the union to be reused
     /// for a new set of operations.
+    ///
+    /// If the gadget is an HLL array of `lg_max_k`, its `2^lg_max_k` byte register array is
+    /// cleared and kept for the next time the union reaches HLL mode, so aggregation loops that
+    /// reset one union per group do not allocate a new array for every group. The kept array is
+    /// included in [`estimated_size`](Self::estimated_size).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::hll::HllUnion;
+    /// let mut union = HllUnion::new(10);
+    /// for group in 0..3 {
+    ///     for i in 0..10_000 {
+    ///         union.update_value((group, i));
+    ///     }
+    ///     assert!((union.estimate() - 10_000.0).abs() < 10_000.0 * 0.1);
+    ///     union.reset();
+    ///     assert!(union.is_empty());
+    /// }
+    /// ```
     pub fn reset(&mut self) {
-        self.gadget = HllSketch::new(self.lg_max_k, HllType::Hll8);
+        let gadget = std::mem::replace(
+            &mut self.gadget,
+            HllSketch::with_seed(self.lg_max_k, HllType::Hll8, self.seed),
+        );
+        if gadget.lg_config_k() == self.lg_max_k {
+            if let Mode::Array8(mut array) = gadget.into_mode() {
+                array.clear();
+                self.spare = Some(array);
+            }
+        }
     }
 
     /// Get the current cardinality estimate of the union
@@ -332,10 +448,321 @@ impl HllUnion {
     pub fn lower_bound(&self, num_std_dev: NumStdDev) -> f64 {
         self.gadget.lower_bound(num_std_dev)
     }
+
+    /// Returns the exact number of bytes `self.to_sketch(hll_type).serialize()` will produce.
+    ///
+    /// In List and Set modes, and in HLL mode for `Hll8`, the size is computed from the gadget
+    /// directly. Otherwise the HLL array has to be converted, as the size of an `Hll4` sketch
+    /// depends on how many registers overflow into its auxiliary map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::hll::HllType;
+    /// # use datasketches::hll::HllUnion;
+    /// let mut union = HllUnion::new(10);
+    /// for i in 0..10_000 {
+    ///     union.update_value(i);
+    /// }
+    /// for hll_type in [HllType::Hll4, HllType::Hll6, HllType::Hll8] {
+    ///     let bytes = union.to_sketch(hll_type).serialize();
+    ///     assert_eq!(union.serialized_size_bytes(hll_type), bytes.len());
+    /// }
+    /// ```
+    pub fn serialized_size_bytes(&self, hll_type: HllType) -> usize {
+        match self.gadget.mode() {
+            Mode::Array8(_) if hll_type != HllType::Hll8 => {
+                self.to_sketch(hll_type).serialized_size_bytes()
+            }
+            _ => self.gadget.serialized_size_bytes(),
+        }
+    }
+
+    /// Returns the estimated size of the union in bytes.
+    pub fn estimated_size(&self) -> usize {
+        // The gadget is stored inline, so only its heap allocations are added on top.
+        let spare_size = self.spare.as_ref().map_or(0, Array8::estimated_size);
+        size_of::<Self>() - size_of::<HllSketch>() + self.gadget.estimated_size() + spare_size
+    }
+
+    /// Saves the state of this union as a checkpoint that [`load_state`](Self::load_state) can
+    /// restore.
+    ///
+    /// The state is a [`StateEnvelope`] around `lg_max_k`, the seed and the serialized gadget
+    /// sketch.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::hll::HllUnion;
+    /// let mut union = HllUnion::new(12);
+    /// union.update_value("apple");
+    ///
+    /// let state = union.save_state();
+    /// let restored = HllUnion::load_state(&state).unwrap();
+    /// assert_eq!(restored.lg_max_k(), 12);
+    /// assert_eq!(restored.estimate(), union.estimate());
+    /// ```
+    pub fn save_state(&self) -> Vec<u8> {
+        let sketch_size = self.gadget.serialized_size_bytes();
+        encode_state(&Family::HLL, 1 + 8 + sketch_size, |bytes| {
+            bytes.write_u8(self.lg_max_k);
+            bytes.write_u64_le(self.seed);
+            self.gadget.write_to(bytes);
+        })
+    }
+
+    /// Restores a union from a checkpoint written by [`save_state`](Self::save_state).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bytes are not a valid HLL union state, including state saved by a
+    /// different kind of union.
+    pub fn load_state(bytes: &[u8]) -> Result<Self, Error> {
+        let envelope = StateEnvelope::decode_family(bytes, &Family::HLL)?;
+        let mut cursor = SketchSlice::new(envelope.payload());
+        let lg_max_k = cursor.read_u8().map_err(insufficient_data("lg_max_k"))?;
+        check_lg_k("lg_max_k", lg_max_k).map_err(Error::deserial)?;
+        let seed = cursor.read_u64_le().map_err(insufficient_data("seed"))?;
+
+        let gadget = HllSketch::deserialize_with_seed(cursor.remaining(), seed)?;
+        if gadget.lg_config_k() > lg_max_k {
+            return Err(Error::deserial(format!(
+                "gadget lg_config_k {} exceeds lg_max_k {lg_max_k}",
+                gadget.lg_config_k()
+            )));
+        }
+        if matches!(gadget.current_mode(), HllMode::Hll4 | HllMode::Hll6) {
+            return Err(Error::deserial(format!(
+                "union gadget must be an Hll8 array, got {:?}",
+                gadget.current_mode()
+            )));
+        }
+        Ok(Self {
+            lg_max_k,
+            gadget,
+            spare: None,
+            seed,
+        })
+    }
+}
+
+impl MemoryUsage for HllUnion {
+    fn memory_usage(&self) -> usize {
+        self.estimated_size()
+    }
+}
+
+/// Unions every sketch of the iterator into this union.
+///
+/// This is equivalent to calling [`update`](HllUnion::update) for each sketch, but when any
+/// input is in HLL mode the gadget is promoted and resized to its final lg_k once, and every
+/// input is then merged straight into that array. This avoids repeated promotions and
+/// downsampling when folding many per-partition sketches.
+///
+/// # Panics
+///
+/// Panics if the seed of any sketch does not match the seed of this union.
+///
+/// # Examples
+///
+/// ```
+/// # use datasketches::hll::HllSketch;
+/// # use datasketches::hll::HllType;
+/// # use datasketches::hll::HllUnion;
+/// let sketches: Vec<HllSketch> = (0..4)
+///     .map(|p| {
+///         let mut sketch = HllSketch::new(10, HllType::Hll4);
+///         for i in 0..1000 {
+///             sketch.update(p * 1000 + i);
+///         }
+///         sketch
+///     })
+///     .collect();
+///
+/// let mut union = HllUnion::new(10);
+/// union.extend(&sketches);
+/// let estimate = union.estimate();
+/// assert!((estimate - 4000.0).abs() < 4000.0 * 0.1);
+/// ```
+impl<'a> Extend<&'a HllSketch> for HllUnion {
+    fn extend<I: IntoIterator<Item = &'a HllSketch>>(&mut self, iter: I) {
+        let sketches: Vec<&HllSketch> = iter.into_iter().collect();
+        for sketch in &sketches {
+            self.check_seed(sketch);
+        }
+        let sketches: Vec<&HllSketch> = sketches.into_iter().filter(|s| !s.is_empty()).collect();
+        let is_array = |sketch: &HllSketch| {
+            matches!(
+                sketch.mode(),
+                Mode::Array4(_) | Mode::Array6(_) | Mode::Array8(_)
+            )
+        };
+
+        if !sketches.iter().any(|sketch| is_array(sketch)) {
+            for sketch in sketches {
+                self.update(sketch);
+            }
+            return;
+        }
+
+        // The result's lg_k is bounded by every HLL-mode input; coupons fit any lg_k.
+        let gadget_lg_k = self.gadget.lg_config_k();
+        let lg_k = sketches
+            .iter()
+            .filter(|sketch| is_array(sketch))
+            .map(|sketch| sketch.lg_config_k())
+            .chain(is_array(&self.gadget).then_some(gadget_lg_k))
+            .fold(self.lg_max_k, u8::min);
+
+        let gadget = std::mem::replace(
+            &mut self.gadget,
+            HllSketch::with_seed(lg_k, HllType::Hll8, self.seed),
+        );
+        let mut array = match gadget.mode() {
+            Mode::Array8(_) if gadget_lg_k == lg_k => match gadget.into_mode() {
+                Mode::Array8(array) => array,
+                _ => unreachable!("gadget mode checked above"),
+            },
+            Mode::Array8(_) => {
+                let mut array = Array8::take_or_new(&mut self.spare, lg_k);
+                merge_array_with_downsample(&mut array, lg_k, gadget.mode(), gadget_lg_k);
+                array
+            }
+            Mode::List { .. } | Mode::Set { .. } => {
+                let mut array = Array8::take_or_new(&mut self.spare, lg_k);
+                merge_coupons_into_mode(&mut array, gadget.mode());
+                array
+            }
+            Mode::Array4(_) | Mode::Array6(_) => {
+                unreachable!("gadget mode changed unexpectedly; should never be Array4/Array6")
+            }
+        };
+
+        for sketch in sketches {
+            if is_array(sketch) {
+                merge_array_into_array8(&mut array, lg_k, sketch.mode(), sketch.lg_config_k());
+            } else {
+                merge_coupons_into_mode(&mut array, sketch.mode());
+            }
+        }
+        array.rebuild_estimator_from_registers();
+        self.gadget = HllSketch::from_mode(lg_k, Mode::Array8(array), self.seed);
+    }
+}
+
+/// Builds a union of every sketch of the iterator.
+///
+/// The union's lg_max_k is the largest lg_k among the sketches, or 12 if there are none, and its
+/// seed is the seed of the first sketch. Use [`HllUnion::new`] followed by
+/// [`extend`](Extend::extend) to choose lg_max_k explicitly.
+///
+/// # Panics
+///
+/// Panics if the sketches were built with different seeds.
+///
+/// # Examples
+///
+/// ```
+/// # use datasketches::hll::HllSketch;
+/// # use datasketches::hll::HllType;
+/// # use datasketches::hll::HllUnion;
+/// let mut left = HllSketch::new(10, HllType::Hll8);
+/// let mut right = HllSketch::new(11, HllType::Hll8);
+/// left.update("apple");
+/// right.update("banana");
+///
+/// let union: HllUnion = [&left, &right].into_iter().collect();
+/// assert_eq!(union.lg_max_k(), 11);
+/// assert!((union.estimate() - 2.0).abs() < 0.01);
+/// ```
+impl<'a> FromIterator<&'a HllSketch> for HllUnion {
+    fn from_iter<I: IntoIterator<Item = &'a HllSketch>>(iter: I) -> Self {
+        let sketches: Vec<&HllSketch> = iter.into_iter().collect();
+        let lg_max_k = sketches
+            .iter()
+            .map(|sketch| sketch.lg_config_k())
+            .max()
+            .unwrap_or(DEFAULT_LG_MAX_K);
+        let seed = sketches
+            .first()
+            .map_or(DEFAULT_UPDATE_SEED, |sketch| sketch.seed());
+        let mut union = HllUnion::with_seed(lg_max_k, seed);
+        union.extend(sketches);
+        union
+    }
+}
+
+/// `union += &sketch` is equivalent to [`union.update(&sketch)`](HllUnion::update).
+impl AddAssign<&HllSketch> for HllUnion {
+    fn add_assign(&mut self, sketch: &HllSketch) {
+        self.update(sketch);
+    }
+}
+
+/// `&a + &b` returns the union of both sketches.
+///
+/// This is the same as updating an [`HllUnion`] sized for the larger lg_k of the two inputs with
+/// both sketches, and taking the result with the target HLL type of `a`.
+///
+/// # Panics
+///
+/// Panics if the sketches were built with different seeds.
+///
+/// # Examples
+///
+/// ```
+/// # use datasketches::hll::HllSketch;
+/// # use datasketches::hll::HllType;
+/// let mut left = HllSketch::new(10, HllType::Hll4);
+/// let mut right = HllSketch::new(12, HllType::Hll8);
+/// left.update("apple");
+/// right.update("banana");
+///
+/// let result = &left + &right;
+/// assert_eq!(result.target_type(), HllType::Hll4);
+/// assert!((result.estimate() - 2.0).abs() < 0.01);
+/// ```
+impl Add<&HllSketch> for &HllSketch {
+    type Output = HllSketch;
+
+    fn add(self, other: &HllSketch) -> HllSketch {
+        HllUnion::of_pair(self, other).to_sketch(self.target_type())
+    }
+}
+
+/// Estimates the number of distinct items in the union of two HLL sketches.
+///
+/// This is the estimate of an [`HllUnion`] sized for the larger lg_k of the two inputs and
+/// updated with both sketches, without building a result sketch. Neither input is modified, so
+/// it suits pairwise comparisons over many sketches, such as Jaccard similarity matrices.
+///
+/// # Panics
+///
+/// Panics if the sketches were built with different seeds.
+///
+/// # Examples
+///
+/// ```
+/// # use datasketches::hll::HllSketch;
+/// # use datasketches::hll::HllType;
+/// # use datasketches::hll::union_estimate;
+/// let mut a = HllSketch::new(12, HllType::Hll4);
+/// let mut b = HllSketch::new(12, HllType::Hll8);
+/// for i in 0..10_000 {
+///     a.update(i);
+///     b.update(i + 5_000);
+/// }
+///
+/// let estimate = union_estimate(&a, &b);
+/// assert!((estimate - 15_000.0).abs() < 15_000.0 * 0.05);
+/// ```
+pub fn union_estimate(a: &HllSketch, b: &HllSketch) -> f64 {
+    HllUnion::of_pair(a, b).estimate()
 }
 
 /// Convert a coupon mode (List or Set) to Hll8 target type
-fn convert_coupon_mode_to_hll8(src_mode: &Mode, src_lg_k: u8) -> HllSketch {
+fn convert_coupon_mode_to_hll8(src_mode: &Mode, src_lg_k: u8, seed: u64) -> HllSketch {
     match src_mode {
         Mode::List { list, .. } => HllSketch::from_mode(
             src_lg_k,
@@ -343,6 +770,7 @@ fn convert_coupon_mode_to_hll8(src_mode: &Mode, src_lg_k: u8) -> HllSketch {
                 list: list.clone(),
                 hll_type: HllType::Hll8,
             },
+            seed,
         ),
         Mode::Set { set, .. } => HllSketch::from_mode(
             src_lg_k,
@@ -350,6 +778,7 @@ fn convert_coupon_mode_to_hll8(src_mode: &Mode, src_lg_k: u8) -> HllSketch {
                 set: set.clone(),
                 hll_type: HllType::Hll8,
             },
+            seed,
         ),
         _ => unreachable!("convert_coupon_mode_to_hll8 called with non-coupon mode"),
     }
@@ -359,16 +788,16 @@ fn convert_coupon_mode_to_hll8(src_mode: &Mode, src_lg_k: u8) -> HllSketch {
 ///
 /// Iterates over all coupons in the source and updates the gadget.
 /// The gadget handles mode transitions automatically (List → Set → Array).
-fn merge_coupons_into_gadget(gadget: &mut HllSketch, src_mode: &Mode) {
+fn merge_coupons_into_gadget(gadget: &mut HllSketch, src_mode: &Mode, spare: &mut Option<Array8>) {
     match src_mode {
         Mode::List { list, .. } => {
             for coupon in list.container().iter() {
-                gadget.update_with_coupon(coupon);
+                gadget.update_with_coupon_reusing(coupon, spare);
             }
         }
         Mode::Set { set, .. } => {
             for coupon in set.container().iter() {
-                gadget.update_with_coupon(coupon);
+                gadget.update_with_coupon_reusing(coupon, spare);
             }
         }
         Mode::Array4(_) | Mode::Array6(_) | Mode::Array8(_) => {
@@ -517,48 +946,20 @@ fn merge_array_with_downsample(dst: &mut Array8, dst_lg_k: u8, src_mode: &Mode,
 /// Convert Array8 to a different HLL type
 ///
 /// Creates a new sketch with the requested type by copying register values
-/// from the Array8 source. Preserves the HIP accumulator.
-fn convert_array8_to_type(src: &Array8, lg_config_k: u8, target_type: HllType) -> HllSketch {
-    match target_type {
-        HllType::Hll8 => HllSketch::from_mode(lg_config_k, Mode::Array8(src.clone())),
-        HllType::Hll6 => {
-            let mut array6 = Array6::new(lg_config_k);
-            for slot in 0..src.num_registers() {
-                let val = src.values()[slot];
-                if val > 0 {
-                    let clamped_val = val.min(63);
-                    let coupon = Coupon::pack(slot as u32, clamped_val);
-                    array6.update(coupon);
-                }
-            }
-
-            let src_est = src.estimate();
-            let arr6_est = array6.estimate();
-            if src_est > arr6_est {
-                array6.set_hip_accum(src_est);
-            }
-
-            HllSketch::from_mode(lg_config_k, Mode::Array6(array6))
-        }
-        HllType::Hll4 => {
-            let mut array4 = Array4::new(lg_config_k);
-            for slot in 0..src.num_registers() {
-                let val = src.values()[slot];
-                if val > 0 {
-                    let coupon = Coupon::pack(slot as u32, val);
-                    array4.update(coupon);
-                }
-            }
-
-            let src_est = src.estimate();
-            let arr4_est = array4.estimate();
-            if src_est > arr4_est {
-                array4.set_hip_accum(src_est);
-            }
-
-            HllSketch::from_mode(lg_config_k, Mode::Array4(array4))
-        }
-    }
+/// from the Array8 source. Preserves the HIP estimator state, so the result has
+/// the same estimate and bounds as the source.
+fn convert_array8_to_type(
+    src: &Array8,
+    lg_config_k: u8,
+    target_type: HllType,
+    seed: u64,
+) -> HllSketch {
+    let mode = match target_type {
+        HllType::Hll8 => Mode::Array8(src.clone()),
+        HllType::Hll6 => Mode::Array6(Array6::from_array8(src, lg_config_k)),
+        HllType::Hll4 => Mode::Array4(Array4::from_array8(src, lg_config_k)),
+    };
+    HllSketch::from_mode(lg_config_k, mode, seed)
 }
 
 /// Copy Array4/Array6 registers into Array8 by converting to coupons
@@ -576,9 +977,14 @@ fn copy_array46_via_coupons(dst: &mut Array8, num_registers: usize, get_value: i
 ///
 /// Directly copies if src_lg_k <= tgt_lg_k, downsamples otherwise.
 /// Result is marked as out-of-order and HIP accumulator is preserved.
-fn copy_or_downsample(src_mode: &Mode, src_lg_k: u8, tgt_lg_k: u8) -> Array8 {
+fn copy_or_downsample(
+    spare: &mut Option<Array8>,
+    src_mode: &Mode,
+    src_lg_k: u8,
+    tgt_lg_k: u8,
+) -> Array8 {
     if src_lg_k <= tgt_lg_k {
-        let mut result = Array8::new(src_lg_k);
+        let mut result = Array8::take_or_new(spare, src_lg_k);
         let src_hip = get_array_hip_accum(src_mode);
 
         match src_mode {
@@ -602,7 +1008,7 @@ fn copy_or_downsample(src_mode: &Mode, src_lg_k: u8, tgt_lg_k: u8) -> Array8 {
         result
     } else {
         // Downsample from src to tgt
-        let mut result = Array8::new(tgt_lg_k);
+        let mut result = Array8::take_or_new(spare, tgt_lg_k);
         merge_array_with_downsample(&mut result, tgt_lg_k, src_mode, src_lg_k);
         result
     }
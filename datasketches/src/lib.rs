@@ -22,17 +22,45 @@
 //! systems that must deal with massive data.
 //!
 //! This library is divided into modules that constitute distinct groups of functionality.
+//!
+//! # Feature flags
+//!
+//! No sketch is compiled by default. Each sketch family is behind a feature named after its
+//! module, so that binary-size-sensitive builds, such as WebAssembly, only compile the families
+//! they use:
+//!
+//! | Feature       | Sketches                                 |
+//! |---------------|------------------------------------------|
+//! | `bloom`       | Bloom filter                             |
+//! | `countmin`    | Count-Min sketch                         |
+//! | `cpc`         | Compressed Probabilistic Counting sketch |
+//! | `frequencies` | Frequent items sketch                    |
+//! | `hll`         | HyperLogLog sketch and union             |
+//! | `tdigest`     | t-digest                                 |
+//! | `theta`       | Theta sketch and set operations          |
+//! | `tuple`       | Tuple sketch and set operations          |
+//!
+//! The [`codec`], [`common`], [`hash`] and [`hash_value`] modules are always available and build
+//! without any sketch feature. The `cardinality` module is available with any of the `cpc`,
+//! `hll` and `theta` features. The `testing` module, with helpers for comparing sketch estimates
+//! in tests, and test-only constructors such as `ThetaSketchBuilder::build_from_seeded_state` are
+//! behind the `testing` feature.
 
 #![cfg_attr(docsrs, feature(doc_cfg))]
 #![deny(missing_docs)]
 
-// See https://github.com/apache/datasketches-rust/issues/28 for more information.
-#[cfg(target_endian = "big")]
-compile_error!("datasketches does not support big-endian targets");
+// Hashing and serialization never depend on native endianness, but big-endian targets are not
+// tested. See https://github.com/apache/datasketches-rust/issues/28 for more information.
+#[cfg(all(target_endian = "big", not(feature = "big-endian")))]
+compile_error!(
+    "datasketches is not tested on big-endian targets; enable the `big-endian` feature to build anyway"
+);
 
 // sketches modules
 #[cfg(feature = "bloom")]
 pub mod bloom;
+#[cfg(any(feature = "cpc", feature = "hll", feature = "theta"))]
+pub mod cardinality;
 #[cfg(feature = "countmin")]
 pub mod countmin;
 #[cfg(feature = "cpc")]
@@ -51,11 +79,18 @@ pub mod thetacommon;
 #[cfg(feature = "tuple")]
 pub mod tuple;
 
+// integrations
+#[cfg(feature = "datafusion")]
+pub mod datafusion;
+
 // common modules
 pub mod codec;
 pub mod common;
+pub mod descriptor;
 pub mod error;
+pub mod hash;
 pub mod hash_value;
+#[cfg(feature = "testing")]
+pub mod testing;
 
-// private internal modules
-mod hash;
+pub use self::descriptor::inspect;
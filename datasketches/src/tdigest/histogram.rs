@@ -0,0 +1,91 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+/// Bin counts of a distribution, derived from a sketch's PMF.
+///
+/// A histogram with _m_ split points has _m+1_ bins. Bin 0 covers values below the first split
+/// point, bin _i_ covers `[split_points[i-1], split_points[i])`, and the last bin covers values
+/// at or above the last split point. Counts are approximate and therefore fractional; they sum
+/// to the total weight of the sketch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram {
+    split_points: Vec<f64>,
+    counts: Vec<f64>,
+}
+
+impl Histogram {
+    pub(super) fn new(split_points: Vec<f64>, counts: Vec<f64>) -> Self {
+        debug_assert_eq!(split_points.len() + 1, counts.len());
+        Self {
+            split_points,
+            counts,
+        }
+    }
+
+    /// Returns the split points separating adjacent bins.
+    pub fn split_points(&self) -> &[f64] {
+        &self.split_points
+    }
+
+    /// Returns the approximate count of each bin.
+    pub fn counts(&self) -> &[f64] {
+        &self.counts
+    }
+
+    /// Returns the number of bins.
+    pub fn num_bins(&self) -> usize {
+        self.counts.len()
+    }
+
+    /// Returns the sum of all bin counts.
+    pub fn total_count(&self) -> f64 {
+        self.counts.iter().sum()
+    }
+
+    /// Returns Prometheus-style cumulative buckets as `(upper_bound, cumulative_count)` pairs.
+    ///
+    /// There is one bucket per split point, holding the count of values below it, followed by a
+    /// final `+Inf` bucket holding the total count.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::tdigest::TDigestMut;
+    /// let mut sketch = TDigestMut::new(100);
+    /// for i in 0..100 {
+    ///     sketch.update(i as f64);
+    /// }
+    /// let histogram = sketch.to_histogram_with_edges(&[10.0, 50.0]).unwrap();
+    /// let buckets = histogram.cumulative_buckets();
+    /// assert_eq!(buckets.len(), 3);
+    /// assert_eq!(buckets[2], (f64::INFINITY, 100.0));
+    /// ```
+    pub fn cumulative_buckets(&self) -> Vec<(f64, f64)> {
+        let upper_bounds = self
+            .split_points
+            .iter()
+            .copied()
+            .chain(std::iter::once(f64::INFINITY));
+        let mut cumulative = 0.0;
+        upper_bounds
+            .zip(self.counts.iter())
+            .map(|(upper_bound, count)| {
+                cumulative += count;
+                (upper_bound, cumulative)
+            })
+            .collect()
+    }
+}
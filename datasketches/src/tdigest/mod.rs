@@ -60,8 +60,14 @@
 //! assert!(frozen.rank(2.0).is_some());
 //! ```
 
-mod serialization;
+mod histogram;
+pub(crate) mod serialization;
 
 mod sketch;
+pub use self::histogram::Histogram;
+pub use self::sketch::BUFFER_MULTIPLIER;
+pub use self::sketch::MAX_BUFFER_MULTIPLIER;
+pub use self::sketch::NanPolicy;
 pub use self::sketch::TDigest;
+pub use self::sketch::TDigestBuilder;
 pub use self::sketch::TDigestMut;
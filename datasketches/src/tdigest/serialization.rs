@@ -15,12 +15,15 @@
 // specific language governing permissions and limitations
 // under the License.
 
-pub(super) const PREAMBLE_LONGS_EMPTY_OR_SINGLE: u8 = 1;
-pub(super) const PREAMBLE_LONGS_MULTIPLE: u8 = 2;
-pub(super) const SERIAL_VERSION: u8 = 1;
-pub(super) const FLAGS_IS_EMPTY: u8 = 1 << 0;
-pub(super) const FLAGS_IS_SINGLE_VALUE: u8 = 1 << 1;
-pub(super) const FLAGS_REVERSE_MERGE: u8 = 1 << 2;
+use crate::codec::format;
+
+pub(super) const PREAMBLE_LONGS_EMPTY_OR_SINGLE: u8 =
+    format::tdigest::PREAMBLE_LONGS_EMPTY_OR_SINGLE;
+pub(super) const PREAMBLE_LONGS_MULTIPLE: u8 = format::tdigest::PREAMBLE_LONGS_MULTIPLE;
+pub(super) const SERIAL_VERSION: u8 = format::tdigest::SERIAL_VERSION;
+pub(crate) const FLAGS_IS_EMPTY: u8 = format::tdigest::FLAGS_IS_EMPTY;
+pub(crate) const FLAGS_IS_SINGLE_VALUE: u8 = format::tdigest::FLAGS_IS_SINGLE_VALUE;
+pub(super) const FLAGS_REVERSE_MERGE: u8 = format::tdigest::FLAGS_REVERSE_MERGE;
 /// the format of the reference implementation is using double (f64) precision
 pub(super) const COMPAT_DOUBLE: u32 = 1;
 /// the format of the reference implementation is using float (f32) precision
@@ -22,10 +22,12 @@ use std::num::NonZeroU64;
 use crate::codec::SketchBytes;
 use crate::codec::SketchSlice;
 use crate::codec::assert::ensure_preamble_longs_in;
-use crate::codec::assert::ensure_serial_version_is;
 use crate::codec::assert::insufficient_data;
 use crate::codec::family::Family;
+use crate::common::MemoryUsage;
+use crate::common::SketchBuilder;
 use crate::error::Error;
+use crate::tdigest::Histogram;
 use crate::tdigest::serialization::COMPAT_DOUBLE;
 use crate::tdigest::serialization::COMPAT_FLOAT;
 use crate::tdigest::serialization::FLAGS_IS_EMPTY;
@@ -37,17 +39,38 @@ use crate::tdigest::serialization::SERIAL_VERSION;
 
 /// The default value of K if one is not specified.
 const DEFAULT_K: u16 = 200;
-/// Multiplier for buffer size relative to centroids capacity.
-const BUFFER_MULTIPLIER: usize = 4;
+/// Default multiplier for the buffer size relative to the centroids capacity.
+///
+/// See [`TDigestMut::with_buffer_multiplier`].
+pub const BUFFER_MULTIPLIER: usize = 4;
+/// Largest multiplier accepted by [`TDigestMut::with_buffer_multiplier`].
+pub const MAX_BUFFER_MULTIPLIER: usize = 1024;
 /// Default weight for single values.
 const DEFAULT_WEIGHT: NonZeroU64 = NonZeroU64::new(1).unwrap();
 
+/// How [`TDigestMut::update`] handles [`f64::NAN`] values.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum NanPolicy {
+    /// Drop the value and count it in [`TDigestMut::num_ignored`].
+    #[default]
+    Ignore,
+    /// Reject the value: [`TDigestMut::update`] panics and [`TDigestMut::try_update`] returns an
+    /// error.
+    Error,
+    /// Record the value as [`f64::MAX`], the largest finite value, so that it contributes to the
+    /// total weight and the top of the rank domain.
+    CountAsMax,
+}
+
 /// T-Digest sketch for estimating quantiles and ranks.
 ///
 /// See the [module level documentation](super) for more.
 #[derive(Debug, Clone)]
 pub struct TDigestMut {
     k: u16,
+    nan_policy: NanPolicy,
+    num_ignored: u64,
+    buffer_multiplier: usize,
 
     reverse_merge: bool,
     min: f64,
@@ -66,6 +89,24 @@ impl Default for TDigestMut {
 }
 
 impl TDigestMut {
+    /// Returns a builder with k 200 and [`NanPolicy::Ignore`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::tdigest::NanPolicy;
+    /// # use datasketches::tdigest::TDigestMut;
+    /// let sketch = TDigestMut::builder()
+    ///     .k(100)
+    ///     .nan_policy(NanPolicy::CountAsMax)
+    ///     .build();
+    /// assert_eq!(sketch.k(), 100);
+    /// assert_eq!(sketch.nan_policy(), NanPolicy::CountAsMax);
+    /// ```
+    pub fn builder() -> TDigestBuilder {
+        TDigestBuilder::default()
+    }
+
     /// Creates a tdigest instance with the given value of k.
     ///
     /// The fallible version of this method is [`TDigestMut::try_new`].
@@ -146,6 +187,9 @@ impl TDigestMut {
 
         TDigestMut {
             k,
+            nan_policy: NanPolicy::default(),
+            num_ignored: 0,
+            buffer_multiplier: BUFFER_MULTIPLIER,
             reverse_merge,
             min,
             max,
@@ -156,9 +200,131 @@ impl TDigestMut {
         }
     }
 
+    /// Sets how [`f64::NAN`] values passed to [`update`](Self::update) are handled.
+    ///
+    /// The policy is not serialized; deserialized sketches use [`NanPolicy::Ignore`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::tdigest::{NanPolicy, TDigestMut};
+    /// let mut sketch = TDigestMut::new(100).with_nan_policy(NanPolicy::CountAsMax);
+    /// sketch.update(1.0);
+    /// sketch.update(f64::NAN);
+    /// assert_eq!(sketch.total_weight(), 2);
+    /// assert_eq!(sketch.max_value(), Some(f64::MAX));
+    /// ```
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
+    }
+
+    /// Returns the policy applied to [`f64::NAN`] values.
+    pub fn nan_policy(&self) -> NanPolicy {
+        self.nan_policy
+    }
+
+    /// Sets how many values are buffered before they are compressed into centroids, as a multiple
+    /// of the centroids capacity (about `2 * k`). The default is [`BUFFER_MULTIPLIER`].
+    ///
+    /// A larger multiplier uses more memory but compresses less often, so updates pause less
+    /// frequently; a smaller one keeps the sketch compact. The buffer grows as values arrive
+    /// rather than being allocated up front. The multiplier is not serialized; deserialized
+    /// sketches use the default.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `buffer_multiplier` is zero or greater than [`MAX_BUFFER_MULTIPLIER`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::tdigest::TDigestMut;
+    /// let mut small = TDigestMut::new(100).with_buffer_multiplier(1);
+    /// let mut large = TDigestMut::new(100).with_buffer_multiplier(16);
+    /// assert_eq!(small.buffer_multiplier(), 1);
+    /// for i in 0..1000 {
+    ///     small.update(i as f64);
+    ///     large.update(i as f64);
+    /// }
+    /// // `large` still buffers all 1000 values, while `small` has compressed them into centroids.
+    /// assert!(large.estimated_size() >= 1000 * size_of::<f64>());
+    /// assert!(small.estimated_size() < 1000 * size_of::<f64>());
+    /// ```
+    pub fn with_buffer_multiplier(mut self, buffer_multiplier: usize) -> Self {
+        assert!(
+            (1..=MAX_BUFFER_MULTIPLIER).contains(&buffer_multiplier),
+            "buffer_multiplier must be in [1, {MAX_BUFFER_MULTIPLIER}], got {buffer_multiplier}"
+        );
+
+        self.buffer_multiplier = buffer_multiplier;
+        let capacity = self.buffer_capacity();
+        if self.buffer.len() >= capacity {
+            self.compress();
+        }
+        self.buffer.shrink_to(capacity);
+        self
+    }
+
+    /// Returns the buffer size as a multiple of the centroids capacity.
+    pub fn buffer_multiplier(&self) -> usize {
+        self.buffer_multiplier
+    }
+
+    /// Compresses the buffered values into centroids.
+    ///
+    /// Updates compress automatically once the buffer is full, and queries and serialization
+    /// compress before reading the centroids. Calling this explicitly moves that work to a time
+    /// of the caller's choosing, e.g. before a sketch is stored or handed to a latency-sensitive
+    /// reader. It does nothing if no values are buffered.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::tdigest::TDigestMut;
+    /// let mut sketch = TDigestMut::new(100);
+    /// for i in 0..100 {
+    ///     sketch.update(i as f64);
+    /// }
+    /// let mut compressed = sketch.clone();
+    /// compressed.compress();
+    /// assert_eq!(compressed.total_weight(), 100);
+    /// assert_eq!(compressed.quantile(0.5), sketch.quantile(0.5));
+    /// ```
+    pub fn compress(&mut self) {
+        if self.buffer.is_empty() {
+            return;
+        }
+        let tmp = self.buffered_centroids(0);
+        self.do_merge(tmp, self.buffer.len() as u64)
+    }
+
+    fn buffer_capacity(&self) -> usize {
+        self.centroids_capacity * self.buffer_multiplier
+    }
+
+    /// Returns the buffered values as unit weight centroids, with room for `additional` more
+    /// centroids besides the current ones, ready to be passed to `do_merge`.
+    fn buffered_centroids(&self, additional: usize) -> Vec<Centroid> {
+        let mut tmp = Vec::with_capacity(self.buffer.len() + self.centroids.len() + additional);
+        tmp.extend(self.buffer.iter().map(|&v| Centroid {
+            mean: v,
+            weight: DEFAULT_WEIGHT,
+        }));
+        tmp
+    }
+
     /// Update this TDigest with the given value.
     ///
-    /// [f64::NAN], [f64::INFINITY], and [f64::NEG_INFINITY] values are ignored.
+    /// [f64::INFINITY] and [f64::NEG_INFINITY] values are ignored. [f64::NAN] values are handled
+    /// according to the [`NanPolicy`], which ignores them by default. Ignored values are counted
+    /// in [`num_ignored`](Self::num_ignored).
+    ///
+    /// The fallible version of this method is [`TDigestMut::try_update`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value is `NaN` and the policy is [`NanPolicy::Error`].
     ///
     /// # Examples
     ///
@@ -168,12 +334,44 @@ impl TDigestMut {
     /// sketch.update(1.0);
     /// assert!(sketch.total_weight() >= 1);
     /// ```
+    #[track_caller]
     pub fn update(&mut self, value: f64) {
-        if value.is_nan() || value.is_infinite() {
-            return;
+        self.assert_not_rejected(value);
+        self.push_value(value);
+    }
+
+    /// Update this TDigest with the given value.
+    ///
+    /// The panicking version of this method is [`TDigestMut::update`].
+    ///
+    /// # Errors
+    ///
+    /// If the value is `NaN` and the policy is [`NanPolicy::Error`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::tdigest::{NanPolicy, TDigestMut};
+    /// let mut sketch = TDigestMut::new(100).with_nan_policy(NanPolicy::Error);
+    /// assert!(sketch.try_update(1.0).is_ok());
+    /// assert!(sketch.try_update(f64::NAN).is_err());
+    /// assert_eq!(sketch.total_weight(), 1);
+    /// ```
+    pub fn try_update(&mut self, value: f64) -> Result<(), Error> {
+        if self.rejects(value) {
+            return Err(Error::invalid_argument("value must not be NaN"));
         }
+        self.push_value(value);
+        Ok(())
+    }
+
+    /// Buffers `value` once the caller has checked that the [`NanPolicy`] does not reject it.
+    fn push_value(&mut self, value: f64) {
+        let Some(value) = self.accept_value(value) else {
+            return;
+        };
 
-        if self.buffer.len() == self.centroids_capacity * BUFFER_MULTIPLIER {
+        if self.buffer.len() >= self.buffer_capacity() {
             self.compress();
         }
 
@@ -182,6 +380,267 @@ impl TDigestMut {
         self.max = self.max.max(value);
     }
 
+    /// Returns true if the [`NanPolicy`] rejects `value` with an error.
+    fn rejects(&self, value: f64) -> bool {
+        value.is_nan() && self.nan_policy == NanPolicy::Error
+    }
+
+    #[track_caller]
+    fn assert_not_rejected(&self, value: f64) {
+        assert!(!self.rejects(value), "value must not be NaN");
+    }
+
+    /// Applies the [`NanPolicy`] to a value it does not reject, counting the value in
+    /// `num_ignored` if it is dropped.
+    fn accept_value(&mut self, value: f64) -> Option<f64> {
+        if value.is_nan() {
+            match self.nan_policy {
+                NanPolicy::CountAsMax => Some(f64::MAX),
+                // `NanPolicy::Error` values are rejected before they get here.
+                NanPolicy::Ignore | NanPolicy::Error => {
+                    self.num_ignored += 1;
+                    None
+                }
+            }
+        } else if value.is_infinite() {
+            self.num_ignored += 1;
+            None
+        } else {
+            Some(value)
+        }
+    }
+
+    /// Update this TDigest with every value of `values`.
+    ///
+    /// Values are handled as [`update`](Self::update) handles them, but the whole slice is merged
+    /// with the buffered values in one compression, so loading a large raw buffer does not go
+    /// through the buffer one value at a time.
+    ///
+    /// The fallible version of this method is [`TDigestMut::try_merge_values`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if any value is `NaN` and the policy is [`NanPolicy::Error`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::tdigest::TDigestMut;
+    /// let values: Vec<f64> = (0..1000).map(f64::from).collect();
+    /// let mut sketch = TDigestMut::new(100);
+    /// sketch.merge_values(&values);
+    /// assert_eq!(sketch.total_weight(), 1000);
+    /// assert_eq!(sketch.max_value(), Some(999.0));
+    /// ```
+    #[track_caller]
+    pub fn merge_values(&mut self, values: &[f64]) {
+        for &value in values {
+            self.assert_not_rejected(value);
+        }
+        self.merge_accepted_values(values);
+    }
+
+    /// Update this TDigest with every value of `values`.
+    ///
+    /// The panicking version of this method is [`TDigestMut::merge_values`].
+    ///
+    /// # Errors
+    ///
+    /// If any value is `NaN` and the policy is [`NanPolicy::Error`]. The sketch is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::tdigest::{NanPolicy, TDigestMut};
+    /// let mut sketch = TDigestMut::new(100).with_nan_policy(NanPolicy::Error);
+    /// assert!(sketch.try_merge_values(&[1.0, f64::NAN]).is_err());
+    /// assert!(sketch.is_empty());
+    /// assert!(sketch.try_merge_values(&[1.0, 2.0]).is_ok());
+    /// assert_eq!(sketch.total_weight(), 2);
+    /// ```
+    pub fn try_merge_values(&mut self, values: &[f64]) -> Result<(), Error> {
+        if values.iter().any(|&value| self.rejects(value)) {
+            return Err(Error::invalid_argument("value must not be NaN"));
+        }
+        self.merge_accepted_values(values);
+        Ok(())
+    }
+
+    /// Merges `values` once the caller has checked that the [`NanPolicy`] rejects none of them.
+    fn merge_accepted_values(&mut self, values: &[f64]) {
+        let mut tmp = self.buffered_centroids(values.len());
+        for &value in values {
+            if let Some(value) = self.accept_value(value) {
+                self.min = self.min.min(value);
+                self.max = self.max.max(value);
+                tmp.push(Centroid {
+                    mean: value,
+                    weight: DEFAULT_WEIGHT,
+                });
+            }
+        }
+        if tmp.len() > self.buffer.len() {
+            let weight = tmp.len() as u64;
+            self.do_merge(tmp, weight);
+        }
+    }
+
+    /// Inserts a centroid of `weight` values with the given `mean`.
+    ///
+    /// This loads a pre-aggregated `(mean, count)` pair, e.g. a centroid of another t-digest
+    /// implementation, without reconstructing the raw stream. The centroid is merged with the
+    /// buffered values right away, so use [`insert_centroids`](Self::insert_centroids) to load
+    /// many pairs in one compression. Its mean also extends [`min_value`](Self::min_value) and
+    /// [`max_value`](Self::max_value), which are only as exact as the inserted means.
+    ///
+    /// The fallible version of this method is [`TDigestMut::try_insert_centroid`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mean` is not finite, if `weight` is zero, or if the total weight would overflow
+    /// `u64`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::tdigest::TDigestMut;
+    /// let mut sketch = TDigestMut::new(100);
+    /// sketch.insert_centroid(1.0, 10);
+    /// sketch.insert_centroid(3.0, 30);
+    /// assert_eq!(sketch.total_weight(), 40);
+    /// assert_eq!(sketch.min_value(), Some(1.0));
+    /// ```
+    #[track_caller]
+    pub fn insert_centroid(&mut self, mean: f64, weight: u64) {
+        self.insert_centroids(&[(mean, weight)]);
+    }
+
+    /// Inserts a centroid of `weight` values with the given `mean`.
+    ///
+    /// The panicking version of this method is [`TDigestMut::insert_centroid`].
+    ///
+    /// # Errors
+    ///
+    /// If `mean` is not finite, if `weight` is zero, or if the total weight would overflow `u64`.
+    /// The sketch is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::tdigest::TDigestMut;
+    /// let mut sketch = TDigestMut::new(100);
+    /// assert!(sketch.try_insert_centroid(1.0, 10).is_ok());
+    /// assert!(sketch.try_insert_centroid(f64::NAN, 1).is_err());
+    /// assert!(sketch.try_insert_centroid(2.0, 0).is_err());
+    /// assert!(sketch.try_insert_centroid(2.0, u64::MAX).is_err());
+    /// assert_eq!(sketch.total_weight(), 10);
+    /// ```
+    pub fn try_insert_centroid(&mut self, mean: f64, weight: u64) -> Result<(), Error> {
+        self.try_insert_centroids(&[(mean, weight)])
+    }
+
+    /// Inserts a centroid for every `(mean, weight)` pair of `centroids`.
+    ///
+    /// Each pair is handled as [`insert_centroid`](Self::insert_centroid) handles it, but the
+    /// whole slice is merged with the buffered values in one compression.
+    ///
+    /// The fallible version of this method is [`TDigestMut::try_insert_centroids`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if a mean is not finite, if a weight is zero, or if the total weight would overflow
+    /// `u64`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::tdigest::TDigestMut;
+    /// let mut sketch = TDigestMut::new(100);
+    /// sketch.insert_centroids(&[(1.0, 10), (2.0, 20), (3.0, 30)]);
+    /// assert_eq!(sketch.total_weight(), 60);
+    /// assert_eq!(sketch.max_value(), Some(3.0));
+    /// ```
+    #[track_caller]
+    pub fn insert_centroids(&mut self, centroids: &[(f64, u64)]) {
+        self.assert_valid_centroids(centroids);
+        self.merge_centroids(centroids);
+    }
+
+    /// Inserts a centroid for every `(mean, weight)` pair of `centroids`.
+    ///
+    /// The panicking version of this method is [`TDigestMut::insert_centroids`].
+    ///
+    /// # Errors
+    ///
+    /// If a mean is not finite, if a weight is zero, or if the total weight would overflow `u64`.
+    /// The sketch is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::tdigest::TDigestMut;
+    /// let mut sketch = TDigestMut::new(100);
+    /// assert!(sketch.try_insert_centroids(&[(1.0, 10), (2.0, 0)]).is_err());
+    /// assert!(sketch.is_empty());
+    /// assert!(sketch.try_insert_centroids(&[(1.0, 10), (2.0, 20)]).is_ok());
+    /// assert_eq!(sketch.total_weight(), 30);
+    /// ```
+    pub fn try_insert_centroids(&mut self, centroids: &[(f64, u64)]) -> Result<(), Error> {
+        let mut total_weight = self.total_weight();
+        for &(mean, weight) in centroids {
+            if !mean.is_finite() {
+                return Err(Error::invalid_argument(format!(
+                    "centroid mean must be finite, got {mean}"
+                )));
+            }
+            if weight == 0 {
+                return Err(Error::invalid_argument("centroid weight must be positive"));
+            }
+            total_weight = total_weight.checked_add(weight).ok_or_else(|| {
+                Error::invalid_argument(format!(
+                    "centroid weight {weight} overflows the total weight {total_weight}"
+                ))
+            })?;
+        }
+        self.merge_centroids(centroids);
+        Ok(())
+    }
+
+    #[track_caller]
+    fn assert_valid_centroids(&self, centroids: &[(f64, u64)]) {
+        let mut total_weight = self.total_weight();
+        for &(mean, weight) in centroids {
+            assert!(mean.is_finite(), "centroid mean must be finite, got {mean}");
+            assert!(weight > 0, "centroid weight must be positive");
+            assert!(
+                weight <= u64::MAX - total_weight,
+                "centroid weight {weight} overflows the total weight {total_weight}"
+            );
+            total_weight += weight;
+        }
+    }
+
+    /// Merges `centroids` with the buffered values in one compression, once the caller has
+    /// checked their means and weights.
+    fn merge_centroids(&mut self, centroids: &[(f64, u64)]) {
+        if centroids.is_empty() {
+            return;
+        }
+        let mut tmp = self.buffered_centroids(centroids.len());
+        let mut weight = self.buffer.len() as u64;
+        for &(mean, centroid_weight) in centroids {
+            let centroid_weight = NonZeroU64::new(centroid_weight).expect("checked by the caller");
+            tmp.push(Centroid {
+                mean,
+                weight: centroid_weight,
+            });
+            self.min = self.min.min(mean);
+            self.max = self.max.max(mean);
+            weight += centroid_weight.get();
+        }
+        self.do_merge(tmp, weight);
+    }
+
     /// Returns parameter k (compression) that was used to configure this TDigest.
     pub fn k(&self) -> u16 {
         self.k
@@ -215,6 +674,25 @@ impl TDigestMut {
         self.centroids_weight + self.buffer.len() as u64
     }
 
+    /// Returns the number of values dropped by [`update`](Self::update): infinities, and `NaN`
+    /// values under [`NanPolicy::Ignore`].
+    ///
+    /// The count accumulates across [`merge`](Self::merge) and is not serialized.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::tdigest::TDigestMut;
+    /// let mut sketch = TDigestMut::new(100);
+    /// sketch.update(1.0);
+    /// sketch.update(f64::NAN);
+    /// sketch.update(f64::INFINITY);
+    /// assert_eq!(sketch.num_ignored(), 2);
+    /// ```
+    pub fn num_ignored(&self) -> u64 {
+        self.num_ignored
+    }
+
     /// Merge the given TDigest into this one
     ///
     /// # Examples
@@ -229,19 +707,12 @@ impl TDigestMut {
     /// assert_eq!(left.total_weight(), 2);
     /// ```
     pub fn merge(&mut self, other: &TDigestMut) {
+        self.num_ignored += other.num_ignored;
         if other.is_empty() {
             return;
         }
 
-        let mut tmp = Vec::with_capacity(
-            self.centroids.len() + self.buffer.len() + other.centroids.len() + other.buffer.len(),
-        );
-        for &v in &self.buffer {
-            tmp.push(Centroid {
-                mean: v,
-                weight: DEFAULT_WEIGHT,
-            });
-        }
+        let mut tmp = self.buffered_centroids(other.centroids.len() + other.buffer.len());
         for &v in &other.buffer {
             tmp.push(Centroid {
                 mean: v,
@@ -269,6 +740,8 @@ impl TDigestMut {
         self.compress();
         TDigest {
             k: self.k,
+            nan_policy: self.nan_policy,
+            num_ignored: self.num_ignored,
             reverse_merge: self.reverse_merge,
             min: self.min,
             max: self.max,
@@ -389,6 +862,100 @@ impl TDigestMut {
         self.view().quantile(rank)
     }
 
+    /// See [`TDigest::percentile`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::tdigest::TDigestMut;
+    /// # let mut sketch = TDigestMut::new(100);
+    /// # for i in 1..=100 {
+    /// #     sketch.update(i as f64);
+    /// # }
+    /// let p99 = sketch.percentile(99.0).unwrap();
+    /// assert!((98.0..=100.0).contains(&p99));
+    /// ```
+    pub fn percentile(&mut self, percentile: f64) -> Option<f64> {
+        let rank = percentile_to_rank(percentile);
+        self.quantile(rank)
+    }
+
+    /// See [`TDigest::percentiles`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::tdigest::TDigestMut;
+    /// # let mut sketch = TDigestMut::new(100);
+    /// # for i in 1..=100 {
+    /// #     sketch.update(i as f64);
+    /// # }
+    /// let values = sketch.percentiles(&[50.0, 90.0, 99.0]).unwrap();
+    /// assert_eq!(values.len(), 3);
+    /// ```
+    pub fn percentiles(&mut self, percentiles: &[f64]) -> Option<Vec<f64>> {
+        let ranks: Vec<f64> = percentiles
+            .iter()
+            .copied()
+            .map(percentile_to_rank)
+            .collect();
+
+        if self.is_empty() {
+            return None;
+        }
+
+        let view = self.view();
+        ranks.into_iter().map(|rank| view.quantile(rank)).collect()
+    }
+
+    /// See [`TDigest::to_histogram`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::tdigest::TDigestMut;
+    /// # let mut sketch = TDigestMut::new(100);
+    /// # for i in 0..100 {
+    /// #     sketch.update(i as f64);
+    /// # }
+    /// let histogram = sketch.to_histogram(4).unwrap();
+    /// assert_eq!(histogram.num_bins(), 4);
+    /// ```
+    pub fn to_histogram(&mut self, num_bins: usize) -> Option<Histogram> {
+        assert!(num_bins > 0, "num_bins must be positive");
+
+        if self.is_empty() {
+            return None;
+        }
+
+        self.view().histogram(num_bins)
+    }
+
+    /// See [`TDigest::to_histogram_with_edges`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::tdigest::TDigestMut;
+    /// # let mut sketch = TDigestMut::new(100);
+    /// # for i in 0..100 {
+    /// #     sketch.update(i as f64);
+    /// # }
+    /// let histogram = sketch.to_histogram_with_edges(&[25.0, 75.0]).unwrap();
+    /// assert_eq!(histogram.num_bins(), 3);
+    /// ```
+    pub fn to_histogram_with_edges(&mut self, split_points: &[f64]) -> Option<Histogram> {
+        if !split_points.is_empty() {
+            check_split_points(split_points);
+        }
+
+        if self.is_empty() {
+            return None;
+        }
+
+        self.view().histogram_with_edges(split_points)
+    }
+
     /// Serializes this TDigest to bytes.
     ///
     /// # Examples
@@ -403,7 +970,40 @@ impl TDigestMut {
     /// ```
     pub fn serialize(&mut self) -> Vec<u8> {
         self.compress();
+        let mut bytes = SketchBytes::with_capacity(0);
+        self.write_to(&mut bytes);
+        bytes.into_bytes()
+    }
+
+    /// Serializes this TDigest into `buf`, returning the number of bytes written.
+    ///
+    /// Writes the same bytes as [`serialize`](Self::serialize) without allocating the output.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `buf` is too short to hold the serialized sketch. The sketch is
+    /// written up to the end of `buf` before the length is checked, so the contents of `buf`
+    /// are unspecified when an error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::tdigest::TDigestMut;
+    /// # let mut sketch = TDigestMut::new(100);
+    /// # sketch.update(1.0);
+    /// let mut buf = [0u8; 64];
+    /// let len = sketch.serialize_into_slice(&mut buf).unwrap();
+    /// let decoded = TDigestMut::deserialize(&buf[..len], false).unwrap();
+    /// assert_eq!(decoded.max_value(), Some(1.0));
+    /// ```
+    pub fn serialize_into_slice(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        self.compress();
+        let mut bytes = SketchBytes::from_slice(buf);
+        self.write_to(&mut bytes);
+        bytes.finish()
+    }
 
+    fn write_to(&self, bytes: &mut SketchBytes<'_>) {
         let mut total_size = 0;
         if self.is_empty() || self.is_single_value() {
             // 1 byte preamble
@@ -432,7 +1032,7 @@ impl TDigestMut {
             total_size += self.centroids.len() * (size_of::<f64>() + size_of::<u64>());
         }
 
-        let mut bytes = SketchBytes::with_capacity(total_size);
+        bytes.reserve(total_size);
         bytes.write_u8(match self.total_weight() {
             0 => PREAMBLE_LONGS_EMPTY_OR_SINGLE,
             1 => PREAMBLE_LONGS_EMPTY_OR_SINGLE,
@@ -456,11 +1056,11 @@ impl TDigestMut {
         });
         bytes.write_u16_le(0); // unused
         if self.is_empty() {
-            return bytes.into_bytes();
+            return;
         }
         if self.is_single_value() {
             bytes.write_f64_le(self.min);
-            return bytes.into_bytes();
+            return;
         }
         bytes.write_u32_le(self.centroids.len() as u32);
         bytes.write_u32_le(0); // unused
@@ -470,7 +1070,6 @@ impl TDigestMut {
             bytes.write_f64_le(centroid.mean);
             bytes.write_u64_le(centroid.weight.get());
         }
-        bytes.into_bytes()
     }
 
     /// Deserializes a TDigest from bytes.
@@ -511,7 +1110,7 @@ impl TDigestMut {
                 Err(err)
             };
         }
-        ensure_serial_version_is(SERIAL_VERSION, serial_version)?;
+        Family::TDIGEST.validate_serial_version(serial_version)?;
         let k = cursor.read_u16_le().map_err(insufficient_data("k"))?;
         if k < 10 {
             return Err(Error::deserial(format!("k must be at least 10, got {k}")));
@@ -722,21 +1321,6 @@ impl TDigestMut {
         self.total_weight() == 1
     }
 
-    /// Process buffered values and merge centroids if needed.
-    fn compress(&mut self) {
-        if self.buffer.is_empty() {
-            return;
-        }
-        let mut tmp = Vec::with_capacity(self.buffer.len() + self.centroids.len());
-        for &v in &self.buffer {
-            tmp.push(Centroid {
-                mean: v,
-                weight: DEFAULT_WEIGHT,
-            });
-        }
-        self.do_merge(tmp, self.buffer.len() as u64)
-    }
-
     /// Merges the given buffer of centroids into this TDigest.
     ///
     /// # Contract
@@ -802,11 +1386,84 @@ impl TDigestMut {
     }
 }
 
+/// Builder for [`TDigestMut`].
+///
+/// Created by [`TDigestMut::builder`]. The parameters are validated when the sketch is built.
+#[derive(Debug, Clone)]
+pub struct TDigestBuilder {
+    k: u16,
+    nan_policy: NanPolicy,
+    buffer_multiplier: usize,
+}
+
+impl Default for TDigestBuilder {
+    fn default() -> Self {
+        Self {
+            k: DEFAULT_K,
+            nan_policy: NanPolicy::default(),
+            buffer_multiplier: BUFFER_MULTIPLIER,
+        }
+    }
+}
+
+impl TDigestBuilder {
+    /// Set k, which controls the size and accuracy of the sketch. Must be at least 10.
+    pub fn k(mut self, k: u16) -> Self {
+        self.k = k;
+        self
+    }
+
+    /// Set how [`f64::NAN`] values are handled; see [`TDigestMut::with_nan_policy`].
+    pub fn nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
+    }
+
+    /// Set the buffer size as a multiple of the centroids capacity; see
+    /// [`TDigestMut::with_buffer_multiplier`]. Must be in `[1, MAX_BUFFER_MULTIPLIER]`.
+    pub fn buffer_multiplier(mut self, buffer_multiplier: usize) -> Self {
+        self.buffer_multiplier = buffer_multiplier;
+        self
+    }
+
+    /// Build the TDigestMut.
+    ///
+    /// # Panics
+    ///
+    /// Panics if k is less than 10, or if the buffer multiplier is zero or greater than
+    /// [`MAX_BUFFER_MULTIPLIER`].
+    pub fn build(self) -> TDigestMut {
+        TDigestMut::new(self.k)
+            .with_nan_policy(self.nan_policy)
+            .with_buffer_multiplier(self.buffer_multiplier)
+    }
+}
+
+impl SketchBuilder for TDigestBuilder {
+    type Sketch = TDigestMut;
+
+    fn try_build(self) -> Result<TDigestMut, Error> {
+        let sketch = TDigestMut::try_new(self.k)?;
+        check_buffer_multiplier(self.buffer_multiplier)?;
+        Ok(sketch
+            .with_nan_policy(self.nan_policy)
+            .with_buffer_multiplier(self.buffer_multiplier))
+    }
+}
+
+impl MemoryUsage for TDigestMut {
+    fn memory_usage(&self) -> usize {
+        self.estimated_size()
+    }
+}
+
 /// Immutable (frozen) T-Digest sketch for estimating quantiles and ranks.
 ///
 /// See the [module level documentation](super) for more.
 pub struct TDigest {
     k: u16,
+    nan_policy: NanPolicy,
+    num_ignored: u64,
 
     reverse_merge: bool,
     min: f64,
@@ -827,6 +1484,11 @@ impl TDigest {
         self.centroids.is_empty()
     }
 
+    /// See [`TDigestMut::num_ignored`].
+    pub fn num_ignored(&self) -> u64 {
+        self.num_ignored
+    }
+
     /// Returns minimum value seen by TDigest; `None` if TDigest is empty.
     pub fn min_value(&self) -> Option<f64> {
         if self.is_empty() {
@@ -984,6 +1646,133 @@ impl TDigest {
         self.view().quantile(rank)
     }
 
+    /// Compute approximate value at the given percentile.
+    ///
+    /// This is a convenience wrapper around [`quantile`](Self::quantile) that takes a percentile
+    /// in `[0.0, 100.0]` rather than a normalized rank, so `percentile(99.0)` is the p99 value
+    /// and is equivalent to `quantile(0.99)`.
+    ///
+    /// Returns `None` if TDigest is empty.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `percentile` is not in [0.0, 100.0].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::tdigest::TDigestMut;
+    /// # let mut sketch = TDigestMut::new(100);
+    /// # for i in 1..=100 {
+    /// #     sketch.update(i as f64);
+    /// # }
+    /// let digest = sketch.freeze();
+    /// let p99 = digest.percentile(99.0).unwrap();
+    /// assert!((98.0..=100.0).contains(&p99));
+    /// ```
+    pub fn percentile(&self, percentile: f64) -> Option<f64> {
+        self.view().quantile(percentile_to_rank(percentile))
+    }
+
+    /// Compute approximate values at each of the given percentiles.
+    ///
+    /// See [`percentile`](Self::percentile). The returned values are in the same order as
+    /// `percentiles`.
+    ///
+    /// Returns `None` if TDigest is empty.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any percentile is not in [0.0, 100.0].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::tdigest::TDigestMut;
+    /// # let mut sketch = TDigestMut::new(100);
+    /// # for i in 1..=100 {
+    /// #     sketch.update(i as f64);
+    /// # }
+    /// let digest = sketch.freeze();
+    /// let values = digest.percentiles(&[50.0, 90.0, 99.0]).unwrap();
+    /// assert!(values[0] <= values[1] && values[1] <= values[2]);
+    /// ```
+    pub fn percentiles(&self, percentiles: &[f64]) -> Option<Vec<f64>> {
+        let ranks: Vec<f64> = percentiles
+            .iter()
+            .copied()
+            .map(percentile_to_rank)
+            .collect();
+
+        if self.is_empty() {
+            return None;
+        }
+
+        let view = self.view();
+        ranks.into_iter().map(|rank| view.quantile(rank)).collect()
+    }
+
+    /// Returns a histogram of `num_bins` equal-width bins spanning `[min_value, max_value]`.
+    ///
+    /// Bin counts are the PMF at evenly spaced split points scaled by the total weight. If the
+    /// sketch holds a single distinct value, or the range is too narrow to split, fewer bins are
+    /// returned (down to a single bin holding the total weight).
+    ///
+    /// Returns `None` if TDigest is empty.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num_bins` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::tdigest::TDigestMut;
+    /// # let mut sketch = TDigestMut::new(100);
+    /// # for i in 0..100 {
+    /// #     sketch.update(i as f64);
+    /// # }
+    /// let digest = sketch.freeze();
+    /// let histogram = digest.to_histogram(4).unwrap();
+    /// assert_eq!(histogram.num_bins(), 4);
+    /// assert!((histogram.total_count() - 100.0).abs() < 1e-9);
+    /// ```
+    pub fn to_histogram(&self, num_bins: usize) -> Option<Histogram> {
+        assert!(num_bins > 0, "num_bins must be positive");
+        self.view().histogram(num_bins)
+    }
+
+    /// Returns a histogram with bins delimited by the given split points.
+    ///
+    /// The result has `split_points.len() + 1` bins whose counts are the [`pmf`](Self::pmf)
+    /// scaled by the total weight. An empty `split_points` yields a single bin.
+    ///
+    /// Returns `None` if TDigest is empty.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `split_points` is not unique, not monotonically increasing, or contains `NaN`
+    /// values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::tdigest::TDigestMut;
+    /// # let mut sketch = TDigestMut::new(100);
+    /// # for i in 0..100 {
+    /// #     sketch.update(i as f64);
+    /// # }
+    /// let digest = sketch.freeze();
+    /// let histogram = digest.to_histogram_with_edges(&[25.0, 75.0]).unwrap();
+    /// assert_eq!(histogram.num_bins(), 3);
+    /// ```
+    pub fn to_histogram_with_edges(&self, split_points: &[f64]) -> Option<Histogram> {
+        if !split_points.is_empty() {
+            check_split_points(split_points);
+        }
+        self.view().histogram_with_edges(split_points)
+    }
+
     /// Converts this immutable TDigest into a mutable one.
     ///
     /// # Examples
@@ -998,7 +1787,7 @@ impl TDigest {
     /// assert_eq!(mutable.total_weight(), 2);
     /// ```
     pub fn unfreeze(self) -> TDigestMut {
-        TDigestMut::make(
+        let mut sketch = TDigestMut::make(
             self.k,
             self.reverse_merge,
             self.min,
@@ -1006,7 +1795,10 @@ impl TDigest {
             self.centroids,
             self.centroids_weight,
             vec![],
-        )
+        );
+        sketch.nan_policy = self.nan_policy;
+        sketch.num_ignored = self.num_ignored;
+        sketch
     }
 
     /// Returns the estimated size of the sketch in bytes
@@ -1015,6 +1807,12 @@ impl TDigest {
     }
 }
 
+impl MemoryUsage for TDigest {
+    fn memory_usage(&self) -> usize {
+        self.estimated_size()
+    }
+}
+
 struct TDigestView<'a> {
     min: f64,
     max: f64,
@@ -1023,6 +1821,34 @@ struct TDigestView<'a> {
 }
 
 impl TDigestView<'_> {
+    fn histogram(&self, num_bins: usize) -> Option<Histogram> {
+        let width = (self.max - self.min) / num_bins as f64;
+        let mut split_points: Vec<f64> = Vec::with_capacity(num_bins - 1);
+        for i in 1..num_bins {
+            let point = self.min + width * i as f64;
+            // Keep split points strictly increasing even if the range underflows.
+            if point > self.min && split_points.last().is_none_or(|&last| point > last) {
+                split_points.push(point);
+            }
+        }
+        self.histogram_with_edges(&split_points)
+    }
+
+    fn histogram_with_edges(&self, split_points: &[f64]) -> Option<Histogram> {
+        if self.centroids.is_empty() {
+            return None;
+        }
+
+        let total_weight = self.centroids_weight as f64;
+        let counts = if split_points.is_empty() {
+            vec![total_weight]
+        } else {
+            let pmf = self.pmf(split_points)?;
+            pmf.into_iter().map(|mass| mass * total_weight).collect()
+        };
+        Some(Histogram::new(split_points.to_vec(), counts))
+    }
+
     fn pmf(&self, split_points: &[f64]) -> Option<Vec<f64>> {
         let mut buckets = self.cdf(split_points)?;
         for i in (1..buckets.len()).rev() {
@@ -1224,6 +2050,16 @@ impl TDigestView<'_> {
     }
 }
 
+/// Converts a percentile in [0.0, 100.0] to a normalized rank in [0.0, 1.0].
+#[track_caller]
+fn percentile_to_rank(percentile: f64) -> f64 {
+    assert!(
+        (0.0..=100.0).contains(&percentile),
+        "percentile must be in [0.0, 100.0]"
+    );
+    percentile / 100.0
+}
+
 /// Checks the sequential validity of the given array of double values.
 /// They must be unique, monotonically increasing and not NaN.
 #[track_caller]
@@ -1302,6 +2138,15 @@ impl Centroid {
     }
 }
 
+fn check_buffer_multiplier(buffer_multiplier: usize) -> Result<(), Error> {
+    if !(1..=MAX_BUFFER_MULTIPLIER).contains(&buffer_multiplier) {
+        return Err(Error::invalid_argument(format!(
+            "buffer_multiplier must be in [1, {MAX_BUFFER_MULTIPLIER}], got {buffer_multiplier}"
+        )));
+    }
+    Ok(())
+}
+
 fn check_non_nan(value: f64, tag: &'static str) -> Result<(), Error> {
     if value.is_nan() {
         return Err(Error::deserial(format!(
@@ -0,0 +1,166 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Helpers for comparing sketch results in tests.
+//!
+//! Two sketches built from the same data through different paths (merged in another order,
+//! round-tripped through another implementation, built with another `HllType`) agree on their
+//! estimates only up to rounding. The functions here compare the estimate and the confidence
+//! bounds of any [`CardinalityEstimate`] with a relative tolerance, so that downstream test suites
+//! do not each need their own fuzzy comparison.
+//!
+//! This module is only available with the `testing` feature, so that test helpers stay out of the
+//! API of production builds. Enable it for tests only:
+//!
+//! ```toml
+//! [dev-dependencies]
+//! datasketches = { version = "*", features = ["testing"] }
+//! ```
+//!
+//! # Examples
+//!
+//! ```
+//! # #[cfg(feature = "hll")]
+//! # {
+//! # use datasketches::hll::HllSketch;
+//! # use datasketches::hll::HllType;
+//! use datasketches::testing::assert_sketch_estimates_close;
+//!
+//! let mut hll4 = HllSketch::new(12, HllType::Hll4);
+//! let mut hll8 = HllSketch::new(12, HllType::Hll8);
+//! for i in 0..10_000 {
+//!     hll4.update(i);
+//!     hll8.update(i);
+//! }
+//! assert_sketch_estimates_close(&hll4, &hll8, 1e-9);
+//! # }
+//! ```
+
+use std::fmt::Write;
+
+use crate::common::CardinalityEstimate;
+use crate::common::NumStdDev;
+
+/// Returns whether `a` and `b` differ by at most `epsilon` relative to the larger magnitude.
+///
+/// Equal values, including two zeros or two infinities of the same sign, are always close. NaN is
+/// never close to anything.
+///
+/// # Examples
+///
+/// ```
+/// # use datasketches::testing::approx_eq;
+/// assert!(approx_eq(1000.0, 1000.5, 1e-3));
+/// assert!(!approx_eq(1000.0, 1002.0, 1e-3));
+/// assert!(approx_eq(0.0, 0.0, 0.0));
+/// ```
+pub fn approx_eq(a: f64, b: f64, epsilon: f64) -> bool {
+    a == b || (a - b).abs() <= epsilon * a.abs().max(b.abs())
+}
+
+/// Returns whether the estimates and the lower and upper bounds at one, two and three standard
+/// deviations of `a` and `b` are all [`approx_eq`] within `epsilon`.
+pub fn sketch_estimates_close<A, B>(a: &A, b: &B, epsilon: f64) -> bool
+where
+    A: CardinalityEstimate + ?Sized,
+    B: CardinalityEstimate + ?Sized,
+{
+    estimate_differences(a, b, epsilon).is_empty()
+}
+
+/// Asserts that the estimates and bounds of `a` and `b` are [close](sketch_estimates_close)
+/// within `epsilon`.
+///
+/// # Panics
+///
+/// Panics with every differing value if they are not.
+#[track_caller]
+pub fn assert_sketch_estimates_close<A, B>(a: &A, b: &B, epsilon: f64)
+where
+    A: CardinalityEstimate + ?Sized,
+    B: CardinalityEstimate + ?Sized,
+{
+    let differences = estimate_differences(a, b, epsilon);
+    if !differences.is_empty() {
+        panic!("sketch estimates differ by more than {epsilon}:{differences}");
+    }
+}
+
+/// Asserts that the estimate of `sketch` is [`approx_eq`] to `expected` within `epsilon`.
+///
+/// # Panics
+///
+/// Panics with the estimate and its bounds at two standard deviations if it is not.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "theta")]
+/// # {
+/// # use datasketches::theta::ThetaSketchBuilder;
+/// use datasketches::testing::assert_estimate_close;
+///
+/// let mut sketch = ThetaSketchBuilder::default().build();
+/// for i in 0..100_000 {
+///     sketch.update(i);
+/// }
+/// assert_estimate_close(&sketch, 100_000.0, 0.05);
+/// # }
+/// ```
+#[track_caller]
+pub fn assert_estimate_close<S>(sketch: &S, expected: f64, epsilon: f64)
+where
+    S: CardinalityEstimate + ?Sized,
+{
+    let estimate = sketch.estimate();
+    if !approx_eq(estimate, expected, epsilon) {
+        panic!(
+            "estimate {estimate} is not within {epsilon} of {expected} (bounds at two standard deviations: [{}, {}])",
+            sketch.lower_bound(NumStdDev::Two),
+            sketch.upper_bound(NumStdDev::Two),
+        );
+    }
+}
+
+/// Lists the values of `a` and `b` that are not close, one per line.
+fn estimate_differences<A, B>(a: &A, b: &B, epsilon: f64) -> String
+where
+    A: CardinalityEstimate + ?Sized,
+    B: CardinalityEstimate + ?Sized,
+{
+    let mut differences = String::new();
+    let mut check = |name: &str, x: f64, y: f64| {
+        if !approx_eq(x, y, epsilon) {
+            write!(differences, "\n  {name}: {x} != {y}").unwrap();
+        }
+    };
+    check("estimate", a.estimate(), b.estimate());
+    for num_std_dev in [NumStdDev::One, NumStdDev::Two, NumStdDev::Three] {
+        let n = num_std_dev as u8;
+        check(
+            &format!("lower_bound({n})"),
+            a.lower_bound(num_std_dev),
+            b.lower_bound(num_std_dev),
+        );
+        check(
+            &format!("upper_bound({n})"),
+            a.upper_bound(num_std_dev),
+            b.upper_bound(num_std_dev),
+        );
+    }
+    differences
+}
@@ -0,0 +1,212 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+use std::cmp::Ordering;
+
+use crate::common::ResizeFactor;
+use crate::error::Error;
+use crate::hash::DEFAULT_UPDATE_SEED;
+use crate::hash::compute_seed_hash;
+use crate::hash::named_seed_hash;
+use crate::theta::CompactThetaSketch;
+use crate::theta::ThetaSketchView;
+use crate::theta::hash_table::ThetaHashTable;
+use crate::thetacommon::constants::HASH_TABLE_REBUILD_THRESHOLD;
+use crate::thetacommon::constants::MAX_THETA;
+
+/// Stateless set difference operator (A and not B) for Theta sketches.
+///
+/// The result retains the entries of sketch A that are absent from sketch B. Empty-state and
+/// theta selection follow the DataSketches Java and C++ `AnotB` rules:
+///
+/// * If A is empty, the result is empty regardless of B.
+/// * If B is empty, the result is A in compact form.
+/// * Otherwise the result theta is `min(theta_A, theta_B)`, and only entries of A below that theta
+///   that are not in B are retained. The result is empty only if it retains nothing and its theta
+///   is still `MAX_THETA` (i.e. both inputs were in exact mode).
+///
+/// # Examples
+///
+/// ```
+/// # use datasketches::theta::ThetaAnotB;
+/// # use datasketches::theta::ThetaSketchBuilder;
+/// let mut a = ThetaSketchBuilder::default().build();
+/// let mut b = ThetaSketchBuilder::default().build();
+/// for i in 0..10 {
+///     a.update(i);
+/// }
+/// for i in 5..15 {
+///     b.update(i);
+/// }
+///
+/// let result = ThetaAnotB::new_with_default_seed()
+///     .compute(&a, &b, true)
+///     .unwrap();
+/// assert_eq!(result.estimate(), 5.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ThetaAnotB {
+    seed: u64,
+    seed_hash: u16,
+}
+
+impl ThetaAnotB {
+    /// Creates a new set difference operator for the given `seed`.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            seed_hash: compute_seed_hash(seed),
+        }
+    }
+
+    /// Creates a new set difference operator with the default seed.
+    pub fn new_with_default_seed() -> Self {
+        Self::new(DEFAULT_UPDATE_SEED)
+    }
+
+    /// Computes `a` and not `b` as a compact sketch.
+    ///
+    /// If `ordered` is true, the retained entries of the result are sorted ascending.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a non-empty input was built with a different seed than this operator,
+    /// or if an input sketch looks corrupted.
+    pub fn compute<A, B>(&self, a: &A, b: &B, ordered: bool) -> Result<CompactThetaSketch, Error>
+    where
+        A: ThetaSketchView,
+        B: ThetaSketchView,
+    {
+        if a.is_empty() {
+            return Ok(CompactThetaSketch::from_parts(
+                vec![],
+                MAX_THETA,
+                self.seed_hash,
+                true,
+                true,
+            ));
+        }
+        self.check_seed_hash("A", a.seed_hash())?;
+
+        if b.is_empty() {
+            let entries = a.iter().map(|entry| entry.hash()).collect();
+            return Ok(self.make_result(entries, a.theta(), a.is_ordered(), ordered));
+        }
+        self.check_seed_hash("B", b.seed_hash())?;
+
+        let theta = a.theta().min(b.theta());
+
+        if b.num_retained() == 0 {
+            let entries = retained_below(a, theta);
+            return Ok(self.make_result(entries, theta, a.is_ordered(), ordered));
+        }
+
+        if a.is_ordered() && b.is_ordered() {
+            // Both inputs are sorted, so a linear merge yields a sorted difference.
+            let mut entries = Vec::with_capacity(a.num_retained());
+            let mut b_iter = b.iter().map(|entry| entry.hash()).peekable();
+            for hash in retained_below(a, theta) {
+                loop {
+                    match b_iter.peek().map(|b_hash| b_hash.cmp(&hash)) {
+                        Some(Ordering::Less) => {
+                            b_iter.next();
+                        }
+                        Some(Ordering::Equal) => break,
+                        Some(Ordering::Greater) | None => {
+                            entries.push(hash);
+                            break;
+                        }
+                    }
+                }
+            }
+            return Ok(self.make_result(entries, theta, true, ordered));
+        }
+
+        // Hash-based: load B (below theta) into a lookup table and probe it with A.
+        let lg_size = ThetaHashTable::lg_size_from_count_for_rebuild(
+            b.num_retained(),
+            HASH_TABLE_REBUILD_THRESHOLD,
+        );
+        let mut table = ThetaHashTable::from_raw_parts(
+            lg_size,
+            lg_size - 1,
+            ResizeFactor::X1,
+            1.0,
+            MAX_THETA,
+            self.seed,
+            false,
+        );
+        for hash in b.iter().map(|entry| entry.hash()) {
+            if hash < theta {
+                if !table.try_insert_hash(hash) {
+                    return Err(Error::invalid_argument(
+                        "duplicate key, possibly corrupted input sketch",
+                    ));
+                }
+            } else if b.is_ordered() {
+                break; // early stop for ordered sketches
+            }
+        }
+
+        let entries = retained_below(a, theta)
+            .into_iter()
+            .filter(|hash| !table.contains_hash(*hash))
+            .collect();
+        Ok(self.make_result(entries, theta, a.is_ordered(), ordered))
+    }
+
+    fn check_seed_hash(&self, name: &str, seed_hash: u16) -> Result<(), Error> {
+        if seed_hash != self.seed_hash {
+            return Err(Error::invalid_argument(format!(
+                "incompatible seed hash of sketch {name}: expected {}, got {}",
+                named_seed_hash(self.seed_hash),
+                named_seed_hash(seed_hash)
+            )));
+        }
+        Ok(())
+    }
+
+    fn make_result(
+        &self,
+        mut entries: Vec<u64>,
+        theta: u64,
+        is_ordered: bool,
+        ordered: bool,
+    ) -> CompactThetaSketch {
+        let empty = entries.is_empty() && theta == MAX_THETA;
+        let is_single = entries.len() == 1 && theta == MAX_THETA;
+        if ordered && !is_ordered {
+            entries.sort_unstable();
+        }
+        CompactThetaSketch::from_parts(
+            entries,
+            theta,
+            self.seed_hash,
+            is_ordered || ordered || empty || is_single,
+            empty,
+        )
+    }
+}
+
+/// Collects the hashes of `sketch` that are below `theta`, stopping early for ordered sketches.
+fn retained_below<S: ThetaSketchView>(sketch: &S, theta: u64) -> Vec<u64> {
+    let hashes = sketch.iter().map(|entry| entry.hash());
+    if sketch.is_ordered() {
+        hashes.take_while(|hash| *hash < theta).collect()
+    } else {
+        hashes.filter(|hash| *hash < theta).collect()
+    }
+}
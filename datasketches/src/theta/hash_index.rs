@@ -0,0 +1,117 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use crate::common::MemoryUsage;
+
+/// Prefilter bits per retained hash.
+const PREFILTER_BITS_PER_HASH: usize = 8;
+
+/// Index for repeated membership queries against the retained hashes of a
+/// [`CompactThetaSketch`](super::CompactThetaSketch).
+///
+/// Created by [`CompactThetaSketch::hash_index`](super::CompactThetaSketch::hash_index). The
+/// hashes are kept sorted for binary search, behind a prefilter bitset with one bit set per
+/// retained hash, like a Bloom filter with a single hash function. Retained hashes are uniformly
+/// distributed, so their low bits address the bitset directly. With 8 bits per retained hash,
+/// about 88% of queries for hashes that are not retained are answered without searching.
+///
+/// # Examples
+///
+/// ```
+/// # use datasketches::theta::ThetaSketchBuilder;
+/// let mut sketch = ThetaSketchBuilder::default().build();
+/// for i in 0..1_000 {
+///     sketch.update(i);
+/// }
+/// let compact = sketch.compact(false);
+/// let index = compact.hash_index();
+///
+/// assert_eq!(index.len(), 1_000);
+/// assert!(
+///     compact
+///         .iter()
+///         .all(|entry| index.contains_hash(entry.hash()))
+/// );
+/// assert!(!index.contains_hash(1));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ThetaHashIndex {
+    hashes: Vec<u64>,
+    theta: u64,
+    prefilter: Vec<u64>,
+    mask: u64,
+}
+
+impl ThetaHashIndex {
+    /// Builds the index from retained hashes sorted in ascending order.
+    pub(super) fn new(hashes: Vec<u64>, theta: u64) -> Self {
+        debug_assert!(hashes.is_sorted(), "hashes must be sorted");
+        let num_bits = (hashes.len() * PREFILTER_BITS_PER_HASH)
+            .next_power_of_two()
+            .max(64);
+        let mask = num_bits as u64 - 1;
+        let mut prefilter = vec![0u64; num_bits / 64];
+        for &hash in &hashes {
+            let bit = hash & mask;
+            prefilter[(bit >> 6) as usize] |= 1 << (bit & 63);
+        }
+        Self {
+            hashes,
+            theta,
+            prefilter,
+            mask,
+        }
+    }
+
+    /// Returns true if `hash` is one of the retained hashes.
+    ///
+    /// `hash` follows the convention of
+    /// [`ThetaSketch::update_hash`](super::ThetaSketch::update_hash).
+    pub fn contains_hash(&self, hash: u64) -> bool {
+        if hash == 0 || hash >= self.theta {
+            return false;
+        }
+        let bit = hash & self.mask;
+        if self.prefilter[(bit >> 6) as usize] & (1 << (bit & 63)) == 0 {
+            return false;
+        }
+        self.hashes.binary_search(&hash).is_ok()
+    }
+
+    /// Returns the number of indexed hashes.
+    pub fn len(&self) -> usize {
+        self.hashes.len()
+    }
+
+    /// Returns true if no hashes are indexed.
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+
+    /// Returns the estimated size of the index in bytes.
+    pub fn estimated_size(&self) -> usize {
+        size_of::<Self>()
+            + self.hashes.capacity() * size_of::<u64>()
+            + self.prefilter.capacity() * size_of::<u64>()
+    }
+}
+
+impl MemoryUsage for ThetaHashIndex {
+    fn memory_usage(&self) -> usize {
+        self.estimated_size()
+    }
+}
@@ -15,9 +15,11 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use crate::common::MemoryUsage;
 use crate::common::ResizeFactor;
 use crate::error::Error;
 use crate::hash::DEFAULT_UPDATE_SEED;
+use crate::hash::named_seed_hash;
 use crate::theta::CompactThetaSketch;
 use crate::theta::ThetaSketchView;
 use crate::theta::hash_table::ThetaHashTable;
@@ -81,8 +83,8 @@ impl ThetaIntersection {
         if !sketch.is_empty() && sketch.seed_hash() != self.table.seed_hash() {
             return Err(Error::invalid_argument(format!(
                 "incompatible seed hash: expected {}, got {}",
-                self.table.seed_hash(),
-                sketch.seed_hash()
+                named_seed_hash(self.table.seed_hash()),
+                named_seed_hash(sketch.seed_hash())
             )));
         }
 
@@ -224,4 +226,15 @@ impl ThetaIntersection {
             self.table.is_empty(),
         )
     }
+
+    /// Returns the estimated size of the intersection in bytes.
+    pub fn estimated_size(&self) -> usize {
+        size_of::<Self>() + self.table.estimated_size()
+    }
+}
+
+impl MemoryUsage for ThetaIntersection {
+    fn memory_usage(&self) -> usize {
+        self.estimated_size()
+    }
 }
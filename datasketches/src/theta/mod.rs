@@ -29,6 +29,8 @@
 //!
 //! * **ThetaSketch**: Mutable sketch for building from input data
 //! * **CompactThetaSketch**: Immutable sketch with compact memory layout
+//! * **ThetaUnion**, **ThetaIntersection** and **ThetaAnotB**: Set operations over sketches
+//! * **ThetaSetOperationBuilder**: Shared configuration for building set operations
 //!
 //! # Usage
 //!
@@ -39,15 +41,27 @@
 //! assert!(sketch.estimate() >= 1.0);
 //! ```
 
+mod a_not_b;
 mod bit_pack;
+mod hash_index;
 mod hash_table;
 mod intersection;
+mod planning;
 mod serialization;
+mod set_operation;
 mod sketch;
 mod union;
 
+pub use self::a_not_b::ThetaAnotB;
+pub use self::hash_index::ThetaHashIndex;
 pub use self::hash_table::ThetaEntry;
 pub use self::intersection::ThetaIntersection;
+pub use self::planning::error_for_lg_k;
+pub use self::planning::lg_k_for_error;
+pub use self::set_operation::ThetaSetOperationBuilder;
+pub use self::set_operation::estimate_union_size;
+pub use self::set_operation::union_many;
+pub use self::set_operation::union_ordered;
 pub use self::sketch::CompactThetaSketch;
 pub use self::sketch::ThetaSketch;
 pub use self::sketch::ThetaSketchBuilder;
@@ -0,0 +1,77 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Capacity planning helpers for Theta sketches.
+//!
+//! These functions compute the same figures as the accuracy tables of the DataSketches
+//! documentation, so that tools can pick a configuration programmatically.
+
+use crate::thetacommon::constants::MAX_LG_K;
+use crate::thetacommon::constants::MIN_LG_K;
+
+/// Returns the relative standard error of the estimate of a Theta sketch configured with `lg_k`.
+///
+/// This is the one standard deviation error of a sketch in estimation mode, `1 / sqrt(k)` with
+/// `k = 2^lg_k` nominal entries; it also applies to the result of a union with the same `lg_k`.
+/// At two or three standard deviations, the error is two or three times larger. A sketch that
+/// has retained every distinct item it has seen is exact.
+///
+/// # Panics
+///
+/// Panics if `lg_k` is not in `[5, 26]`.
+///
+/// # Examples
+///
+/// ```
+/// # use datasketches::theta::error_for_lg_k;
+/// assert_eq!(error_for_lg_k(12), 1.0 / 64.0);
+/// ```
+pub fn error_for_lg_k(lg_k: u8) -> f64 {
+    assert!(
+        (MIN_LG_K..=MAX_LG_K).contains(&lg_k),
+        "lg_k must be in [{MIN_LG_K}, {MAX_LG_K}], got {lg_k}"
+    );
+    1.0 / ((1_u64 << lg_k) as f64).sqrt()
+}
+
+/// Returns the smallest `lg_k` whose [`error_for_lg_k`] is at most `relative_error`.
+///
+/// Returns `None` if even the largest supported `lg_k` cannot reach the requested error.
+///
+/// # Panics
+///
+/// Panics if `relative_error` is not positive.
+///
+/// # Examples
+///
+/// ```
+/// # use datasketches::theta::error_for_lg_k;
+/// # use datasketches::theta::lg_k_for_error;
+/// let lg_k = lg_k_for_error(0.01).unwrap();
+/// assert_eq!(lg_k, 14);
+/// assert!(error_for_lg_k(lg_k) <= 0.01);
+/// assert!(error_for_lg_k(lg_k - 1) > 0.01);
+///
+/// assert_eq!(lg_k_for_error(0.0001), None);
+/// ```
+pub fn lg_k_for_error(relative_error: f64) -> Option<u8> {
+    assert!(
+        relative_error > 0.0,
+        "relative_error must be positive, got {relative_error}"
+    );
+    (MIN_LG_K..=MAX_LG_K).find(|&lg_k| error_for_lg_k(lg_k) <= relative_error)
+}
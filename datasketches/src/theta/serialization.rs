@@ -17,8 +17,13 @@
 
 //! Binary serialization format constants for Theta sketches.
 
-pub(super) const UNCOMPRESSED_SERIAL_VERSION: u8 = 3;
-pub(super) const COMPRESSED_SERIAL_VERSION: u8 = 4;
+use crate::codec::format;
+
+pub(super) const UNCOMPRESSED_SERIAL_VERSION: u8 = format::theta::SERIAL_VERSION;
+pub(super) const COMPRESSED_SERIAL_VERSION: u8 = format::theta::COMPRESSED_SERIAL_VERSION;
+
+/// Preamble longs of the updatable (QuickSelect) format.
+pub(super) const UPDATABLE_PREAMBLE_LONGS: u8 = format::theta::UPDATABLE_PREAMBLE_LONGS;
 
 pub(super) const V2_PREAMBLE_EMPTY: u8 = 1;
 pub(super) const V2_PREAMBLE_PRECISE: u8 = 2;
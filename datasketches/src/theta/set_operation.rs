@@ -0,0 +1,350 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use crate::common::ResizeFactor;
+use crate::error::Error;
+use crate::hash::DEFAULT_UPDATE_SEED;
+use crate::theta::CompactThetaSketch;
+use crate::theta::ThetaAnotB;
+use crate::theta::ThetaIntersection;
+use crate::theta::ThetaSketchView;
+use crate::theta::ThetaUnion;
+use crate::theta::ThetaUnionBuilder;
+
+/// Builder for Theta set operations.
+///
+/// Mirrors the Java `SetOperationBuilder`: the nominal size, resize factor, sampling probability
+/// and seed are configured once, independently of the input sketches, and then used to build a
+/// [`ThetaUnion`], [`ThetaIntersection`] or [`ThetaAnotB`]. Unions downsample their inputs to the
+/// configured lg_k, so inputs built with a larger lg_k yield a result of the configured size.
+///
+/// # Examples
+///
+/// ```
+/// # use datasketches::theta::ThetaSetOperationBuilder;
+/// # use datasketches::theta::ThetaSketchBuilder;
+/// let mut a = ThetaSketchBuilder::default().build();
+/// let mut b = ThetaSketchBuilder::default().build();
+/// for i in 0..10 {
+///     a.update(i);
+/// }
+/// for i in 5..15 {
+///     b.update(i);
+/// }
+///
+/// let builder = ThetaSetOperationBuilder::default().lg_k(10);
+///
+/// let mut union = builder.clone().build_union();
+/// union.update(&a).unwrap();
+/// union.update(&b).unwrap();
+/// assert_eq!(union.to_sketch(true).estimate(), 15.0);
+///
+/// let mut intersection = builder.clone().build_intersection();
+/// intersection.update(&a).unwrap();
+/// intersection.update(&b).unwrap();
+/// assert_eq!(intersection.to_sketch(true).estimate(), 5.0);
+///
+/// let a_not_b = builder.build_a_not_b();
+/// assert_eq!(a_not_b.compute(&a, &b, true).unwrap().estimate(), 5.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ThetaSetOperationBuilder {
+    union: ThetaUnionBuilder,
+    seed: u64,
+}
+
+impl Default for ThetaSetOperationBuilder {
+    fn default() -> Self {
+        Self {
+            union: ThetaUnionBuilder::default(),
+            seed: DEFAULT_UPDATE_SEED,
+        }
+    }
+}
+
+impl ThetaSetOperationBuilder {
+    /// Set lg_k (log2 of nominal size k) used by unions.
+    ///
+    /// # Panics
+    ///
+    /// If lg_k is not in range [5, 26]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::theta::ThetaSetOperationBuilder;
+    /// ThetaSetOperationBuilder::default().lg_k(12).build_union();
+    /// ```
+    pub fn lg_k(mut self, lg_k: u8) -> Self {
+        self.union = self.union.lg_k(lg_k);
+        self
+    }
+
+    /// Set resize factor used by unions.
+    pub fn resize_factor(mut self, resize_factor: ResizeFactor) -> Self {
+        self.union = self.union.resize_factor(resize_factor);
+        self
+    }
+
+    /// Set sampling probability p used by unions.
+    ///
+    /// # Panics
+    ///
+    /// Panics if p is not in range `(0.0, 1.0]`
+    pub fn sampling_probability(mut self, p: f32) -> Self {
+        self.union = self.union.sampling_probability(p);
+        self
+    }
+
+    /// Set hash seed shared by every set operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::theta::ThetaSetOperationBuilder;
+    /// ThetaSetOperationBuilder::default()
+    ///     .seed(7)
+    ///     .build_intersection();
+    /// ```
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.union = self.union.seed(seed);
+        self.seed = seed;
+        self
+    }
+
+    /// Build a [`ThetaUnion`].
+    pub fn build_union(self) -> ThetaUnion {
+        self.union.build()
+    }
+
+    /// Build a [`ThetaIntersection`].
+    pub fn build_intersection(self) -> ThetaIntersection {
+        ThetaIntersection::new(self.seed)
+    }
+
+    /// Build a [`ThetaAnotB`].
+    pub fn build_a_not_b(self) -> ThetaAnotB {
+        ThetaAnotB::new(self.seed)
+    }
+
+    /// Union all `sketches` into a compact sketch.
+    ///
+    /// If `ordered` is true, the retained entries of the result are sorted ascending.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a non-empty input was built with a different seed than this builder,
+    /// or if an input sketch looks corrupted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::theta::ThetaSetOperationBuilder;
+    /// # use datasketches::theta::ThetaSketchBuilder;
+    /// let sketches: Vec<_> = (0..4)
+    ///     .map(|i| {
+    ///         let mut sketch = ThetaSketchBuilder::default().build();
+    ///         sketch.update(i);
+    ///         sketch
+    ///     })
+    ///     .collect();
+    ///
+    /// let result = ThetaSetOperationBuilder::default()
+    ///     .union_many(&sketches, true)
+    ///     .unwrap();
+    /// assert_eq!(result.estimate(), 4.0);
+    /// ```
+    pub fn union_many<'a, S, I>(
+        self,
+        sketches: I,
+        ordered: bool,
+    ) -> Result<CompactThetaSketch, Error>
+    where
+        S: ThetaSketchView + 'a,
+        I: IntoIterator<Item = &'a S>,
+    {
+        let mut union = self.build_union();
+        for sketch in sketches {
+            union.update(sketch)?;
+        }
+        Ok(union.to_sketch(ordered))
+    }
+
+    /// Unions two compact sketches into an ordered compact sketch without building a
+    /// [`ThetaUnion`].
+    ///
+    /// The retained entries of both inputs are merged in hash order, so no hash table is
+    /// allocated and the result needs no final sort. Ordered inputs are merged as they are;
+    /// unordered inputs are sorted first. The result has the retained entries and theta of
+    /// [`union_many`](Self::union_many) with `ordered` set.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a non-empty input was built with a different seed than this builder.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::theta::ThetaSetOperationBuilder;
+    /// # use datasketches::theta::ThetaSketchBuilder;
+    /// let mut a = ThetaSketchBuilder::default().build();
+    /// let mut b = ThetaSketchBuilder::default().build();
+    /// for i in 0..10 {
+    ///     a.update(i);
+    ///     b.update(i + 5);
+    /// }
+    ///
+    /// let result = ThetaSetOperationBuilder::default()
+    ///     .union_ordered(&a.compact(true), &b.compact(true))
+    ///     .unwrap();
+    /// assert!(result.is_ordered());
+    /// assert_eq!(result.estimate(), 15.0);
+    /// ```
+    pub fn union_ordered(
+        &self,
+        a: &CompactThetaSketch,
+        b: &CompactThetaSketch,
+    ) -> Result<CompactThetaSketch, Error> {
+        self.union.union_ordered(a, b)
+    }
+
+    /// Estimates the size of the union of `sketches` without building a [`ThetaUnion`].
+    ///
+    /// The retained entries of the inputs are streamed in hash order and only the smallest k + 1
+    /// distinct hashes are counted, so no hash table is allocated. The estimate has the accuracy
+    /// of a union with the configured lg_k; it equals the [`union_many`](Self::union_many)
+    /// estimate when the union is exact, and may differ slightly in estimation mode where the
+    /// union result depends on the order in which its table was rebuilt.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a non-empty input was built with a different seed than this builder.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::theta::ThetaSetOperationBuilder;
+    /// # use datasketches::theta::ThetaSketchBuilder;
+    /// let sketches: Vec<_> = (0..4)
+    ///     .map(|i| {
+    ///         let mut sketch = ThetaSketchBuilder::default().build();
+    ///         for j in 0..10 {
+    ///             sketch.update(i * 5 + j);
+    ///         }
+    ///         sketch.compact(true)
+    ///     })
+    ///     .collect();
+    ///
+    /// let estimate = ThetaSetOperationBuilder::default()
+    ///     .estimate_union_size(&sketches)
+    ///     .unwrap();
+    /// assert_eq!(estimate, 25.0);
+    /// ```
+    pub fn estimate_union_size<'a, I>(&self, sketches: I) -> Result<f64, Error>
+    where
+        I: IntoIterator<Item = &'a CompactThetaSketch>,
+    {
+        self.union.estimate_union_size(sketches)
+    }
+}
+
+/// Union all `sketches` into a compact sketch using the default set operation configuration.
+///
+/// This is a shorthand for [`ThetaSetOperationBuilder::union_many`] on a default builder.
+///
+/// # Errors
+///
+/// Returns an error if a non-empty input was not built with the default seed, or if an input
+/// sketch looks corrupted.
+///
+/// # Examples
+///
+/// ```
+/// # use datasketches::theta::ThetaSketchBuilder;
+/// let mut a = ThetaSketchBuilder::default().build();
+/// let mut b = ThetaSketchBuilder::default().build();
+/// a.update("apple");
+/// b.update("banana");
+///
+/// let result = datasketches::theta::union_many([&a, &b], true).unwrap();
+/// assert_eq!(result.estimate(), 2.0);
+/// ```
+pub fn union_many<'a, S, I>(sketches: I, ordered: bool) -> Result<CompactThetaSketch, Error>
+where
+    S: ThetaSketchView + 'a,
+    I: IntoIterator<Item = &'a S>,
+{
+    ThetaSetOperationBuilder::default().union_many(sketches, ordered)
+}
+
+/// Unions two compact sketches into an ordered compact sketch using the default set operation
+/// configuration.
+///
+/// This is a shorthand for [`ThetaSetOperationBuilder::union_ordered`] on a default builder.
+///
+/// # Errors
+///
+/// Returns an error if a non-empty input was not built with the default seed.
+///
+/// # Examples
+///
+/// ```
+/// # use datasketches::theta::ThetaSketchBuilder;
+/// let mut a = ThetaSketchBuilder::default().build();
+/// let mut b = ThetaSketchBuilder::default().build();
+/// a.update("apple");
+/// b.update("banana");
+///
+/// let (a, b) = (a.compact(true), b.compact(true));
+/// let result = datasketches::theta::union_ordered(&a, &b).unwrap();
+/// assert_eq!(result.estimate(), 2.0);
+/// ```
+pub fn union_ordered(
+    a: &CompactThetaSketch,
+    b: &CompactThetaSketch,
+) -> Result<CompactThetaSketch, Error> {
+    ThetaSetOperationBuilder::default().union_ordered(a, b)
+}
+
+/// Estimates the size of the union of `sketches` using the default set operation configuration.
+///
+/// This is a shorthand for [`ThetaSetOperationBuilder::estimate_union_size`] on a default builder.
+///
+/// # Errors
+///
+/// Returns an error if a non-empty input was not built with the default seed.
+///
+/// # Examples
+///
+/// ```
+/// # use datasketches::theta::ThetaSketchBuilder;
+/// let mut a = ThetaSketchBuilder::default().build();
+/// let mut b = ThetaSketchBuilder::default().build();
+/// a.update("apple");
+/// b.update("apple");
+/// b.update("banana");
+///
+/// let (a, b) = (a.compact(true), b.compact(true));
+/// let estimate = datasketches::theta::estimate_union_size([&a, &b]).unwrap();
+/// assert_eq!(estimate, 2.0);
+/// ```
+pub fn estimate_union_size<'a, I>(sketches: I) -> Result<f64, Error>
+where
+    I: IntoIterator<Item = &'a CompactThetaSketch>,
+{
+    ThetaSetOperationBuilder::default().estimate_union_size(sketches)
+}
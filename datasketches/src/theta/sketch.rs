@@ -20,6 +20,7 @@
 //! This module provides ThetaSketch (mutable) and CompactThetaSketch (immutable)
 //! for cardinality estimation.
 
+use std::borrow::Cow;
 use std::hash::Hash;
 
 use crate::codec::SketchBytes;
@@ -27,11 +28,17 @@ use crate::codec::SketchSlice;
 use crate::codec::assert::ensure_preamble_longs_in_range;
 use crate::codec::assert::insufficient_data;
 use crate::codec::family::Family;
+use crate::common::MemoryUsage;
 use crate::common::NumStdDev;
 use crate::common::ResizeFactor;
+use crate::common::SketchBuilder;
 use crate::error::Error;
 use crate::hash::DEFAULT_UPDATE_SEED;
 use crate::hash::compute_seed_hash;
+use crate::hash::named_seed_hash;
+use crate::hash::seed_from_str;
+use crate::hash_value::ValueRef;
+use crate::theta::ThetaHashIndex;
 use crate::theta::bit_pack::BLOCK_WIDTH;
 use crate::theta::bit_pack::BitPacker;
 use crate::theta::bit_pack::BitUnpacker;
@@ -43,6 +50,8 @@ use crate::theta::serialization;
 use crate::theta::serialization::V2_PREAMBLE_EMPTY;
 use crate::theta::serialization::V2_PREAMBLE_ESTIMATE;
 use crate::theta::serialization::V2_PREAMBLE_PRECISE;
+#[cfg(feature = "stats")]
+use crate::thetacommon::HashTableStats;
 use crate::thetacommon::RawThetaSketchView;
 use crate::thetacommon::binomial_bounds;
 use crate::thetacommon::constants::DEFAULT_LG_K;
@@ -51,6 +60,7 @@ use crate::thetacommon::constants::FLAGS_IS_EMPTY;
 use crate::thetacommon::constants::FLAGS_IS_ORDERED;
 use crate::thetacommon::constants::FLAGS_IS_READ_ONLY;
 use crate::thetacommon::constants::MAX_LG_K;
+use crate::thetacommon::constants::MAX_RETAINED_ENTRIES;
 use crate::thetacommon::constants::MAX_THETA;
 use crate::thetacommon::constants::MIN_LG_K;
 
@@ -89,16 +99,45 @@ impl RawThetaSketchView<ThetaEntry> for ThetaSketch {
 }
 
 /// Mutable theta sketch for building from input data
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ThetaSketch {
     table: ThetaHashTable,
 }
 
 impl ThetaSketch {
+    /// Create an updatable sketch with default configuration that continues from `sketch`.
+    ///
+    /// This is shorthand for
+    /// `ThetaSketchBuilder::default().build_from_compact(sketch)`; see
+    /// [`ThetaSketchBuilder::build_from_compact`] for details and for using a custom lg_k or seed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `sketch` is non-empty and was not built with the default seed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::theta::ThetaSketch;
+    /// # use datasketches::theta::ThetaSketchBuilder;
+    /// let mut sketch = ThetaSketchBuilder::default().build();
+    /// sketch.update("apple");
+    /// let compact = sketch.compact(true);
+    ///
+    /// let mut resumed = ThetaSketch::from_compact(&compact).unwrap();
+    /// resumed.update("banana");
+    /// assert_eq!(resumed.estimate(), 2.0);
+    /// ```
+    pub fn from_compact(sketch: &CompactThetaSketch) -> Result<Self, Error> {
+        ThetaSketchBuilder::default().build_from_compact(sketch)
+    }
+
     /// Update the sketch with a hashable value.
     ///
     /// You may use [`hash_value`](crate::hash_value) wrappers when matching other datasketches
-    /// implementations require a specific value hashing strategy.
+    /// implementations require a specific value hashing strategy;
+    /// [`canonical::from_value`](crate::hash_value::canonical::from_value) covers strings, bytes,
+    /// `u64`, `i64` and `f64`.
     ///
     /// # Examples
     ///
@@ -118,6 +157,170 @@ impl ThetaSketch {
         self.table.try_insert(value);
     }
 
+    /// Update the sketch with a value whose type is only known at runtime.
+    ///
+    /// This is [`update`](Self::update) with a [`ValueRef`], which hashes like the value it holds
+    /// wrapped in [`Canonical`](crate::hash_value::canonical::Canonical). Unlike the generic
+    /// method, it is compiled once for every value type. Empty strings and byte strings are
+    /// ignored, as in [`update_str`](Self::update_str) and [`update_bytes`](Self::update_bytes)
+    /// and the other implementations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::theta::ThetaSketchBuilder;
+    /// # use datasketches::hash_value::ValueRef;
+    /// # use datasketches::hash_value::canonical::from_value;
+    /// let mut sketch = ThetaSketchBuilder::default().build();
+    /// for value in [
+    ///     ValueRef::Str("apple"),
+    ///     ValueRef::I64(42),
+    ///     ValueRef::F64(1.5),
+    /// ] {
+    ///     sketch.update_value_ref(value);
+    /// }
+    ///
+    /// let mut typed = ThetaSketchBuilder::default().build();
+    /// typed.update(from_value("apple"));
+    /// typed.update(from_value(42_i64));
+    /// typed.update(from_value(1.5));
+    /// assert_eq!(sketch.estimate(), typed.estimate());
+    /// ```
+    pub fn update_value_ref(&mut self, value: ValueRef<'_>) {
+        if !value.is_empty() {
+            self.update(value);
+        }
+    }
+
+    /// Update the sketch with a string, hashed as its UTF-8 bytes.
+    ///
+    /// This is how Java and C++ hash strings, so identical strings produce identical sketches
+    /// across languages; `update(&str)` instead hashes the string as Rust's [`Hash`] does, with a
+    /// terminator byte. Empty strings are ignored and leave an empty sketch empty, as in the other
+    /// implementations.
+    ///
+    /// It is equivalent to updating with
+    /// [`raw_bytes::from_str`](crate::hash_value::raw_bytes::from_str) after checking that the
+    /// string is non-empty, but hashes the bytes in one shot instead of through a
+    /// [`Hasher`](std::hash::Hasher).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::hash_value::raw_bytes;
+    /// # use datasketches::theta::ThetaSketchBuilder;
+    /// let mut sketch = ThetaSketchBuilder::default().build();
+    /// sketch.update_str("");
+    /// assert!(sketch.is_empty());
+    /// sketch.update_str("apple");
+    ///
+    /// let mut expected = ThetaSketchBuilder::default().build();
+    /// expected.update(raw_bytes::from_str("apple"));
+    /// assert_eq!(sketch.serialize(), expected.serialize());
+    /// ```
+    pub fn update_str(&mut self, value: &str) {
+        self.update_bytes(value.as_bytes());
+    }
+
+    /// Update the sketch with a byte string, hashed as-is.
+    ///
+    /// This is how Java and C++ hash `byte[]` values; see [`update_str`](Self::update_str).
+    /// Empty byte strings are ignored.
+    pub fn update_bytes(&mut self, value: &[u8]) {
+        if !value.is_empty() {
+            let hash = self.table.hash_bytes(value);
+            self.table.try_insert_hash(hash);
+        }
+    }
+
+    /// Update the sketch with a precomputed hash value.
+    ///
+    /// This skips hashing, for systems that already compute MurmurHash3 values and share them
+    /// across sketches. To match [`update`](Self::update) and the Java and C++ implementations,
+    /// `hash` must be the first 64-bit half of MurmurHash3_x64_128 of the value, computed with the
+    /// seed of this sketch and shifted right by one bit (`h1 >> 1`). The hashes yielded by
+    /// [`iter`](Self::iter) already follow this convention.
+    ///
+    /// Hashes of zero, and hashes not below the current theta (including any value with the top
+    /// bit set), are screened out but still mark the sketch as non-empty, just like a screened
+    /// [`update`](Self::update).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::theta::ThetaSketchBuilder;
+    /// let mut source = ThetaSketchBuilder::default().build();
+    /// source.update("apple");
+    /// source.update("banana");
+    ///
+    /// let mut sketch = ThetaSketchBuilder::default().build();
+    /// for entry in source.iter() {
+    ///     sketch.update_hash(entry.hash());
+    /// }
+    /// assert_eq!(sketch.estimate(), 2.0);
+    /// ```
+    pub fn update_hash(&mut self, hash: u64) {
+        self.table.try_insert_hash(hash);
+    }
+
+    /// Returns true if `hash` is one of the retained hashes.
+    ///
+    /// `hash` follows the convention of [`update_hash`](Self::update_hash).
+    pub fn contains_hash(&self, hash: u64) -> bool {
+        self.table.contains_hash(hash)
+    }
+
+    /// Merges another Theta sketch, updatable or compact, into this sketch.
+    ///
+    /// This is a lightweight alternative to a [`ThetaUnion`](crate::theta::ThetaUnion) when the
+    /// result only needs to keep being updated: theta becomes the smaller of both thetas, the
+    /// retained entries of this sketch that are no longer below it are dropped, and the entries of
+    /// `other` below it are inserted. The result keeps the lg_k of this sketch and is rebuilt as
+    /// usual when it grows beyond it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `other` is non-empty and was built with a different seed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::theta::ThetaSketchBuilder;
+    /// let mut sketch = ThetaSketchBuilder::default().build();
+    /// let mut other = ThetaSketchBuilder::default().build();
+    /// sketch.update("apple");
+    /// other.update("apple");
+    /// other.update("banana");
+    ///
+    /// sketch.merge(&other.compact(true)).unwrap();
+    /// sketch.update("cherry");
+    /// assert_eq!(sketch.estimate(), 3.0);
+    /// ```
+    pub fn merge<S: ThetaSketchView>(&mut self, other: &S) -> Result<(), Error> {
+        if other.is_empty() {
+            return Ok(());
+        }
+        if other.seed_hash() != self.seed_hash() {
+            return Err(Error::invalid_argument(format!(
+                "incompatible seed hash: expected {}, got {}",
+                named_seed_hash(self.seed_hash()),
+                named_seed_hash(other.seed_hash())
+            )));
+        }
+
+        self.table.set_empty(false);
+        self.table.lower_theta(other.theta());
+        for entry in other.iter() {
+            let hash = entry.hash();
+            if hash < self.table.theta() {
+                self.table.try_insert_hash(hash);
+            } else if other.is_ordered() {
+                break;
+            }
+        }
+        Ok(())
+    }
+
     /// Return cardinality estimate
     ///
     /// # Examples
@@ -182,6 +385,32 @@ impl ThetaSketch {
         self.table.reset();
     }
 
+    /// Returns statistics of the internal hash table, such as its load factor, how often it was
+    /// resized or rebuilt, and the average probe length of updates.
+    ///
+    /// Statistics cover the lifetime of the sketch since it was built or last
+    /// [reset](Self::reset).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::theta::ThetaSketchBuilder;
+    /// let mut sketch = ThetaSketchBuilder::default().lg_k(10).build();
+    /// for i in 0..10_000 {
+    ///     sketch.update(i);
+    /// }
+    ///
+    /// let stats = sketch.stats();
+    /// assert!(stats.num_resizes() > 0);
+    /// assert!(stats.num_rebuilds() > 0);
+    /// assert!(stats.average_probe_length() >= 1.0);
+    /// assert!(stats.load_factor() > 0.0 && stats.load_factor() < 1.0);
+    /// ```
+    #[cfg(feature = "stats")]
+    pub fn stats(&self) -> HashTableStats {
+        self.table.stats()
+    }
+
     /// Return iterator over retained entries.
     ///
     /// # Examples
@@ -197,6 +426,32 @@ impl ThetaSketch {
         self.table.iter_entries().copied()
     }
 
+    /// Return iterator over retained entries in ascending hash order.
+    ///
+    /// Only the retained hashes are copied and sorted, which suits stream-merging the hash lists
+    /// of two sketches without building a [`CompactThetaSketch`] for each.
+    ///
+    /// Hashes are one-way: they cannot be mapped back to the original keys. To recover keys for
+    /// the matches, build a `TupleSketch` that carries the key as its summary instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::theta::ThetaSketchBuilder;
+    /// let mut sketch = ThetaSketchBuilder::default().build();
+    /// for i in 0..100 {
+    ///     sketch.update(i);
+    /// }
+    /// let hashes: Vec<u64> = sketch.iter_ordered().map(|entry| entry.hash()).collect();
+    /// assert!(hashes.is_sorted());
+    /// assert_eq!(hashes.len(), 100);
+    /// ```
+    pub fn iter_ordered(&self) -> impl Iterator<Item = ThetaEntry> + '_ {
+        let mut hashes: Vec<u64> = self.table.iter().collect();
+        hashes.sort_unstable();
+        hashes.into_iter().map(ThetaEntry::new)
+    }
+
     /// Return this sketch in compact (immutable) form.
     ///
     /// If `ordered` is true, retained hash values are sorted in ascending order.
@@ -225,6 +480,205 @@ impl ThetaSketch {
         )
     }
 
+    /// Serializes this sketch in the updatable format.
+    ///
+    /// Unlike [`compact`](Self::compact) followed by [`CompactThetaSketch::serialize`], this keeps
+    /// the whole hash table together with lg_k, the resize factor and the sampling probability, so
+    /// that [`deserialize`](Self::deserialize) restores a sketch that keeps accepting updates as if
+    /// it had never been serialized. The layout is the Java `UpdateSketch` (QuickSelect) format.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::theta::ThetaSketch;
+    /// # use datasketches::theta::ThetaSketchBuilder;
+    /// let mut sketch = ThetaSketchBuilder::default().lg_k(10).build();
+    /// sketch.update("apple");
+    ///
+    /// let mut resumed = ThetaSketch::deserialize(&sketch.serialize()).unwrap();
+    /// assert_eq!(resumed.lg_k(), 10);
+    /// resumed.update("banana");
+    /// assert_eq!(resumed.estimate(), 2.0);
+    /// ```
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut bytes = SketchBytes::with_capacity(self.serialized_size_bytes());
+        self.write_to(&mut bytes);
+        bytes.into_bytes()
+    }
+
+    /// Serializes this sketch in the updatable format into `buf`, returning the number of bytes
+    /// written.
+    ///
+    /// Writes the same bytes as [`serialize`](Self::serialize) without allocating the output.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `buf` is too short to hold the serialized sketch. The sketch is
+    /// written up to the end of `buf` before the length is checked, so the contents of `buf`
+    /// are unspecified when an error is returned.
+    pub fn serialize_into_slice(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let mut bytes = SketchBytes::from_slice(buf);
+        self.write_to(&mut bytes);
+        bytes.finish()
+    }
+
+    /// Returns the exact length of [`serialize`](Self::serialize).
+    ///
+    /// The updatable format stores every slot of the hash table, so this depends on the current
+    /// table size rather than on the number of retained entries.
+    pub fn serialized_size_bytes(&self) -> usize {
+        (serialization::UPDATABLE_PREAMBLE_LONGS as usize + (1 << self.table.lg_cur_size())) * 8
+    }
+
+    fn write_to(&self, bytes: &mut SketchBytes<'_>) {
+        let table = &self.table;
+        bytes.write_u8(
+            serialization::UPDATABLE_PREAMBLE_LONGS | (table.resize_factor().lg_value() << 6),
+        );
+        bytes.write_u8(serialization::UNCOMPRESSED_SERIAL_VERSION);
+        bytes.write_u8(Family::QUICKSELECT.id);
+        bytes.write_u8(table.lg_nom_size());
+        bytes.write_u8(table.lg_cur_size());
+        bytes.write_u8(if table.is_empty() { FLAGS_IS_EMPTY } else { 0 });
+        bytes.write_u16_le(table.seed_hash());
+        bytes.write_u32_le(table.num_retained() as u32);
+        bytes.write_f32_le(table.sampling_probability());
+        bytes.write_u64_le(table.theta());
+        for slot in table.iter_slots() {
+            bytes.write_u64_le(slot.map_or(0, ThetaEntry::hash));
+        }
+    }
+
+    /// Deserializes an updatable sketch written by [`serialize`](Self::serialize), expecting the
+    /// default seed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bytes are not a valid updatable theta sketch image, or if the
+    /// sketch was not built with the default seed.
+    pub fn deserialize(bytes: &[u8]) -> Result<Self, Error> {
+        Self::deserialize_with_seed(bytes, DEFAULT_UPDATE_SEED)
+    }
+
+    /// Deserializes an updatable sketch written by [`serialize`](Self::serialize) that was built
+    /// with `seed`.
+    ///
+    /// Unlike compact sketches, the seed itself is required (not just its hash) because the
+    /// restored sketch hashes further updates with it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bytes are not a valid updatable theta sketch image, or if the
+    /// stored seed hash does not match `seed`.
+    pub fn deserialize_with_seed(bytes: &[u8], seed: u64) -> Result<Self, Error> {
+        let mut cursor = SketchSlice::new(bytes);
+        let first_byte = cursor
+            .read_u8()
+            .map_err(insufficient_data("preamble_longs"))?;
+        let ser_ver = cursor
+            .read_u8()
+            .map_err(insufficient_data("serial_version"))?;
+        let family_id = cursor.read_u8().map_err(insufficient_data("family_id"))?;
+        let lg_nom_size = cursor.read_u8().map_err(insufficient_data("lg_nom_size"))?;
+        let lg_cur_size = cursor.read_u8().map_err(insufficient_data("lg_cur_size"))?;
+        let flags = cursor.read_u8().map_err(insufficient_data("flags"))?;
+        let seed_hash = cursor
+            .read_u16_le()
+            .map_err(insufficient_data("seed_hash"))?;
+        let num_retained = cursor
+            .read_u32_le()
+            .map_err(insufficient_data("num_retained"))? as usize;
+        let sampling_probability = cursor
+            .read_f32_le()
+            .map_err(insufficient_data("sampling_probability"))?;
+        let theta = cursor
+            .read_u64_le()
+            .map_err(insufficient_data("theta_long"))?;
+
+        Family::QUICKSELECT.validate_id(family_id)?;
+        ensure_preamble_longs_in_range(
+            Family::QUICKSELECT.min_pre_longs..=Family::QUICKSELECT.max_pre_longs,
+            first_byte & 0x3F,
+        )?;
+        Family::QUICKSELECT.validate_serial_version(ser_ver)?;
+        let expected_seed_hash = compute_seed_hash(seed);
+        if seed_hash != expected_seed_hash {
+            return Err(Error::deserial(format!(
+                "incompatible seed hash: expected {}, got {}",
+                named_seed_hash(expected_seed_hash),
+                named_seed_hash(seed_hash)
+            )));
+        }
+        if !(MIN_LG_K..=MAX_LG_K).contains(&lg_nom_size) {
+            return Err(Error::deserial(format!(
+                "lg_k must be in [{MIN_LG_K}, {MAX_LG_K}], got {lg_nom_size}"
+            )));
+        }
+        if !(MIN_LG_K..=lg_nom_size + 1).contains(&lg_cur_size) {
+            return Err(Error::deserial(format!(
+                "lg_arr must be in [{MIN_LG_K}, {}], got {lg_cur_size}",
+                lg_nom_size + 1
+            )));
+        }
+        if !(sampling_probability > 0.0 && sampling_probability <= 1.0) {
+            return Err(Error::deserial(format!(
+                "sampling_probability must be in (0.0, 1.0], got {sampling_probability}"
+            )));
+        }
+        if !(1..=MAX_THETA).contains(&theta) {
+            return Err(Error::deserial(format!(
+                "theta must be in [1, {MAX_THETA}], got {theta}"
+            )));
+        }
+
+        // Check the slots are present before allocating a table for them.
+        let num_slots = 1usize << lg_cur_size;
+        if cursor.remaining().len() < num_slots * 8 {
+            return Err(Error::insufficient_data(format!(
+                "entries: expected {num_slots} slots, got {} bytes",
+                cursor.remaining().len()
+            )));
+        }
+
+        let empty = (flags & FLAGS_IS_EMPTY) != 0;
+        let mut table = ThetaHashTable::from_raw_parts(
+            lg_cur_size,
+            lg_nom_size,
+            ResizeFactor::from_lg_value(first_byte >> 6),
+            sampling_probability,
+            theta,
+            seed,
+            empty,
+        );
+        if num_retained > table.get_capacity() || (empty && num_retained > 0) {
+            return Err(Error::deserial(format!(
+                "corrupted: {num_retained} retained entries do not fit a table of lg_arr {lg_cur_size}"
+            )));
+        }
+        for _ in 0..num_slots {
+            let hash = cursor.read_u64_le().map_err(insufficient_data("entries"))?;
+            if hash == 0 {
+                continue;
+            }
+            // Stop before the table would resize or rebuild past the stored count.
+            if table.num_retained() == num_retained {
+                return Err(Error::deserial(format!(
+                    "corrupted: more than {num_retained} retained entries in the table"
+                )));
+            }
+            if hash >= theta || !table.try_insert_hash(hash) {
+                return Err(Error::deserial("corrupted: invalid retained hash value"));
+            }
+        }
+        if table.num_retained() != num_retained {
+            return Err(Error::deserial(format!(
+                "corrupted: expected {num_retained} retained entries, found {}",
+                table.num_retained()
+            )));
+        }
+        Ok(Self { table })
+    }
+
     /// Returns the approximate lower error bound given the specified number of Standard Deviations.
     ///
     /// # Arguments
@@ -304,6 +758,12 @@ impl ThetaSketch {
     }
 }
 
+impl MemoryUsage for ThetaSketch {
+    fn memory_usage(&self) -> usize {
+        self.estimated_size()
+    }
+}
+
 /// Compact (immutable) theta sketch.
 ///
 /// This is the serialized-friendly form of a theta sketch: a compact array of retained hash values
@@ -325,6 +785,9 @@ impl CompactThetaSketch {
         ordered: bool,
         empty: bool,
     ) -> Self {
+        // A single exact entry is trivially ordered, and is serialized as the minimal 16-byte
+        // single item image other implementations write.
+        let ordered = ordered || (entries.len() == 1 && theta == MAX_THETA);
         Self {
             entries,
             theta,
@@ -387,6 +850,213 @@ impl CompactThetaSketch {
         self.entries.iter().copied().map(ThetaEntry::new)
     }
 
+    /// Returns true if `hash` is one of the retained hashes.
+    ///
+    /// `hash` follows the convention of [`ThetaSketch::update_hash`]. Ordered sketches are
+    /// binary searched and unordered ones are scanned; build a [`ThetaHashIndex`] with
+    /// [`hash_index`](Self::hash_index) to query a sketch many times.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::theta::ThetaSketchBuilder;
+    /// let mut sketch = ThetaSketchBuilder::default().build();
+    /// sketch.update("apple");
+    /// let hash = sketch.iter().next().unwrap().hash();
+    ///
+    /// let compact = sketch.compact(true);
+    /// assert!(compact.contains_hash(hash));
+    /// assert!(!compact.contains_hash(hash + 1));
+    /// ```
+    pub fn contains_hash(&self, hash: u64) -> bool {
+        if hash == 0 || hash >= self.theta {
+            return false;
+        }
+        if self.ordered {
+            self.entries.binary_search(&hash).is_ok()
+        } else {
+            self.entries.contains(&hash)
+        }
+    }
+
+    /// Builds a [`ThetaHashIndex`] over the retained hashes, for repeated membership queries
+    /// with a prefilter in front of a binary search.
+    pub fn hash_index(&self) -> ThetaHashIndex {
+        ThetaHashIndex::new(self.ordered_hashes().into_owned(), self.theta)
+    }
+
+    /// Returns a sketch retaining only the hashes for which `predicate` returns true.
+    ///
+    /// Theta, the seed hash and the ordering are preserved, so the result estimates the number
+    /// of distinct items of the input stream whose hashes satisfy the predicate, as the filter
+    /// utilities of the C++ library do. As there, the result is empty only if the input was not
+    /// in estimation mode and no hash was retained.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::theta::ThetaSketchBuilder;
+    /// let mut sketch = ThetaSketchBuilder::default().build();
+    /// for i in 0..100 {
+    ///     sketch.update(i);
+    /// }
+    /// let compact = sketch.compact(true);
+    ///
+    /// let low = compact.filter(|hash| hash < u64::MAX / 4);
+    /// assert_eq!(low.theta64(), compact.theta64());
+    /// assert!(low.is_ordered());
+    /// assert!(low.num_retained() < compact.num_retained());
+    /// assert!(low.iter().all(|entry| compact.contains_hash(entry.hash())));
+    ///
+    /// assert!(compact.filter(|_| false).is_empty());
+    /// ```
+    pub fn filter<F: FnMut(u64) -> bool>(&self, mut predicate: F) -> CompactThetaSketch {
+        let entries: Vec<u64> = self
+            .entries
+            .iter()
+            .copied()
+            .filter(|&hash| predicate(hash))
+            .collect();
+        let empty = !self.is_estimation_mode() && entries.is_empty();
+        CompactThetaSketch::from_parts(entries, self.theta, self.seed_hash, self.ordered, empty)
+    }
+
+    /// Return iterator over retained entries in ascending hash order.
+    ///
+    /// Ordered sketches are iterated in place; otherwise the hashes are copied and sorted. See
+    /// [`ThetaSketch::iter_ordered`].
+    pub fn iter_ordered(&self) -> impl Iterator<Item = ThetaEntry> + '_ {
+        let hashes = self.ordered_hashes();
+        (0..hashes.len()).map(move |i| ThetaEntry::new(hashes[i]))
+    }
+
+    pub(super) fn ordered_hashes(&self) -> Cow<'_, [u64]> {
+        if self.ordered {
+            Cow::Borrowed(self.entries.as_slice())
+        } else {
+            let mut hashes = self.entries.clone();
+            hashes.sort_unstable();
+            Cow::Owned(hashes)
+        }
+    }
+
+    /// Returns whether this sketch and `other` have no retained hash in common.
+    ///
+    /// Both sketches are compared below the smaller of their thetas, as a [`ThetaIntersection`]
+    /// would, but no result sketch is built and the scan stops at the first common hash. If both
+    /// sketches are exact, `true` means that the input sets are disjoint; in estimation mode it
+    /// means that the estimated intersection is empty.
+    ///
+    /// The scan is a single merge pass when both sketches are ordered; unordered entries are
+    /// sorted first.
+    ///
+    /// [`ThetaIntersection`]: crate::theta::ThetaIntersection
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if both sketches are non-empty and their seed hashes differ.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::theta::ThetaSketchBuilder;
+    /// let mut a = ThetaSketchBuilder::default().build();
+    /// let mut b = ThetaSketchBuilder::default().build();
+    /// a.update("apple");
+    /// b.update("banana");
+    /// assert!(a.compact(true).is_disjoint(&b.compact(true)).unwrap());
+    ///
+    /// b.update("apple");
+    /// assert!(!a.compact(true).is_disjoint(&b.compact(true)).unwrap());
+    /// ```
+    pub fn is_disjoint(&self, other: &CompactThetaSketch) -> Result<bool, Error> {
+        let (num_common, _) = self.count_common(other, true)?;
+        Ok(num_common == 0)
+    }
+
+    /// Returns the estimated number of distinct items that this sketch and `other` have in
+    /// common.
+    ///
+    /// This is the estimate of the [`ThetaIntersection`] of both sketches, computed in a single
+    /// pass over their entries without building the intersection.
+    ///
+    /// [`ThetaIntersection`]: crate::theta::ThetaIntersection
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if both sketches are non-empty and their seed hashes differ.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::theta::ThetaSketchBuilder;
+    /// let mut a = ThetaSketchBuilder::default().build();
+    /// let mut b = ThetaSketchBuilder::default().build();
+    /// for i in 0..100 {
+    ///     a.update(i);
+    ///     b.update(i + 60);
+    /// }
+    /// let overlap = a.compact(true).overlap_estimate(&b.compact(false)).unwrap();
+    /// assert_eq!(overlap, 40.0);
+    /// ```
+    pub fn overlap_estimate(&self, other: &CompactThetaSketch) -> Result<f64, Error> {
+        let (num_common, theta) = self.count_common(other, false)?;
+        if num_common == 0 {
+            return Ok(0.0);
+        }
+        Ok(num_common as f64 / (theta as f64 / MAX_THETA as f64))
+    }
+
+    /// Counts the hashes below the smaller theta that both sketches retain, and returns the count
+    /// along with that theta. Stops at the first common hash if `stop_at_first` is set.
+    fn count_common(
+        &self,
+        other: &CompactThetaSketch,
+        stop_at_first: bool,
+    ) -> Result<(usize, u64), Error> {
+        if self.is_empty() || other.is_empty() {
+            return Ok((0, MAX_THETA));
+        }
+        if self.seed_hash != other.seed_hash {
+            return Err(Error::invalid_argument(format!(
+                "incompatible seed hash: expected {}, got {}",
+                named_seed_hash(self.seed_hash),
+                named_seed_hash(other.seed_hash)
+            )));
+        }
+
+        let theta = self.theta.min(other.theta);
+        let a = self.ordered_hashes();
+        let b = other.ordered_hashes();
+        let (Some(&a_min), Some(&a_max), Some(&b_min), Some(&b_max)) =
+            (a.first(), a.last(), b.first(), b.last())
+        else {
+            return Ok((0, theta));
+        };
+        // Hash ranges that do not overlap below theta cannot share a hash.
+        if a_max < b_min || b_max < a_min || a_min >= theta || b_min >= theta {
+            return Ok((0, theta));
+        }
+
+        let (mut i, mut j) = (0, 0);
+        let mut num_common = 0;
+        while i < a.len() && j < b.len() && a[i] < theta && b[j] < theta {
+            match a[i].cmp(&b[j]) {
+                std::cmp::Ordering::Less => i += 1,
+                std::cmp::Ordering::Greater => j += 1,
+                std::cmp::Ordering::Equal => {
+                    num_common += 1;
+                    if stop_at_first {
+                        break;
+                    }
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+        Ok((num_common, theta))
+    }
+
     /// Returns the approximate lower error bound given the specified number of Standard Deviations.
     pub fn lower_bound(&self, num_std_dev: NumStdDev) -> f64 {
         if !self.is_estimation_mode() {
@@ -427,10 +1097,33 @@ impl CompactThetaSketch {
     /// This uses `serVer = 4` when the sketch is ordered and suitable for compression, and falls
     /// back to uncompressed `serVer = 3` otherwise.
     pub fn serialize_compressed(&self) -> Vec<u8> {
+        let mut bytes = SketchBytes::with_capacity(0);
+        self.write_compressed_to(&mut bytes);
+        bytes.into_bytes()
+    }
+
+    /// Serializes this sketch in compressed form if applicable into `buf`, returning the number
+    /// of bytes written.
+    ///
+    /// Writes the same bytes as [`serialize_compressed`](Self::serialize_compressed) without
+    /// allocating the output.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `buf` is too short to hold the serialized sketch. The sketch is
+    /// written up to the end of `buf` before the length is checked, so the contents of `buf`
+    /// are unspecified when an error is returned.
+    pub fn serialize_compressed_into_slice(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let mut bytes = SketchBytes::from_slice(buf);
+        self.write_compressed_to(&mut bytes);
+        bytes.finish()
+    }
+
+    fn write_compressed_to(&self, bytes: &mut SketchBytes<'_>) {
         if self.is_suitable_for_compression() {
-            self.serialize_v4()
+            self.write_v4_to(bytes);
         } else {
-            self.serialize()
+            self.write_to(bytes);
         }
     }
 
@@ -441,8 +1134,65 @@ impl CompactThetaSketch {
     }
 
     /// Serializes this sketch into the uncompressed compact theta format.
+    ///
+    /// The preamble only holds what the sketch needs: an empty sketch or a single exact entry
+    /// takes one 8-byte preamble long, so a single item serializes to 16 bytes, as in the Java
+    /// and C++ libraries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::theta::ThetaSketchBuilder;
+    /// let mut sketch = ThetaSketchBuilder::default().build();
+    /// sketch.update("apple");
+    /// assert_eq!(sketch.compact(false).serialize().len(), 16);
+    /// ```
     pub fn serialize(&self) -> Vec<u8> {
-        let mut bytes = SketchBytes::with_capacity(64 + self.entries.len() * 8);
+        let mut bytes = SketchBytes::with_capacity(self.serialized_size_bytes());
+        self.write_to(&mut bytes);
+        bytes.into_bytes()
+    }
+
+    /// Returns the exact number of bytes [`serialize`](Self::serialize) will produce.
+    pub fn serialized_size_bytes(&self) -> usize {
+        (self.preamble_longs(false) as usize + self.entries.len()) * 8
+    }
+
+    /// Serializes this sketch into `buf` in the uncompressed compact theta format, returning the
+    /// number of bytes written.
+    ///
+    /// Writes the same bytes as [`serialize`](Self::serialize) without allocating the output.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `buf` is too short to hold the serialized sketch. The sketch is
+    /// written up to the end of `buf` before the length is checked, so the contents of `buf`
+    /// are unspecified when an error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::theta::CompactThetaSketch;
+    /// # use datasketches::theta::ThetaSketchBuilder;
+    /// let mut sketch = ThetaSketchBuilder::default().build();
+    /// sketch.update("apple");
+    /// let compact = sketch.compact(true);
+    ///
+    /// let mut buf = [0u8; 64];
+    /// let len = compact.serialize_into_slice(&mut buf).unwrap();
+    /// let decoded = CompactThetaSketch::deserialize(&buf[..len]).unwrap();
+    /// assert_eq!(decoded.estimate(), 1.0);
+    ///
+    /// assert!(compact.serialize_into_slice(&mut buf[..4]).is_err());
+    /// ```
+    pub fn serialize_into_slice(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let mut bytes = SketchBytes::from_slice(buf);
+        self.write_to(&mut bytes);
+        bytes.finish()
+    }
+
+    pub(super) fn write_to(&self, bytes: &mut SketchBytes<'_>) {
+        bytes.reserve(self.serialized_size_bytes());
 
         let pre_longs = self.preamble_longs(false);
         bytes.write_u8(pre_longs);
@@ -473,10 +1223,9 @@ impl CompactThetaSketch {
         for hash in self.entries.iter() {
             bytes.write_u64_le(*hash);
         }
-        bytes.into_bytes()
     }
 
-    fn serialize_v4(&self) -> Vec<u8> {
+    fn write_v4_to(&self, bytes: &mut SketchBytes<'_>) {
         let pre_longs = self.preamble_longs(true);
         let entry_bits = Self::compute_entry_bits(&self.entries);
         let num_entries_bytes = Self::num_entries_bytes(self.entries.len());
@@ -485,7 +1234,7 @@ impl CompactThetaSketch {
         let compressed_bits = entry_bits as usize * self.entries.len();
         let compressed_bytes = compressed_bits.div_ceil(8);
         let out_bytes = (pre_longs as usize * 8) + (num_entries_bytes as usize) + compressed_bytes;
-        let mut bytes = SketchBytes::with_capacity(out_bytes);
+        bytes.reserve(out_bytes);
 
         bytes.write_u8(pre_longs);
         bytes.write_u8(serialization::COMPRESSED_SERIAL_VERSION);
@@ -540,8 +1289,6 @@ impl CompactThetaSketch {
             let bytes_used = packer.byte_used();
             bytes.write(&block[0..bytes_used]);
         }
-
-        bytes.into_bytes()
     }
 
     fn compute_entry_bits(entries: &[u64]) -> u8 {
@@ -568,6 +1315,44 @@ impl CompactThetaSketch {
 
     /// Deserializes a compact theta sketch from bytes using the provided expected seed.
     pub fn deserialize_with_seed(bytes: &[u8], seed: u64) -> Result<Self, Error> {
+        Self::deserialize_impl(bytes, Some(compute_seed_hash(seed)))
+    }
+
+    /// Deserializes a compact theta sketch from bytes without validating its seed hash.
+    ///
+    /// # Warning
+    ///
+    /// The seed hash guards against combining sketches whose hashes were computed with different
+    /// seeds. Skipping the check is only sound when the result is used on its own, e.g. to read
+    /// the estimate and bounds of historical data whose seed has been lost. Never feed a sketch
+    /// obtained this way into a union, intersection or set difference together with sketches
+    /// built from a different seed: the hashes are incomparable and the result is silently wrong.
+    ///
+    /// The seed hash stored in the bytes is preserved and available through
+    /// [`seed_hash`](Self::seed_hash). Serial version 1 images carry no seed hash; they are
+    /// assigned the hash of the default seed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::theta::CompactThetaSketch;
+    /// # use datasketches::theta::ThetaSketchBuilder;
+    /// let mut sketch = ThetaSketchBuilder::default().seed(7).build();
+    /// sketch.update("apple");
+    /// let bytes = sketch.compact(true).serialize();
+    ///
+    /// assert!(CompactThetaSketch::deserialize(&bytes).is_err());
+    /// let decoded = CompactThetaSketch::deserialize_unchecked_seed(&bytes).unwrap();
+    /// assert_eq!(decoded.estimate(), 1.0);
+    /// assert_eq!(decoded.seed_hash(), sketch.seed_hash());
+    /// ```
+    pub fn deserialize_unchecked_seed(bytes: &[u8]) -> Result<Self, Error> {
+        Self::deserialize_impl(bytes, None)
+    }
+
+    /// Deserializes a compact theta sketch, validating the stored seed hash against
+    /// `expected_seed_hash` unless it is `None`.
+    fn deserialize_impl(bytes: &[u8], expected_seed_hash: Option<u16>) -> Result<Self, Error> {
         let mut cursor = SketchSlice::new(bytes);
         let pre_longs = cursor
             .read_u8()
@@ -585,14 +1370,17 @@ impl CompactThetaSketch {
             pre_longs,
         )?;
 
+        Family::THETA.validate_serial_version(ser_ver)?;
         match ser_ver {
-            1 => Self::deserialize_v1(cursor, seed),
-            2 => Self::deserialize_v2(pre_longs, cursor, seed),
-            3 => Self::deserialize_v3(pre_longs, cursor, seed),
-            4 => Self::deserialize_v4(pre_longs, cursor, seed),
-            _ => Err(Error::deserial(format!(
-                "unsupported serial version: expected 1, 2, 3, or 4, got {ser_ver}",
-            ))),
+            1 => Self::deserialize_v1(cursor, expected_seed_hash),
+            2 => Self::deserialize_v2(pre_longs, cursor, expected_seed_hash),
+            3 => Self::deserialize_v3(pre_longs, cursor, expected_seed_hash),
+            4 => Self::deserialize_v4(pre_longs, cursor, expected_seed_hash),
+            _ => Err(Error::unsupported_version(
+                Family::THETA.name,
+                ser_ver,
+                Family::THETA.serial_versions,
+            )),
         }
     }
 
@@ -601,6 +1389,7 @@ impl CompactThetaSketch {
         num_entries: usize,
         theta: u64,
     ) -> Result<Vec<u64>, Error> {
+        Self::check_num_entries(num_entries, cursor.remaining().len(), 64)?;
         let mut entries = Vec::with_capacity(num_entries);
         for _ in 0..num_entries {
             let hash = cursor.read_u64_le().map_err(insufficient_data("entries"))?;
@@ -612,8 +1401,47 @@ impl CompactThetaSketch {
         Ok(entries)
     }
 
-    fn deserialize_v1(mut cursor: SketchSlice<'_>, expected_seed: u64) -> Result<Self, Error> {
-        let seed_hash = compute_seed_hash(expected_seed);
+    /// Checks a stored entry count against the largest sketch that can exist and against the
+    /// bytes left in the image, so that a corrupted count never drives a large allocation.
+    fn check_num_entries(
+        num_entries: usize,
+        remaining: usize,
+        entry_bits: u8,
+    ) -> Result<(), Error> {
+        if num_entries > MAX_RETAINED_ENTRIES {
+            return Err(Error::deserial(format!(
+                "corrupted: {num_entries} retained entries exceed the maximum of {MAX_RETAINED_ENTRIES}"
+            )));
+        }
+        let bytes_needed = (num_entries * entry_bits as usize).div_ceil(8);
+        if remaining < bytes_needed {
+            return Err(Error::insufficient_data(format!(
+                "entries: expected {num_entries} entries of {entry_bits} bits, got {remaining} bytes"
+            )));
+        }
+        Ok(())
+    }
+
+    fn check_seed_hash(seed_hash: u16, expected_seed_hash: Option<u16>) -> Result<(), Error> {
+        match expected_seed_hash {
+            Some(expected_seed_hash) if seed_hash != expected_seed_hash => {
+                Err(Error::deserial(format!(
+                    "incompatible seed hash: expected {}, got {}",
+                    named_seed_hash(expected_seed_hash),
+                    named_seed_hash(seed_hash)
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn deserialize_v1(
+        mut cursor: SketchSlice<'_>,
+        expected_seed_hash: Option<u16>,
+    ) -> Result<Self, Error> {
+        // Serial version 1 does not store a seed hash.
+        let seed_hash =
+            expected_seed_hash.unwrap_or_else(|| compute_seed_hash(DEFAULT_UPDATE_SEED));
         cursor.read_u8().map_err(insufficient_data("<unused>"))?;
         cursor
             .read_u32_le()
@@ -653,7 +1481,7 @@ impl CompactThetaSketch {
     fn deserialize_v2(
         pre_longs: u8,
         mut cursor: SketchSlice<'_>,
-        expected_seed: u64,
+        expected_seed_hash: Option<u16>,
     ) -> Result<Self, Error> {
         cursor.read_u8().map_err(insufficient_data("<unused>"))?;
         cursor
@@ -662,12 +1490,7 @@ impl CompactThetaSketch {
         let seed_hash = cursor
             .read_u16_le()
             .map_err(insufficient_data("seed_hash"))?;
-        let expected_seed_hash = compute_seed_hash(expected_seed);
-        if seed_hash != expected_seed_hash {
-            return Err(Error::deserial(format!(
-                "incompatible seed hash: expected {expected_seed_hash}, got {seed_hash}",
-            )));
-        }
+        Self::check_seed_hash(seed_hash, expected_seed_hash)?;
 
         match pre_longs {
             V2_PREAMBLE_EMPTY => Ok(Self {
@@ -722,7 +1545,7 @@ impl CompactThetaSketch {
     fn deserialize_v3(
         pre_longs: u8,
         mut cursor: SketchSlice<'_>,
-        expected_seed: u64,
+        expected_seed_hash: Option<u16>,
     ) -> Result<Self, Error> {
         cursor
             .read_u16_le()
@@ -737,12 +1560,7 @@ impl CompactThetaSketch {
         let num_entries;
         let mut entries = vec![];
         if !empty {
-            let expected_seed_hash = compute_seed_hash(expected_seed);
-            if seed_hash != expected_seed_hash {
-                return Err(Error::deserial(format!(
-                    "incompatible seed hash: expected {expected_seed_hash}, got {seed_hash}",
-                )));
-            }
+            Self::check_seed_hash(seed_hash, expected_seed_hash)?;
             if pre_longs == 1 {
                 num_entries = 1;
             } else {
@@ -773,7 +1591,7 @@ impl CompactThetaSketch {
     fn deserialize_v4(
         pre_longs: u8,
         mut cursor: SketchSlice<'_>,
-        expected_seed: u64,
+        expected_seed_hash: Option<u16>,
     ) -> Result<Self, Error> {
         let entry_bits = cursor.read_u8().map_err(insufficient_data("entry_bits"))?;
         let num_entries_bytes = cursor.read_u8().map_err(insufficient_data("num_entries"))?;
@@ -783,12 +1601,7 @@ impl CompactThetaSketch {
             .map_err(insufficient_data("seed_hash"))?;
         let empty = (flags & FLAGS_IS_EMPTY) != 0;
         if !empty {
-            let expected_seed_hash = compute_seed_hash(expected_seed);
-            if seed_hash != expected_seed_hash {
-                return Err(Error::deserial(format!(
-                    "incompatible seed hash: expected {expected_seed_hash}, got {seed_hash}",
-                )));
-            }
+            Self::check_seed_hash(seed_hash, expected_seed_hash)?;
         }
         let theta = if pre_longs > 1 {
             cursor
@@ -798,6 +1611,18 @@ impl CompactThetaSketch {
             MAX_THETA
         };
 
+        // Deltas are below theta, so they never need all 64 bits.
+        if !(1..=63).contains(&entry_bits) {
+            return Err(Error::deserial(format!(
+                "entry_bits must be in [1, 63], got {entry_bits}"
+            )));
+        }
+        if num_entries_bytes > 4 {
+            return Err(Error::deserial(format!(
+                "num_entries_bytes must be in [0, 4], got {num_entries_bytes}"
+            )));
+        }
+
         // unpack num_entries
         let mut num_entries = 0usize;
         for i in 0..num_entries_bytes {
@@ -807,6 +1632,8 @@ impl CompactThetaSketch {
             num_entries |= (entry_count_byte as usize) << ((i as usize) << 3);
         }
 
+        Self::check_num_entries(num_entries, cursor.remaining().len(), entry_bits)?;
+
         // unpack blocks of BLOCK_WIDTH deltas
         let mut i = 0usize;
         let mut entries = vec![0u64; num_entries];
@@ -838,11 +1665,12 @@ impl CompactThetaSketch {
         // undo deltas
         let mut previous = 0;
         for e in &mut entries {
-            *e += previous;
-            previous = *e;
-            if *e == 0 || *e >= theta {
+            // A corrupted delta can wrap around; it is rejected like any other invalid hash.
+            *e = e.wrapping_add(previous);
+            if *e <= previous || *e >= theta {
                 return Err(Error::deserial("corrupted: invalid retained hash value"));
             }
+            previous = *e;
         }
 
         let ordered = (flags & FLAGS_IS_ORDERED) != 0;
@@ -862,6 +1690,12 @@ impl CompactThetaSketch {
     }
 }
 
+impl MemoryUsage for CompactThetaSketch {
+    fn memory_usage(&self) -> usize {
+        self.estimated_size()
+    }
+}
+
 impl RawThetaSketchView<ThetaEntry> for CompactThetaSketch {
     fn seed_hash(&self) -> u16 {
         CompactThetaSketch::seed_hash(self)
@@ -979,6 +1813,30 @@ impl ThetaSketchBuilder {
         self
     }
 
+    /// Set the hash seed derived from a phrase with [`seed_from_str`].
+    ///
+    /// Sketches built with the same phrase can be merged; compact sketches built with it are
+    /// deserialized with `deserialize_with_seed(bytes, seed_from_str(phrase))`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::hash::seed_from_str;
+    /// # use datasketches::theta::ThetaSketchBuilder;
+    /// let mut sketch = ThetaSketchBuilder::default()
+    ///     .seed_from_str("teamA-2024")
+    ///     .build();
+    /// sketch.update("apple");
+    ///
+    /// let other = ThetaSketchBuilder::default()
+    ///     .seed(seed_from_str("teamA-2024"))
+    ///     .build();
+    /// assert_eq!(sketch.seed_hash(), other.seed_hash());
+    /// ```
+    pub fn seed_from_str(self, phrase: &str) -> Self {
+        self.seed(seed_from_str(phrase))
+    }
+
     /// Build the ThetaSketch.
     ///
     /// # Examples
@@ -997,6 +1855,130 @@ impl ThetaSketchBuilder {
 
         ThetaSketch { table }
     }
+
+    /// Build an updatable ThetaSketch that continues from a compact sketch.
+    ///
+    /// The retained hashes of `sketch` are re-inserted and its theta is carried over, so further
+    /// updates behave as if they had been applied to the sketch before it was compacted. If
+    /// `sketch` retains more than `k` entries, the result is rebuilt down to `k` entries with a
+    /// correspondingly lower theta.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `sketch` is non-empty and was built with a different seed than this
+    /// builder.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::theta::ThetaSketchBuilder;
+    /// let mut sketch = ThetaSketchBuilder::default().build();
+    /// sketch.update("apple");
+    /// let compact = sketch.compact(true);
+    ///
+    /// let mut resumed = ThetaSketchBuilder::default()
+    ///     .build_from_compact(&compact)
+    ///     .unwrap();
+    /// resumed.update("apple");
+    /// resumed.update("banana");
+    /// assert_eq!(resumed.estimate(), 2.0);
+    /// ```
+    pub fn build_from_compact(self, sketch: &CompactThetaSketch) -> Result<ThetaSketch, Error> {
+        let mut result = self.build();
+        if sketch.is_empty() {
+            return Ok(result);
+        }
+
+        if sketch.seed_hash() != result.seed_hash() {
+            return Err(Error::invalid_argument(format!(
+                "incompatible seed hash: expected {}, got {}",
+                named_seed_hash(result.seed_hash()),
+                named_seed_hash(sketch.seed_hash())
+            )));
+        }
+
+        let table = &mut result.table;
+        table.set_empty(false);
+        table.set_theta(table.theta().min(sketch.theta64()));
+        for entry in sketch.iter() {
+            table.try_insert_hash(entry.hash());
+        }
+        Ok(result)
+    }
+
+    /// Build a ThetaSketch from an explicit theta and retained hashes.
+    ///
+    /// This is only available with the `testing` feature and is intended for tests: it reaches
+    /// states such as a theta right above a retained hash or a table filled up to its resize or
+    /// rebuild threshold without feeding the sketch millions of updates. The sketch is non-empty
+    /// and behaves as if the hashes had been retained by earlier updates. Its theta is the
+    /// lower of `theta` and the starting theta of the sampling probability; if there are more
+    /// hashes than the table holds, it is resized or rebuilt as on update, which can lower
+    /// theta further.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `theta` is not in `[1, 2^63 - 1]`, or if a hash is zero, not below
+    /// theta, or repeated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::theta::ThetaSketchBuilder;
+    /// let theta = 1 << 62;
+    /// let sketch = ThetaSketchBuilder::default()
+    ///     .lg_k(5)
+    ///     .build_from_seeded_state(theta, [1, 2, theta - 1])
+    ///     .unwrap();
+    /// assert_eq!(sketch.theta64(), theta);
+    /// assert_eq!(sketch.num_retained(), 3);
+    /// assert_eq!(sketch.estimate(), 6.0);
+    /// ```
+    #[cfg(feature = "testing")]
+    pub fn build_from_seeded_state(
+        self,
+        theta: u64,
+        hashes: impl IntoIterator<Item = u64>,
+    ) -> Result<ThetaSketch, Error> {
+        if !(1..=MAX_THETA).contains(&theta) {
+            return Err(Error::invalid_argument(format!(
+                "theta must be in [1, {MAX_THETA}], got {theta}"
+            )));
+        }
+
+        let mut result = self.build();
+        let table = &mut result.table;
+        let theta = table.theta().min(theta);
+        let mut hashes = hashes.into_iter().collect::<Vec<_>>();
+        hashes.sort_unstable();
+        if let Some(&hash) = hashes.iter().find(|&&hash| hash == 0 || hash >= theta) {
+            return Err(Error::invalid_argument(format!(
+                "hash must be in [1, {theta}), got {hash}"
+            )));
+        }
+        if let Some(pair) = hashes.windows(2).find(|pair| pair[0] == pair[1]) {
+            return Err(Error::invalid_argument(format!(
+                "duplicate hash {}",
+                pair[0]
+            )));
+        }
+
+        table.set_empty(false);
+        table.set_theta(theta);
+        for hash in hashes {
+            table.try_insert_hash(hash);
+        }
+        Ok(result)
+    }
+}
+
+impl SketchBuilder for ThetaSketchBuilder {
+    type Sketch = ThetaSketch;
+
+    /// Build the ThetaSketch. This never fails: the setters validate their arguments.
+    fn try_build(self) -> Result<ThetaSketch, Error> {
+        Ok(self.build())
+    }
 }
 
 #[cfg(test)]
@@ -1163,7 +2145,7 @@ mod tests {
         bytes[1] = 99;
 
         let err = CompactThetaSketch::deserialize(&bytes).unwrap_err();
-        assert_eq!(err.kind(), crate::error::ErrorKind::InvalidData);
+        assert_eq!(err.kind(), crate::error::ErrorKind::UnsupportedVersion);
         assert!(err.message().contains("unsupported serial version"));
     }
 
@@ -1180,4 +2162,141 @@ mod tests {
         assert_eq!(err.kind(), crate::error::ErrorKind::InvalidData);
         assert!(err.message().contains("insufficient data"));
     }
+
+    #[test]
+    fn deserialize_unchecked_seed_accepts_foreign_seed() {
+        let seed = 12345;
+        let mut theta = ThetaSketchBuilder::default().lg_k(5).seed(seed).build();
+        for i in 0..1000 {
+            theta.update(i);
+        }
+        let compact = theta.compact(true);
+
+        for bytes in [compact.serialize(), compact.serialize_compressed()] {
+            let err = CompactThetaSketch::deserialize(&bytes).unwrap_err();
+            assert!(err.message().contains("incompatible seed hash"));
+
+            let decoded = CompactThetaSketch::deserialize_unchecked_seed(&bytes).unwrap();
+            assert_eq!(decoded.seed_hash(), compute_seed_hash(seed));
+            assert_eq!(decoded.theta64(), compact.theta64());
+            assert_eq!(decoded.estimate(), compact.estimate());
+            assert_eq!(
+                sorted_compact_entries(&decoded),
+                sorted_compact_entries(&compact)
+            );
+        }
+    }
+
+    #[test]
+    fn deserialize_unchecked_seed_still_validates_structure() {
+        let mut theta = ThetaSketchBuilder::default().seed(7).build();
+        theta.update("apple");
+        theta.update("banana");
+        let mut bytes = theta.compact(true).serialize();
+        bytes.pop();
+
+        let err = CompactThetaSketch::deserialize_unchecked_seed(&bytes).unwrap_err();
+        assert!(err.message().contains("insufficient data"));
+    }
+
+    #[test]
+    fn from_compact_continues_updates() {
+        let mut original = ThetaSketchBuilder::default().lg_k(10).build();
+        let mut expected = ThetaSketchBuilder::default().lg_k(10).build();
+        for i in 0..5000 {
+            original.update(i);
+            expected.update(i);
+        }
+
+        let mut resumed = ThetaSketchBuilder::default()
+            .lg_k(10)
+            .build_from_compact(&original.compact(false))
+            .unwrap();
+        assert_eq!(resumed.theta64(), original.theta64());
+        assert_eq!(
+            sorted_theta_entries(&resumed),
+            sorted_theta_entries(&original)
+        );
+
+        for i in 5000..20000 {
+            resumed.update(i);
+            expected.update(i);
+        }
+        assert_eq!(resumed.theta64(), expected.theta64());
+        assert_eq!(
+            sorted_theta_entries(&resumed),
+            sorted_theta_entries(&expected)
+        );
+    }
+
+    #[test]
+    fn from_compact_rebuilds_to_smaller_k() {
+        let mut large = ThetaSketchBuilder::default().lg_k(12).build();
+        for i in 0..10000 {
+            large.update(i);
+        }
+        let small = ThetaSketchBuilder::default()
+            .lg_k(6)
+            .build_from_compact(&large.compact(true))
+            .unwrap();
+        assert!(small.theta64() < large.theta64());
+        assert!(small.num_retained() <= 1 << 7);
+        assert!((small.estimate() - 10000.0).abs() < 10000.0 * 0.5);
+    }
+
+    #[test]
+    fn from_compact_empty_and_seed_mismatch() {
+        let empty = ThetaSketchBuilder::default().seed(1).build().compact(true);
+        let resumed = ThetaSketch::from_compact(&empty).unwrap();
+        assert!(resumed.is_empty());
+
+        let mut other = ThetaSketchBuilder::default().seed(1).build();
+        other.update("apple");
+        let err = ThetaSketch::from_compact(&other.compact(true)).unwrap_err();
+        assert!(err.message().contains("incompatible seed hash"));
+    }
+
+    #[test]
+    fn from_compact_preserves_non_empty_without_entries() {
+        let mut sampled = ThetaSketchBuilder::default()
+            .sampling_probability(0.001)
+            .build();
+        sampled.update(1);
+        let compact = sampled.compact(true);
+        assert!(!compact.is_empty());
+        assert_eq!(compact.num_retained(), 0);
+
+        let resumed = ThetaSketch::from_compact(&compact).unwrap();
+        assert!(!resumed.is_empty());
+        assert_eq!(resumed.theta64(), compact.theta64());
+    }
+
+    #[test]
+    fn update_hash_matches_update() {
+        let mut by_value = ThetaSketchBuilder::default().lg_k(5).build();
+        let mut by_hash = ThetaSketchBuilder::default().lg_k(5).build();
+        for i in 0..1000 {
+            by_value.update(i);
+            let hash = by_hash.table.hash(i);
+            by_hash.update_hash(hash);
+        }
+        assert_eq!(by_hash.theta64(), by_value.theta64());
+        assert_eq!(
+            sorted_theta_entries(&by_hash),
+            sorted_theta_entries(&by_value)
+        );
+    }
+
+    #[test]
+    fn update_hash_screens_out_of_range_hashes() {
+        let mut sketch = ThetaSketchBuilder::default().build();
+        sketch.update_hash(0);
+        sketch.update_hash(u64::MAX);
+        assert!(!sketch.is_empty());
+        assert_eq!(sketch.num_retained(), 0);
+
+        sketch.update_hash(42);
+        sketch.update_hash(42);
+        assert_eq!(sketch.num_retained(), 1);
+    }
 }
@@ -15,15 +15,29 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::ops::AddAssign;
+
+use crate::codec::SketchSlice;
+use crate::codec::StateEnvelope;
+use crate::codec::assert::insufficient_data;
+use crate::codec::encode_state;
+use crate::codec::family::Family;
+use crate::common::MemoryUsage;
 use crate::common::ResizeFactor;
 use crate::error::Error;
 use crate::hash::DEFAULT_UPDATE_SEED;
+use crate::hash::compute_seed_hash;
+use crate::hash::named_seed_hash;
 use crate::theta::CompactThetaSketch;
 use crate::theta::ThetaSketchView;
 use crate::theta::hash_table::ThetaEntry;
 use crate::thetacommon::constants::DEFAULT_LG_K;
 use crate::thetacommon::constants::MAX_LG_K;
+use crate::thetacommon::constants::MAX_THETA;
 use crate::thetacommon::constants::MIN_LG_K;
+use crate::thetacommon::hash_table::starting_theta_from_sampling_probability;
 use crate::thetacommon::union::RawThetaUnion;
 use crate::thetacommon::union::RawThetaUnionPolicy;
 
@@ -66,6 +80,105 @@ impl ThetaUnion {
     pub fn reset(&mut self) {
         self.raw.reset();
     }
+
+    /// Returns the estimated size of the union in bytes.
+    pub fn estimated_size(&self) -> usize {
+        size_of::<Self>() + self.raw.estimated_size()
+    }
+
+    /// Saves the state of this union as a checkpoint that [`load_state`](Self::load_state) can
+    /// restore.
+    ///
+    /// The state is a [`StateEnvelope`] around the union configuration, including the seed, and
+    /// its current result as an ordered compact sketch.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::theta::ThetaSketchBuilder;
+    /// # use datasketches::theta::ThetaUnion;
+    /// # use datasketches::theta::ThetaUnionBuilder;
+    /// let mut sketch = ThetaSketchBuilder::default().build();
+    /// sketch.update("apple");
+    /// let mut union = ThetaUnionBuilder::default().lg_k(10).build();
+    /// union.update(&sketch).unwrap();
+    ///
+    /// let state = union.save_state();
+    /// let restored = ThetaUnion::load_state(&state).unwrap();
+    /// assert_eq!(
+    ///     restored.to_sketch(true).estimate(),
+    ///     union.to_sketch(true).estimate()
+    /// );
+    /// ```
+    pub fn save_state(&self) -> Vec<u8> {
+        let table = self.raw.table();
+        encode_state(&Family::UNION, 14, |bytes| {
+            bytes.write_u8(table.lg_nom_size());
+            bytes.write_u8(table.resize_factor().lg_value());
+            bytes.write_f32_le(table.sampling_probability());
+            bytes.write_u64_le(table.hash_seed());
+            self.to_sketch(true).write_to(bytes);
+        })
+    }
+
+    /// Restores a union from a checkpoint written by [`save_state`](Self::save_state).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bytes are not a valid Theta union state, including state saved by
+    /// a different kind of union.
+    pub fn load_state(bytes: &[u8]) -> Result<Self, Error> {
+        let envelope = StateEnvelope::decode_family(bytes, &Family::UNION)?;
+        let mut cursor = SketchSlice::new(envelope.payload());
+        let lg_k = cursor.read_u8().map_err(insufficient_data("lg_k"))?;
+        let lg_resize_factor = cursor
+            .read_u8()
+            .map_err(insufficient_data("lg_resize_factor"))?;
+        let sampling_probability = cursor
+            .read_f32_le()
+            .map_err(insufficient_data("sampling_probability"))?;
+        let seed = cursor.read_u64_le().map_err(insufficient_data("seed"))?;
+        if !(MIN_LG_K..=MAX_LG_K).contains(&lg_k) {
+            return Err(Error::deserial(format!(
+                "lg_k must be in [{MIN_LG_K}, {MAX_LG_K}], got {lg_k}"
+            )));
+        }
+        if !(sampling_probability > 0.0 && sampling_probability <= 1.0) {
+            return Err(Error::deserial(format!(
+                "sampling_probability must be in (0.0, 1.0], got {sampling_probability}"
+            )));
+        }
+
+        let result = CompactThetaSketch::deserialize_with_seed(cursor.remaining(), seed)?;
+        let mut union = ThetaUnionBuilder::default()
+            .lg_k(lg_k)
+            .resize_factor(ResizeFactor::from_lg_value(lg_resize_factor))
+            .sampling_probability(sampling_probability)
+            .seed(seed)
+            .build();
+        union.update(&result)?;
+        Ok(union)
+    }
+}
+
+impl MemoryUsage for ThetaUnion {
+    fn memory_usage(&self) -> usize {
+        self.estimated_size()
+    }
+}
+
+/// `union += &sketch` is equivalent to [`union.update(&sketch)`](ThetaUnion::update).
+///
+/// # Panics
+///
+/// Panics if the sketch was built with a different seed than the union. Use
+/// [`update`](ThetaUnion::update) to handle that case as an error.
+impl<S: ThetaSketchView> AddAssign<&S> for ThetaUnion {
+    fn add_assign(&mut self, sketch: &S) {
+        if let Err(err) = self.update(sketch) {
+            panic!("cannot add sketch to ThetaUnion: {err}");
+        }
+    }
 }
 
 /// Builder for [`ThetaUnion`].
@@ -171,4 +284,146 @@ impl ThetaUnionBuilder {
             ),
         }
     }
+
+    /// Unions two compact sketches with a merge of their sorted entries.
+    ///
+    /// The smallest k distinct hashes below the smallest theta are kept and the (k + 1)-th one
+    /// becomes the theta of the result, like a union trimmed to its nominal size. The entries are
+    /// emitted in hash order, so the result is ordered without a final sort.
+    pub(super) fn union_ordered(
+        &self,
+        a: &CompactThetaSketch,
+        b: &CompactThetaSketch,
+    ) -> Result<CompactThetaSketch, Error> {
+        let seed_hash = compute_seed_hash(self.seed);
+        let mut theta = starting_theta_from_sampling_probability(self.sampling_probability);
+        let mut empty = true;
+        for sketch in [a, b] {
+            if sketch.is_empty() {
+                continue;
+            }
+            if sketch.seed_hash() != seed_hash {
+                return Err(Error::invalid_argument(format!(
+                    "incompatible seed hash: expected {}, got {}",
+                    named_seed_hash(seed_hash),
+                    named_seed_hash(sketch.seed_hash()),
+                )));
+            }
+            empty = false;
+            theta = theta.min(sketch.theta64());
+        }
+        if empty {
+            return Ok(CompactThetaSketch::from_parts(
+                vec![],
+                theta,
+                seed_hash,
+                true,
+                true,
+            ));
+        }
+
+        let (a, b) = (a.ordered_hashes(), b.ordered_hashes());
+        let nominal_num = 1usize << self.lg_k;
+        let mut entries = Vec::with_capacity((a.len() + b.len()).min(nominal_num));
+        let (mut i, mut j) = (0, 0);
+        loop {
+            let hash = match (a.get(i), b.get(j)) {
+                (Some(&x), Some(&y)) => {
+                    // A hash retained by both sketches is emitted once.
+                    if x <= y {
+                        i += 1;
+                    }
+                    if y <= x {
+                        j += 1;
+                    }
+                    x.min(y)
+                }
+                (Some(&x), None) => {
+                    i += 1;
+                    x
+                }
+                (None, Some(&y)) => {
+                    j += 1;
+                    y
+                }
+                (None, None) => break,
+            };
+            if hash >= theta {
+                break;
+            }
+            if entries.len() == nominal_num {
+                theta = hash;
+                break;
+            }
+            entries.push(hash);
+        }
+
+        Ok(CompactThetaSketch::from_parts(
+            entries, theta, seed_hash, true, false,
+        ))
+    }
+
+    /// Estimates the size of the union of `sketches` without building the union.
+    ///
+    /// The distinct hashes below the smallest theta are counted with a k-way merge of the sorted
+    /// entries of the inputs, which stops at the (k + 1)-th smallest one and uses it as the theta
+    /// of the result, like a union trimmed to its nominal size.
+    pub(super) fn estimate_union_size<'a, I>(&self, sketches: I) -> Result<f64, Error>
+    where
+        I: IntoIterator<Item = &'a CompactThetaSketch>,
+    {
+        let seed_hash = compute_seed_hash(self.seed);
+        let mut theta = starting_theta_from_sampling_probability(self.sampling_probability);
+        let mut inputs = vec![];
+        for sketch in sketches {
+            if sketch.is_empty() {
+                continue;
+            }
+            if sketch.seed_hash() != seed_hash {
+                return Err(Error::invalid_argument(format!(
+                    "incompatible seed hash: expected {}, got {}",
+                    named_seed_hash(seed_hash),
+                    named_seed_hash(sketch.seed_hash()),
+                )));
+            }
+            theta = theta.min(sketch.theta64());
+            inputs.push(sketch.ordered_hashes());
+        }
+        if inputs.is_empty() {
+            return Ok(0.0);
+        }
+
+        let nominal_num = 1usize << self.lg_k;
+        let mut positions = vec![0; inputs.len()];
+        let mut heap = inputs
+            .iter()
+            .enumerate()
+            .filter_map(|(i, hashes)| hashes.first().map(|&hash| Reverse((hash, i))))
+            .collect::<BinaryHeap<_>>();
+        let mut num_distinct = 0;
+        let mut last = None;
+        while let Some(Reverse((hash, i))) = heap.pop() {
+            if hash >= theta {
+                break;
+            }
+            positions[i] += 1;
+            if let Some(&next) = inputs[i].get(positions[i]) {
+                heap.push(Reverse((next, i)));
+            }
+            if last == Some(hash) {
+                continue;
+            }
+            last = Some(hash);
+            if num_distinct == nominal_num {
+                theta = hash;
+                break;
+            }
+            num_distinct += 1;
+        }
+
+        if theta == MAX_THETA {
+            return Ok(num_distinct as f64);
+        }
+        Ok(num_distinct as f64 / (theta as f64 / MAX_THETA as f64))
+    }
 }
@@ -499,7 +499,7 @@ fn compute_approx_binomial_lower_bound(
     }
     if num_samples > 120 {
         // plenty of samples, so gaussian approximation to binomial distribution isn't too bad
-        let raw_lb = cont_classic_lb(num_samples, theta, num_std_dev as u8 as f64);
+        let raw_lb = cont_classic_lb(num_samples, theta, num_std_dev.as_f64());
         return raw_lb - 0.5; // fake round down
     }
     // at this point we know 2 <= num_samples <= 120
@@ -537,7 +537,7 @@ fn compute_approx_binomial_upper_bound(
     }
     if num_samples > 120 {
         // plenty of samples, so gaussian approximation to binomial distribution isn't too bad
-        let raw_ub = cont_classic_ub(num_samples, theta, num_std_dev as u8 as f64);
+        let raw_ub = cont_classic_ub(num_samples, theta, num_std_dev.as_f64());
         return raw_ub + 0.5; // fake round up
     }
     // at this point we know 2 <= num_samples <= 120
@@ -15,6 +15,8 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use crate::codec::format;
+
 /// Maximum theta value.
 ///
 /// The value is `i64::MAX` to be compatible with datasketches-java.
@@ -31,16 +33,19 @@ pub const DEFAULT_LG_K: u8 = 12;
 pub const HASH_TABLE_RESIZE_THRESHOLD: f64 = 0.5;
 /// Rebuild threshold (15/16 = 93.75% load factor).
 pub const HASH_TABLE_REBUILD_THRESHOLD: f64 = 15.0 / 16.0;
+/// Maximum number of entries any sketch can retain: a hash table of `2 * 2^MAX_LG_K` slots filled
+/// up to the rebuild threshold.
+pub const MAX_RETAINED_ENTRIES: usize = (1 << (MAX_LG_K + 1)) / 16 * 15;
 
 pub const STRIDE_HASH_BITS: u8 = 7;
 pub const STRIDE_MASK: u64 = (1 << STRIDE_HASH_BITS) - 1;
 
 // Flag bits of the flags byte in the Theta-family wire format, shared by Theta and Tuple sketches.
 /// Flags byte bit: the sketch is read-only.
-pub const FLAGS_IS_READ_ONLY: u8 = 1 << 1;
+pub const FLAGS_IS_READ_ONLY: u8 = format::theta::FLAGS_IS_READ_ONLY;
 /// Flags byte bit: the sketch is logically empty.
-pub const FLAGS_IS_EMPTY: u8 = 1 << 2;
+pub const FLAGS_IS_EMPTY: u8 = format::theta::FLAGS_IS_EMPTY;
 /// Flags byte bit: the sketch is in compact form.
-pub const FLAGS_IS_COMPACT: u8 = 1 << 3;
+pub const FLAGS_IS_COMPACT: u8 = format::theta::FLAGS_IS_COMPACT;
 /// Flags byte bit: retained entries are ordered by ascending hash.
-pub const FLAGS_IS_ORDERED: u8 = 1 << 4;
+pub const FLAGS_IS_ORDERED: u8 = format::theta::FLAGS_IS_ORDERED;
@@ -37,6 +37,68 @@ pub struct RawCompactParts<E> {
     pub empty: bool,
 }
 
+/// Statistics of a Theta family hash table, for performance observability.
+///
+/// Counters cover the lifetime of the table since it was created or last reset.
+#[cfg(feature = "stats")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HashTableStats {
+    load_factor: f64,
+    lg_cur_size: u8,
+    num_resizes: u64,
+    num_rebuilds: u64,
+    num_probes: u64,
+    num_lookups: u64,
+}
+
+#[cfg(feature = "stats")]
+impl HashTableStats {
+    /// Returns the fraction of table slots holding a retained entry.
+    pub fn load_factor(&self) -> f64 {
+        self.load_factor
+    }
+
+    /// Returns log2 of the current number of table slots.
+    pub fn lg_cur_size(&self) -> u8 {
+        self.lg_cur_size
+    }
+
+    /// Returns how many times the table grew by its resize factor.
+    pub fn num_resizes(&self) -> u64 {
+        self.num_resizes
+    }
+
+    /// Returns how many times the full table was rebuilt, discarding entries above the new theta.
+    pub fn num_rebuilds(&self) -> u64 {
+        self.num_rebuilds
+    }
+
+    /// Returns the number of updates that passed the theta screen and searched the table.
+    pub fn num_lookups(&self) -> u64 {
+        self.num_lookups
+    }
+
+    /// Returns the average number of slots inspected per lookup, or 0 if there were none.
+    ///
+    /// A lookup that finds its slot at the first try has a probe length of 1.
+    pub fn average_probe_length(&self) -> f64 {
+        if self.num_lookups == 0 {
+            0.0
+        } else {
+            self.num_probes as f64 / self.num_lookups as f64
+        }
+    }
+}
+
+#[cfg(feature = "stats")]
+#[derive(Debug, Clone, Copy, Default)]
+struct StatsCounters {
+    num_resizes: u64,
+    num_rebuilds: u64,
+    num_probes: u64,
+    num_lookups: u64,
+}
+
 /// Generic hash-table mechanics shared by Theta and Tuple sketches.
 ///
 /// The entry type supplies the retained hash and any sketch-specific payload. The table owns all
@@ -47,7 +109,7 @@ pub struct RawCompactParts<E> {
 /// * After it reaches the capacity bigger than 2^lg_nom_size, every time the number of entries
 ///   exceeds the threshold, it will rebuild the table: only keep the min 2^lg_nom_size entries and
 ///   update the theta to the k-th smallest entry.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct RawHashTable<E> {
     lg_cur_size: u8,
     lg_nom_size: u8,
@@ -70,6 +132,9 @@ pub struct RawHashTable<E> {
 
     // Number of retained non-zero hashes currently stored in `entries`.
     num_retained: usize,
+
+    #[cfg(feature = "stats")]
+    stats: StatsCounters,
 }
 
 impl<E> RawHashTable<E>
@@ -128,6 +193,8 @@ where
             theta,
             entries,
             num_retained: 0,
+            #[cfg(feature = "stats")]
+            stats: StatsCounters::default(),
         }
     }
 
@@ -139,6 +206,14 @@ where
         h1 >> 1 // To make it compatible with Java version
     }
 
+    /// Hash a byte string with the table seed and return the hash.
+    ///
+    /// Unlike [`hash`](Self::hash) of a slice, the bytes are hashed without a length prefix.
+    pub fn hash_bytes(&self, bytes: &[u8]) -> u64 {
+        let (h1, _) = MurmurHash3X64128::hash_bytes(bytes, self.hash_seed);
+        h1 >> 1 // To make it compatible with Java version
+    }
+
     /// Inserts or updates the entry slot for a pre-hashed key.
     ///
     /// The callback `f` is invoked with the current entry for `hash`:
@@ -168,6 +243,11 @@ where
                 "Resize or rebuild should be called to make sure it always can find the entry."
             );
         };
+        #[cfg(feature = "stats")]
+        {
+            self.stats.num_lookups += 1;
+            self.stats.num_probes += self.probe_length(hash, index);
+        }
 
         if let Some(entry) = self.entries[index].as_mut() {
             f(Some(entry));
@@ -222,6 +302,35 @@ where
         }
     }
 
+    /// Lowers theta to `theta` and drops the retained entries that are no longer below it.
+    ///
+    /// Does nothing if `theta` is not below the current theta.
+    pub fn lower_theta(&mut self, theta: u64) {
+        if theta >= self.theta {
+            return;
+        }
+        self.set_theta(theta);
+
+        let retained: Vec<E> = std::mem::take(&mut self.entries)
+            .into_iter()
+            .flatten()
+            .filter(|entry| entry.hash() < theta)
+            .collect();
+        let size = 1 << self.lg_cur_size;
+        let mut new_entries: Vec<Option<E>> = std::iter::repeat_with(|| None).take(size).collect();
+        self.num_retained = retained.len();
+        for entry in retained {
+            let Some(idx) = Self::find_in_entries(&new_entries, entry.hash(), self.lg_cur_size)
+            else {
+                unreachable!(
+                    "find_in_entries should always return Some if the entry is not empty."
+                );
+            };
+            new_entries[idx] = Some(entry);
+        }
+        self.entries = new_entries;
+    }
+
     /// Reset the table to empty state.
     pub fn reset(&mut self) {
         let init_theta = starting_theta_from_sampling_probability(self.sampling_probability);
@@ -238,6 +347,10 @@ where
         self.theta = init_theta;
         self.is_empty = true;
         self.lg_cur_size = init_lg_cur;
+        #[cfg(feature = "stats")]
+        {
+            self.stats = StatsCounters::default();
+        }
     }
 
     /// Return number of retained entries.
@@ -260,6 +373,11 @@ where
         self.entries.iter().filter_map(Option::as_ref)
     }
 
+    /// Get iterator over every slot of the table, yielding `None` for empty slots.
+    pub fn iter_slots(&self) -> impl Iterator<Item = Option<&E>> + '_ {
+        self.entries.iter().map(Option::as_ref)
+    }
+
     /// Returns the retained entries and theta as raw compact-sketch parts.
     ///
     /// An empty table reports `MAX_THETA` rather than its current theta, matching Java's
@@ -294,7 +412,6 @@ where
     }
 
     /// Get log2 of current size.
-    #[cfg(test)]
     pub fn lg_cur_size(&self) -> u8 {
         self.lg_cur_size
     }
@@ -304,6 +421,16 @@ where
         self.lg_nom_size
     }
 
+    /// Get the resize factor.
+    pub fn resize_factor(&self) -> ResizeFactor {
+        self.resize_factor
+    }
+
+    /// Get the sampling probability.
+    pub fn sampling_probability(&self) -> f32 {
+        self.sampling_probability
+    }
+
     /// Get the hash of the seed that was used to hash the input.
     pub fn seed_hash(&self) -> u16 {
         compute_seed_hash(self.hash_seed)
@@ -342,6 +469,35 @@ where
             })
     }
 
+    /// Returns the statistics collected since the table was created or last reset.
+    #[cfg(feature = "stats")]
+    pub fn stats(&self) -> HashTableStats {
+        HashTableStats {
+            load_factor: self.num_retained as f64 / self.entries.len() as f64,
+            lg_cur_size: self.lg_cur_size,
+            num_resizes: self.stats.num_resizes,
+            num_rebuilds: self.stats.num_rebuilds,
+            num_probes: self.stats.num_probes,
+            num_lookups: self.stats.num_lookups,
+        }
+    }
+
+    /// Returns the number of slots a lookup of `key` inspects to reach `index`.
+    ///
+    /// Walks the probe sequence again, so the lookup path itself stays free of bookkeeping.
+    #[cfg(feature = "stats")]
+    fn probe_length(&self, key: u64, index: usize) -> u64 {
+        let mask = self.entries.len() - 1;
+        let stride = Self::get_stride(key, self.lg_cur_size);
+        let mut probe = (key as usize) & mask;
+        let mut length = 1;
+        while probe != index {
+            probe = (probe + stride) & mask;
+            length += 1;
+        }
+        length
+    }
+
     /// Returns the estimated size of the heap allocations in bytes.
     pub fn estimated_size(&self) -> usize {
         self.entries.capacity() * size_of::<Option<E>>()
@@ -393,8 +549,19 @@ where
             new_entries[idx] = Some(entry);
         }
 
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            from_lg_size = self.lg_cur_size,
+            to_lg_size = new_lg_size,
+            num_retained = self.num_retained,
+            "resized theta hash table"
+        );
         self.entries = new_entries;
         self.lg_cur_size = new_lg_size;
+        #[cfg(feature = "stats")]
+        {
+            self.stats.num_resizes += 1;
+        }
     }
 
     fn rebuild(&mut self) {
@@ -432,6 +599,17 @@ where
         );
         self.num_retained = num_inserted;
         self.entries = new_entries;
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            lg_size = self.lg_cur_size,
+            num_retained = self.num_retained,
+            theta = self.theta,
+            "rebuilt theta hash table"
+        );
+        #[cfg(feature = "stats")]
+        {
+            self.stats.num_rebuilds += 1;
+        }
     }
 
     fn get_stride(key: u64, lg_size: u8) -> usize {
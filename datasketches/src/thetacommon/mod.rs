@@ -22,6 +22,9 @@ pub(crate) mod constants;
 pub(crate) mod hash_table;
 pub(crate) mod union;
 
+#[cfg(feature = "stats")]
+pub use self::hash_table::HashTableStats;
+
 /// An entry retained by a Theta sketch family hash table.
 pub trait RawHashTableEntry {
     /// Return the hash used as this entry's key.
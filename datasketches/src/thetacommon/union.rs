@@ -17,6 +17,7 @@
 
 use crate::common::ResizeFactor;
 use crate::error::Error;
+use crate::hash::named_seed_hash;
 use crate::thetacommon::RawHashTableEntry;
 use crate::thetacommon::RawThetaSketchView;
 use crate::thetacommon::constants::MAX_THETA;
@@ -58,6 +59,11 @@ where
         }
     }
 
+    /// Returns the hash table holding the retained entries and the union configuration.
+    pub fn table(&self) -> &RawHashTable<E> {
+        &self.table
+    }
+
     /// Incorporate a sketch into the union.
     pub fn update<S>(&mut self, sketch: &S) -> Result<(), Error>
     where
@@ -71,8 +77,8 @@ where
         if self.table.seed_hash() != sketch.seed_hash() {
             return Err(Error::invalid_argument(format!(
                 "incompatible seed hash: expected {}, got {}",
-                self.table.seed_hash(),
-                sketch.seed_hash(),
+                named_seed_hash(self.table.seed_hash()),
+                named_seed_hash(sketch.seed_hash()),
             )));
         }
 
@@ -152,6 +158,11 @@ where
         self.table.reset();
         self.union_theta = self.table.theta();
     }
+
+    /// Returns the estimated size of the heap allocations in bytes.
+    pub fn estimated_size(&self) -> usize {
+        self.table.estimated_size()
+    }
 }
 
 #[cfg(test)]
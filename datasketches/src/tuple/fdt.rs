@@ -0,0 +1,668 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Frequent Distinct Tuples (FDT) sketch.
+//!
+//! Given a stream of tuples of dimension values, the FDT sketch estimates, for any chosen subset of
+//! dimensions (the primary key), the number of distinct tuples associated with each primary key
+//! value, and identifies the primary keys with the most distinct tuples. For example, with tuples
+//! of `(ip, user)`, choosing `ip` as the primary key estimates the number of distinct users per IP
+//! address. See the Java `FdtSketch` for background.
+//!
+//! The sketch is a Tuple sketch keyed by the whole tuple, whose summary retains the tuple itself,
+//! so the grouping by primary key happens when the result is queried. Sketches built over
+//! different parts of a stream are merged with [`FdtUnion`].
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::hash::Hasher;
+
+use crate::codec::SketchBytes;
+use crate::codec::SketchSlice;
+use crate::common::MemoryUsage;
+use crate::common::NumStdDev;
+use crate::error::Error;
+use crate::thetacommon::binomial_bounds;
+use crate::thetacommon::constants::MAX_LG_K;
+use crate::thetacommon::constants::MIN_LG_K;
+use crate::tuple::CompactTupleSketch;
+use crate::tuple::SummaryCombinePolicy;
+use crate::tuple::SummaryPolicy;
+use crate::tuple::SummaryUpdatePolicy;
+use crate::tuple::TupleSketch;
+use crate::tuple::TupleSketchBuilder;
+use crate::tuple::TupleSummaryValue;
+use crate::tuple::TupleUnion;
+use crate::tuple::TupleUnionBuilder;
+
+/// Maximum number of strings in a tuple, bounded by the one-byte count of the serialized summary.
+const MAX_TUPLE_LEN: usize = 127;
+
+/// Frequent Distinct Tuples sketch.
+///
+/// Tuples are given as slices of strings. Each distinct tuple is retained with the sampling
+/// behavior of a Tuple sketch of nominal size k, and [`result`](Self::result) groups the retained
+/// tuples by a primary key built from a subset of their dimensions.
+///
+/// As in the Java `FdtSketch`, the key of a tuple is the UTF-8 bytes of its strings joined with
+/// commas, and tuples whose key is empty (no strings, or a single empty string) are ignored.
+/// Serialized sketches are compact Tuple sketches whose summaries use the Java
+/// `ArrayOfStringsSummary` encoding.
+///
+/// # Examples
+///
+/// ```
+/// # use datasketches::common::NumStdDev;
+/// # use datasketches::tuple::FdtSketch;
+/// let mut sketch = FdtSketch::new(12);
+/// sketch.update(&["10.0.0.1", "alice"]);
+/// sketch.update(&["10.0.0.1", "bob"]);
+/// sketch.update(&["10.0.0.1", "bob"]);
+/// sketch.update(&["10.0.0.2", "alice"]);
+///
+/// // Distinct users per IP address.
+/// let groups = sketch.result(&[0], None, NumStdDev::Two, ',');
+/// assert_eq!(groups[0].primary_key(), "10.0.0.1");
+/// assert_eq!(groups[0].count(), 2);
+/// assert_eq!(groups[1].primary_key(), "10.0.0.2");
+/// assert_eq!(groups[1].estimate(), 1.0);
+/// ```
+#[derive(Debug)]
+pub struct FdtSketch {
+    sketch: TupleSketch<FdtSummaryPolicy>,
+}
+
+impl FdtSketch {
+    /// Creates a sketch with the given lg_k (log2 of nominal size k).
+    ///
+    /// # Panics
+    ///
+    /// If lg_k is not in range [5, 26]
+    pub fn new(lg_k: u8) -> Self {
+        Self {
+            sketch: TupleSketchBuilder::new(FdtSummaryPolicy).lg_k(lg_k).build(),
+        }
+    }
+
+    /// Creates a sketch sized so that groups whose share of the distinct tuples is at least
+    /// `threshold` are estimated with a relative standard error of at most `rse`.
+    ///
+    /// The nominal size is `k = 1 / (threshold * rse^2)`, rounded up to a power of two and to the
+    /// minimum lg_k, as in the Java `FdtSketch`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `threshold` or `rse` is not in range `(0.0, 1.0]`, or if the required lg_k
+    /// exceeds 26.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::tuple::FdtSketch;
+    /// let sketch = FdtSketch::with_threshold(0.01, 0.05);
+    /// assert_eq!(sketch.lg_k(), 16);
+    /// ```
+    pub fn with_threshold(threshold: f64, rse: f64) -> Self {
+        Self::new(lg_k_for_threshold(threshold, rse))
+    }
+
+    /// Updates the sketch with a tuple of dimension values.
+    ///
+    /// Tuples whose comma-joined key is empty are ignored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the tuple holds more than 127 strings.
+    pub fn update<T: AsRef<str>>(&mut self, tuple: &[T]) {
+        assert!(
+            tuple.len() <= MAX_TUPLE_LEN,
+            "tuple must hold at most {MAX_TUPLE_LEN} strings, got {}",
+            tuple.len()
+        );
+        let key = JoinedKey(tuple);
+        if key.is_empty() {
+            return;
+        }
+        self.sketch.update(key, tuple);
+    }
+
+    /// Returns the estimated number of distinct tuples.
+    pub fn estimate(&self) -> f64 {
+        self.sketch.estimate()
+    }
+
+    /// Returns the approximate lower error bound of the distinct tuple count.
+    pub fn lower_bound(&self, num_std_dev: NumStdDev) -> f64 {
+        self.sketch.lower_bound(num_std_dev)
+    }
+
+    /// Returns the approximate upper error bound of the distinct tuple count.
+    pub fn upper_bound(&self, num_std_dev: NumStdDev) -> f64 {
+        self.sketch.upper_bound(num_std_dev)
+    }
+
+    /// Returns theta as a fraction (0.0 to 1.0).
+    pub fn theta(&self) -> f64 {
+        self.sketch.theta()
+    }
+
+    /// Returns true if the sketch has not seen any tuple.
+    pub fn is_empty(&self) -> bool {
+        self.sketch.is_empty()
+    }
+
+    /// Returns true if the sketch is in estimation mode.
+    pub fn is_estimation_mode(&self) -> bool {
+        self.sketch.is_estimation_mode()
+    }
+
+    /// Returns the number of retained tuples.
+    pub fn num_retained(&self) -> usize {
+        self.sketch.num_retained()
+    }
+
+    /// Returns lg_k (log2 of nominal size k).
+    pub fn lg_k(&self) -> u8 {
+        self.sketch.lg_k()
+    }
+
+    /// Returns an iterator over the retained tuples.
+    pub fn iter(&self) -> impl Iterator<Item = &[String]> + '_ {
+        self.sketch.iter().map(|(_, tuple)| tuple.0.as_slice())
+    }
+
+    /// Resets the sketch to the empty state.
+    pub fn reset(&mut self) {
+        self.sketch.reset();
+    }
+
+    /// Serializes the sketch to bytes, as an ordered compact Tuple sketch whose summaries are the
+    /// retained tuples.
+    pub fn serialize(&self) -> Vec<u8> {
+        self.sketch.compact(true).serialize()
+    }
+
+    /// Deserializes a sketch from bytes written by [`serialize`](Self::serialize).
+    ///
+    /// A compact image does not record the nominal size, so the sketch resumes with the given
+    /// lg_k.
+    ///
+    /// # Panics
+    ///
+    /// If lg_k is not in range [5, 26]
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bytes are not a valid compact Tuple sketch with string array
+    /// summaries built with the default seed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::tuple::FdtSketch;
+    /// let mut sketch = FdtSketch::new(12);
+    /// sketch.update(&["10.0.0.1", "alice"]);
+    ///
+    /// let mut resumed = FdtSketch::deserialize(&sketch.serialize(), 12).unwrap();
+    /// resumed.update(&["10.0.0.1", "bob"]);
+    /// assert_eq!(resumed.estimate(), 2.0);
+    /// ```
+    pub fn deserialize(bytes: &[u8], lg_k: u8) -> Result<Self, Error> {
+        let compact = CompactTupleSketch::<FdtSummary>::deserialize(bytes)?;
+        Self::from_compact(&compact, lg_k)
+    }
+
+    fn from_compact(compact: &CompactTupleSketch<FdtSummary>, lg_k: u8) -> Result<Self, Error> {
+        let sketch = TupleSketchBuilder::new(FdtSummaryPolicy)
+            .lg_k(lg_k)
+            .build_from_compact(compact)?;
+        Ok(Self { sketch })
+    }
+
+    /// Groups the retained tuples by primary key, most frequent first.
+    ///
+    /// The primary key of a tuple is made of the dimensions at `primary_key_indices`, joined with
+    /// `separator`. Each group reports the number of retained tuples with that primary key and the
+    /// distinct tuple count estimated from it, with bounds at `num_std_dev` standard deviations.
+    /// Groups with equal counts are ordered by primary key. If `limit` is given, at most that many
+    /// groups are returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an index in `primary_key_indices` is out of range for a retained tuple.
+    pub fn result(
+        &self,
+        primary_key_indices: &[usize],
+        limit: Option<usize>,
+        num_std_dev: NumStdDev,
+        separator: char,
+    ) -> Vec<FdtGroup> {
+        let mut counts: HashMap<String, u64> = HashMap::new();
+        for tuple in self.iter() {
+            let mut primary_key = String::new();
+            for (i, index) in primary_key_indices.iter().enumerate() {
+                if i > 0 {
+                    primary_key.push(separator);
+                }
+                primary_key.push_str(&tuple[*index]);
+            }
+            *counts.entry(primary_key).or_default() += 1;
+        }
+
+        let mut groups: Vec<FdtGroup> = counts
+            .into_iter()
+            .map(|(primary_key, count)| {
+                let estimate = self.subset_estimate(count);
+                let upper_bound = self.subset_upper_bound(count, num_std_dev);
+                let lower_bound = self.subset_lower_bound(count, num_std_dev);
+                FdtGroup {
+                    primary_key,
+                    count,
+                    estimate,
+                    upper_bound,
+                    lower_bound,
+                    fraction: count as f64 / self.num_retained() as f64,
+                    rse: self.subset_upper_bound(count, NumStdDev::One) / estimate - 1.0,
+                }
+            })
+            .collect();
+        groups.sort_by(|a, b| {
+            b.count
+                .cmp(&a.count)
+                .then_with(|| a.primary_key.cmp(&b.primary_key))
+        });
+        if let Some(limit) = limit {
+            groups.truncate(limit);
+        }
+        groups
+    }
+
+    fn subset_estimate(&self, count: u64) -> f64 {
+        count as f64 / self.theta()
+    }
+
+    fn subset_lower_bound(&self, count: u64, num_std_dev: NumStdDev) -> f64 {
+        if !self.is_estimation_mode() {
+            return count as f64;
+        }
+        binomial_bounds::lower_bound(count, self.theta(), num_std_dev)
+            .expect("theta should always be valid")
+    }
+
+    fn subset_upper_bound(&self, count: u64, num_std_dev: NumStdDev) -> f64 {
+        if !self.is_estimation_mode() {
+            return count as f64;
+        }
+        binomial_bounds::upper_bound(count, self.theta(), num_std_dev, self.is_empty())
+            .expect("theta should always be valid")
+    }
+
+    /// Returns the estimated size of the sketch in bytes.
+    pub fn estimated_size(&self) -> usize {
+        self.sketch.estimated_size()
+    }
+}
+
+impl MemoryUsage for FdtSketch {
+    fn memory_usage(&self) -> usize {
+        self.estimated_size()
+    }
+}
+
+/// Union of Frequent Distinct Tuples sketches.
+///
+/// A tuple retained by several input sketches keeps the first copy merged into the union, as in
+/// the Java `ArrayOfStringsSummarySetOperations`.
+///
+/// # Examples
+///
+/// ```
+/// # use datasketches::tuple::FdtSketch;
+/// # use datasketches::tuple::FdtUnion;
+/// let mut a = FdtSketch::new(12);
+/// a.update(&["10.0.0.1", "alice"]);
+/// let mut b = FdtSketch::new(12);
+/// b.update(&["10.0.0.1", "alice"]);
+/// b.update(&["10.0.0.1", "bob"]);
+///
+/// let mut union = FdtUnion::new(12);
+/// union.update(&a).unwrap();
+/// union.update(&b).unwrap();
+/// assert_eq!(union.to_sketch().estimate(), 2.0);
+/// ```
+#[derive(Debug)]
+pub struct FdtUnion {
+    lg_k: u8,
+    union: TupleUnion<FdtSummaryPolicy>,
+}
+
+impl FdtUnion {
+    /// Creates a union with the given lg_k (log2 of nominal size k).
+    ///
+    /// # Panics
+    ///
+    /// If lg_k is not in range [5, 26]
+    pub fn new(lg_k: u8) -> Self {
+        Self {
+            lg_k,
+            union: TupleUnionBuilder::new(FdtSummaryPolicy).lg_k(lg_k).build(),
+        }
+    }
+
+    /// Merges a sketch into the union.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the sketch was built with a different seed than the union.
+    pub fn update(&mut self, sketch: &FdtSketch) -> Result<(), Error> {
+        self.union.update(&sketch.sketch)
+    }
+
+    /// Returns the union as a sketch with the union's lg_k.
+    pub fn to_sketch(&self) -> FdtSketch {
+        FdtSketch::from_compact(&self.union.to_sketch(true), self.lg_k)
+            .expect("union result has the union's seed")
+    }
+
+    /// Resets the union to its initial empty state.
+    pub fn reset(&mut self) {
+        self.union.reset();
+    }
+
+    /// Returns the estimated size of the union in bytes.
+    pub fn estimated_size(&self) -> usize {
+        self.union.estimated_size()
+    }
+}
+
+impl MemoryUsage for FdtUnion {
+    fn memory_usage(&self) -> usize {
+        self.estimated_size()
+    }
+}
+
+/// A group of tuples sharing a primary key, as returned by [`FdtSketch::result`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct FdtGroup {
+    primary_key: String,
+    count: u64,
+    estimate: f64,
+    upper_bound: f64,
+    lower_bound: f64,
+    fraction: f64,
+    rse: f64,
+}
+
+impl FdtGroup {
+    /// Returns the primary key of the group.
+    pub fn primary_key(&self) -> &str {
+        &self.primary_key
+    }
+
+    /// Returns the number of retained tuples with this primary key.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Returns the estimated number of distinct tuples with this primary key.
+    pub fn estimate(&self) -> f64 {
+        self.estimate
+    }
+
+    /// Returns the upper bound of the estimate.
+    pub fn upper_bound(&self) -> f64 {
+        self.upper_bound
+    }
+
+    /// Returns the lower bound of the estimate.
+    pub fn lower_bound(&self) -> f64 {
+        self.lower_bound
+    }
+
+    /// Returns the fraction of the retained tuples that belong to this group.
+    pub fn fraction(&self) -> f64 {
+        self.fraction
+    }
+
+    /// Returns the relative standard error of the estimate, at one standard deviation.
+    pub fn rse(&self) -> f64 {
+        self.rse
+    }
+}
+
+/// Computes the lg_k needed to reach `rse` for groups holding a `threshold` share of the tuples.
+fn lg_k_for_threshold(threshold: f64, rse: f64) -> u8 {
+    assert!(
+        threshold > 0.0 && threshold <= 1.0,
+        "threshold must be in (0.0, 1.0], got {threshold}"
+    );
+    assert!(
+        rse > 0.0 && rse <= 1.0,
+        "rse must be in (0.0, 1.0], got {rse}"
+    );
+    let k = (1.0 / (threshold * rse * rse)).ceil();
+    let lg_k = k.log2().ceil();
+    assert!(
+        lg_k <= MAX_LG_K as f64,
+        "threshold {threshold} and rse {rse} require lg_k {lg_k}, which exceeds {MAX_LG_K}"
+    );
+    (lg_k as u8).max(MIN_LG_K)
+}
+
+/// Tuple key hashed as its strings joined with commas, without building the joined string.
+///
+/// Writing the pieces one at a time gives the same hash as a single write of the joined bytes,
+/// which is how the Java `ArrayOfStringsSketch` hashes its keys.
+struct JoinedKey<'a, T>(&'a [T]);
+
+impl<T: AsRef<str>> JoinedKey<'_, T> {
+    fn is_empty(&self) -> bool {
+        match self.0 {
+            [] => true,
+            [s] => s.as_ref().is_empty(),
+            _ => false,
+        }
+    }
+}
+
+impl<T: AsRef<str>> Hash for JoinedKey<'_, T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for (i, s) in self.0.iter().enumerate() {
+            if i > 0 {
+                state.write(b",");
+            }
+            state.write(s.as_ref().as_bytes());
+        }
+    }
+}
+
+/// Summary policy retaining the first tuple seen for each key.
+#[derive(Debug, Clone, Copy)]
+struct FdtSummaryPolicy;
+
+impl SummaryPolicy for FdtSummaryPolicy {
+    type Summary = FdtSummary;
+
+    fn create(&self) -> Self::Summary {
+        FdtSummary(Vec::new())
+    }
+}
+
+impl<T: AsRef<str>> SummaryUpdatePolicy<&[T]> for FdtSummaryPolicy {
+    fn update(&self, summary: &mut Self::Summary, value: &[T]) {
+        if summary.0.is_empty() {
+            summary
+                .0
+                .extend(value.iter().map(|s| s.as_ref().to_string()));
+        }
+    }
+}
+
+impl SummaryCombinePolicy for FdtSummaryPolicy {
+    fn combine(&self, _summary: &mut Self::Summary, _other: &Self::Summary) {
+        // Both summaries hold the tuple of the same key; keep the existing one.
+    }
+}
+
+/// The tuple retained for a key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FdtSummary(Vec<String>);
+
+/// Summaries use the Java `ArrayOfStringsSummary` encoding: a 4-byte little-endian total length,
+/// a 1-byte string count, then each string as a 4-byte little-endian byte length followed by its
+/// UTF-8 bytes.
+///
+/// # Panics
+///
+/// Serializing panics if the tuple holds more than 127 strings.
+impl TupleSummaryValue for FdtSummary {
+    fn serialize_size(&self) -> usize {
+        4 + 1 + self.0.iter().map(|s| 4 + s.len()).sum::<usize>()
+    }
+
+    fn serialize_value(&self, bytes: &mut SketchBytes<'_>) {
+        assert!(
+            self.0.len() <= MAX_TUPLE_LEN,
+            "string array summary must hold at most {MAX_TUPLE_LEN} strings, got {}",
+            self.0.len()
+        );
+        bytes.write_u32_le(self.serialize_size() as u32);
+        bytes.write_u8(self.0.len() as u8);
+        for s in &self.0 {
+            bytes.write_u32_le(s.len() as u32);
+            bytes.write(s.as_bytes());
+        }
+    }
+
+    fn deserialize_value(cursor: &mut SketchSlice<'_>) -> Result<Self, Error> {
+        let total_len = cursor
+            .read_u32_le()
+            .map_err(|_| Error::insufficient_data("failed to read string array summary length"))?
+            as usize;
+        let num_strings = cursor
+            .read_u8()
+            .map_err(|_| Error::insufficient_data("failed to read string array summary count"))?;
+        if num_strings as usize > MAX_TUPLE_LEN {
+            return Err(Error::deserial(format!(
+                "string array summary must hold at most {MAX_TUPLE_LEN} strings, got {num_strings}"
+            )));
+        }
+
+        let mut strings = FdtSummary(Vec::with_capacity(num_strings as usize));
+        for _ in 0..num_strings {
+            let len = cursor
+                .read_u32_le()
+                .map_err(|_| Error::insufficient_data("failed to read string length"))?
+                as usize;
+            let remaining = cursor.remaining();
+            if remaining.len() < len {
+                return Err(Error::insufficient_data(format!(
+                    "string needs {len} bytes, got {}",
+                    remaining.len()
+                )));
+            }
+            let s = std::str::from_utf8(&remaining[..len])
+                .map_err(|_| Error::deserial("string array summary is not valid UTF-8"))?;
+            strings.0.push(s.to_string());
+            cursor.advance(len as u64);
+        }
+
+        if strings.serialize_size() != total_len {
+            return Err(Error::deserial(format!(
+                "string array summary length mismatch: header says {total_len}, got {}",
+                strings.serialize_size()
+            )));
+        }
+        Ok(strings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorKind;
+    use crate::hash::DEFAULT_UPDATE_SEED;
+    use crate::hash::MurmurHash3X64128;
+
+    #[test]
+    fn lg_k_for_threshold_matches_java() {
+        assert_eq!(lg_k_for_threshold(0.01, 0.05), 16);
+        assert_eq!(lg_k_for_threshold(0.1, 0.1), 10);
+        assert_eq!(lg_k_for_threshold(1.0, 1.0), MIN_LG_K);
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds")]
+    fn lg_k_for_threshold_rejects_oversized() {
+        lg_k_for_threshold(1e-6, 0.01);
+    }
+
+    #[test]
+    fn joined_key_hashes_joined_bytes() {
+        for (tuple, joined) in [
+            (&["10.0.0.1", "alice"][..], "10.0.0.1,alice"),
+            (&["a", "", "b"][..], "a,,b"),
+            (
+                &["a much longer first dimension value"][..],
+                "a much longer first dimension value",
+            ),
+        ] {
+            let mut hasher = MurmurHash3X64128::with_seed(DEFAULT_UPDATE_SEED);
+            JoinedKey(tuple).hash(&mut hasher);
+            assert_eq!(
+                hasher.finish128(),
+                MurmurHash3X64128::hash_bytes(joined.as_bytes(), DEFAULT_UPDATE_SEED)
+            );
+        }
+        assert!(JoinedKey::<&str>(&[]).is_empty());
+        assert!(JoinedKey(&[""]).is_empty());
+        assert!(!JoinedKey(&["", ""]).is_empty());
+    }
+
+    #[test]
+    fn summary_value_round_trip() {
+        let summary = FdtSummary(vec![
+            "a".to_string(),
+            String::new(),
+            "\u{00e9}t\u{00e9}".to_string(),
+        ]);
+
+        let mut bytes = SketchBytes::with_capacity(summary.serialize_size() + 1);
+        summary.serialize_value(&mut bytes);
+        bytes.write_u8(0xAA); // trailing byte belonging to the next entry
+        let bytes = bytes.into_bytes();
+        assert_eq!(bytes.len(), summary.serialize_size() + 1);
+        assert_eq!(&bytes[..5], &[23, 0, 0, 0, 3]);
+
+        let mut cursor = SketchSlice::new(&bytes);
+        let decoded = FdtSummary::deserialize_value(&mut cursor).unwrap();
+        assert_eq!(decoded, summary);
+        assert_eq!(cursor.remaining(), &[0xAA]);
+    }
+
+    #[test]
+    fn summary_value_rejects_truncated_input() {
+        let summary = FdtSummary(vec!["apple".to_string()]);
+        let mut bytes = SketchBytes::with_capacity(summary.serialize_size());
+        summary.serialize_value(&mut bytes);
+        let bytes = bytes.into_bytes();
+
+        let mut cursor = SketchSlice::new(&bytes[..bytes.len() - 1]);
+        let err = FdtSummary::deserialize_value(&mut cursor).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+}
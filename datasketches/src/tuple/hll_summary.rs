@@ -0,0 +1,199 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Ready-made summary that holds an HLL sketch per key.
+//!
+//! Pairing a Tuple sketch with [`HllSummaryPolicy`] yields a "sketch of sketches": the Tuple
+//! sketch estimates the number of distinct keys (for example campaigns), while the HLL summary of
+//! each retained key estimates the number of distinct values seen with it (for example users).
+
+use std::hash::Hash;
+
+use crate::codec::SketchBytes;
+use crate::codec::SketchSlice;
+use crate::error::Error;
+use crate::hll::HllSketch;
+use crate::hll::HllType;
+use crate::hll::HllUnion;
+use crate::hll::MAX_LG_K;
+use crate::hll::MIN_LG_K;
+use crate::tuple::SummaryCombinePolicy;
+use crate::tuple::SummaryPolicy;
+use crate::tuple::SummaryUpdatePolicy;
+use crate::tuple::TupleSummaryValue;
+
+/// Summary policy whose summary is an [`HllSketch`].
+///
+/// Update values of any hashable type are inserted into the HLL sketch of their key. When used as
+/// a combine policy, summaries of shared keys are merged with an HLL union, so the same policy can
+/// drive both the Tuple sketch and the Tuple union.
+///
+/// # Examples
+///
+/// ```
+/// # use datasketches::hll::HllType;
+/// # use datasketches::tuple::HllSummaryPolicy;
+/// # use datasketches::tuple::TupleSketchBuilder;
+/// let policy = HllSummaryPolicy::new(10, HllType::Hll8);
+/// let mut sketch = TupleSketchBuilder::new(policy).build();
+/// sketch.update("campaign-a", "user-1");
+/// sketch.update("campaign-a", "user-2");
+/// sketch.update("campaign-b", "user-1");
+///
+/// assert_eq!(sketch.estimate(), 2.0);
+/// let max_users = sketch
+///     .iter()
+///     .map(|(_, users)| users.estimate().round())
+///     .fold(0.0, f64::max);
+/// assert_eq!(max_users, 2.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HllSummaryPolicy {
+    lg_k: u8,
+    hll_type: HllType,
+}
+
+impl HllSummaryPolicy {
+    /// Creates a policy whose summaries are HLL sketches with the given lg_k and type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lg_k` is not in the range `[4, 21]`.
+    pub fn new(lg_k: u8, hll_type: HllType) -> Self {
+        assert!(
+            (MIN_LG_K..=MAX_LG_K).contains(&lg_k),
+            "lg_k must be in [{MIN_LG_K}, {MAX_LG_K}], got {lg_k}"
+        );
+        Self { lg_k, hll_type }
+    }
+
+    /// Returns the lg_k of the summary sketches.
+    pub fn lg_k(&self) -> u8 {
+        self.lg_k
+    }
+
+    /// Returns the HLL type of the summary sketches.
+    pub fn hll_type(&self) -> HllType {
+        self.hll_type
+    }
+}
+
+impl SummaryPolicy for HllSummaryPolicy {
+    type Summary = HllSketch;
+
+    fn create(&self) -> Self::Summary {
+        HllSketch::new(self.lg_k, self.hll_type)
+    }
+}
+
+impl<T: Hash> SummaryUpdatePolicy<T> for HllSummaryPolicy {
+    fn update(&self, summary: &mut Self::Summary, value: T) {
+        summary.update(value);
+    }
+}
+
+impl SummaryCombinePolicy for HllSummaryPolicy {
+    fn combine(&self, summary: &mut Self::Summary, other: &Self::Summary) {
+        let mut union = HllUnion::new(self.lg_k);
+        union.update(summary);
+        union.update(other);
+        *summary = union.to_sketch(self.hll_type);
+    }
+}
+
+/// HLL summaries are stored as a 4-byte little-endian length followed by the serialized sketch.
+impl TupleSummaryValue for HllSketch {
+    fn serialize_size(&self) -> usize {
+        4 + self.serialized_size_bytes()
+    }
+
+    fn serialize_value(&self, bytes: &mut SketchBytes<'_>) {
+        let sketch = self.serialize();
+        bytes.write_u32_le(sketch.len() as u32);
+        bytes.write(&sketch);
+    }
+
+    fn deserialize_value(cursor: &mut SketchSlice<'_>) -> Result<Self, Error> {
+        let len = cursor
+            .read_u32_le()
+            .map_err(|_| Error::insufficient_data("failed to read HLL summary length"))?
+            as usize;
+        let remaining = cursor.remaining();
+        if remaining.len() < len {
+            return Err(Error::insufficient_data(format!(
+                "HLL summary needs {len} bytes, got {}",
+                remaining.len()
+            )));
+        }
+        let sketch = HllSketch::deserialize(&remaining[..len])?;
+        cursor.advance(len as u64);
+        Ok(sketch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorKind;
+
+    #[test]
+    fn combine_unions_summaries() {
+        let policy = HllSummaryPolicy::new(8, HllType::Hll4);
+        let mut a = policy.create();
+        let mut b = policy.create();
+        for i in 0..100 {
+            policy.update(&mut a, i);
+            policy.update(&mut b, i + 50);
+        }
+        policy.combine(&mut a, &b);
+        assert_eq!(a.target_type(), HllType::Hll4);
+        assert_eq!(a.lg_config_k(), 8);
+        assert!((a.estimate() - 150.0).abs() < 15.0);
+    }
+
+    #[test]
+    fn summary_value_round_trip() {
+        let policy = HllSummaryPolicy::new(10, HllType::Hll6);
+        let mut summary = policy.create();
+        for i in 0..1000 {
+            policy.update(&mut summary, i);
+        }
+
+        let mut bytes = SketchBytes::with_capacity(summary.serialize_size() + 1);
+        summary.serialize_value(&mut bytes);
+        bytes.write_u8(0xAA); // trailing byte belonging to the next entry
+        let bytes = bytes.into_bytes();
+        assert_eq!(bytes.len(), summary.serialize_size() + 1);
+
+        let mut cursor = SketchSlice::new(&bytes);
+        let decoded = HllSketch::deserialize_value(&mut cursor).unwrap();
+        assert_eq!(decoded, summary);
+        assert_eq!(cursor.remaining(), &[0xAA]);
+    }
+
+    #[test]
+    fn summary_value_rejects_truncated_input() {
+        let summary = HllSummaryPolicy::new(10, HllType::Hll8).create();
+        let mut bytes = SketchBytes::with_capacity(summary.serialize_size());
+        summary.serialize_value(&mut bytes);
+        let bytes = bytes.into_bytes();
+
+        let mut cursor = SketchSlice::new(&bytes[..bytes.len() - 1]);
+        let err = HllSketch::deserialize_value(&mut cursor).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+}
@@ -26,7 +26,10 @@
 //! creates summaries, while [`SummaryUpdatePolicy`] folds update values into them. Summaries that
 //! implement `Default` and `AddAssign` can use [`DefaultUpdatePolicy`]. Set operations combine the
 //! summaries of shared keys through [`SummaryCombinePolicy`]; the union defaults to
-//! [`DefaultUnionPolicy`].
+//! [`DefaultUnionPolicy`]. With the `hll` feature enabled, `HllSummaryPolicy` keeps an HLL
+//! sketch per key for two-level distinct counts. [`FdtSketch`] builds on the Tuple sketch to
+//! estimate distinct tuple counts per primary key (Frequent Distinct Tuples), and [`FdtUnion`]
+//! merges such sketches.
 //!
 //! # Usage
 //!
@@ -38,13 +41,21 @@
 //! assert!(sketch.estimate() >= 1.0);
 //! ```
 
+mod fdt;
 mod hash_table;
+#[cfg(feature = "hll")]
+mod hll_summary;
 mod policy;
 mod serialization;
 mod sketch;
 mod union;
 
+pub use self::fdt::FdtGroup;
+pub use self::fdt::FdtSketch;
+pub use self::fdt::FdtUnion;
 pub use self::hash_table::TupleEntry;
+#[cfg(feature = "hll")]
+pub use self::hll_summary::HllSummaryPolicy;
 pub use self::policy::DefaultUnionPolicy;
 pub use self::policy::DefaultUpdatePolicy;
 pub use self::policy::SummaryCombinePolicy;
@@ -27,12 +27,11 @@
 
 use crate::codec::SketchBytes;
 use crate::codec::SketchSlice;
+use crate::codec::format;
 use crate::error::Error;
 
-/// Current serial version written by this implementation.
-pub(super) const SERIAL_VERSION: u8 = 3;
-/// Legacy serial version still accepted on read.
-pub(super) const SERIAL_VERSION_LEGACY: u8 = 1;
+/// Current serial version written by this implementation. The legacy version 1 is still read.
+pub(super) const SERIAL_VERSION: u8 = format::tuple::SERIAL_VERSION;
 
 /// Current sketch-type byte written by this implementation.
 pub(super) const SKETCH_TYPE: u8 = 1;
@@ -51,7 +50,7 @@ pub trait TupleSummaryValue: Sized {
     fn serialize_size(&self) -> usize;
 
     /// Serializes the summary into the byte buffer.
-    fn serialize_value(&self, bytes: &mut SketchBytes);
+    fn serialize_value(&self, bytes: &mut SketchBytes<'_>);
 
     /// Deserializes a summary from the byte cursor, advancing it past the bytes consumed.
     ///
@@ -68,7 +67,7 @@ macro_rules! impl_primitive_summary {
                 size_of::<$name>()
             }
 
-            fn serialize_value(&self, bytes: &mut SketchBytes) {
+            fn serialize_value(&self, bytes: &mut SketchBytes<'_>) {
                 bytes.$write(*self);
             }
 
@@ -29,11 +29,14 @@ use crate::codec::SketchSlice;
 use crate::codec::assert::ensure_preamble_longs_in_range;
 use crate::codec::assert::insufficient_data;
 use crate::codec::family::Family;
+use crate::common::MemoryUsage;
 use crate::common::NumStdDev;
 use crate::common::ResizeFactor;
+use crate::common::SketchBuilder;
 use crate::error::Error;
 use crate::hash::DEFAULT_UPDATE_SEED;
 use crate::hash::compute_seed_hash;
+use crate::hash::named_seed_hash;
 use crate::thetacommon::RawThetaSketchView;
 use crate::thetacommon::binomial_bounds;
 use crate::thetacommon::constants::DEFAULT_LG_K;
@@ -49,7 +52,6 @@ use crate::tuple::hash_table::TupleHashTable;
 use crate::tuple::policy::SummaryPolicy;
 use crate::tuple::policy::SummaryUpdatePolicy;
 use crate::tuple::serialization::SERIAL_VERSION;
-use crate::tuple::serialization::SERIAL_VERSION_LEGACY;
 use crate::tuple::serialization::SKETCH_TYPE;
 use crate::tuple::serialization::SKETCH_TYPE_LEGACY;
 use crate::tuple::serialization::TupleSummaryValue;
@@ -217,6 +219,15 @@ where
     }
 }
 
+impl<P> MemoryUsage for TupleSketch<P>
+where
+    P: SummaryPolicy,
+{
+    fn memory_usage(&self) -> usize {
+        self.estimated_size()
+    }
+}
+
 impl<P> TupleSketch<P>
 where
     P: SummaryPolicy,
@@ -421,6 +432,47 @@ impl<S> CompactTupleSketch<S> {
     /// assert!(!bytes.is_empty());
     /// ```
     pub fn serialize(&self) -> Vec<u8>
+    where
+        S: TupleSummaryValue,
+    {
+        let mut bytes = SketchBytes::with_capacity(0);
+        self.write_to(&mut bytes);
+        bytes.into_bytes()
+    }
+
+    /// Serializes this sketch into `buf`, returning the number of bytes written.
+    ///
+    /// Writes the same bytes as [`serialize`](Self::serialize) without allocating the output.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `buf` is too short to hold the serialized sketch. The sketch is
+    /// written up to the end of `buf` before the length is checked, so the contents of `buf`
+    /// are unspecified when an error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use datasketches::tuple::{CompactTupleSketch, DefaultUpdatePolicy, TupleSketchBuilder};
+    /// let policy = DefaultUpdatePolicy::<u64>::default();
+    /// let mut sketch = TupleSketchBuilder::new(policy).build();
+    /// sketch.update("apple", 1);
+    ///
+    /// let mut buf = [0u8; 64];
+    /// let len = sketch.compact(true).serialize_into_slice(&mut buf).unwrap();
+    /// let decoded = CompactTupleSketch::<u64>::deserialize(&buf[..len]).unwrap();
+    /// assert_eq!(decoded.num_retained(), 1);
+    /// ```
+    pub fn serialize_into_slice(&self, buf: &mut [u8]) -> Result<usize, Error>
+    where
+        S: TupleSummaryValue,
+    {
+        let mut bytes = SketchBytes::from_slice(buf);
+        self.write_to(&mut bytes);
+        bytes.finish()
+    }
+
+    fn write_to(&self, bytes: &mut SketchBytes<'_>)
     where
         S: TupleSummaryValue,
     {
@@ -430,7 +482,7 @@ impl<S> CompactTupleSketch<S> {
             .iter()
             .map(|entry| 8 + entry.summary().serialize_size())
             .sum();
-        let mut bytes = SketchBytes::with_capacity(8 * pre_longs as usize + entries_size);
+        bytes.reserve(8 * pre_longs as usize + entries_size);
 
         bytes.write_u8(pre_longs);
         bytes.write_u8(SERIAL_VERSION);
@@ -458,9 +510,8 @@ impl<S> CompactTupleSketch<S> {
 
         for entry in &self.entries {
             bytes.write_u64_le(entry.hash());
-            entry.summary().serialize_value(&mut bytes);
+            entry.summary().serialize_value(bytes);
         }
-        bytes.into_bytes()
     }
 
     /// Deserializes a compact Tuple sketch using the default seed.
@@ -501,12 +552,7 @@ impl<S> CompactTupleSketch<S> {
             Family::TUPLE.min_pre_longs..=Family::TUPLE.max_pre_longs,
             pre_longs,
         )?;
-        if ser_ver != SERIAL_VERSION && ser_ver != SERIAL_VERSION_LEGACY {
-            return Err(Error::deserial(format!(
-                "unsupported serial version: expected {} or {}, got {ser_ver}",
-                SERIAL_VERSION, SERIAL_VERSION_LEGACY,
-            )));
-        }
+        Family::TUPLE.validate_serial_version(ser_ver)?;
         if sketch_type != SKETCH_TYPE && sketch_type != SKETCH_TYPE_LEGACY {
             return Err(Error::deserial(format!(
                 "unsupported sketch type: expected {} or {}, got {sketch_type}",
@@ -530,7 +576,9 @@ impl<S> CompactTupleSketch<S> {
         let expected_seed_hash = compute_seed_hash(seed);
         if seed_hash != expected_seed_hash {
             return Err(Error::deserial(format!(
-                "incompatible seed hash: expected {expected_seed_hash}, got {seed_hash}",
+                "incompatible seed hash: expected {}, got {}",
+                named_seed_hash(expected_seed_hash),
+                named_seed_hash(seed_hash)
             )));
         }
 
@@ -566,6 +614,12 @@ impl<S> CompactTupleSketch<S> {
     }
 }
 
+impl<S> MemoryUsage for CompactTupleSketch<S> {
+    fn memory_usage(&self) -> usize {
+        self.estimated_size()
+    }
+}
+
 impl<S: Clone> RawThetaSketchView<TupleEntry<S>> for CompactTupleSketch<S> {
     fn seed_hash(&self) -> u16 {
         self.seed_hash
@@ -704,6 +758,56 @@ where
             policy: self.policy,
         }
     }
+
+    /// Builds a [`TupleSketch`] that resumes from the retained entries of a compact sketch.
+    ///
+    /// The result takes the lower of its own starting theta and the compact sketch's theta, and
+    /// retains clones of the compact sketch's summaries.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `sketch` is non-empty and its seed hash does not match the builder's
+    /// seed.
+    pub(crate) fn build_from_compact(
+        self,
+        sketch: &CompactTupleSketch<P::Summary>,
+    ) -> Result<TupleSketch<P>, Error>
+    where
+        P::Summary: Clone,
+    {
+        let mut result = self.build();
+        if sketch.is_empty() {
+            return Ok(result);
+        }
+
+        if sketch.seed_hash() != result.seed_hash() {
+            return Err(Error::invalid_argument(format!(
+                "incompatible seed hash: expected {}, got {}",
+                named_seed_hash(result.seed_hash()),
+                named_seed_hash(sketch.seed_hash())
+            )));
+        }
+
+        let table = &mut result.table;
+        table.set_empty(false);
+        table.set_theta(table.theta().min(sketch.theta64()));
+        for (hash, summary) in sketch.iter() {
+            table.try_insert_hash(hash, |_| Some(summary.clone()));
+        }
+        Ok(result)
+    }
+}
+
+impl<P> SketchBuilder for TupleSketchBuilder<P>
+where
+    P: SummaryPolicy,
+{
+    type Sketch = TupleSketch<P>;
+
+    /// Build the TupleSketch. This never fails: the setters validate their arguments.
+    fn try_build(self) -> Result<TupleSketch<P>, Error> {
+        Ok(self.build())
+    }
 }
 
 #[cfg(test)]
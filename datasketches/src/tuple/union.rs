@@ -22,6 +22,7 @@
 //! behavior is that when an incoming key already exists in the union, the two summaries are
 //! combined with a [`SummaryCombinePolicy`] instead of one being dropped.
 
+use crate::common::MemoryUsage;
 use crate::common::ResizeFactor;
 use crate::error::Error;
 use crate::hash::DEFAULT_UPDATE_SEED;
@@ -124,6 +125,20 @@ where
     pub fn reset(&mut self) {
         self.raw.reset();
     }
+
+    /// Returns the estimated size of the union in bytes.
+    pub fn estimated_size(&self) -> usize {
+        size_of::<Self>() + self.raw.estimated_size()
+    }
+}
+
+impl<P> MemoryUsage for TupleUnion<P>
+where
+    P: SummaryCombinePolicy,
+{
+    fn memory_usage(&self) -> usize {
+        self.estimated_size()
+    }
 }
 
 /// Builder for [`TupleUnion`].
@@ -24,6 +24,7 @@ use std::path::PathBuf;
 
 use common::serialization_test_data;
 use datasketches::bloom::BloomFilter;
+use datasketches::bloom::BloomFilterBuilder;
 
 fn test_bloom_filter_file(path: PathBuf, expected_num_items: u64, expected_num_hashes: u16) {
     let bytes = fs::read(&path).unwrap();
@@ -93,6 +94,15 @@ fn test_bloom_filter_file(path: PathBuf, expected_num_items: u64, expected_num_h
         )
     });
 
+    // Java and C++ write the same layout with zeroed reserved bytes, so the image round-trips
+    // byte for byte.
+    assert_eq!(
+        serialized_bytes,
+        bytes,
+        "serialized bytes differ from {}",
+        path.display()
+    );
+
     // Check that both filters are functionally equivalent
     assert_eq!(
         filter1.num_hashes(),
@@ -222,3 +232,88 @@ fn test_cpp_bloom_n30000000_h5() {
     let path = serialization_test_data("cpp_generated_files", "bf_n30000000_h5_cpp.sk");
     test_bloom_filter_file(path, 30000000, 5);
 }
+
+/// Assembles an image field by field following the preamble layout of `BloomFilter.java`.
+fn java_layout_image(
+    num_hashes: i16,
+    seed: u64,
+    words: &[u64],
+    num_bits_set: Option<i64>,
+    reserved: u8,
+) -> Vec<u8> {
+    let mut bytes = vec![];
+    let empty = num_bits_set.is_none();
+    bytes.push(if empty { 3 } else { 4 });
+    bytes.push(1); // serial version
+    bytes.push(21); // family ID
+    bytes.push(if empty { 0x04 } else { 0 });
+    bytes.extend_from_slice(&num_hashes.to_le_bytes());
+    bytes.extend_from_slice(&[reserved; 2]);
+    bytes.extend_from_slice(&seed.to_le_bytes());
+    bytes.extend_from_slice(&(words.len() as i32).to_le_bytes());
+    bytes.extend_from_slice(&[reserved; 4]);
+    if let Some(num_bits_set) = num_bits_set {
+        bytes.extend_from_slice(&num_bits_set.to_le_bytes());
+        for word in words {
+            bytes.extend_from_slice(&word.to_le_bytes());
+        }
+    }
+    bytes
+}
+
+#[test]
+fn test_java_layout() {
+    let mut filter = BloomFilterBuilder::with_size(256, 5).seed(42).build();
+    for i in 0..20_u64 {
+        filter.insert(i);
+    }
+    let bytes = filter.serialize();
+    let words = bytes[32..]
+        .chunks_exact(8)
+        .map(|word| u64::from_le_bytes(word.try_into().unwrap()))
+        .collect::<Vec<_>>();
+    assert_eq!(words.len(), 4);
+    assert_eq!(
+        bytes,
+        java_layout_image(5, 42, &words, Some(filter.bits_used() as i64), 0)
+    );
+
+    let empty = BloomFilterBuilder::with_size(256, 5).seed(42).build();
+    assert_eq!(
+        empty.serialize(),
+        java_layout_image(5, 42, &[0; 4], None, 0)
+    );
+}
+
+#[test]
+fn test_java_layout_reserved_bytes_and_dirty_count() {
+    let words = [0b1011, 0, 1 << 63];
+
+    // Reserved bytes are ignored, and a bit count of -1 is recounted.
+    for num_bits_set in [4, -1] {
+        let bytes = java_layout_image(3, 7, &words, Some(num_bits_set), 0xab);
+        let filter = BloomFilter::deserialize(&bytes).unwrap();
+        assert_eq!(filter.num_hashes(), 3);
+        assert_eq!(filter.seed(), 7);
+        assert_eq!(filter.capacity(), 192);
+        assert_eq!(filter.bits_used(), 4);
+        assert!(!filter.is_empty());
+
+        // Reserved bytes are written back as zero; the bit count is kept as read.
+        assert_eq!(
+            filter.serialize(),
+            java_layout_image(3, 7, &words, Some(num_bits_set), 0)
+        );
+    }
+
+    let bytes = java_layout_image(3, 7, &words, None, 0xab);
+    let filter = BloomFilter::deserialize(&bytes).unwrap();
+    assert!(filter.is_empty());
+    assert_eq!(filter.capacity(), 192);
+    assert_eq!(filter.serialize(), java_layout_image(3, 7, &words, None, 0));
+
+    // Java reads the hash count as a signed short.
+    let mut bytes = java_layout_image(3, 7, &words, Some(4), 0);
+    bytes[4..6].copy_from_slice(&(-3_i16).to_le_bytes());
+    assert!(BloomFilter::deserialize(&bytes).is_err());
+}
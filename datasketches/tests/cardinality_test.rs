@@ -0,0 +1,108 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+#![cfg(all(feature = "cpc", feature = "hll", feature = "theta", feature = "tuple"))]
+
+use datasketches::cardinality::estimate_from_bytes;
+use datasketches::cardinality::estimate_from_bytes_with_seed;
+use datasketches::common::NumStdDev;
+use datasketches::cpc::CpcSketch;
+use datasketches::error::ErrorKind;
+use datasketches::hll::HllSketch;
+use datasketches::hll::HllType;
+use datasketches::theta::ThetaSketchBuilder;
+use datasketches::tuple::DefaultUpdatePolicy;
+use datasketches::tuple::TupleSketchBuilder;
+
+#[test]
+fn test_estimate_from_bytes() {
+    for n in [0, 10, 10_000] {
+        let mut hll = HllSketch::new(12, HllType::Hll6);
+        let mut cpc = CpcSketch::new(11);
+        let mut theta = ThetaSketchBuilder::default().build();
+        for i in 0..n {
+            hll.update(i);
+            cpc.update(i);
+            theta.update(i);
+        }
+
+        let images = [
+            (
+                "HLL",
+                hll.serialize(),
+                hll.estimate(),
+                hll.upper_bound(NumStdDev::One),
+            ),
+            (
+                "CPC",
+                cpc.serialize(),
+                cpc.estimate(),
+                cpc.upper_bound(NumStdDev::One),
+            ),
+            (
+                "THETA",
+                theta.compact(true).serialize(),
+                theta.estimate(),
+                theta.upper_bound(NumStdDev::One),
+            ),
+            (
+                "QUICKSELECT",
+                theta.serialize(),
+                theta.estimate(),
+                theta.upper_bound(NumStdDev::One),
+            ),
+        ];
+        for (family_name, bytes, estimate, upper_bound) in images {
+            let result = estimate_from_bytes(&bytes, NumStdDev::One).unwrap();
+            assert_eq!(result.family_name(), family_name);
+            assert_eq!(result.estimate(), estimate, "{family_name} n={n}");
+            assert_eq!(result.upper_bound(), upper_bound, "{family_name} n={n}");
+            let (lb, est, ub) = result.to_tuple();
+            assert!(lb <= est && est <= ub, "{family_name} n={n}");
+        }
+    }
+}
+
+#[test]
+fn test_estimate_from_bytes_with_seed() {
+    let mut cpc = CpcSketch::with_seed(11, 42);
+    let mut hll = HllSketch::with_seed(12, HllType::Hll8, 42);
+    for i in 0..100 {
+        cpc.update(i);
+        hll.update(i);
+    }
+
+    let bytes = cpc.serialize();
+    assert!(estimate_from_bytes(&bytes, NumStdDev::Two).is_err());
+    let result = estimate_from_bytes_with_seed(&bytes, NumStdDev::Two, 42).unwrap();
+    assert_eq!(result.estimate(), cpc.estimate());
+
+    // HLL images carry no seed hash
+    let bytes = hll.serialize();
+    let result = estimate_from_bytes(&bytes, NumStdDev::Two).unwrap();
+    assert_eq!(result.estimate(), hll.estimate());
+}
+
+#[test]
+fn test_estimate_from_bytes_rejects_other_families() {
+    let mut tuple = TupleSketchBuilder::new(DefaultUpdatePolicy::<u64>::default()).build();
+    tuple.update(1, 1u64);
+    let err = estimate_from_bytes(&tuple.compact(true).serialize(), NumStdDev::Two).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+
+    assert!(estimate_from_bytes(&[], NumStdDev::Two).is_err());
+    assert!(estimate_from_bytes(&[1, 2, 255], NumStdDev::Two).is_err());
+}
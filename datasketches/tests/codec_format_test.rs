@@ -0,0 +1,245 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+#![cfg(all(
+    feature = "bloom",
+    feature = "countmin",
+    feature = "cpc",
+    feature = "frequencies",
+    feature = "hll",
+    feature = "tdigest",
+    feature = "theta",
+    feature = "tuple"
+))]
+
+use datasketches::bloom::BloomFilterBuilder;
+use datasketches::codec::format;
+use datasketches::codec::format::PreambleHeader;
+use datasketches::countmin::CountMinSketch;
+use datasketches::cpc::CpcSketch;
+use datasketches::frequencies::FrequentItemsSketch;
+use datasketches::hll::HllSketch;
+use datasketches::hll::HllType;
+use datasketches::tdigest::TDigestMut;
+use datasketches::theta::ThetaSketchBuilder;
+use datasketches::tuple::DefaultUpdatePolicy;
+use datasketches::tuple::TupleSketchBuilder;
+
+/// Checks the header of an empty and a non-empty image of the same family.
+fn check_images(
+    empty: &[u8],
+    non_empty: &[u8],
+    family_id: u8,
+    serial_version: u8,
+    flags_byte: usize,
+    empty_flag: u8,
+) {
+    for (bytes, is_empty) in [(empty, true), (non_empty, false)] {
+        let header = PreambleHeader::read(bytes).unwrap();
+        assert_eq!(header.family_id(), family_id);
+        assert_eq!(header.serial_version(), serial_version);
+        assert_eq!(bytes[flags_byte] & empty_flag != 0, is_empty, "{bytes:?}");
+    }
+}
+
+#[test]
+fn test_bloom_format() {
+    let mut filter = BloomFilterBuilder::with_size(128, 3).build();
+    let empty = filter.serialize();
+    filter.insert(1);
+    let non_empty = filter.serialize();
+    check_images(
+        &empty,
+        &non_empty,
+        format::bloom::FAMILY_ID,
+        format::bloom::SERIAL_VERSION,
+        format::bloom::FLAGS_BYTE,
+        format::bloom::FLAGS_IS_EMPTY,
+    );
+    assert_eq!(empty[0], format::bloom::PREAMBLE_LONGS_EMPTY);
+    assert_eq!(non_empty[0], format::bloom::PREAMBLE_LONGS_NONEMPTY);
+}
+
+#[test]
+fn test_countmin_format() {
+    let mut sketch = CountMinSketch::<u64>::new(3, 16);
+    let empty = sketch.serialize();
+    sketch.update(1);
+    let non_empty = sketch.serialize();
+    check_images(
+        &empty,
+        &non_empty,
+        format::countmin::FAMILY_ID,
+        format::countmin::SERIAL_VERSION,
+        format::countmin::FLAGS_BYTE,
+        format::countmin::FLAGS_IS_EMPTY,
+    );
+    assert_eq!(empty[0], format::countmin::PREAMBLE_LONGS);
+    assert_eq!(non_empty[0], format::countmin::PREAMBLE_LONGS);
+}
+
+#[test]
+fn test_cpc_format() {
+    let mut sketch = CpcSketch::new(10);
+    let empty = sketch.serialize();
+    sketch.update(1);
+    let non_empty = sketch.serialize();
+    for bytes in [&empty, &non_empty] {
+        let header = PreambleHeader::read(bytes).unwrap();
+        assert_eq!(header.family_id(), format::cpc::FAMILY_ID);
+        assert_eq!(header.serial_version(), format::cpc::SERIAL_VERSION);
+        assert_eq!(bytes[format::cpc::LG_K_BYTE], 10);
+        let flags = bytes[format::cpc::FLAGS_BYTE];
+        assert_ne!(flags & format::cpc::FLAGS_IS_COMPRESSED, 0);
+        assert_ne!(flags & format::cpc::FLAGS_HAS_HIP, 0);
+    }
+    // One coupon fits in the table; an empty sketch holds neither table nor window.
+    assert_eq!(
+        empty[format::cpc::FLAGS_BYTE] & format::cpc::FLAGS_HAS_TABLE,
+        0
+    );
+    assert_ne!(
+        non_empty[format::cpc::FLAGS_BYTE] & format::cpc::FLAGS_HAS_TABLE,
+        0
+    );
+}
+
+#[test]
+fn test_frequencies_format() {
+    let mut sketch = FrequentItemsSketch::<i64>::new(8);
+    let empty = sketch.serialize();
+    sketch.update(1);
+    let non_empty = sketch.serialize();
+    check_images(
+        &empty,
+        &non_empty,
+        format::frequencies::FAMILY_ID,
+        format::frequencies::SERIAL_VERSION,
+        format::frequencies::FLAGS_BYTE,
+        format::frequencies::FLAGS_IS_EMPTY,
+    );
+    assert_eq!(empty[0], format::frequencies::PREAMBLE_LONGS_EMPTY);
+    assert_eq!(non_empty[0], format::frequencies::PREAMBLE_LONGS_NONEMPTY);
+}
+
+#[test]
+fn test_hll_format() {
+    let mut sketch = HllSketch::new(11, HllType::Hll8);
+    let empty = sketch.serialize();
+    sketch.update(1);
+    let list = sketch.serialize();
+    check_images(
+        &empty,
+        &list,
+        format::hll::FAMILY_ID,
+        format::hll::SERIAL_VERSION,
+        format::hll::FLAGS_BYTE,
+        format::hll::FLAGS_IS_EMPTY,
+    );
+    assert_eq!(list[0], format::hll::LIST_PREAMBLE_INTS);
+    assert_eq!(list[format::hll::LG_K_BYTE], 11);
+    assert_eq!(list[format::hll::MODE_BYTE] & 0x3, 0);
+
+    for i in 0..10_000 {
+        sketch.update(i);
+    }
+    let hll = sketch.serialize();
+    assert_eq!(hll[0], format::hll::HLL_PREAMBLE_INTS);
+    assert_eq!(hll[format::hll::MODE_BYTE] & 0x3, 2);
+    assert_eq!(
+        hll[format::hll::FLAGS_BYTE] & format::hll::FLAGS_IS_OUT_OF_ORDER,
+        0
+    );
+}
+
+#[test]
+fn test_tdigest_format() {
+    let mut digest = TDigestMut::new(100);
+    let empty = digest.serialize();
+    digest.update(1.0);
+    let single = digest.serialize();
+    check_images(
+        &empty,
+        &single,
+        format::tdigest::FAMILY_ID,
+        format::tdigest::SERIAL_VERSION,
+        format::tdigest::FLAGS_BYTE,
+        format::tdigest::FLAGS_IS_EMPTY,
+    );
+    assert_eq!(empty[0], format::tdigest::PREAMBLE_LONGS_EMPTY_OR_SINGLE);
+    assert_eq!(single[0], format::tdigest::PREAMBLE_LONGS_EMPTY_OR_SINGLE);
+    assert_ne!(
+        single[format::tdigest::FLAGS_BYTE] & format::tdigest::FLAGS_IS_SINGLE_VALUE,
+        0
+    );
+
+    digest.update(2.0);
+    let multiple = digest.serialize();
+    assert_eq!(multiple[0], format::tdigest::PREAMBLE_LONGS_MULTIPLE);
+}
+
+#[test]
+fn test_theta_format() {
+    let mut sketch = ThetaSketchBuilder::default().build();
+    let updatable = sketch.serialize();
+    let header = PreambleHeader::read(&updatable).unwrap();
+    assert_eq!(header.family_id(), format::theta::UPDATABLE_FAMILY_ID);
+    assert_eq!(
+        header.preamble_size(),
+        format::theta::UPDATABLE_PREAMBLE_LONGS
+    );
+    assert_eq!(header.serial_version(), format::theta::SERIAL_VERSION);
+
+    let empty = sketch.compact(true).serialize();
+    for i in 0..100 {
+        sketch.update(i);
+    }
+    let compact = sketch.compact(true);
+    check_images(
+        &empty,
+        &compact.serialize(),
+        format::theta::FAMILY_ID,
+        format::theta::SERIAL_VERSION,
+        format::theta::FLAGS_BYTE,
+        format::theta::FLAGS_IS_EMPTY,
+    );
+    let flags = compact.serialize()[format::theta::FLAGS_BYTE];
+    assert_ne!(flags & format::theta::FLAGS_IS_COMPACT, 0);
+    assert_ne!(flags & format::theta::FLAGS_IS_ORDERED, 0);
+
+    let compressed = PreambleHeader::read(&compact.serialize_compressed()).unwrap();
+    assert_eq!(
+        compressed.serial_version(),
+        format::theta::COMPRESSED_SERIAL_VERSION
+    );
+}
+
+#[test]
+fn test_tuple_format() {
+    let mut sketch = TupleSketchBuilder::new(DefaultUpdatePolicy::<u64>::default()).build();
+    let empty = sketch.compact(true).serialize();
+    sketch.update(1, 1);
+    let non_empty = sketch.compact(true).serialize();
+    check_images(
+        &empty,
+        &non_empty,
+        format::tuple::FAMILY_ID,
+        format::tuple::SERIAL_VERSION,
+        format::tuple::FLAGS_BYTE,
+        format::tuple::FLAGS_IS_EMPTY,
+    );
+}
@@ -27,15 +27,14 @@ use googletest::assert_that;
 use googletest::prelude::contains_substring;
 
 // This test validates binary format compatibility (deserialize + byte round-trip) for
-// C++ Count-Min snapshots. It intentionally does not assert estimate equivalence against
-// original input keys because per-row hash seed derivation differs across implementations.
+// C++ Count-Min snapshots, and returns the sketch so that callers can check its estimates.
 fn assert_cpp_snapshot(
     filename: &str,
     seed: u64,
     expected_num_hashes: u8,
     expected_num_buckets: u32,
     expected_total_weight: u64,
-) {
+) -> CountMinSketch<u64> {
     let path = serialization_test_data("cpp_generated_files", filename);
     let bytes = fs::read(&path).unwrap();
 
@@ -49,16 +48,30 @@ fn assert_cpp_snapshot(
 
     let roundtrip = sketch.serialize();
     assert_eq!(roundtrip, bytes, "round-trip bytes differ for {filename}");
+    sketch
 }
 
 #[test]
 fn test_deserialize_cpp_empty_snapshot() {
-    assert_cpp_snapshot("count_min_empty_cpp.sk", 9001, 1, 5, 0);
+    let sketch = assert_cpp_snapshot("count_min_empty_cpp.sk", 9001, 1, 5, 0);
+    assert_eq!(sketch.estimate(1_u64), 0);
+    assert_eq!(sketch.upper_bound(1_u64), 0);
 }
 
 #[test]
 fn test_deserialize_cpp_non_empty_snapshot() {
-    assert_cpp_snapshot("count_min_non_empty_cpp.sk", 9001, 3, 1024, 2850);
+    let sketch = assert_cpp_snapshot("count_min_non_empty_cpp.sk", 9001, 3, 1024, 2850);
+
+    // The C++ generator updates items 0..10 (as uint64_t) with weight 10 * i * i. Finding
+    // each weight in every row checks that the rows are hashed with the C++ seeds.
+    for i in 0..10_u64 {
+        let weight = 10 * i * i;
+        assert_eq!(sketch.estimate(i), weight, "estimate of {i}");
+        assert_eq!(sketch.lower_bound(i), weight, "lower bound of {i}");
+        let upper_bound = sketch.upper_bound(i);
+        assert!(upper_bound >= weight, "upper bound of {i} is {upper_bound}");
+        assert!(upper_bound <= weight + (2850.0 * sketch.relative_error()).ceil() as u64);
+    }
 }
 
 #[test]
@@ -17,7 +17,9 @@
 
 #![cfg(feature = "countmin")]
 
+use datasketches::common::MemoryUsage;
 use datasketches::countmin::CountMinSketch;
+use datasketches::error::ErrorKind;
 use googletest::assert_that;
 use googletest::prelude::ge;
 use googletest::prelude::le;
@@ -35,20 +37,79 @@ fn test_init_defaults() {
 
 #[test]
 fn test_parameter_suggestions() {
-    assert_eq!(CountMinSketch::<i64>::suggest_num_buckets(0.2), 14);
-    assert_eq!(CountMinSketch::<i64>::suggest_num_buckets(0.1), 28);
-    assert_eq!(CountMinSketch::<i64>::suggest_num_buckets(0.05), 55);
-    assert_eq!(CountMinSketch::<i64>::suggest_num_buckets(0.01), 272);
+    assert_eq!(CountMinSketch::<i64>::suggest_num_buckets(0.2).unwrap(), 14);
+    assert_eq!(CountMinSketch::<i64>::suggest_num_buckets(0.1).unwrap(), 28);
+    assert_eq!(
+        CountMinSketch::<i64>::suggest_num_buckets(0.05).unwrap(),
+        55
+    );
+    assert_eq!(
+        CountMinSketch::<i64>::suggest_num_buckets(0.01).unwrap(),
+        272
+    );
 
-    assert_eq!(CountMinSketch::<i64>::suggest_num_hashes(0.682689492), 2);
-    assert_eq!(CountMinSketch::<i64>::suggest_num_hashes(0.954499736), 4);
-    assert_eq!(CountMinSketch::<i64>::suggest_num_hashes(0.997300204), 6);
+    assert_eq!(
+        CountMinSketch::<i64>::suggest_num_hashes(0.682689492).unwrap(),
+        2
+    );
+    assert_eq!(
+        CountMinSketch::<i64>::suggest_num_hashes(0.954499736).unwrap(),
+        4
+    );
+    assert_eq!(
+        CountMinSketch::<i64>::suggest_num_hashes(0.997300204).unwrap(),
+        6
+    );
 
-    let buckets = CountMinSketch::<i64>::suggest_num_buckets(0.1);
+    let buckets = CountMinSketch::<i64>::suggest_num_buckets(0.1).unwrap();
     let sketch = CountMinSketch::<i64>::new(3, buckets);
     assert_that!(sketch.relative_error(), le(0.1));
 }
 
+#[test]
+fn test_parameter_suggestions_reject_out_of_range_inputs() {
+    for relative_error in [0.0, -0.1, f64::NAN, f64::INFINITY, 2.0, 1e-12] {
+        let err = CountMinSketch::<i64>::suggest_num_buckets(relative_error).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidArgument, "{err}");
+    }
+    for confidence in [0.0, -0.5, 1.0, 1.5, f64::NAN] {
+        let err = CountMinSketch::<i64>::suggest_num_hashes(confidence).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidArgument, "{err}");
+    }
+    assert_eq!(
+        CountMinSketch::<i64>::suggest_num_hashes(1e-300).unwrap(),
+        1
+    );
+}
+
+#[test]
+fn test_estimated_memory_bytes_matches_estimated_size() {
+    for (num_hashes, num_buckets) in [(1, 3), (3, 272), (5, 1024)] {
+        assert_eq!(
+            CountMinSketch::<u8>::estimated_memory_bytes(num_hashes, num_buckets),
+            CountMinSketch::<u8>::new(num_hashes, num_buckets).estimated_size()
+        );
+        assert_eq!(
+            CountMinSketch::<i64>::estimated_memory_bytes(num_hashes, num_buckets),
+            CountMinSketch::<i64>::new(num_hashes, num_buckets).estimated_size()
+        );
+    }
+}
+
+#[test]
+fn test_extend_matches_update_with_weight() {
+    let pairs: Vec<(u64, i64)> = (0..1_000).map(|i| (i % 97, (i % 5) as i64 - 2)).collect();
+
+    let mut expected = CountMinSketch::<i64>::new(3, 32);
+    for &(item, weight) in &pairs {
+        expected.update_with_weight(item, weight);
+    }
+    let mut sketch = CountMinSketch::<i64>::new(3, 32);
+    sketch.extend(pairs);
+
+    assert_eq!(sketch, expected);
+}
+
 #[test]
 fn test_update_and_bounds() {
     let mut sketch = CountMinSketch::<i64>::with_seed(3, 128, 123);
@@ -116,8 +177,8 @@ fn test_negative_weights() {
 
 #[test]
 fn test_halve() {
-    let buckets = CountMinSketch::<u64>::suggest_num_buckets(0.01);
-    let hashes = CountMinSketch::<u64>::suggest_num_hashes(0.9);
+    let buckets = CountMinSketch::<u64>::suggest_num_buckets(0.01).unwrap();
+    let hashes = CountMinSketch::<u64>::suggest_num_hashes(0.9).unwrap();
     let mut sketch = CountMinSketch::<u64>::new(hashes, buckets);
 
     for i in 0..1000usize {
@@ -139,8 +200,8 @@ fn test_halve() {
 
 #[test]
 fn test_decay() {
-    let buckets = CountMinSketch::<u64>::suggest_num_buckets(0.01);
-    let hashes = CountMinSketch::<u64>::suggest_num_hashes(0.9);
+    let buckets = CountMinSketch::<u64>::suggest_num_buckets(0.01).unwrap();
+    let hashes = CountMinSketch::<u64>::suggest_num_hashes(0.9).unwrap();
     let mut sketch = CountMinSketch::<u64>::new(hashes, buckets);
 
     for i in 0..1000usize {
@@ -179,6 +240,24 @@ fn test_merge() {
     assert_that!(left.estimate("b"), ge(4));
 }
 
+#[test]
+fn test_counter_table() {
+    let mut sketch = CountMinSketch::<i64>::new(3, 64);
+    assert!(sketch.counts().iter().all(|&c| c == 0));
+
+    sketch.update_with_weight("a", 5);
+    sketch.update_with_weight("b", -2);
+    assert_eq!(sketch.counts().len(), 3 * 64);
+    assert_eq!(sketch.rows().len(), 3);
+    for row in sketch.rows() {
+        assert_eq!(row.len(), 64);
+        assert_eq!(row.iter().sum::<i64>(), 3);
+    }
+
+    let flattened = sketch.rows().flatten().copied().collect::<Vec<_>>();
+    assert_eq!(flattened, sketch.counts());
+}
+
 #[test]
 fn test_serialize_deserialize_empty() {
     let sketch = CountMinSketch::<i64>::with_seed(2, 5, 123);
@@ -253,3 +332,10 @@ fn test_increment_multi_like_rust_count_min_sketch() {
         assert_that!(sketch.estimate(key), ge(9_000));
     }
 }
+
+#[test]
+fn test_memory_usage() {
+    let sketch = CountMinSketch::<u64>::new(4, 128);
+    assert_eq!(sketch.memory_usage(), sketch.estimated_size());
+    assert_that!(sketch.memory_usage(), ge(4 * 128 * size_of::<u64>()));
+}
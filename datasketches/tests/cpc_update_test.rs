@@ -62,3 +62,17 @@ fn test_many_values() {
     assert_that!(sketch.estimate(), le(sketch.upper_bound(NumStdDev::One)));
     assert!(sketch.validate());
 }
+
+#[test]
+fn test_serialize_into_slice_matches_serialize() {
+    for n in [0, 100, 10_000] {
+        let mut sketch = CpcSketch::new(11);
+        for i in 0..n {
+            sketch.update(i);
+        }
+        let expected = sketch.serialize();
+        let mut buf = vec![0u8; CpcSketch::max_serialized_bytes(11)];
+        let len = sketch.serialize_into_slice(&mut buf).unwrap();
+        assert_eq!(&buf[..len], &expected[..]);
+    }
+}
@@ -0,0 +1,272 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+#![cfg(all(
+    feature = "datafusion",
+    feature = "hll",
+    feature = "tdigest",
+    feature = "theta"
+))]
+
+use std::sync::Arc;
+
+use datafusion_common::ScalarValue;
+use datafusion_common::arrow::array::ArrayRef;
+use datafusion_common::arrow::array::BinaryArray;
+use datafusion_common::arrow::array::BinaryViewArray;
+use datafusion_common::arrow::array::Int64Array;
+use datafusion_common::arrow::array::StringArray;
+use datafusion_expr::Accumulator;
+use datasketches::datafusion::HllCountDistinctAccumulator;
+use datasketches::datafusion::HllUnionAccumulator;
+use datasketches::datafusion::TDigestMergeAccumulator;
+use datasketches::datafusion::ThetaCountDistinctAccumulator;
+use datasketches::datafusion::ThetaUnionAccumulator;
+use datasketches::datafusion::udafs;
+use datasketches::hash::DEFAULT_UPDATE_SEED;
+use datasketches::hash_value::raw_bytes;
+use datasketches::hll::HllSketch;
+use datasketches::hll::HllType;
+use datasketches::tdigest::TDigestMut;
+use datasketches::theta::CompactThetaSketch;
+use datasketches::theta::ThetaSketchBuilder;
+use googletest::assert_that;
+use googletest::prelude::near;
+
+fn binary_array(values: Vec<Option<Vec<u8>>>) -> ArrayRef {
+    Arc::new(BinaryArray::from_iter(values))
+}
+
+fn unwrap_binary(value: ScalarValue) -> Vec<u8> {
+    match value {
+        ScalarValue::Binary(Some(bytes)) => bytes,
+        other => panic!("expected a binary value, got {other:?}"),
+    }
+}
+
+fn unwrap_f64(value: ScalarValue) -> f64 {
+    match value {
+        ScalarValue::Float64(Some(value)) => value,
+        other => panic!("expected a float value, got {other:?}"),
+    }
+}
+
+/// Splits `values` over two accumulators and merges them through their partial states, the way
+/// a two-partition aggregation does.
+fn aggregate_in_two_partitions(
+    mut make: impl FnMut() -> Box<dyn Accumulator>,
+    left: ArrayRef,
+    right: ArrayRef,
+) -> ScalarValue {
+    let mut partial_left = make();
+    let mut partial_right = make();
+    partial_left.update_batch(&[left]).unwrap();
+    partial_right.update_batch(&[right]).unwrap();
+
+    let mut states = partial_left.state().unwrap();
+    states.extend(partial_right.state().unwrap());
+    let states = ScalarValue::iter_to_array(states).unwrap();
+
+    let mut last = make();
+    last.merge_batch(&[states]).unwrap();
+    last.evaluate().unwrap()
+}
+
+#[test]
+fn test_udafs_names() {
+    let names = udafs()
+        .iter()
+        .map(|udaf| udaf.name().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        [
+            "hll_union",
+            "hll_count_distinct",
+            "theta_union",
+            "theta_count_distinct",
+            "tdigest_merge",
+        ]
+    );
+}
+
+#[test]
+fn test_hll_union() {
+    let mut sketches = vec![];
+    for i in 0..4 {
+        let mut sketch = HllSketch::new(12, HllType::Hll8);
+        for j in 0..5_000 {
+            sketch.update(i * 2_500 + j);
+        }
+        sketches.push(Some(sketch.serialize()));
+    }
+    sketches.insert(1, None);
+    let right = sketches.split_off(3);
+
+    let result = aggregate_in_two_partitions(
+        || Box::new(HllUnionAccumulator::new(12, HllType::Hll4)),
+        binary_array(sketches),
+        binary_array(right),
+    );
+    let result = HllSketch::deserialize(&unwrap_binary(result)).unwrap();
+    assert_eq!(result.target_type(), HllType::Hll4);
+    assert_that!(result.estimate(), near(12_500.0, 12_500.0 * 0.05));
+}
+
+#[test]
+fn test_hll_union_rejects_garbage() {
+    let mut acc = HllUnionAccumulator::new(12, HllType::Hll8);
+    let garbage = binary_array(vec![Some(vec![1, 2, 3])]);
+    assert!(acc.update_batch(&[garbage]).is_err());
+}
+
+#[test]
+fn test_hll_count_distinct() {
+    let left: ArrayRef = Arc::new(Int64Array::from_iter(
+        (0..6_000).map(|i| (i % 7 != 0).then_some(i)),
+    ));
+    let right: ArrayRef = Arc::new(Int64Array::from_iter_values(3_000..10_000));
+
+    let result = aggregate_in_two_partitions(
+        || Box::new(HllCountDistinctAccumulator::new(12)),
+        left,
+        right,
+    );
+    // 0..3000 without its 429 multiples of 7, then all of 3000..10000.
+    assert_that!(unwrap_f64(result), near(9_571.0, 9_571.0 * 0.05));
+}
+
+#[test]
+fn test_hll_count_distinct_matches_sketch_update() {
+    // Integers hash as i64 and strings as raw bytes.
+    let mut sketch = HllSketch::new(12, HllType::Hll8);
+    for i in 0..100_i64 {
+        sketch.update(i);
+        sketch.update(raw_bytes::from_str(&format!("item-{i}")));
+    }
+
+    let mut acc = HllCountDistinctAccumulator::new(12);
+    let ints: ArrayRef = Arc::new(Int64Array::from_iter_values(0..100));
+    let strings: ArrayRef = Arc::new(StringArray::from_iter_values(
+        (0..100).map(|i| format!("item-{i}")),
+    ));
+    acc.update_batch(&[ints]).unwrap();
+    acc.update_batch(&[strings]).unwrap();
+
+    let state = unwrap_binary(acc.state().unwrap().remove(0));
+    let merged = HllSketch::deserialize(&state).unwrap();
+    assert_eq!(merged.estimate(), sketch.estimate());
+}
+
+#[test]
+fn test_theta_union() {
+    let mut sketches = vec![];
+    for i in 0..4 {
+        let mut sketch = ThetaSketchBuilder::default().lg_k(12).build();
+        for j in 0..5_000 {
+            sketch.update(i * 2_500 + j);
+        }
+        sketches.push(Some(sketch.compact(i % 2 == 0).serialize()));
+    }
+    let right = sketches.split_off(2);
+
+    let left = binary_array(sketches);
+    let right: ArrayRef = Arc::new(BinaryViewArray::from_iter(right));
+    let result = aggregate_in_two_partitions(
+        || Box::new(ThetaUnionAccumulator::new(12, DEFAULT_UPDATE_SEED)),
+        left,
+        right,
+    );
+    let result = CompactThetaSketch::deserialize(&unwrap_binary(result)).unwrap();
+    assert!(result.is_ordered());
+    assert_that!(result.estimate(), near(12_500.0, 12_500.0 * 0.05));
+}
+
+#[test]
+fn test_theta_union_rejects_seed_mismatch() {
+    let mut sketch = ThetaSketchBuilder::default().seed(7).build();
+    sketch.update(1);
+    let sketches = binary_array(vec![Some(sketch.compact(true).serialize())]);
+
+    let mut acc = ThetaUnionAccumulator::new(12, DEFAULT_UPDATE_SEED);
+    assert!(acc.update_batch(&[sketches]).is_err());
+}
+
+#[test]
+fn test_theta_count_distinct() {
+    let left: ArrayRef = Arc::new(StringArray::from_iter(
+        (0..2_000).map(|i| (i % 5 != 0).then(|| format!("key-{i}"))),
+    ));
+    let right: ArrayRef = Arc::new(StringArray::from_iter_values(
+        (1_000..3_000).map(|i| format!("key-{i}")),
+    ));
+
+    let result = aggregate_in_two_partitions(
+        || Box::new(ThetaCountDistinctAccumulator::new(12, DEFAULT_UPDATE_SEED)),
+        left,
+        right,
+    );
+    // 0..1000 without multiples of 5, then all of 1000..3000.
+    assert_that!(unwrap_f64(result), near(2_800.0, 2_800.0 * 0.05));
+}
+
+#[test]
+fn test_theta_count_distinct_skips_empty_strings() {
+    let mut acc = ThetaCountDistinctAccumulator::new(12, DEFAULT_UPDATE_SEED);
+    let values: ArrayRef = Arc::new(StringArray::from(vec![Some(""), None, Some("a")]));
+    acc.update_batch(&[values]).unwrap();
+    assert_eq!(unwrap_f64(acc.evaluate().unwrap()), 1.0);
+}
+
+#[test]
+fn test_tdigest_merge() {
+    let mut digests = vec![];
+    for i in 0..4 {
+        let mut digest = TDigestMut::new(100);
+        for j in 0..1_000 {
+            digest.update((i * 1_000 + j) as f64);
+        }
+        digests.push(Some(digest.serialize()));
+    }
+    let right = digests.split_off(2);
+
+    let result = aggregate_in_two_partitions(
+        || Box::new(TDigestMergeAccumulator::new(100)),
+        binary_array(digests),
+        binary_array(right),
+    );
+    let mut result = TDigestMut::deserialize(&unwrap_binary(result), false).unwrap();
+    assert_eq!(result.total_weight(), 4_000);
+    assert_eq!(result.min_value(), Some(0.0));
+    assert_eq!(result.max_value(), Some(3_999.0));
+    assert_that!(result.quantile(0.5).unwrap(), near(2_000.0, 40.0));
+}
+
+#[test]
+fn test_empty_input() {
+    let mut acc = HllCountDistinctAccumulator::new(12);
+    assert_eq!(unwrap_f64(acc.evaluate().unwrap()), 0.0);
+
+    let mut acc = ThetaCountDistinctAccumulator::new(12, DEFAULT_UPDATE_SEED);
+    assert_eq!(unwrap_f64(acc.evaluate().unwrap()), 0.0);
+
+    let mut acc = TDigestMergeAccumulator::new(100);
+    let nulls = binary_array(vec![None, None]);
+    acc.update_batch(&[nulls]).unwrap();
+    let result = TDigestMut::deserialize(&unwrap_binary(acc.evaluate().unwrap()), false).unwrap();
+    assert!(result.is_empty());
+}
@@ -20,12 +20,14 @@
 mod common;
 
 use std::fs;
+use std::sync::Arc;
 
 use common::serialization_test_data;
 use datasketches::codec::SketchBytes;
 use datasketches::codec::SketchSlice;
 use datasketches::error::Error;
 use datasketches::error::ErrorKind;
+use datasketches::frequencies::ErrorType;
 use datasketches::frequencies::FrequentItemValue;
 use datasketches::frequencies::FrequentItemsSketch;
 
@@ -92,6 +94,73 @@ fn test_non_clone_item_round_trip() {
     assert_eq!(restored.estimate(&NonCloneSerializableItem(2)), 5);
 }
 
+#[test]
+fn test_shared_string_items_round_trip() {
+    let keys: Vec<Arc<str>> = (0..40).map(|i| Arc::from(format!("key-{i}"))).collect();
+    let mut sketch = FrequentItemsSketch::<Arc<str>>::new(16);
+    for (i, key) in keys.iter().enumerate() {
+        sketch.update_with_count(Arc::clone(key), i as u64 + 1);
+    }
+    let bytes = sketch.serialize();
+
+    let strings = FrequentItemsSketch::<String>::deserialize(&bytes).unwrap();
+    assert_eq!(strings.serialize(), bytes);
+    let shared = FrequentItemsSketch::<Arc<str>>::deserialize(&bytes).unwrap();
+    assert_eq!(shared.total_weight(), sketch.total_weight());
+    assert_eq!(shared.maximum_error(), sketch.maximum_error());
+    for key in &keys {
+        assert_eq!(shared.estimate(key), sketch.estimate(key));
+        assert_eq!(strings.estimate(&**key), sketch.estimate(key));
+    }
+}
+
+#[test]
+fn test_unsupported_serial_version_is_rejected() {
+    let mut sketch = FrequentItemsSketch::<i64>::new(32);
+    sketch.update_with_count(7, 3);
+    let mut bytes = sketch.serialize();
+
+    for version in [0, 2] {
+        bytes[1] = version;
+        let err = FrequentItemsSketch::<i64>::deserialize(&bytes).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnsupportedVersion);
+        assert!(err.to_string().contains("family: FREQUENCY"), "{err}");
+    }
+}
+
+#[test]
+fn test_serde_id_mismatch_is_rejected() {
+    let mut sketch = FrequentItemsSketch::<i64>::new(32);
+    sketch.update_with_count(7, 3);
+    let bytes = sketch.serialize();
+    assert_eq!(u16::from_le_bytes([bytes[6], bytes[7]]), i64::SERDE_ID);
+
+    let err = FrequentItemsSketch::<String>::deserialize(&bytes).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert!(err.message().contains("serde id mismatch"), "{err}");
+    assert!(FrequentItemsSketch::<u64>::deserialize(&bytes).is_err());
+
+    let empty = FrequentItemsSketch::<String>::new(32).serialize();
+    assert!(FrequentItemsSketch::<i64>::deserialize(&empty).is_err());
+}
+
+#[test]
+fn test_unspecified_serde_id_is_not_checked() {
+    let mut sketch = FrequentItemsSketch::<i64>::new(32);
+    sketch.update_with_count(7, 3);
+    let mut bytes = sketch.serialize();
+    // Java and C++ leave the serde id bytes zeroed.
+    bytes[6..8].fill(0);
+    let restored = FrequentItemsSketch::<i64>::deserialize(&bytes).unwrap();
+    assert_eq!(restored.estimate(&7), 3);
+
+    // Custom item types default to the unspecified id.
+    assert_eq!(NonCloneSerializableItem::SERDE_ID, 0);
+    let restored =
+        FrequentItemsSketch::<NonCloneSerializableItem>::deserialize(&sketch.serialize()).unwrap();
+    assert_eq!(restored.estimate(&NonCloneSerializableItem(7)), 3);
+}
+
 #[test]
 fn test_empty_round_trip() {
     let sketch = FrequentItemsSketch::<i64>::new(32);
@@ -117,6 +186,83 @@ fn test_purged_to_empty_round_trip() {
     assert!(restored.is_empty());
 }
 
+/// Checks that re-serializing a sketch read from another implementation preserves its state.
+fn assert_round_trip<T: FrequentItemValue + Clone + std::fmt::Debug>(
+    sketch: &FrequentItemsSketch<T>,
+) {
+    let restored = FrequentItemsSketch::<T>::deserialize(&sketch.serialize()).unwrap();
+    assert_eq!(restored.total_weight(), sketch.total_weight());
+    assert_eq!(restored.maximum_error(), sketch.maximum_error());
+    assert_eq!(restored.num_purges(), sketch.num_purges());
+    assert_eq!(
+        restored.frequent_items(ErrorType::NoFalseNegatives),
+        sketch.frequent_items(ErrorType::NoFalseNegatives)
+    );
+}
+
+#[test]
+fn test_num_purges_round_trip() {
+    let mut sketch = FrequentItemsSketch::<i64>::new(32);
+    sketch.update_with_count(-1, 1_000);
+    for i in 0..1_000 {
+        sketch.update(i);
+    }
+    let num_purges = sketch.num_purges();
+    assert!(num_purges > 0);
+
+    let bytes = sketch.serialize();
+    assert_eq!(bytes[12..16], num_purges.to_le_bytes());
+    let restored = FrequentItemsSketch::<i64>::deserialize(&bytes).unwrap();
+    assert_eq!(restored.num_purges(), num_purges);
+
+    // Merged purges add up
+    let mut merged = FrequentItemsSketch::<i64>::new(1024);
+    merged.merge(&sketch);
+    merged.merge(&restored);
+    assert_eq!(merged.num_purges(), 2 * num_purges);
+
+    merged.reset();
+    assert_eq!(merged.num_purges(), 0);
+}
+
+#[test]
+fn test_unused_preamble_bytes_are_tolerated() {
+    let mut sketch = FrequentItemsSketch::<i64>::new(32);
+    sketch.update_with_count(7, 3);
+    sketch.update_with_count(8, 1);
+    let mut bytes = sketch.serialize();
+
+    // Bytes 12-15 are unused by Java and C++, and hold the purge count here.
+    bytes[12..16].copy_from_slice(&[0xde, 0xad, 0xbe, 0xef]);
+    let restored = FrequentItemsSketch::<i64>::deserialize(&bytes).unwrap();
+    assert_eq!(restored.num_purges(), 0xefbe_adde);
+    assert_eq!(restored.estimate(&7), 3);
+    assert_eq!(restored.estimate(&8), 1);
+    assert_eq!(restored.total_weight(), 4);
+    assert_eq!(restored.maximum_error(), 0);
+}
+
+#[test]
+fn test_invalid_lg_map_sizes_are_rejected() {
+    let mut sketch = FrequentItemsSketch::<i64>::new(32);
+    sketch.update_with_count(7, 3);
+    let bytes = sketch.serialize();
+
+    for (lg_max, lg_cur) in [(64, 3), (255, 255), (5, 6)] {
+        let mut bytes = bytes.clone();
+        bytes[3] = lg_max;
+        bytes[4] = lg_cur;
+        let err = FrequentItemsSketch::<i64>::deserialize(&bytes).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData, "{err}");
+    }
+
+    // Map sizes below the minimum are raised to it.
+    let mut bytes = bytes.clone();
+    bytes[4] = 0;
+    let restored = FrequentItemsSketch::<i64>::deserialize(&bytes).unwrap();
+    assert_eq!(restored.estimate(&7), 3);
+}
+
 #[test]
 fn test_java_frequent_longs_compatibility() {
     let test_cases = [0, 1, 10, 100, 1000, 10000, 100000, 1000000];
@@ -132,6 +278,7 @@ fn test_java_frequent_longs_compatibility() {
             assert_eq!(sketch.maximum_error(), 0);
         }
         assert_eq!(sketch.total_weight(), n);
+        assert_round_trip(&sketch);
     }
 }
 
@@ -159,6 +306,7 @@ fn test_java_frequent_strings_ascii() {
         sketch.estimate(&"ddddddddddddddddddddddddddddd".to_string()),
         4
     );
+    assert_round_trip(&sketch);
 }
 
 #[test]
@@ -176,6 +324,7 @@ fn test_java_frequent_strings_utf8() {
     assert_eq!(sketch.estimate(&"уфхцч".to_string()), 5);
     assert_eq!(sketch.estimate(&"шщъыь".to_string()), 6);
     assert_eq!(sketch.estimate(&"эюя".to_string()), 7);
+    assert_round_trip(&sketch);
 }
 
 #[test]
@@ -17,6 +17,7 @@
 
 #![cfg(feature = "frequencies")]
 
+use datasketches::common::MemoryUsage;
 use datasketches::frequencies::ErrorType;
 use datasketches::frequencies::FrequentItemsSketch;
 
@@ -325,6 +326,31 @@ fn test_longs_purge_keeps_heavy_hitters() {
     assert_eq!(rows[0].estimate(), 10);
 }
 
+#[test]
+fn test_longs_extend_matches_update_with_count() {
+    // Enough distinct items to resize the map up to its maximum size and then purge it.
+    let pairs: Vec<(i64, u64)> = (0..10_000)
+        .map(|i| ((i * 7919) % 2_003, (i % 5) as u64))
+        .collect();
+
+    let mut expected = FrequentItemsSketch::new(16);
+    for &(item, count) in &pairs {
+        expected.update_with_count(item, count);
+    }
+    let mut sketch = FrequentItemsSketch::new(16);
+    sketch.extend(pairs);
+
+    assert_eq!(sketch.total_weight(), expected.total_weight());
+    assert_eq!(sketch.maximum_error(), expected.maximum_error());
+    assert_eq!(
+        sketch.frequent_items(ErrorType::NoFalseNegatives),
+        expected.frequent_items(ErrorType::NoFalseNegatives)
+    );
+    assert_eq!(sketch.num_purges(), expected.num_purges());
+    assert!(sketch.num_purges() > 0);
+    assert_eq!(sketch.serialize(), expected.serialize());
+}
+
 #[test]
 fn test_items_purge_keeps_heavy_hitters() {
     let mut sketch = FrequentItemsSketch::new(8);
@@ -537,3 +563,173 @@ fn test_longs_invalid_map_size_panics() {
 fn test_items_invalid_map_size_panics() {
     FrequentItemsSketch::<String>::new(6);
 }
+
+#[test]
+fn test_memory_usage_grows_with_map() {
+    let mut sketch = FrequentItemsSketch::<i64>::new(1024);
+    let initial = sketch.memory_usage();
+    assert_eq!(initial, sketch.estimated_size());
+    for i in 0..500 {
+        sketch.update(i);
+    }
+    assert!(sketch.memory_usage() > initial);
+}
+
+#[test]
+fn test_try_update_with_count_rejects_weight_overflow() {
+    let mut sketch = FrequentItemsSketch::<i64>::new(8);
+    sketch.try_update_with_count(1, u64::MAX - 5).unwrap();
+    sketch.try_update_with_count(2, 5).unwrap();
+    assert_eq!(sketch.total_weight(), u64::MAX);
+
+    let err = sketch.try_update_with_count(3, 1).unwrap_err();
+    assert!(err.message().contains("overflows u64"));
+    assert_eq!(sketch.total_weight(), u64::MAX);
+    assert_eq!(sketch.num_active_items(), 2);
+    assert_eq!(sketch.estimate(&3), 0);
+
+    // zero counts never overflow
+    sketch.try_update_with_count(3, 0).unwrap();
+}
+
+#[test]
+#[should_panic(expected = "total stream weight overflows u64")]
+fn test_update_with_count_panics_on_weight_overflow() {
+    let mut sketch = FrequentItemsSketch::<String>::new(8);
+    sketch.update_with_count_ref("a", u64::MAX);
+    sketch.update_with_count_ref("b", 1);
+}
+
+#[test]
+#[should_panic(expected = "total stream weight overflows u64: 18446744073709551615 + 1")]
+fn test_extend_panics_on_weight_overflow() {
+    let mut sketch = FrequentItemsSketch::<i64>::new(8);
+    sketch.extend([(1, u64::MAX), (2, 1)]);
+}
+
+#[test]
+fn test_try_merge_rejects_weight_overflow() {
+    let mut left = FrequentItemsSketch::<i64>::new(8);
+    let mut right = FrequentItemsSketch::<i64>::new(8);
+    left.update_with_count(1, u64::MAX / 2 + 1);
+    right.update_with_count(2, u64::MAX / 2 + 1);
+
+    let err = left.try_merge(&right).unwrap_err();
+    assert!(err.message().contains("overflows u64"));
+    assert_eq!(left.total_weight(), u64::MAX / 2 + 1);
+    assert_eq!(left.num_active_items(), 1);
+
+    let mut small = FrequentItemsSketch::<i64>::new(8);
+    small.update_with_count(2, 10);
+    left.try_merge(&small).unwrap();
+    assert_eq!(left.total_weight(), u64::MAX / 2 + 11);
+    assert_eq!(left.estimate(&2), 10);
+}
+
+#[test]
+#[should_panic(expected = "total stream weight overflows u64")]
+fn test_merge_panics_on_weight_overflow() {
+    let mut left = FrequentItemsSketch::<i64>::new(8);
+    let mut right = FrequentItemsSketch::<i64>::new(8);
+    left.update_with_count(1, u64::MAX);
+    right.update(2);
+    left.merge(&right);
+}
+
+#[test]
+fn test_serialize_into_slice_matches_serialize() {
+    let mut sketch = FrequentItemsSketch::<i64>::new(16);
+    for i in 0..100 {
+        sketch.update_with_count(i % 20, i as u64 + 1);
+    }
+    let expected = sketch.serialize();
+    let mut buf = vec![0u8; expected.len()];
+    assert_eq!(
+        sketch.serialize_into_slice(&mut buf).unwrap(),
+        expected.len()
+    );
+    assert_eq!(buf, expected);
+    assert!(
+        sketch
+            .serialize_into_slice(&mut buf[..expected.len() - 1])
+            .is_err()
+    );
+}
+
+#[test]
+fn test_iter_frequent_items() {
+    let mut sketch: FrequentItemsSketch<i64> = FrequentItemsSketch::new(8);
+    sketch.update_with_count(1, 10);
+    for item in 2..=6 {
+        sketch.update(item);
+    }
+    sketch.update_with_count(7, 15);
+    for item in 8..=12 {
+        sketch.update(item);
+    }
+    assert!(sketch.maximum_error() > 0);
+
+    for error_type in [ErrorType::NoFalsePositives, ErrorType::NoFalseNegatives] {
+        let mut rows = sketch
+            .iter_frequent_items(error_type)
+            .map(|row| row.cloned())
+            .collect::<Vec<_>>();
+        rows.sort_by_key(|row| std::cmp::Reverse(row.estimate()));
+        assert_eq!(rows, sketch.frequent_items(error_type));
+
+        for threshold in [0, 5, 12, 20] {
+            let count = sketch
+                .iter_frequent_items_with_threshold(error_type, threshold)
+                .count();
+            assert_eq!(
+                count,
+                sketch
+                    .frequent_items_with_threshold(error_type, threshold)
+                    .len()
+            );
+        }
+    }
+
+    let heavy = sketch
+        .iter_frequent_items(ErrorType::NoFalsePositives)
+        .filter(|row| row.lower_bound() > 12)
+        .map(|row| **row.item())
+        .collect::<Vec<_>>();
+    assert_eq!(heavy, [7]);
+}
+
+#[test]
+fn test_count_and_contains_frequent() {
+    let mut sketch: FrequentItemsSketch<i64> = FrequentItemsSketch::new(8);
+    sketch.update_with_count(1, 10);
+    for item in 2..=6 {
+        sketch.update(item);
+    }
+    sketch.update_with_count(7, 15);
+    for item in 8..=12 {
+        sketch.update(item);
+    }
+    assert!(sketch.maximum_error() > 0);
+
+    for error_type in [ErrorType::NoFalsePositives, ErrorType::NoFalseNegatives] {
+        for threshold in [0, 5, 9, 12, 20] {
+            let rows = sketch.frequent_items_with_threshold(error_type, threshold);
+            assert_eq!(
+                sketch.count_frequent_items(error_type, threshold),
+                rows.len()
+            );
+        }
+    }
+
+    for threshold in [0, 5, 9, 12, 20] {
+        let rows = sketch.frequent_items_with_threshold(ErrorType::NoFalsePositives, threshold);
+        for item in 1..=12 {
+            assert_eq!(
+                sketch.contains_frequent(&item, threshold),
+                rows.iter().any(|row| *row.item() == item),
+                "item {item}, threshold {threshold}"
+            );
+        }
+    }
+    assert!(!sketch.contains_frequent(&100, 0));
+}
@@ -26,6 +26,7 @@ use common::serialization_test_data;
 use datasketches::hash_value::natural_extend;
 use datasketches::hll::HllSketch;
 use datasketches::hll::HllType;
+use datasketches::hll::HllUnion;
 
 fn test_sketch_file(path: PathBuf, expected_cardinality: usize, expected_lg_k: u8) {
     let expected = expected_cardinality as f64;
@@ -133,6 +134,132 @@ fn test_update_after_deserialize_list_mode() {
     }
 }
 
+#[test]
+fn test_serialized_size_bytes_matches_serialize() {
+    const LG_K: u8 = 10;
+    for hll_type in [HllType::Hll4, HllType::Hll6, HllType::Hll8] {
+        let mut sketch = HllSketch::new(LG_K, hll_type);
+        assert_eq!(sketch.serialized_size_bytes(), sketch.serialize().len());
+
+        // Walk through List, Set and HLL modes; Hll4 also gets aux map exceptions.
+        for i in 0..20_000u64 {
+            sketch.update(i);
+            if i.is_power_of_two() || i % 1000 == 0 {
+                assert_eq!(
+                    sketch.serialized_size_bytes(),
+                    sketch.serialize().len(),
+                    "{hll_type:?}: size mismatch after {} updates",
+                    i + 1
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_updatable_serialization_round_trip() {
+    const LG_K: u8 = 10;
+    for hll_type in [HllType::Hll4, HllType::Hll6, HllType::Hll8] {
+        let mut sketch = HllSketch::new(LG_K, hll_type);
+        let bytes = sketch.serialize_updatable();
+        assert_eq!(bytes.len(), sketch.updatable_serialized_size_bytes());
+        assert!(HllSketch::deserialize(&bytes).unwrap().is_empty());
+
+        for i in 0..20_000u64 {
+            sketch.update(i);
+            if i.is_power_of_two() || i % 1000 == 0 {
+                let bytes = sketch.serialize_updatable();
+                assert_eq!(
+                    bytes.len(),
+                    sketch.updatable_serialized_size_bytes(),
+                    "{hll_type:?}: size mismatch after {} updates",
+                    i + 1
+                );
+                assert!(bytes.len() >= sketch.serialized_size_bytes());
+
+                let mut decoded = HllSketch::deserialize(&bytes).unwrap();
+                assert_eq!(decoded.estimate(), sketch.estimate());
+                assert_eq!(decoded.serialize(), sketch.serialize());
+                decoded.update(i);
+                assert_eq!(decoded.estimate(), sketch.estimate());
+            }
+        }
+    }
+}
+
+#[test]
+fn test_union_serialized_size_bytes() {
+    let mut union = HllUnion::new(12);
+    for i in 0..50_000u64 {
+        union.update_value(i);
+        if i.is_power_of_two() || i % 5000 == 0 {
+            for hll_type in [HllType::Hll4, HllType::Hll6, HllType::Hll8] {
+                assert_eq!(
+                    union.serialized_size_bytes(hll_type),
+                    union.to_sketch(hll_type).serialize().len(),
+                    "{hll_type:?}: size mismatch after {} updates",
+                    i + 1
+                );
+            }
+        }
+    }
+}
+
+fn sorted_coupons(bytes: &[u8]) -> Vec<u32> {
+    let mut coupons: Vec<u32> = bytes
+        .chunks_exact(4)
+        .map(|chunk| u32::from_le_bytes(chunk.try_into().unwrap()))
+        .collect();
+    coupons.sort_unstable();
+    coupons
+}
+
+#[test]
+fn test_coupon_modes_serialize_deterministically() {
+    for (n, preamble_size) in [(5, 8), (100, 12), (300, 12)] {
+        let mut forward = HllSketch::new(12, HllType::Hll4);
+        let mut backward = HllSketch::new(12, HllType::Hll4);
+        for i in 0..n {
+            forward.update(i);
+            backward.update(n - 1 - i);
+        }
+        assert_eq!(forward, backward);
+
+        let bytes = forward.serialize();
+        assert_eq!(bytes, backward.serialize(), "n={n}");
+        let coupons: Vec<u32> = bytes[preamble_size..]
+            .chunks_exact(4)
+            .map(|chunk| u32::from_le_bytes(chunk.try_into().unwrap()))
+            .collect();
+        assert!(coupons.is_sorted(), "n={n}");
+
+        let decoded = HllSketch::deserialize(&bytes).unwrap();
+        assert_eq!(decoded.serialize(), bytes, "n={n}");
+    }
+}
+
+#[test]
+fn test_coupon_modes_serialize_compactly() {
+    const LG_K: u8 = 12;
+    let mut list = HllSketch::new(LG_K, HllType::Hll8);
+    for i in 0..5u64 {
+        list.update(i);
+    }
+    // 8-byte List preamble + one int per coupon.
+    assert_eq!(list.serialize().len(), 8 + 5 * 4);
+
+    let mut set = HllSketch::new(LG_K, HllType::Hll8);
+    for i in 0..100u64 {
+        set.update(i);
+    }
+    // 12-byte Set preamble + one int per coupon; the 100 distinct values
+    // map to 100 distinct coupons.
+    let bytes = set.serialize();
+    assert_eq!(bytes.len(), 12 + 100 * 4);
+    assert_eq!(u32::from_le_bytes(bytes[8..12].try_into().unwrap()), 100);
+    assert!(bytes.len() < 40 + (1 << LG_K));
+}
+
 #[test]
 fn test_serialized_bytes_match_reference_files_for_coupon_modes() {
     fn serialized_mode_name(bytes: &[u8]) -> &'static str {
@@ -177,12 +304,23 @@ fn test_serialized_bytes_match_reference_files_for_coupon_modes() {
                     "{} should be a {mode} mode fixture",
                     path.display()
                 );
+                // Rust writes the coupons in ascending order, Java and C++ in hash table order,
+                // so only the preambles are compared byte for byte.
+                let preamble_size = if mode == "List" { 8 } else { 12 };
+                assert_eq!(
+                    bytes[..preamble_size],
+                    expected[..preamble_size],
+                    "Rust {type_name} n{n} {mode} preamble must match {}",
+                    path.display()
+                );
                 assert_eq!(
-                    bytes,
-                    expected,
-                    "Rust {type_name} n{n} {mode} bytes must match {}",
+                    sorted_coupons(&bytes[preamble_size..]),
+                    sorted_coupons(&expected[preamble_size..]),
+                    "Rust {type_name} n{n} {mode} coupons must match {}",
                     path.display()
                 );
+                let reserialized = HllSketch::deserialize(&expected).unwrap().serialize();
+                assert_eq!(reserialized, bytes, "{}", path.display());
             }
         }
     }
@@ -281,3 +419,95 @@ fn test_estimate_accuracy() {
         assert!(error_pct < 2., "Error too high: {:.3}%", error_pct);
     }
 }
+
+#[test]
+fn test_inspect_reports_preamble_per_mode() {
+    use datasketches::hll::HllMode;
+    use datasketches::hll::inspect;
+
+    let sketch = HllSketch::new(10, HllType::Hll6);
+    let info = inspect(&sketch.serialize()).unwrap();
+    assert_eq!(info.family_id(), 7);
+    assert_eq!(info.serial_version(), 1);
+    assert_eq!(info.lg_config_k(), 10);
+    assert_eq!(info.mode(), HllMode::List);
+    assert_eq!(info.target_type(), HllType::Hll6);
+    assert!(info.is_empty());
+    assert_eq!(info.coupon_count(), Some(0));
+    assert_eq!(info.hip_accum(), None);
+
+    let mut sketch = HllSketch::new(16, HllType::Hll6);
+    for i in 0..100 {
+        sketch.update(i);
+    }
+    let info = inspect(&sketch.serialize()).unwrap();
+    assert_eq!(info.mode(), HllMode::Set);
+    assert!(!info.is_empty());
+    assert!(info.coupon_count().unwrap() > 90);
+    assert_eq!(info.cur_min(), None);
+
+    let mut sketch = HllSketch::new(10, HllType::Hll4);
+    for i in 0..100_000 {
+        sketch.update(i);
+    }
+    let info = inspect(&sketch.serialize()).unwrap();
+    assert_eq!(info.mode(), HllMode::Hll4);
+    assert_eq!(info.preamble_ints(), 10);
+    assert_eq!(info.coupon_count(), None);
+    assert!(!info.is_out_of_order());
+    let hip = info.hip_accum().unwrap();
+    assert!((hip - sketch.estimate()).abs() < 1e-6 * hip);
+    assert!(info.kxq0().is_some() && info.kxq1().is_some());
+    assert!(info.cur_min().is_some());
+    assert!(info.num_at_cur_min().is_some());
+    assert!(info.aux_count().is_some());
+}
+
+#[test]
+fn test_inspect_rejects_invalid_preamble() {
+    use datasketches::hll::inspect;
+
+    let mut sketch = HllSketch::new(12, HllType::Hll8);
+    for i in 0..10_000 {
+        sketch.update(i);
+    }
+    let bytes = sketch.serialize();
+    assert!(inspect(&bytes[..4]).is_err());
+    assert!(inspect(&bytes[..20]).is_err());
+    assert!(inspect(&bytes[..40]).is_ok());
+
+    let mut wrong_family = bytes.clone();
+    wrong_family[2] = 3;
+    assert!(inspect(&wrong_family).is_err());
+
+    let mut wrong_lg_k = bytes.clone();
+    wrong_lg_k[3] = 22;
+    assert!(inspect(&wrong_lg_k).is_err());
+
+    let mut wrong_preamble = bytes;
+    wrong_preamble[0] = 2;
+    assert!(inspect(&wrong_preamble).is_err());
+}
+
+#[test]
+fn test_newer_serial_version_is_unsupported() {
+    use datasketches::error::ErrorKind;
+    use datasketches::hll::inspect;
+
+    let mut sketch = HllSketch::new(12, HllType::Hll8);
+    sketch.update("apple");
+    let mut bytes = sketch.serialize();
+    bytes[1] += 1;
+
+    let err = HllSketch::deserialize(&bytes).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnsupportedVersion);
+    assert_eq!(
+        err.to_string(),
+        "UnsupportedVersion, context: { family: HLL, found: 2, max_supported: 1 } => \
+         unsupported serial version: 2 is newer than the latest supported version 1"
+    );
+    assert_eq!(
+        inspect(&bytes).unwrap_err().kind(),
+        ErrorKind::UnsupportedVersion
+    );
+}
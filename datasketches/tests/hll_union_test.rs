@@ -33,6 +33,10 @@ use datasketches::common::NumStdDev;
 use datasketches::hll::HllSketch;
 use datasketches::hll::HllType;
 use datasketches::hll::HllUnion;
+use datasketches::hll::intersection_estimate;
+use datasketches::hll::union_estimate;
+use googletest::assert_that;
+use googletest::prelude::near;
 
 #[test]
 fn test_union_basic_operations() {
@@ -230,6 +234,53 @@ fn test_union_mixed_hll_types() {
     }
 }
 
+#[test]
+fn test_union_result_types_match_hll8() {
+    for n in [3_000, 100_000] {
+        // Direct value updates keep the gadget in order, so it estimates with HIP; merged HLL
+        // sketches make it out of order, so it uses the composite estimator.
+        let mut in_order = HllUnion::new(10);
+        let mut out_of_order = HllUnion::new(10);
+        let mut sketch = HllSketch::new(10, HllType::Hll8);
+        for i in 0..n {
+            in_order.update_value(i);
+            sketch.update(i);
+        }
+        out_of_order.update(&sketch);
+        out_of_order.update(&sketch);
+
+        for union in [in_order, out_of_order] {
+            let expected = union.to_sketch(HllType::Hll8);
+            for hll_type in [HllType::Hll4, HllType::Hll6] {
+                let result = union.to_sketch(hll_type);
+                assert_eq!(result.target_type(), hll_type);
+                assert_eq!(result.estimate(), expected.estimate(), "n={n} {hll_type:?}");
+                for num_std_dev in [NumStdDev::One, NumStdDev::Two, NumStdDev::Three] {
+                    assert_eq!(
+                        result.upper_bound(num_std_dev),
+                        expected.upper_bound(num_std_dev)
+                    );
+                    assert_eq!(
+                        result.lower_bound(num_std_dev),
+                        expected.lower_bound(num_std_dev)
+                    );
+                }
+                for slot in 0..1 << 10 {
+                    assert_eq!(result.register_value(slot), expected.register_value(slot));
+                }
+
+                // The estimator state, including the out-of-order flag, survives serialization
+                let restored = HllSketch::deserialize(&result.serialize()).unwrap();
+                assert_eq!(
+                    restored.estimate(),
+                    expected.estimate(),
+                    "n={n} {hll_type:?}"
+                );
+            }
+        }
+    }
+}
+
 #[test]
 fn test_union_lg_k_handling() {
     // Test multiple downsizing operations: 12 → 10 → 8
@@ -404,6 +455,46 @@ fn test_union_reset() {
     }
 }
 
+#[test]
+fn test_union_reset_reuses_register_array() {
+    let mut union = HllUnion::new(12);
+    let mut last_hll_size = None;
+    for group in 0..4_u64 {
+        // Alternate between updating with values and with HLL-mode sketches
+        let mut sketch = HllSketch::new(12, HllType::Hll4);
+        let mut fresh = HllUnion::new(12);
+        for i in 0..10_000 {
+            if group % 2 == 0 {
+                union.update_value((group, i));
+                fresh.update_value((group, i));
+            } else {
+                sketch.update((group, i));
+            }
+        }
+        if group % 2 == 1 {
+            union.update(&sketch);
+            fresh.update(&sketch);
+        }
+
+        // A reset union gives the same results as a fresh one
+        assert_eq!(
+            union.to_sketch(HllType::Hll8),
+            fresh.to_sketch(HllType::Hll8)
+        );
+
+        // ... and holds one register array, not one per group
+        let hll_size = union.estimated_size();
+        if let Some(last_hll_size) = last_hll_size {
+            assert_eq!(hll_size, last_hll_size);
+        }
+        last_hll_size = Some(hll_size);
+
+        union.reset();
+        assert!(union.is_empty());
+        assert!(union.estimated_size() >= HllUnion::new(12).estimated_size() + (1 << 12));
+    }
+}
+
 #[test]
 fn test_union_commutativity() {
     // Verify A∪B = B∪A
@@ -586,3 +677,293 @@ fn test_union_validation() {
     union.reset();
     assert_eq!(union.lg_max_k(), 15, "lg_max_k should persist after reset");
 }
+
+#[test]
+fn test_add_operators() {
+    let mut left = HllSketch::new(12, HllType::Hll6);
+    let mut right = HllSketch::new(10, HllType::Hll8);
+    for i in 0..10_000 {
+        left.update(i);
+        right.update(i + 5_000);
+    }
+
+    let mut union = HllUnion::new(12);
+    union.update(&left);
+    union.update(&right);
+    let expected = union.to_sketch(HllType::Hll6);
+
+    let sum = &left + &right;
+    assert_eq!(sum.lg_config_k(), 10);
+    assert_eq!(sum.target_type(), HllType::Hll6);
+    assert_eq!(sum, expected);
+
+    let mut union = HllUnion::new(12);
+    union += &left;
+    union += &right;
+    assert_eq!(union.to_sketch(HllType::Hll6), expected);
+}
+
+fn partition_sketch(lg_k: u8, hll_type: HllType, start: u64, n: u64) -> HllSketch {
+    let mut sketch = HllSketch::new(lg_k, hll_type);
+    for i in start..start + n {
+        sketch.update(i);
+    }
+    sketch
+}
+
+#[test]
+fn test_extend_matches_sequential_updates() {
+    let sketches = vec![
+        partition_sketch(12, HllType::Hll8, 0, 5),
+        partition_sketch(11, HllType::Hll4, 0, 300),
+        partition_sketch(12, HllType::Hll6, 100, 20_000),
+        HllSketch::new(8, HllType::Hll4),
+        partition_sketch(10, HllType::Hll4, 50_000, 10_000),
+        partition_sketch(12, HllType::Hll8, 70_000, 100),
+        partition_sketch(12, HllType::Hll8, 80_000, 5_000),
+    ];
+
+    for lg_max_k in [9, 11, 12] {
+        // Start from both an empty gadget and one already in HLL mode.
+        for seed in [
+            None,
+            Some(partition_sketch(12, HllType::Hll8, 90_000, 8_000)),
+        ] {
+            let mut sequential = HllUnion::new(lg_max_k);
+            let mut bulk = HllUnion::new(lg_max_k);
+            if let Some(seed) = &seed {
+                sequential.update(seed);
+                bulk.update(seed);
+            }
+            for sketch in &sketches {
+                sequential.update(sketch);
+            }
+            bulk.extend(&sketches);
+
+            assert_eq!(bulk.lg_config_k(), sequential.lg_config_k());
+            let expected = sequential.estimate();
+            assert!(
+                (bulk.estimate() - expected).abs() <= expected * 1e-12,
+                "lg_max_k={lg_max_k}: {} vs {expected}",
+                bulk.estimate()
+            );
+        }
+    }
+}
+
+#[test]
+fn test_extend_with_coupon_sketches_only() {
+    let sketches: Vec<HllSketch> = (0..10)
+        .map(|p| partition_sketch(12, HllType::Hll4, p * 10, 10))
+        .collect();
+
+    let mut sequential = HllUnion::new(12);
+    for sketch in &sketches {
+        sequential.update(sketch);
+    }
+    let mut bulk = HllUnion::new(12);
+    bulk.extend(&sketches);
+    assert_eq!(
+        bulk.to_sketch(HllType::Hll8),
+        sequential.to_sketch(HllType::Hll8)
+    );
+}
+
+#[test]
+fn test_collect_into_union() {
+    let sketches = [
+        partition_sketch(10, HllType::Hll4, 0, 10_000),
+        partition_sketch(12, HllType::Hll8, 5_000, 10_000),
+    ];
+    let union: HllUnion = sketches.iter().collect();
+    assert_eq!(union.lg_max_k(), 12);
+    assert_eq!(union.lg_config_k(), 10);
+    assert_that!(union.estimate(), near(15_000.0, 15_000.0 * 0.1));
+
+    let empty: HllUnion = std::iter::empty::<&HllSketch>().collect();
+    assert_eq!(empty.lg_max_k(), 12);
+    assert!(empty.is_empty());
+}
+
+#[test]
+fn test_intersection_estimate() {
+    let lg_k = 12;
+    let mut a = HllSketch::new(lg_k, HllType::Hll4);
+    let mut b = HllSketch::new(lg_k, HllType::Hll8);
+    for i in 0..200_000 {
+        a.update(i);
+        b.update(i + 100_000);
+    }
+
+    let result = intersection_estimate(&a, &b, NumStdDev::Two);
+    let (lb, est, ub) = result.to_tuple();
+    assert!(lb <= 100_000.0 && 100_000.0 <= ub, "{result:?}");
+    assert_that!(est, near(100_000.0, 10_000.0));
+    assert!(result.is_reliable());
+
+    // Wider bounds for more standard deviations
+    let one = intersection_estimate(&a, &b, NumStdDev::One);
+    assert_eq!(one.estimate(), est);
+    assert!(one.lower_bound() >= lb && one.upper_bound() <= ub);
+}
+
+#[test]
+fn test_intersection_estimate_disjoint_and_empty() {
+    let mut a = HllSketch::new(12, HllType::Hll8);
+    let mut b = HllSketch::new(10, HllType::Hll8);
+    for i in 0..1_000_000 {
+        a.update(i);
+    }
+    for i in 0..1_000 {
+        b.update(-i - 1);
+    }
+
+    // A tiny intersection relative to the union is dominated by the union's error
+    let result = intersection_estimate(&a, &b, NumStdDev::Two);
+    assert!(result.lower_bound() >= 0.0);
+    assert!(result.estimate() <= 1_000.0);
+    assert!(!result.is_reliable(), "{result:?}");
+
+    let empty = HllSketch::new(12, HllType::Hll8);
+    let result = intersection_estimate(&a, &empty, NumStdDev::Two);
+    assert_eq!(result.to_tuple(), (0.0, 0.0, 0.0));
+    assert_eq!(result.relative_error(), 0.0);
+    assert!(result.is_reliable());
+}
+
+#[test]
+fn test_union_estimate_matches_union() {
+    // Exercise every pair of modes: List, Set and HLL at each lg_k
+    let sizes = [10, 200, 20_000];
+    for (lg_a, type_a) in [(10, HllType::Hll4), (12, HllType::Hll6)] {
+        for (lg_b, type_b) in [(11, HllType::Hll8), (12, HllType::Hll4)] {
+            for n_a in sizes {
+                for n_b in sizes {
+                    let mut a = HllSketch::new(lg_a, type_a);
+                    let mut b = HllSketch::new(lg_b, type_b);
+                    for i in 0..n_a {
+                        a.update(i);
+                    }
+                    for i in 0..n_b {
+                        b.update(i + n_a / 2);
+                    }
+                    let before = (a.clone(), b.clone());
+
+                    let mut union = HllUnion::new(lg_a.max(lg_b));
+                    union.update(&a);
+                    union.update(&b);
+                    let expected = union.estimate();
+
+                    assert_eq!(union_estimate(&a, &b), expected, "n_a={n_a}, n_b={n_b}");
+                    assert_eq!((a, b), before);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_union_estimate_empty() {
+    let empty = HllSketch::new(12, HllType::Hll8);
+    assert_eq!(union_estimate(&empty, &empty), 0.0);
+
+    let mut sketch = HllSketch::new(12, HllType::Hll4);
+    sketch.update("apple");
+    assert_eq!(union_estimate(&empty, &sketch), 1.0);
+}
+
+#[test]
+fn test_save_load_state() {
+    for n in [0, 10, 1_000, 100_000] {
+        let mut union = HllUnion::new(12);
+        let mut small = HllSketch::new(10, HllType::Hll4);
+        for i in 0..n {
+            small.update(i);
+        }
+        union.update(&small);
+
+        let state = union.save_state();
+        let mut restored = HllUnion::load_state(&state).unwrap();
+        assert_eq!(restored.lg_max_k(), union.lg_max_k());
+        assert_eq!(restored.lg_config_k(), union.lg_config_k());
+        assert_eq!(restored.estimate(), union.estimate(), "n={n}");
+
+        // The restored union keeps accumulating like the original
+        let mut more = HllSketch::new(12, HllType::Hll8);
+        for i in n..n + 1_000 {
+            more.update(i);
+        }
+        union.update(&more);
+        restored.update(&more);
+        assert_eq!(restored.estimate(), union.estimate(), "n={n}");
+    }
+}
+
+#[test]
+fn test_load_state_rejects_invalid_state() {
+    let mut union = HllUnion::new(12);
+    union.update_value("apple");
+    let state = union.save_state();
+
+    // A plain serialized sketch is not a union state
+    let sketch = union.to_sketch(HllType::Hll8).serialize();
+    assert!(HllUnion::load_state(&sketch).is_err());
+
+    for len in 0..state.len() {
+        assert!(HllUnion::load_state(&state[..len]).is_err(), "len={len}");
+    }
+}
+
+#[test]
+fn test_union_with_seed() {
+    for n in [10, 1_000, 100_000] {
+        let mut seeded = HllSketch::builder()
+            .lg_config_k(12)
+            .hll_type(HllType::Hll4)
+            .seed(42)
+            .build();
+        let mut default = HllSketch::new(12, HllType::Hll4);
+        for i in 0..n {
+            seeded.update(i);
+            default.update(i);
+        }
+        assert_eq!(seeded.seed(), 42);
+        assert_ne!(seeded, default, "n={n}");
+
+        let decoded = HllSketch::deserialize_with_seed(&seeded.serialize(), 42).unwrap();
+        assert_eq!(decoded, seeded, "n={n}");
+
+        let mut union = HllUnion::with_seed(12, 42);
+        union.update(&seeded);
+        for i in 0..n {
+            union.update_value(i);
+        }
+        let result = union.to_sketch(HllType::Hll4);
+        assert_eq!(result.seed(), 42);
+        assert_eq!(result.estimate(), seeded.estimate(), "n={n}");
+
+        let restored = HllUnion::load_state(&union.save_state()).unwrap();
+        assert_eq!(restored.seed(), 42);
+        assert_eq!(restored.estimate(), union.estimate(), "n={n}");
+
+        let pair: HllUnion = [&seeded, &decoded].into_iter().collect();
+        assert_eq!(pair.seed(), 42);
+        assert_eq!((&seeded + &decoded).seed(), 42);
+    }
+}
+
+#[test]
+#[should_panic(expected = "sketch seed does not match the union seed")]
+fn test_union_rejects_seed_mismatch() {
+    let sketch = HllSketch::with_seed(12, HllType::Hll8, 42);
+    let mut union = HllUnion::new(12);
+    union.update(&sketch);
+}
+
+#[test]
+#[should_panic(expected = "sketch seed does not match the union seed")]
+fn test_union_extend_rejects_seed_mismatch() {
+    let seeded = HllSketch::with_seed(12, HllType::Hll8, 42);
+    let default = HllSketch::new(12, HllType::Hll8);
+    let _: HllUnion = [&seeded, &default].into_iter().collect();
+}
@@ -17,9 +17,23 @@
 
 #![cfg(feature = "hll")]
 
+use datasketches::common::MemoryUsage;
 use datasketches::common::NumStdDev;
+use datasketches::common::SketchBuilder;
+use datasketches::error::ErrorKind;
+use datasketches::hash_value::ValueRef;
+use datasketches::hash_value::canonical;
+use datasketches::hash_value::canonical_float;
+use datasketches::hash_value::raw_bytes;
+use datasketches::hll::Coupon;
+use datasketches::hll::HllMode;
 use datasketches::hll::HllSketch;
 use datasketches::hll::HllType;
+use datasketches::hll::HllUnion;
+use datasketches::hll::MAX_LG_K;
+use datasketches::hll::MIN_LG_K;
+use datasketches::hll::RESIZE_DENOMINATOR;
+use datasketches::hll::RESIZE_NUMERATOR;
 
 #[test]
 fn test_basic_update() {
@@ -78,6 +92,90 @@ fn test_set_to_hll_promotion() {
     );
 }
 
+/// Returns the mode a sketch is in after `num_coupons` distinct coupons, following the Java
+/// promotion rules: a list of 8 coupons, then for lg_k >= 8 a hash set that grows at the resize
+/// load factor until it has K / 8 slots.
+fn expected_mode(lg_k: u8, num_coupons: usize) -> HllMode {
+    if num_coupons < 8 {
+        return HllMode::List;
+    }
+    if lg_k < 8 {
+        return HllMode::Hll8;
+    }
+    let max_set_slots = 1usize << (lg_k - 3);
+    if RESIZE_DENOMINATOR as usize * num_coupons > RESIZE_NUMERATOR as usize * max_set_slots {
+        HllMode::Hll8
+    } else {
+        HllMode::Set
+    }
+}
+
+#[test]
+fn test_promotion_thresholds() {
+    for lg_k in MIN_LG_K..=13 {
+        let mut sketch = HllSketch::new(lg_k, HllType::Hll8);
+        let mut seen = std::collections::HashSet::new();
+        let mut i = 0u64;
+        while sketch.current_mode() != HllMode::Hll8 {
+            let coupon = Coupon::from_hash(i);
+            i += 1;
+            sketch.update_with_coupon(coupon);
+            if seen.insert(coupon) {
+                assert_eq!(
+                    sketch.current_mode(),
+                    expected_mode(lg_k, seen.len()),
+                    "lg_k={lg_k}, coupons={}",
+                    seen.len()
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_oversized_set_image_is_promoted() {
+    let mut sketch = HllSketch::new(8, HllType::Hll8);
+    for i in 0..10 {
+        sketch.update(i);
+    }
+    assert_eq!(sketch.current_mode(), HllMode::Set);
+
+    // A set of 32 slots is above the K / 8 = 16 slots lg_k 7 allows, so the sketch is promoted
+    // at the next resize instead of growing the set.
+    let mut bytes = sketch.serialize();
+    bytes[3] = 7;
+    let mut sketch = HllSketch::deserialize(&bytes).unwrap();
+    let mut i = 10;
+    while sketch.current_mode() == HllMode::Set {
+        sketch.update(i);
+        i += 1;
+    }
+    assert_eq!(sketch.current_mode(), HllMode::Hll8);
+    assert!(i <= 40, "promoted after {i} updates");
+}
+
+#[test]
+fn test_reset() {
+    for hll_type in [HllType::Hll4, HllType::Hll6, HllType::Hll8] {
+        let fresh = HllSketch::new(12, hll_type);
+        for n in [0, 5, 100, 10_000] {
+            let mut sketch = HllSketch::new(12, hll_type);
+            for i in 0..n {
+                sketch.update(i);
+            }
+            sketch.reset();
+            assert!(sketch.is_empty());
+            assert_eq!(sketch.current_mode(), HllMode::List);
+            assert_eq!(sketch.target_type(), hll_type);
+            assert_eq!(sketch.estimated_size(), fresh.estimated_size());
+            assert_eq!(sketch.serialize(), fresh.serialize());
+
+            sketch.update("apple");
+            assert_eq!(sketch.estimate(), 1.0);
+        }
+    }
+}
+
 #[test]
 fn test_duplicate_handling() {
     let mut sketch = HllSketch::new(12, HllType::Hll8);
@@ -229,6 +327,43 @@ fn test_invalid_lg_k_high() {
     HllSketch::new(22, HllType::Hll8);
 }
 
+#[test]
+fn test_lg_k_limits_agree() {
+    assert_eq!((MIN_LG_K, MAX_LG_K), (4, 21));
+
+    for lg_k in [MIN_LG_K - 1, MAX_LG_K + 1] {
+        let err = HllSketch::builder()
+            .lg_config_k(lg_k)
+            .try_build()
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidArgument);
+    }
+
+    let mut sketch = HllSketch::new(MAX_LG_K, HllType::Hll4);
+    sketch.update(1);
+    let bytes = sketch.serialize();
+    assert_eq!(HllSketch::deserialize(&bytes).unwrap(), sketch);
+    for lg_k in [MIN_LG_K - 1, MAX_LG_K + 1] {
+        let mut bad = bytes.clone();
+        bad[3] = lg_k;
+        let err = HllSketch::deserialize(&bad).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert!(
+            err.to_string().contains("lg_config_k must be in [4, 21]"),
+            "{err}"
+        );
+        assert!(datasketches::hll::inspect(&bad).is_err());
+    }
+
+    let union = HllUnion::new(MAX_LG_K);
+    let state = union.save_state();
+    assert!(HllUnion::load_state(&state).is_ok());
+    // The union payload starts with lg_max_k, after the 16 byte state envelope.
+    let mut bad = state.clone();
+    bad[16] = MAX_LG_K + 1;
+    assert!(HllUnion::load_state(&bad).is_err());
+}
+
 #[test]
 fn test_bounds_basic() {
     let mut sketch = HllSketch::new(12, HllType::Hll8);
@@ -356,3 +491,359 @@ fn test_bounds_empty_sketch() {
     assert!(upper >= 0.0, "Upper bound should be non-negative");
     assert!(lower <= upper, "Lower bound should be <= upper bound");
 }
+
+#[test]
+fn test_memory_usage_tracks_mode() {
+    let mut sketch = HllSketch::new(12, HllType::Hll8);
+    let empty = sketch.memory_usage();
+    assert_eq!(empty, sketch.estimated_size());
+
+    for i in 0..10_000 {
+        sketch.update(i);
+    }
+    // Hll8 registers alone need 2^12 bytes.
+    assert!(sketch.memory_usage() >= 1 << 12);
+    assert!(sketch.memory_usage() > empty);
+}
+
+#[test]
+fn test_downsample_matches_direct_sketch() {
+    for hll_type in [HllType::Hll4, HllType::Hll6, HllType::Hll8] {
+        for n in [0u64, 10, 300, 50_000] {
+            let mut source = HllSketch::new(14, hll_type);
+            let mut direct = HllSketch::new(10, hll_type);
+            for i in 0..n {
+                source.update(i);
+                direct.update(i);
+            }
+
+            let reduced = source.downsample(10);
+            assert_eq!(reduced.lg_config_k(), 10);
+            assert_eq!(reduced.target_type(), hll_type);
+            assert_eq!(reduced.is_empty(), n == 0);
+
+            let expected = direct.estimate();
+            let actual = reduced.estimate();
+            assert!(
+                (actual - expected).abs() <= expected * 0.05,
+                "{hll_type:?} n={n}: downsampled estimate {actual} too far from {expected}"
+            );
+
+            // The reduced sketch round-trips and is as cheap to store as a native one.
+            let bytes = reduced.serialize();
+            assert_eq!(HllSketch::deserialize(&bytes).unwrap().estimate(), actual);
+            if n >= 50_000 {
+                assert_eq!(bytes.len(), direct.serialize().len());
+            }
+        }
+    }
+}
+
+#[test]
+fn test_downsample_to_same_lg_k_is_copy() {
+    let mut sketch = HllSketch::new(12, HllType::Hll6);
+    for i in 0..1000 {
+        sketch.update(i);
+    }
+    let copy = sketch.downsample(12);
+    assert_eq!(copy.serialize(), sketch.serialize());
+}
+
+#[test]
+#[should_panic(expected = "target_lg_k must be in [4, 10]")]
+fn test_downsample_rejects_larger_lg_k() {
+    HllSketch::new(10, HllType::Hll8).downsample(11);
+}
+
+/// Estimates must be bit-identical on every target, so these are pinned to exact bit patterns
+/// rather than checked against a tolerance.
+#[test]
+fn test_estimates_are_bit_stable() {
+    // Coupon (list) mode: cubic interpolation over the coupon tables.
+    let mut sketch = HllSketch::new(12, HllType::Hll8);
+    for i in 0..50u64 {
+        sketch.update(i);
+    }
+    assert_eq!(sketch.estimate().to_bits(), 0x4049_0000_330a_ab24);
+    assert_eq!(
+        sketch.upper_bound(NumStdDev::One).to_bits(),
+        0x4049_0052_00e3_c8ad
+    );
+
+    // HLL mode, in order: HIP accumulator and KxQ registers.
+    let mut sketch = HllSketch::new(12, HllType::Hll8);
+    for i in 0..100_000u64 {
+        sketch.update(i);
+    }
+    assert_eq!(sketch.estimate().to_bits(), 0x40f8_b1c7_bb46_5fc4);
+    assert_eq!(
+        sketch.upper_bound(NumStdDev::Two).to_bits(),
+        0x40f9_57b0_6e45_b939
+    );
+    assert_eq!(
+        sketch.lower_bound(NumStdDev::Two).to_bits(),
+        0x40f8_10bf_aef7_a32d
+    );
+
+    // Out of order (union results): composite estimator, with and without linear counting.
+    let union_of_halves = |lg_k: u8, hll_type: HllType, n: u64| {
+        let mut even = HllSketch::new(lg_k, hll_type);
+        let mut odd = HllSketch::new(lg_k, hll_type);
+        for i in 0..n {
+            if i % 2 == 0 {
+                even.update(i);
+            } else {
+                odd.update(i);
+            }
+        }
+        let mut union = HllUnion::new(lg_k);
+        union.update(&even);
+        union.update(&odd);
+        union.to_sketch(hll_type)
+    };
+    let sketch = union_of_halves(12, HllType::Hll4, 1_500);
+    assert_eq!(sketch.estimate().to_bits(), 0x4097_40f8_206d_bbe0);
+    let sketch = union_of_halves(10, HllType::Hll6, 50_000);
+    assert_eq!(sketch.estimate().to_bits(), 0x40e9_3775_f68c_0a77);
+}
+
+#[test]
+fn test_serialize_into_slice_matches_serialize() {
+    for hll_type in [HllType::Hll4, HllType::Hll6, HllType::Hll8] {
+        for n in [0, 10, 1_000, 100_000] {
+            let mut sketch = HllSketch::new(12, hll_type);
+            for i in 0..n {
+                sketch.update(i);
+            }
+            let expected = sketch.serialize();
+            let mut buf = vec![0u8; expected.len() + 4];
+            let len = sketch.serialize_into_slice(&mut buf).unwrap();
+            assert_eq!(&buf[..len], &expected[..]);
+
+            let mut short = vec![0u8; expected.len() - 1];
+            assert!(sketch.serialize_into_slice(&mut short).is_err());
+        }
+    }
+}
+
+#[test]
+fn test_current_mode_tracks_promotion() {
+    for (hll_type, array_mode) in [
+        (HllType::Hll4, HllMode::Hll4),
+        (HllType::Hll6, HllMode::Hll6),
+        (HllType::Hll8, HllMode::Hll8),
+    ] {
+        let mut sketch = HllSketch::new(12, hll_type);
+        assert_eq!(sketch.current_mode(), HllMode::List);
+
+        let mut seen = vec![HllMode::List];
+        for i in 0..10_000 {
+            sketch.update(i);
+            let mode = sketch.current_mode();
+            if seen.last() != Some(&mode) {
+                seen.push(mode);
+            }
+        }
+        assert_eq!(seen, [HllMode::List, HllMode::Set, array_mode]);
+
+        let restored = HllSketch::deserialize(&sketch.serialize()).unwrap();
+        assert_eq!(restored.current_mode(), array_mode);
+    }
+}
+
+#[test]
+fn test_register_value_matches_across_modes_and_types() {
+    let lg_k = 8;
+    let k = 1u32 << lg_k;
+    let registers = |sketch: &HllSketch| -> Vec<u8> {
+        (0..k).map(|slot| sketch.register_value(slot)).collect()
+    };
+
+    for n in [0, 5, 100, 20_000] {
+        let mut sketches = [
+            HllSketch::new(lg_k, HllType::Hll4),
+            HllSketch::new(lg_k, HllType::Hll6),
+            HllSketch::new(lg_k, HllType::Hll8),
+        ];
+        for sketch in &mut sketches {
+            for i in 0..n {
+                sketch.update(i);
+            }
+        }
+
+        let expected = registers(&sketches[2]);
+        assert_eq!(expected.iter().all(|&v| v == 0), n == 0);
+        for sketch in &sketches {
+            assert_eq!(
+                registers(sketch),
+                expected,
+                "n={n} {:?}",
+                sketch.target_type()
+            );
+        }
+
+        // Promoting coupons to an array keeps every register
+        let mut union = HllUnion::new(lg_k);
+        union.update(&sketches[0]);
+        let promoted = union.to_sketch(HllType::Hll8);
+        assert_eq!(registers(&promoted), expected, "n={n}");
+    }
+}
+
+#[test]
+fn test_hll4_round_trip_preserves_registers() {
+    // Large n pushes cur_min above 0 and produces aux exceptions for lg_k 4
+    for (lg_k, n) in [(4, 1_000_000), (10, 50_000), (12, 5_000)] {
+        let mut sketch = HllSketch::new(lg_k, HllType::Hll4);
+        for i in 0..n {
+            sketch.update(i);
+        }
+        assert_eq!(sketch.current_mode(), HllMode::Hll4);
+
+        let bytes = sketch.serialize();
+        let restored = HllSketch::deserialize(&bytes).unwrap();
+        for slot in 0..1u32 << lg_k {
+            assert_eq!(
+                restored.register_value(slot),
+                sketch.register_value(slot),
+                "lg_k={lg_k} slot={slot}"
+            );
+        }
+        assert_eq!(restored.estimate().to_bits(), sketch.estimate().to_bits());
+        assert_eq!(restored.serialize(), bytes);
+        assert_eq!(restored, sketch);
+    }
+}
+
+#[test]
+#[should_panic(expected = "slot must be less than 16")]
+fn test_register_value_rejects_out_of_range_slot() {
+    HllSketch::new(4, HllType::Hll8).register_value(16);
+}
+
+#[test]
+fn test_canonical_values_match_cross_language_wrappers() {
+    let mut canonical = HllSketch::new(12, HllType::Hll8);
+    let mut wrapped = HllSketch::new(12, HllType::Hll8);
+    for i in 0..1_000_i64 {
+        let key = format!("key-{i}");
+        canonical.update(canonical::from_value(key.as_str()));
+        canonical.update(canonical::from_value(i));
+        canonical.update(canonical::from_value(i as f64 / 2.0));
+        wrapped.update(raw_bytes::from_str(&key));
+        wrapped.update(i);
+        wrapped.update(canonical_float::from_f64(i as f64 / 2.0));
+    }
+    assert_eq!(canonical, wrapped);
+
+    // The canonical hash of a string differs from Rust's, which appends a terminator.
+    let mut hashed = HllSketch::new(12, HllType::Hll8);
+    hashed.update("apple");
+    let mut raw = HllSketch::new(12, HllType::Hll8);
+    raw.update(canonical::from_value("apple"));
+    assert_ne!(hashed, raw);
+}
+
+#[test]
+fn test_update_value_matches_canonical_values() {
+    let mut dynamic = HllSketch::new(12, HllType::Hll6);
+    let mut typed = HllSketch::new(12, HllType::Hll6);
+    for i in 0..1_000_i64 {
+        let key = format!("key-{i}");
+        let values = [
+            ValueRef::Str(&key),
+            ValueRef::Bytes(key.as_bytes()),
+            ValueRef::U64(i as u64),
+            ValueRef::I64(-i),
+            ValueRef::F64(i as f64 / 2.0),
+        ];
+        for value in values {
+            dynamic.update_value_ref(value);
+        }
+        typed.update(canonical::from_value(key.as_str()));
+        typed.update(canonical::from_value(i as u64));
+        typed.update(canonical::from_value(-i));
+        typed.update(canonical::from_value(i as f64 / 2.0));
+    }
+    assert_eq!(dynamic, typed);
+
+    // Unions take a ValueRef through their generic update method.
+    let mut union = HllUnion::new(12);
+    union.update_value(ValueRef::Str("apple"));
+    let mut sketch = HllSketch::new(12, HllType::Hll8);
+    sketch.update_value_ref(ValueRef::Str("apple"));
+    assert_eq!(union.to_sketch(HllType::Hll8), sketch);
+
+    // Empty strings and byte strings are ignored, as by update_str and update_bytes.
+    let mut sketch = HllSketch::new(12, HllType::Hll8);
+    sketch.update_value_ref(ValueRef::Str(""));
+    sketch.update_value_ref(ValueRef::Bytes(&[]));
+    assert!(sketch.is_empty());
+}
+
+#[test]
+fn test_update_str_hashes_utf8_bytes() {
+    for hll_type in [HllType::Hll4, HllType::Hll6, HllType::Hll8] {
+        let mut sketch = HllSketch::new(10, hll_type);
+        let mut expected = HllSketch::new(10, hll_type);
+        for i in 0..5_000 {
+            let value = format!("item-{i}-é");
+            sketch.update_str(&value);
+            expected.update(raw_bytes::from_str(&value));
+            if i % 2 == 0 {
+                sketch.update_bytes(value.as_bytes());
+            }
+        }
+        assert_eq!(sketch, expected);
+
+        sketch.update_str("");
+        sketch.update_bytes(&[]);
+        assert_eq!(sketch, expected);
+    }
+
+    let mut sketch = HllSketch::new(10, HllType::Hll8);
+    sketch.update_str("");
+    assert!(sketch.is_empty());
+}
+
+#[test]
+fn test_hip_and_composite_estimates() {
+    for hll_type in [HllType::Hll4, HllType::Hll6, HllType::Hll8] {
+        let mut sketch = HllSketch::new(12, hll_type);
+        sketch.update(1);
+        assert_eq!(sketch.current_mode(), HllMode::List);
+        assert_eq!(sketch.hip_estimate(), Some(sketch.estimate()));
+        assert_eq!(sketch.composite_estimate(), sketch.estimate());
+
+        for i in 0..20_000 {
+            sketch.update(i);
+        }
+        assert!(!matches!(
+            sketch.current_mode(),
+            HllMode::List | HllMode::Set
+        ));
+        assert_eq!(sketch.hip_estimate(), Some(sketch.estimate()));
+        let composite = sketch.composite_estimate();
+        assert!(
+            (composite - 20_000.0).abs() < 20_000.0 * 0.05,
+            "{hll_type:?}: composite estimate {composite}"
+        );
+
+        // A merged sketch is out of order and falls back to the composite estimate, also after
+        // a serialization round trip.
+        let mut other = HllSketch::new(12, hll_type);
+        for i in 10_000..30_000 {
+            other.update(i);
+        }
+        let mut union = HllUnion::new(12);
+        union.update(&sketch);
+        union.update(&other);
+        let merged = union.to_sketch(hll_type);
+        assert_eq!(merged.hip_estimate(), None);
+        assert_eq!(merged.estimate(), merged.composite_estimate());
+
+        let merged = HllSketch::deserialize(&merged.serialize()).unwrap();
+        assert_eq!(merged.hip_estimate(), None);
+        assert_eq!(merged.estimate(), merged.composite_estimate());
+    }
+}
@@ -0,0 +1,221 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Tests for the family-agnostic `datasketches::inspect` function.
+
+use datasketches::inspect;
+
+#[test]
+fn test_inspect_rejects_short_and_unknown_input() {
+    assert!(inspect(&[]).is_err());
+    assert!(inspect(&[1, 1]).is_err());
+    assert!(inspect(&[1, 1, 200, 0, 0, 0, 0, 0]).is_err());
+}
+
+#[cfg(feature = "theta")]
+#[test]
+fn test_inspect_theta() {
+    use datasketches::theta::ThetaSketchBuilder;
+
+    let mut sketch = ThetaSketchBuilder::default().lg_k(5).seed(42).build();
+    for i in 0..1000 {
+        sketch.update(i);
+    }
+    let compact = sketch.compact(true);
+    let descriptor = inspect(&compact.serialize()).unwrap();
+    assert_eq!(descriptor.family_id(), 3);
+    assert_eq!(descriptor.family_name(), "THETA");
+    assert!(!descriptor.is_empty());
+    assert_eq!(
+        descriptor.parameter("seed_hash"),
+        Some(compact.seed_hash().to_string().as_str())
+    );
+    assert_eq!(descriptor.parameter("ordered"), Some("true"));
+    assert_eq!(
+        descriptor.num_retained(),
+        Some(compact.num_retained() as u64)
+    );
+    assert_eq!(descriptor.estimate(), Some(compact.estimate()));
+}
+
+#[cfg(feature = "theta")]
+#[test]
+fn test_inspect_updatable_theta() {
+    use datasketches::theta::ThetaSketchBuilder;
+
+    let mut sketch = ThetaSketchBuilder::default().lg_k(5).build();
+    for i in 0..1000 {
+        sketch.update(i);
+    }
+    let descriptor = inspect(&sketch.serialize()).unwrap();
+    assert_eq!(descriptor.family_id(), 2);
+    assert_eq!(descriptor.family_name(), "QUICKSELECT");
+    assert!(!descriptor.is_empty());
+    assert_eq!(descriptor.parameter("lg_k"), Some("5"));
+    assert_eq!(descriptor.parameter("resize_factor"), Some("8"));
+    assert_eq!(
+        descriptor.num_retained(),
+        Some(sketch.num_retained() as u64)
+    );
+    assert_eq!(descriptor.estimate(), Some(sketch.estimate()));
+}
+
+#[cfg(feature = "hll")]
+#[test]
+fn test_inspect_hll() {
+    use datasketches::hll::HllSketch;
+    use datasketches::hll::HllType;
+
+    let mut sketch = HllSketch::new(11, HllType::Hll4);
+    for i in 0..10_000 {
+        sketch.update(i);
+    }
+    let descriptor = inspect(&sketch.serialize()).unwrap();
+    assert_eq!(descriptor.family_name(), "HLL");
+    assert_eq!(descriptor.serial_version(), 1);
+    assert_eq!(descriptor.parameter("lg_k"), Some("11"));
+    assert_eq!(descriptor.parameter("target_type"), Some("Hll4"));
+    assert_eq!(descriptor.parameter("mode"), Some("Hll4"));
+    assert_eq!(descriptor.num_retained(), None);
+    assert_eq!(descriptor.estimate(), Some(sketch.estimate()));
+}
+
+#[cfg(feature = "tuple")]
+#[test]
+fn test_inspect_tuple() {
+    use datasketches::tuple::DefaultUpdatePolicy;
+    use datasketches::tuple::TupleSketchBuilder;
+
+    let mut sketch = TupleSketchBuilder::new(DefaultUpdatePolicy::<u64>::default())
+        .lg_k(5)
+        .build();
+    let descriptor = inspect(&sketch.compact(false).serialize()).unwrap();
+    assert_eq!(descriptor.family_name(), "TUPLE");
+    assert!(descriptor.is_empty());
+    assert_eq!(descriptor.num_retained(), Some(0));
+    assert_eq!(descriptor.estimate(), Some(0.0));
+
+    for i in 0..1000 {
+        sketch.update(i, 1u64);
+    }
+    let compact = sketch.compact(false);
+    let descriptor = inspect(&compact.serialize()).unwrap();
+    assert!(!descriptor.is_empty());
+    assert_eq!(
+        descriptor.num_retained(),
+        Some(compact.num_retained() as u64)
+    );
+    assert_eq!(descriptor.estimate(), Some(compact.estimate()));
+    assert_eq!(
+        descriptor.parameter("theta"),
+        Some(compact.theta().to_string().as_str())
+    );
+}
+
+#[cfg(feature = "frequencies")]
+#[test]
+fn test_inspect_frequencies() {
+    use datasketches::frequencies::FrequentItemsSketch;
+
+    let mut sketch = FrequentItemsSketch::<i64>::new(64);
+    sketch.update_with_count(1, 10);
+    sketch.update_with_count(2, 5);
+    let descriptor = inspect(&sketch.serialize()).unwrap();
+    assert_eq!(descriptor.family_name(), "FREQUENCY");
+    assert_eq!(descriptor.parameter("lg_max_map_size"), Some("6"));
+    assert_eq!(descriptor.parameter("total_weight"), Some("15"));
+    assert_eq!(descriptor.parameter("serde_id"), Some("1"));
+    assert_eq!(descriptor.num_retained(), Some(2));
+    assert_eq!(descriptor.estimate(), None);
+
+    let empty = FrequentItemsSketch::<i64>::new(64);
+    let descriptor = inspect(&empty.serialize()).unwrap();
+    assert!(descriptor.is_empty());
+    assert_eq!(descriptor.num_retained(), Some(0));
+}
+
+#[cfg(feature = "cpc")]
+#[test]
+fn test_inspect_cpc() {
+    use datasketches::cpc::CpcSketch;
+
+    let mut sketch = CpcSketch::new(10);
+    for i in 0..500 {
+        sketch.update(i);
+    }
+    let descriptor = inspect(&sketch.serialize()).unwrap();
+    assert_eq!(descriptor.family_name(), "CPC");
+    assert_eq!(descriptor.parameter("lg_k"), Some("10"));
+    assert_eq!(
+        descriptor.num_retained(),
+        Some(u64::from(sketch.num_coupons()))
+    );
+    assert_eq!(descriptor.estimate(), Some(sketch.estimate()));
+}
+
+#[cfg(feature = "countmin")]
+#[test]
+fn test_inspect_countmin() {
+    use datasketches::countmin::CountMinSketch;
+
+    let mut sketch = CountMinSketch::<u64>::new(3, 16);
+    sketch.update("apple");
+    let descriptor = inspect(&sketch.serialize()).unwrap();
+    assert_eq!(descriptor.family_name(), "COUNTMIN");
+    assert!(!descriptor.is_empty());
+    assert_eq!(descriptor.parameter("num_hashes"), Some("3"));
+    assert_eq!(descriptor.parameter("num_buckets"), Some("16"));
+    assert_eq!(descriptor.num_retained(), None);
+}
+
+#[cfg(feature = "tdigest")]
+#[test]
+fn test_inspect_tdigest() {
+    use datasketches::tdigest::TDigestMut;
+
+    let mut sketch = TDigestMut::new(100);
+    sketch.update(1.0);
+    let descriptor = inspect(&sketch.serialize()).unwrap();
+    assert_eq!(descriptor.family_name(), "TDIGEST");
+    assert_eq!(descriptor.parameter("k"), Some("100"));
+    assert_eq!(descriptor.num_retained(), Some(1));
+
+    for i in 0..10_000 {
+        sketch.update(i as f64);
+    }
+    let descriptor = inspect(&sketch.serialize()).unwrap();
+    assert!(descriptor.num_retained().unwrap() > 1);
+    assert_eq!(descriptor.estimate(), None);
+}
+
+#[cfg(feature = "bloom")]
+#[test]
+fn test_inspect_bloom() {
+    use datasketches::bloom::BloomFilterBuilder;
+
+    let mut filter = BloomFilterBuilder::with_size(1024, 3).seed(7).build();
+    assert!(inspect(&filter.serialize()).unwrap().is_empty());
+
+    filter.insert("apple");
+    let descriptor = inspect(&filter.serialize()).unwrap();
+    assert_eq!(descriptor.family_name(), "BLOOMFILTER");
+    assert!(!descriptor.is_empty());
+    assert_eq!(descriptor.parameter("num_hashes"), Some("3"));
+    assert_eq!(descriptor.parameter("capacity"), Some("1024"));
+    assert_eq!(descriptor.parameter("seed"), Some("7"));
+    assert_eq!(descriptor.parameter("bits_used"), Some("3"));
+}
@@ -0,0 +1,79 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+#![cfg(all(feature = "rayon", feature = "bloom", feature = "countmin"))]
+
+use datasketches::bloom::BloomFilter;
+use datasketches::bloom::BloomFilterBuilder;
+use datasketches::countmin::CountMinSketch;
+use rayon::prelude::*;
+
+#[test]
+fn test_bloom_from_par_iter_matches_sequential() {
+    let builder = BloomFilterBuilder::with_accuracy(100_000, 0.01).seed(42);
+    let filter = BloomFilter::from_par_iter(builder.clone(), (0..100_000_u64).into_par_iter());
+
+    let mut expected = builder.build();
+    for i in 0..100_000_u64 {
+        expected.insert(i);
+    }
+    assert_eq!(filter, expected);
+    assert_eq!(filter.seed(), 42);
+}
+
+#[test]
+fn test_bloom_from_par_iter_empty() {
+    let builder = BloomFilterBuilder::with_size(1024, 3);
+    let filter = BloomFilter::from_par_iter(builder, Vec::<u64>::new());
+    assert!(filter.is_empty());
+    assert_eq!(filter.capacity(), 1024);
+    assert_eq!(filter.num_hashes(), 3);
+}
+
+#[test]
+fn test_countmin_from_par_iter_matches_sequential() {
+    let items = (0..100_000_u64).into_par_iter().map(|i| (i % 1_000, i % 3));
+    let builder = CountMinSketch::<u64>::builder()
+        .num_hashes(5)
+        .num_buckets(256)
+        .seed(7);
+    let sketch = CountMinSketch::from_par_iter(builder, items);
+
+    let mut expected = CountMinSketch::<u64>::with_seed(5, 256, 7);
+    expected.extend((0..100_000_u64).map(|i| (i % 1_000, i % 3)));
+    assert_eq!(sketch, expected);
+    assert_eq!(sketch.seed(), 7);
+}
+
+#[test]
+fn test_countmin_from_par_iter_empty() {
+    let builder = CountMinSketch::<i64>::builder()
+        .num_hashes(3)
+        .num_buckets(5);
+    let sketch = CountMinSketch::from_par_iter(builder, Vec::<(u64, i64)>::new());
+    assert!(sketch.is_empty());
+    assert_eq!(sketch, CountMinSketch::<i64>::new(3, 5));
+}
+
+#[test]
+#[should_panic(expected = "num_hashes must be at least 1")]
+fn test_countmin_from_par_iter_rejects_invalid_config() {
+    let builder = CountMinSketch::<i64>::builder()
+        .num_hashes(0)
+        .num_buckets(5);
+    CountMinSketch::from_par_iter(builder, Vec::<(u64, i64)>::new());
+}
@@ -0,0 +1,156 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+#![cfg(all(
+    feature = "bloom",
+    feature = "countmin",
+    feature = "cpc",
+    feature = "frequencies",
+    feature = "hll",
+    feature = "tdigest",
+    feature = "theta",
+    feature = "tuple"
+))]
+
+use datasketches::bloom::BloomFilterBuilder;
+use datasketches::common::SketchBuilder;
+use datasketches::countmin::CountMinSketch;
+use datasketches::cpc::CpcSketch;
+use datasketches::error::ErrorKind;
+use datasketches::frequencies::FrequentItemsSketch;
+use datasketches::hll::HllSketch;
+use datasketches::hll::HllType;
+use datasketches::tdigest::NanPolicy;
+use datasketches::tdigest::TDigestMut;
+use datasketches::theta::ThetaSketchBuilder;
+use datasketches::tuple::DefaultUpdatePolicy;
+use datasketches::tuple::TupleSketchBuilder;
+
+fn assert_invalid<B: SketchBuilder>(builder: B) {
+    match builder.try_build() {
+        Ok(_) => panic!("expected an invalid configuration"),
+        Err(err) => assert_eq!(err.kind(), ErrorKind::InvalidArgument, "{err}"),
+    }
+}
+
+#[test]
+fn test_hll_builder() {
+    let sketch = HllSketch::builder().try_build().unwrap();
+    assert_eq!(sketch.lg_config_k(), 12);
+    assert_eq!(sketch.target_type(), HllType::Hll4);
+
+    let sketch = HllSketch::builder()
+        .lg_config_k(21)
+        .hll_type(HllType::Hll6)
+        .try_build()
+        .unwrap();
+    assert_eq!(sketch, HllSketch::new(21, HllType::Hll6));
+
+    assert_invalid(HllSketch::builder().lg_config_k(3));
+    assert_invalid(HllSketch::builder().lg_config_k(22));
+}
+
+#[test]
+fn test_cpc_builder() {
+    let sketch = CpcSketch::builder().try_build().unwrap();
+    assert_eq!(sketch.lg_k(), 11);
+
+    let mut sketch = CpcSketch::builder().lg_k(10).seed(7).try_build().unwrap();
+    sketch.update(1);
+    let mut expected = CpcSketch::with_seed(10, 7);
+    expected.update(1);
+    assert_eq!(sketch.serialize(), expected.serialize());
+
+    assert_invalid(CpcSketch::builder().lg_k(3));
+    assert_invalid(CpcSketch::builder().lg_k(27));
+}
+
+#[test]
+fn test_countmin_builder() {
+    let sketch = CountMinSketch::<u64>::builder()
+        .num_hashes(3)
+        .num_buckets(100)
+        .seed(7)
+        .try_build()
+        .unwrap();
+    assert_eq!(sketch, CountMinSketch::with_seed(3, 100, 7));
+
+    assert_invalid(CountMinSketch::<u64>::builder().num_buckets(100));
+    assert_invalid(CountMinSketch::<u64>::builder().num_hashes(3));
+    assert_invalid(
+        CountMinSketch::<u64>::builder()
+            .num_hashes(0)
+            .num_buckets(100),
+    );
+    assert_invalid(
+        CountMinSketch::<u64>::builder()
+            .num_hashes(3)
+            .num_buckets(2),
+    );
+    assert_invalid(
+        CountMinSketch::<u64>::builder()
+            .num_hashes(255)
+            .num_buckets(u32::MAX),
+    );
+}
+
+#[test]
+fn test_frequent_items_builder() {
+    let mut sketch = FrequentItemsSketch::<String>::builder()
+        .max_map_size(8)
+        .try_build()
+        .unwrap();
+    for i in 0..100 {
+        sketch.update(i.to_string());
+    }
+    assert!(sketch.maximum_error() > 0);
+
+    assert_invalid(FrequentItemsSketch::<String>::builder());
+    assert_invalid(FrequentItemsSketch::<String>::builder().max_map_size(0));
+    assert_invalid(FrequentItemsSketch::<String>::builder().max_map_size(100));
+}
+
+#[test]
+fn test_tdigest_builder() {
+    let sketch = TDigestMut::builder().try_build().unwrap();
+    assert_eq!(sketch.k(), 200);
+    assert_eq!(sketch.nan_policy(), NanPolicy::Ignore);
+
+    let sketch = TDigestMut::builder()
+        .k(10)
+        .nan_policy(NanPolicy::Error)
+        .try_build()
+        .unwrap();
+    assert_eq!(sketch.k(), 10);
+    assert_eq!(sketch.nan_policy(), NanPolicy::Error);
+
+    assert_invalid(TDigestMut::builder().k(9));
+}
+
+#[test]
+fn test_builders_with_eager_validation() {
+    let sketch = ThetaSketchBuilder::default().lg_k(10).try_build().unwrap();
+    assert_eq!(sketch.lg_k(), 10);
+
+    let sketch = TupleSketchBuilder::new(DefaultUpdatePolicy::<u64>::default())
+        .try_build()
+        .unwrap();
+    assert!(sketch.is_empty());
+
+    let filter = BloomFilterBuilder::with_size(1_000, 3).try_build().unwrap();
+    assert!(filter.is_empty());
+}
@@ -17,6 +17,11 @@
 
 #![cfg(feature = "tdigest")]
 
+use datasketches::common::SketchBuilder;
+use datasketches::error::ErrorKind;
+use datasketches::tdigest::BUFFER_MULTIPLIER;
+use datasketches::tdigest::MAX_BUFFER_MULTIPLIER;
+use datasketches::tdigest::NanPolicy;
 use datasketches::tdigest::TDigestMut;
 use googletest::assert_that;
 use googletest::prelude::eq;
@@ -229,6 +234,222 @@ fn test_invalid_inputs() {
         }
     }
     assert!(td.is_empty());
+    assert_eq!(td.num_ignored(), n);
+}
+
+#[test]
+fn test_nan_policy() {
+    let mut td = TDigestMut::new(100);
+    assert_eq!(td.nan_policy(), NanPolicy::Ignore);
+    td.update(1.0);
+    td.update(f64::NAN);
+    assert_eq!(td.total_weight(), 1);
+    assert_eq!(td.num_ignored(), 1);
+
+    let mut other = TDigestMut::new(100);
+    other.update(f64::NAN);
+    other.update(f64::NEG_INFINITY);
+    td.merge(&other);
+    assert_eq!(td.num_ignored(), 3);
+    let td = td.freeze();
+    assert_eq!(td.num_ignored(), 3);
+    assert_eq!(td.unfreeze().num_ignored(), 3);
+
+    let mut td = TDigestMut::new(100).with_nan_policy(NanPolicy::Error);
+    td.update(1.0);
+    let err = td.try_update(f64::NAN).unwrap_err();
+    assert_eq!(err.message(), "value must not be NaN");
+    td.update(f64::INFINITY);
+    assert_eq!(td.total_weight(), 1);
+    assert_eq!(td.num_ignored(), 1);
+
+    let mut td = TDigestMut::new(100).with_nan_policy(NanPolicy::CountAsMax);
+    for i in 0..10 {
+        td.update(i as f64);
+    }
+    td.update(f64::NAN);
+    assert_eq!(td.total_weight(), 11);
+    assert_eq!(td.num_ignored(), 0);
+    assert_eq!(td.max_value(), Some(f64::MAX));
+    assert_eq!(td.rank(9.0).map(|r| r < 1.0), Some(true));
+}
+
+#[test]
+#[should_panic(expected = "value must not be NaN")]
+fn test_nan_policy_error_panics() {
+    let mut td = TDigestMut::new(100).with_nan_policy(NanPolicy::Error);
+    td.update(f64::NAN);
+}
+
+#[test]
+fn test_buffer_multiplier() {
+    let n = 100_000;
+    let mut sizes = vec![];
+    for buffer_multiplier in [1, BUFFER_MULTIPLIER, 16] {
+        let mut td = TDigestMut::builder()
+            .buffer_multiplier(buffer_multiplier)
+            .build();
+        assert_eq!(td.buffer_multiplier(), buffer_multiplier);
+        sizes.push(td.estimated_size());
+        for i in 0..n {
+            td.update(i as f64);
+        }
+        assert_eq!(td.total_weight(), n);
+        let median = (n / 2) as f64;
+        assert_that!(td.quantile(0.5).unwrap(), near(median, 0.05 * median));
+    }
+    assert!(sizes.is_sorted() && sizes[0] < sizes[2], "{sizes:?}");
+
+    // Shrinking the buffer below the buffered values compresses them first
+    let mut td = TDigestMut::new(100);
+    for i in 0..500 {
+        td.update(i as f64);
+    }
+    let mut expected = td.clone();
+    expected.compress();
+    let td = td.with_buffer_multiplier(1);
+    assert_eq!(td.total_weight(), 500);
+    assert_eq!(td.clone().serialize(), expected.serialize());
+
+    for buffer_multiplier in [0, MAX_BUFFER_MULTIPLIER + 1, usize::MAX] {
+        let err = TDigestMut::builder()
+            .buffer_multiplier(buffer_multiplier)
+            .try_build()
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidArgument);
+    }
+
+    // The largest multiplier does not allocate its buffer up front
+    let td = TDigestMut::builder()
+        .k(u16::MAX)
+        .buffer_multiplier(MAX_BUFFER_MULTIPLIER)
+        .build();
+    assert_eq!(td.buffer_multiplier(), MAX_BUFFER_MULTIPLIER);
+    assert!(td.estimated_size() < TDigestMut::new(u16::MAX).estimated_size() * 2);
+}
+
+#[test]
+#[should_panic(expected = "buffer_multiplier must be in [1, 1024], got 1025")]
+fn test_buffer_multiplier_too_large() {
+    TDigestMut::new(100).with_buffer_multiplier(MAX_BUFFER_MULTIPLIER + 1);
+}
+
+#[test]
+fn test_compress() {
+    let mut td = TDigestMut::new(100);
+    td.compress();
+    assert!(td.is_empty());
+
+    for i in 0..1_000 {
+        td.update(i as f64);
+    }
+    let mut compressed = td.clone();
+    compressed.compress();
+    assert_eq!(compressed.total_weight(), td.total_weight());
+    assert_eq!(compressed.min_value(), td.min_value());
+    assert_eq!(compressed.max_value(), td.max_value());
+    assert_eq!(compressed.serialize(), td.serialize());
+
+    // Compressing again with nothing buffered changes nothing
+    let bytes = compressed.serialize();
+    compressed.compress();
+    assert_eq!(compressed.serialize(), bytes);
+}
+
+#[test]
+fn test_merge_values() {
+    let n = 100_000;
+    let values: Vec<f64> = (0..n).map(|i| i as f64).collect();
+    let mut td = TDigestMut::new(100);
+    td.update(-1.0);
+    td.merge_values(&values[..n / 2]);
+    td.merge_values(&values[n / 2..]);
+    td.merge_values(&[]);
+    assert_eq!(td.total_weight(), n as u64 + 1);
+    assert_eq!(td.min_value(), Some(-1.0));
+    assert_eq!(td.max_value(), Some((n - 1) as f64));
+    let median = (n / 2) as f64;
+    assert_that!(td.quantile(0.5).unwrap(), near(median, 0.1 * median));
+
+    td.merge_values(&[f64::NAN, f64::INFINITY, f64::NEG_INFINITY]);
+    assert_eq!(td.total_weight(), n as u64 + 1);
+    assert_eq!(td.num_ignored(), 3);
+
+    let mut td = TDigestMut::new(100).with_nan_policy(NanPolicy::CountAsMax);
+    td.merge_values(&[1.0, f64::NAN]);
+    assert_eq!(td.total_weight(), 2);
+    assert_eq!(td.max_value(), Some(f64::MAX));
+
+    let mut td = TDigestMut::new(100).with_nan_policy(NanPolicy::Error);
+    td.update(1.0);
+    let err = td.try_merge_values(&[2.0, f64::NAN]).unwrap_err();
+    assert_eq!(err.message(), "value must not be NaN");
+    assert_eq!(td.total_weight(), 1);
+    assert_eq!(td.max_value(), Some(1.0));
+}
+
+#[test]
+fn test_insert_centroid() {
+    let mut td = TDigestMut::new(100);
+    for i in 0..1_000 {
+        td.insert_centroid(i as f64, 10);
+    }
+    td.update(2_000.0);
+    assert_eq!(td.total_weight(), 10_001);
+    assert_eq!(td.min_value(), Some(0.0));
+    assert_eq!(td.max_value(), Some(2_000.0));
+    assert_that!(td.quantile(0.5).unwrap(), near(500.0, 25.0));
+
+    // A sketch loaded from centroids serializes like any other
+    let bytes = td.serialize();
+    let mut decoded = TDigestMut::deserialize(&bytes, false).unwrap();
+    assert_eq!(decoded.total_weight(), 10_001);
+    assert_eq!(decoded.quantile(0.5), td.quantile(0.5));
+
+    for (mean, weight) in [(f64::NAN, 1), (f64::INFINITY, 1), (1.0, 0), (1.0, u64::MAX)] {
+        let err = td.try_insert_centroid(mean, weight).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidArgument);
+    }
+    assert_eq!(td.total_weight(), 10_001);
+}
+
+#[test]
+#[should_panic(expected = "centroid weight must be positive")]
+fn test_insert_centroid_zero_weight() {
+    TDigestMut::new(100).insert_centroid(1.0, 0);
+}
+
+#[test]
+fn test_insert_centroids() {
+    let centroids: Vec<(f64, u64)> = (0..1_000).map(|i| (i as f64, 10)).collect();
+    let mut td = TDigestMut::new(100);
+    td.update(2_000.0);
+    td.insert_centroids(&centroids);
+    td.insert_centroids(&[]);
+    assert_eq!(td.total_weight(), 10_001);
+    assert_eq!(td.min_value(), Some(0.0));
+    assert_eq!(td.max_value(), Some(2_000.0));
+    // One compression of many heavy centroids is a little coarser than inserting them one by one.
+    assert_that!(td.quantile(0.5).unwrap(), near(500.0, 50.0));
+
+    // A bad pair anywhere in the slice leaves the sketch unchanged, including a total weight
+    // that only overflows once the earlier pairs are added.
+    for bad in [
+        &[(1.0, 1), (f64::NAN, 1)][..],
+        &[(1.0, 1), (2.0, 0)][..],
+        &[(1.0, u64::MAX - 20_000), (2.0, 20_000)][..],
+    ] {
+        let err = td.try_insert_centroids(bad).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidArgument);
+    }
+    assert_eq!(td.total_weight(), 10_001);
+    assert_eq!(td.min_value(), Some(0.0));
+}
+
+#[test]
+#[should_panic(expected = "overflows the total weight")]
+fn test_insert_centroids_overflow() {
+    TDigestMut::new(100).insert_centroids(&[(1.0, u64::MAX), (2.0, 1)]);
 }
 
 #[test]
@@ -239,3 +460,120 @@ fn test_estimate_repeat_values() {
     }
     assert_eq!(tdigest.quantile(0.9), Some(1.0));
 }
+
+#[test]
+fn test_histogram_empty() {
+    let mut tdigest = TDigestMut::new(100);
+    assert_eq!(tdigest.to_histogram(10), None);
+    assert_eq!(tdigest.to_histogram_with_edges(&[1.0]), None);
+    let tdigest = tdigest.freeze();
+    assert_eq!(tdigest.to_histogram(10), None);
+    assert_eq!(tdigest.to_histogram_with_edges(&[]), None);
+}
+
+#[test]
+fn test_histogram_single_value() {
+    let mut tdigest = TDigestMut::new(100);
+    tdigest.update(5.0);
+    tdigest.update(5.0);
+    let histogram = tdigest.to_histogram(8).unwrap();
+    assert_eq!(histogram.num_bins(), 1);
+    assert!(histogram.split_points().is_empty());
+    assert_eq!(histogram.counts(), &[2.0]);
+    assert_eq!(histogram.cumulative_buckets(), vec![(f64::INFINITY, 2.0)]);
+}
+
+#[test]
+fn test_histogram_uniform() {
+    let n = 10_000;
+    let mut tdigest = TDigestMut::new(100);
+    for i in 0..n {
+        tdigest.update(i as f64);
+    }
+
+    let histogram = tdigest.to_histogram(10).unwrap();
+    assert_eq!(histogram.num_bins(), 10);
+    assert_eq!(histogram.split_points().len(), 9);
+    assert_that!(histogram.split_points()[0], near(999.9, 1e-9));
+    assert_that!(histogram.total_count(), near(n as f64, 1e-6));
+    for &count in histogram.counts() {
+        assert_that!(count, near(n as f64 / 10.0, n as f64 * 0.01));
+    }
+
+    let frozen = tdigest.freeze();
+    assert_eq!(frozen.to_histogram(10).unwrap(), histogram);
+
+    let edges = [-1.0, 2500.0, 5000.0, 20_000.0];
+    let histogram = frozen.to_histogram_with_edges(&edges).unwrap();
+    assert_eq!(histogram.split_points(), &edges);
+    assert_eq!(histogram.num_bins(), 5);
+    let pmf = frozen.pmf(&edges).unwrap();
+    for (count, mass) in histogram.counts().iter().zip(pmf) {
+        assert_that!(*count, near(mass * n as f64, 1e-9));
+    }
+    assert_that!(histogram.counts()[0], eq(0.0));
+    assert_that!(histogram.counts()[4], eq(0.0));
+
+    let buckets = histogram.cumulative_buckets();
+    assert_eq!(buckets.len(), 5);
+    assert_eq!(buckets[0].0, -1.0);
+    assert_that!(buckets[2].1, near(n as f64 / 2.0, n as f64 * 0.01));
+    assert_eq!(buckets[4].0, f64::INFINITY);
+    assert_that!(buckets[4].1, near(n as f64, 1e-6));
+    for pair in buckets.windows(2) {
+        assert!(pair[0].1 <= pair[1].1);
+    }
+}
+
+#[test]
+#[should_panic(expected = "num_bins must be positive")]
+fn test_histogram_zero_bins() {
+    let mut tdigest = TDigestMut::new(100);
+    tdigest.update(1.0);
+    tdigest.to_histogram(0);
+}
+
+#[test]
+#[should_panic(expected = "split_points must be unique and monotonically increasing")]
+fn test_histogram_unsorted_edges() {
+    let mut tdigest = TDigestMut::new(100);
+    tdigest.update(1.0);
+    tdigest.to_histogram_with_edges(&[2.0, 1.0]);
+}
+
+#[test]
+fn test_percentiles() {
+    let mut tdigest = TDigestMut::new(100);
+    assert_eq!(tdigest.percentile(50.0), None);
+    assert_eq!(tdigest.percentiles(&[]), None);
+
+    for i in 0..10_000 {
+        tdigest.update(i as f64);
+    }
+    assert_eq!(tdigest.percentile(0.0), tdigest.quantile(0.0));
+    assert_eq!(tdigest.percentile(50.0), tdigest.quantile(0.5));
+    assert_eq!(tdigest.percentile(100.0), tdigest.quantile(1.0));
+    assert_that!(tdigest.percentile(99.0).unwrap(), near(9900.0, 50.0));
+
+    let values = tdigest.percentiles(&[50.0, 90.0, 99.9]).unwrap();
+    assert_eq!(values.len(), 3);
+    assert_eq!(values[1], tdigest.quantile(0.9).unwrap());
+
+    let frozen = tdigest.freeze();
+    assert_eq!(frozen.percentiles(&[50.0, 90.0, 99.9]).unwrap(), values);
+    assert_eq!(frozen.percentile(90.0), frozen.quantile(0.9));
+}
+
+#[test]
+#[should_panic(expected = "percentile must be in [0.0, 100.0]")]
+fn test_percentile_out_of_range() {
+    let mut tdigest = TDigestMut::new(100);
+    tdigest.update(1.0);
+    tdigest.percentile(100.5);
+}
+
+#[test]
+#[should_panic(expected = "percentile must be in [0.0, 100.0]")]
+fn test_percentiles_validate_empty_sketch_input() {
+    TDigestMut::new(100).freeze().percentiles(&[f64::NAN]);
+}
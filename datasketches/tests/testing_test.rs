@@ -0,0 +1,90 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+#![cfg(all(feature = "hll", feature = "testing", feature = "theta"))]
+
+use datasketches::common::CardinalityEstimate;
+use datasketches::hll::HllSketch;
+use datasketches::hll::HllType;
+use datasketches::hll::HllUnion;
+use datasketches::testing::approx_eq;
+use datasketches::testing::assert_estimate_close;
+use datasketches::testing::assert_sketch_estimates_close;
+use datasketches::testing::sketch_estimates_close;
+use datasketches::theta::ThetaSketchBuilder;
+
+#[test]
+fn test_approx_eq() {
+    assert!(approx_eq(1.0, 1.0, 0.0));
+    assert!(approx_eq(0.0, -0.0, 0.0));
+    assert!(approx_eq(f64::INFINITY, f64::INFINITY, 0.0));
+    assert!(approx_eq(100.0, 101.0, 0.01));
+    assert!(approx_eq(-100.0, -101.0, 0.01));
+    assert!(!approx_eq(100.0, 102.0, 0.01));
+    assert!(!approx_eq(0.0, 1e-300, 0.5));
+    assert!(!approx_eq(f64::NAN, f64::NAN, 1.0));
+}
+
+#[test]
+fn test_sketch_estimates_close() {
+    let mut sketch = HllSketch::new(12, HllType::Hll8);
+    let mut union = HllUnion::new(12);
+    let mut theta = ThetaSketchBuilder::default().lg_k(12).build();
+    for i in 0..50_000 {
+        sketch.update(i);
+        theta.update(i);
+    }
+    union.update(&sketch);
+
+    // The union uses the composite estimator, the sketch its HIP estimate.
+    assert_sketch_estimates_close(&union, &union.to_sketch(HllType::Hll4), 1e-9);
+    assert_sketch_estimates_close(&sketch, &union, 0.02);
+    assert_sketch_estimates_close(&theta, &theta.compact(true), 0.0);
+    assert_estimate_close(&sketch, 50_000.0, 0.05);
+    assert_estimate_close(&theta, 50_000.0, 0.05);
+
+    // Different sketch families agree only up to their error.
+    assert!(sketch_estimates_close(&sketch, &theta, 0.1));
+    assert!(!sketch_estimates_close(&sketch, &theta, 1e-9));
+
+    // Trait objects can be compared as well.
+    let sketches: Vec<Box<dyn CardinalityEstimate>> = vec![Box::new(sketch), Box::new(theta)];
+    assert!(sketch_estimates_close(
+        sketches[0].as_ref(),
+        sketches[1].as_ref(),
+        0.1
+    ));
+}
+
+#[test]
+#[should_panic(expected = "sketch estimates differ by more than 0.001")]
+fn test_assert_sketch_estimates_close_panics() {
+    let mut a = HllSketch::new(12, HllType::Hll8);
+    let mut b = HllSketch::new(12, HllType::Hll8);
+    a.update(1);
+    b.update(1);
+    b.update(2);
+    assert_sketch_estimates_close(&a, &b, 1e-3);
+}
+
+#[test]
+#[should_panic(expected = "is not within 0.01 of 2")]
+fn test_assert_estimate_close_panics() {
+    let mut sketch = HllSketch::new(12, HllType::Hll8);
+    sketch.update(1);
+    assert_estimate_close(&sketch, 2.0, 0.01);
+}
@@ -0,0 +1,331 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+#![cfg(feature = "theta")]
+
+mod common;
+
+use std::collections::HashSet;
+use std::fs;
+
+use common::serialization_test_data;
+use datasketches::common::NumStdDev;
+use datasketches::theta::CompactThetaSketch;
+use datasketches::theta::ThetaAnotB;
+use datasketches::theta::ThetaSketch;
+use datasketches::theta::ThetaSketchBuilder;
+use datasketches::theta::ThetaSketchView;
+
+fn sketch_with_range(start: u64, count: u64) -> ThetaSketch {
+    let mut sketch = ThetaSketchBuilder::default().build();
+    for i in 0..count {
+        sketch.update(start + i);
+    }
+    sketch
+}
+
+fn java_sketch(n: u64) -> CompactThetaSketch {
+    let path = serialization_test_data("java_generated_files", &format!("theta_n{n}_java.sk"));
+    let bytes = fs::read(&path).unwrap();
+    CompactThetaSketch::deserialize(&bytes).unwrap()
+}
+
+fn hashes<S: ThetaSketchView>(sketch: &S) -> HashSet<u64> {
+    sketch.iter().map(|entry| entry.hash()).collect()
+}
+
+/// Checks the structural invariants every A-not-B result must satisfy.
+fn assert_consistent<A: ThetaSketchView, B: ThetaSketchView>(
+    a: &A,
+    b: &B,
+    result: &CompactThetaSketch,
+) {
+    let theta = if b.is_empty() {
+        a.theta()
+    } else {
+        a.theta().min(b.theta())
+    };
+    let a_hashes = hashes(a);
+    let b_hashes = hashes(b);
+    let expected: HashSet<u64> = a_hashes
+        .iter()
+        .copied()
+        .filter(|hash| *hash < theta && !b_hashes.contains(hash))
+        .collect();
+    assert_eq!(hashes(result), expected);
+    assert_eq!(result.num_retained(), expected.len());
+    assert_eq!(result.theta64(), theta);
+    assert_eq!(
+        result.is_empty(),
+        expected.is_empty() && theta == u64::MAX >> 1
+    );
+}
+
+#[test]
+fn test_both_empty() {
+    let a = ThetaSketchBuilder::default().build();
+    let b = ThetaSketchBuilder::default().build();
+    let result = ThetaAnotB::new_with_default_seed()
+        .compute(&a, &b, true)
+        .unwrap();
+    assert!(result.is_empty());
+    assert!(!result.is_estimation_mode());
+    assert_eq!(result.num_retained(), 0);
+    assert_eq!(result.theta(), 1.0);
+    assert_eq!(result.estimate(), 0.0);
+}
+
+#[test]
+fn test_empty_a_ignores_b() {
+    let a = ThetaSketchBuilder::default().build();
+    let b = sketch_with_range(0, 100);
+    let result = ThetaAnotB::new_with_default_seed()
+        .compute(&a, &b, false)
+        .unwrap();
+    assert!(result.is_empty());
+    assert_eq!(result.theta(), 1.0);
+
+    // An empty A short-circuits before the seed of B is checked.
+    let mut other_seed = ThetaSketchBuilder::default().seed(2).build();
+    other_seed.update(1);
+    let result = ThetaAnotB::new_with_default_seed()
+        .compute(&a, &other_seed, false)
+        .unwrap();
+    assert!(result.is_empty());
+}
+
+#[test]
+fn test_non_empty_no_retained() {
+    let mut a = ThetaSketchBuilder::default()
+        .sampling_probability(0.001)
+        .build();
+    a.update(1);
+    assert!(!a.is_empty());
+    assert_eq!(a.num_retained(), 0);
+
+    let b = ThetaSketchBuilder::default().build();
+    let result = ThetaAnotB::new_with_default_seed()
+        .compute(&a, &b, true)
+        .unwrap();
+    assert!(!result.is_empty());
+    assert!(result.is_estimation_mode());
+    assert_eq!(result.num_retained(), 0);
+    assert!((result.theta() - 0.001).abs() < 1e-10);
+    assert_eq!(result.estimate(), 0.0);
+
+    // A non-empty B with no retained entries still lowers theta.
+    let result = ThetaAnotB::new_with_default_seed()
+        .compute(&sketch_with_range(0, 10), &a, true)
+        .unwrap();
+    assert!(!result.is_empty());
+    assert!((result.theta() - 0.001).abs() < 1e-10);
+}
+
+#[test]
+fn test_empty_b_returns_a() {
+    let a = sketch_with_range(0, 1000);
+    let b = ThetaSketchBuilder::default().build();
+    let result = ThetaAnotB::new_with_default_seed()
+        .compute(&a, &b, true)
+        .unwrap();
+    assert!(!result.is_empty());
+    assert!(result.is_ordered());
+    assert_eq!(result.estimate(), a.estimate());
+    assert_consistent(&a, &b, &result);
+}
+
+#[test]
+fn test_exact_mode_half_overlap() {
+    let a = sketch_with_range(0, 1000);
+    let b = sketch_with_range(500, 1000);
+    let anotb = ThetaAnotB::new_with_default_seed();
+
+    let result = anotb.compute(&a, &b, true).unwrap();
+    assert!(!result.is_empty());
+    assert!(!result.is_estimation_mode());
+    assert!(result.is_ordered());
+    assert_eq!(result.estimate(), 500.0);
+    assert_consistent(&a, &b, &result);
+
+    let result = anotb.compute(&a, &b, false).unwrap();
+    assert!(!result.is_ordered());
+    assert_eq!(result.estimate(), 500.0);
+
+    // Sorted inputs take the merge path and produce ordered output.
+    let result = anotb
+        .compute(&a.compact(true), &b.compact(true), false)
+        .unwrap();
+    assert!(result.is_ordered());
+    assert_eq!(result.estimate(), 500.0);
+    assert_consistent(&a, &b, &result);
+}
+
+#[test]
+fn test_exact_mode_disjoint() {
+    let a = sketch_with_range(0, 1000);
+    let b = sketch_with_range(1000, 1000);
+    let result = ThetaAnotB::new_with_default_seed()
+        .compute(&a, &b, true)
+        .unwrap();
+    assert!(!result.is_empty());
+    assert_eq!(result.estimate(), 1000.0);
+}
+
+#[test]
+fn test_exact_mode_full_overlap_is_empty() {
+    let a = sketch_with_range(0, 1000);
+    let b = sketch_with_range(0, 1000);
+    let result = ThetaAnotB::new_with_default_seed()
+        .compute(&a, &b, false)
+        .unwrap();
+    assert!(result.is_empty());
+    assert!(!result.is_estimation_mode());
+    assert_eq!(result.estimate(), 0.0);
+}
+
+#[test]
+fn test_estimation_mode_half_overlap() {
+    let a = sketch_with_range(0, 10_000);
+    let b = sketch_with_range(5000, 10_000);
+    let anotb = ThetaAnotB::new_with_default_seed();
+
+    for (ordered_a, ordered_b) in [(false, false), (true, false), (false, true), (true, true)] {
+        let ca = a.compact(ordered_a);
+        let cb = b.compact(ordered_b);
+        let result = anotb.compute(&ca, &cb, true).unwrap();
+        assert!(!result.is_empty());
+        assert!(result.is_estimation_mode());
+        assert!((result.estimate() - 5000.0).abs() < 5000.0 * 0.02);
+        assert!(result.lower_bound(NumStdDev::Two) <= result.estimate());
+        assert!(result.upper_bound(NumStdDev::Two) >= result.estimate());
+        assert_consistent(&ca, &cb, &result);
+    }
+}
+
+#[test]
+fn test_estimation_mode_full_overlap_is_not_empty() {
+    let a = sketch_with_range(0, 10_000);
+    let result = ThetaAnotB::new_with_default_seed()
+        .compute(&a, &a, true)
+        .unwrap();
+    assert!(!result.is_empty());
+    assert!(result.is_estimation_mode());
+    assert_eq!(result.num_retained(), 0);
+    assert_eq!(result.estimate(), 0.0);
+}
+
+#[test]
+fn test_theta_is_min_of_inputs() {
+    let a = sketch_with_range(0, 5000);
+    let b = sketch_with_range(0, 20_000);
+    assert!(b.theta64() < a.theta64());
+    let result = ThetaAnotB::new_with_default_seed()
+        .compute(&a, &b, true)
+        .unwrap();
+    assert_eq!(result.theta64(), b.theta64());
+    assert_consistent(&a, &b, &result);
+
+    let result = ThetaAnotB::new_with_default_seed()
+        .compute(&b, &a, true)
+        .unwrap();
+    assert_eq!(result.theta64(), b.theta64());
+    assert_consistent(&b, &a, &result);
+}
+
+#[test]
+fn test_seed_mismatch() {
+    let mut a = ThetaSketchBuilder::default().seed(2).build();
+    a.update(1);
+    let b = sketch_with_range(0, 10);
+    let anotb = ThetaAnotB::new_with_default_seed();
+    assert!(anotb.compute(&a, &b, true).is_err());
+    assert!(anotb.compute(&b, &a, true).is_err());
+
+    let empty_other_seed = ThetaSketchBuilder::default().seed(2).build();
+    let result = anotb.compute(&b, &empty_other_seed, true).unwrap();
+    assert_eq!(result.estimate(), 10.0);
+}
+
+#[test]
+fn test_randomized_against_exact_set_difference() {
+    // Deterministic LCG so the test is reproducible.
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    let mut next = || {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        state >> 33
+    };
+
+    let anotb = ThetaAnotB::new_with_default_seed();
+    for round in 0..40 {
+        let size_a = next() % 6000;
+        let size_b = next() % 6000;
+        let offset = next() % 6000;
+        let a = sketch_with_range(0, size_a);
+        let b = sketch_with_range(offset, size_b);
+        let ordered = round % 2 == 0;
+
+        let result = anotb.compute(&a, &b, ordered).unwrap();
+        assert_consistent(&a, &b, &result);
+        let result = anotb
+            .compute(&a.compact(true), &b.compact(true), ordered)
+            .unwrap();
+        assert_consistent(&a, &b, &result);
+        assert!(result.is_ordered());
+
+        if !a.is_estimation_mode() && !b.is_estimation_mode() {
+            let expected = size_a.saturating_sub(size_b.min(size_a.saturating_sub(offset)));
+            assert_eq!(result.estimate(), expected as f64);
+        }
+    }
+}
+
+#[test]
+fn test_java_generated_inputs() {
+    // The Java fixtures are built from the values 0..n with the default
+    // parameters, so every pair overlaps in the smaller range.
+    let anotb = ThetaAnotB::new_with_default_seed();
+
+    // Exact mode: the difference holds exactly the hashes of n_b..n_a.
+    for (n_a, n_b) in [(10, 1), (100, 10), (1000, 100), (1000, 0)] {
+        let a = java_sketch(n_a);
+        let b = java_sketch(n_b);
+        let result = anotb.compute(&a, &b, true).unwrap();
+        assert_consistent(&a, &b, &result);
+        assert_eq!(hashes(&result), hashes(&sketch_with_range(n_b, n_a - n_b)));
+        assert_eq!(result.estimate(), (n_a - n_b) as f64);
+    }
+
+    // A contained in B leaves an empty result.
+    let result = anotb
+        .compute(&java_sketch(100), &java_sketch(1000), true)
+        .unwrap();
+    assert!(result.is_empty());
+    assert_eq!(result.estimate(), 0.0);
+
+    // Estimation mode.
+    for (n_a, n_b) in [(100_000, 10_000), (1_000_000, 100_000)] {
+        let a = java_sketch(n_a);
+        let b = java_sketch(n_b);
+        let result = anotb.compute(&a, &b, true).unwrap();
+        assert_consistent(&a, &b, &result);
+        assert!(result.is_estimation_mode());
+        let expected = (n_a - n_b) as f64;
+        assert!(result.lower_bound(NumStdDev::Three) <= expected);
+        assert!(result.upper_bound(NumStdDev::Three) >= expected);
+    }
+}
@@ -319,3 +319,62 @@ fn test_seed_mismatch_non_empty_returns_error() {
     let mut i = ThetaIntersection::new(123);
     assert!(i.update(&s).is_err());
 }
+
+#[test]
+fn test_overlap_matches_intersection() {
+    let exact = |start, count| sketch_with_range(start, count);
+    let small = |start, count| {
+        let mut sketch = ThetaSketchBuilder::default().lg_k(8).build();
+        for i in 0..count {
+            sketch.update(start + i);
+        }
+        sketch
+    };
+    let cases = [
+        (exact(0, 100), exact(50, 100)),
+        (exact(0, 100), exact(100, 100)),
+        (
+            sketch_with_range(0, 10_000),
+            sketch_with_range(5_000, 10_000),
+        ),
+        (
+            sketch_with_range(0, 10_000),
+            sketch_with_range(10_000, 10_000),
+        ),
+        (small(0, 10_000), sketch_with_range(2_000, 10_000)),
+        (ThetaSketchBuilder::default().build(), exact(0, 10)),
+    ];
+    for (a, b) in cases {
+        for (ordered_a, ordered_b) in [(true, true), (true, false), (false, false)] {
+            let a = a.compact(ordered_a);
+            let b = b.compact(ordered_b);
+
+            let mut intersection = ThetaIntersection::new_with_default_seed();
+            intersection.update(&a).unwrap();
+            intersection.update(&b).unwrap();
+            let expected = intersection.to_sketch(false);
+
+            assert_eq!(a.overlap_estimate(&b).unwrap(), expected.estimate());
+            assert_eq!(b.overlap_estimate(&a).unwrap(), expected.estimate());
+            assert_eq!(a.is_disjoint(&b).unwrap(), expected.num_retained() == 0);
+        }
+    }
+}
+
+#[test]
+fn test_overlap_seed_mismatch_returns_error() {
+    let a = sketch_with_range(0, 10).compact(true);
+    let mut b = ThetaSketchBuilder::default().seed(123).build();
+    b.update(1u64);
+    let b = b.compact(true);
+    assert!(a.is_disjoint(&b).is_err());
+    assert!(a.overlap_estimate(&b).is_err());
+
+    // Empty sketches carry no hashes, so their seed is not checked
+    let empty = ThetaSketchBuilder::default()
+        .seed(123)
+        .build()
+        .compact(true);
+    assert!(a.is_disjoint(&empty).unwrap());
+    assert_eq!(a.overlap_estimate(&empty).unwrap(), 0.0);
+}
@@ -23,7 +23,11 @@ use std::fs;
 use std::path::PathBuf;
 
 use common::serialization_test_data;
+use datasketches::error::ErrorKind;
 use datasketches::theta::CompactThetaSketch;
+use datasketches::theta::ThetaIntersection;
+use datasketches::theta::ThetaSketch;
+use datasketches::theta::ThetaSketchBuilder;
 use googletest::assert_that;
 use googletest::prelude::near;
 
@@ -116,3 +120,141 @@ fn test_cpp_compatibility() {
     let path = serialization_test_data("cpp_generated_files", "theta_non_empty_no_entries_cpp.sk");
     test_sketch_file(path, 0, false);
 }
+
+#[test]
+fn test_single_item_serializes_to_minimal_image() {
+    let mut sketch = ThetaSketchBuilder::default().build();
+    sketch.update("apple");
+
+    // Unordered results of set operations get the same image as compact(false).
+    let mut intersection = ThetaIntersection::new_with_default_seed();
+    intersection.update(&sketch).unwrap();
+    for compact in [sketch.compact(false), intersection.to_sketch(false)] {
+        assert!(compact.is_ordered());
+        let bytes = compact.serialize();
+        assert_eq!(bytes.len(), 16);
+        assert_eq!(bytes.len(), compact.serialized_size_bytes());
+        // One preamble long, serial version 3, Theta family, and read-only, compact and ordered
+        // flags.
+        assert_eq!(&bytes[..3], &[1, 3, 3]);
+        assert_eq!(bytes[5], 0x1a);
+
+        let decoded = CompactThetaSketch::deserialize(&bytes).unwrap();
+        assert_eq!(decoded.estimate(), 1.0);
+        assert_eq!(
+            decoded.iter().map(|entry| entry.hash()).collect::<Vec<_>>(),
+            compact.iter().map(|entry| entry.hash()).collect::<Vec<_>>()
+        );
+    }
+}
+
+#[test]
+fn test_serialized_size_bytes_matches_serialize() {
+    let mut sketch = ThetaSketchBuilder::default().lg_k(5).build();
+    for n in [0, 1, 2, 10, 1_000] {
+        for i in 0..n {
+            sketch.update(i);
+        }
+        for ordered in [false, true] {
+            let compact = sketch.compact(ordered);
+            assert_eq!(compact.serialized_size_bytes(), compact.serialize().len());
+        }
+        sketch.reset();
+    }
+}
+
+#[test]
+fn test_deserialize_rejects_entry_counts_larger_than_the_image() {
+    let mut sketch = ThetaSketchBuilder::default().lg_k(5).build();
+    for i in 0..1_000 {
+        sketch.update(i);
+    }
+    let compact = sketch.compact(true);
+
+    // Three preamble longs with the entry count at offset 8.
+    let mut bytes = compact.serialize();
+    bytes[8..12].copy_from_slice(&u32::MAX.to_le_bytes());
+    let err = CompactThetaSketch::deserialize(&bytes).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData, "{err}");
+    bytes[8..12].copy_from_slice(&1_000_000u32.to_le_bytes());
+    let err = CompactThetaSketch::deserialize(&bytes).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData, "{err}");
+
+    // Compressed: the entry count follows the preamble longs in `num_entries_bytes` bytes.
+    let compressed = compact.serialize_compressed();
+    let header_len = (compressed[0] & 0x3F) as usize * 8;
+    let mut bytes = compressed[..header_len].to_vec();
+    bytes[4] = 4;
+    bytes.extend_from_slice(&[0xFF, 0xFF, 0xFF, 0x0F, 0, 0, 0, 0]);
+    let err = CompactThetaSketch::deserialize(&bytes).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData, "{err}");
+
+    // More count bytes than a u32, and entry widths the packing never produces.
+    for (offset, value) in [(4, 8), (3, 0), (3, 64), (3, 255)] {
+        let mut bytes = compressed.clone();
+        bytes[offset] = value;
+        let err = CompactThetaSketch::deserialize(&bytes).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData, "{err}");
+    }
+
+    // Updatable: the largest table lg_k allows, without the slots to fill it.
+    let mut sketch = ThetaSketchBuilder::default().lg_k(26).build();
+    sketch.update(1);
+    let mut bytes = sketch.serialize();
+    bytes[4] = 27;
+    let err = ThetaSketch::deserialize(&bytes).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData, "{err}");
+}
+
+#[test]
+fn test_deserialize_corrupted_images_does_not_panic() {
+    // xorshift64, so that failures are reproducible.
+    let mut state = 0x2545_F491_4F6C_DD1Du64;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    let decode = |bytes: &[u8]| {
+        if let Ok(sketch) = CompactThetaSketch::deserialize(bytes) {
+            sketch.estimate();
+            sketch.serialize();
+        }
+        if let Ok(sketch) = ThetaSketch::deserialize(bytes) {
+            sketch.estimate();
+            sketch.serialize();
+        }
+    };
+
+    let mut images = vec![];
+    for n in [0, 1, 10, 1_000] {
+        let mut sketch = ThetaSketchBuilder::default().lg_k(5).build();
+        for i in 0..n {
+            sketch.update(i);
+        }
+        images.push(sketch.serialize());
+        for ordered in [false, true] {
+            let compact = sketch.compact(ordered);
+            images.push(compact.serialize());
+            images.push(compact.serialize_compressed());
+        }
+    }
+
+    for image in &images {
+        for len in 0..image.len() {
+            decode(&image[..len]);
+        }
+        for _ in 0..500 {
+            let mut bytes = image.clone();
+            for _ in 0..=next() % 4 {
+                let value = next();
+                // Favor the preamble, where counts and sizes live.
+                let bound = if value & 1 == 0 { 24 } else { bytes.len() };
+                let index = (value >> 8) as usize % bound.min(bytes.len());
+                bytes[index] = (value >> 32) as u8;
+            }
+            decode(&bytes);
+        }
+    }
+}
@@ -18,7 +18,16 @@
 #![cfg(feature = "theta")]
 
 use datasketches::common::NumStdDev;
+use datasketches::common::ResizeFactor;
+use datasketches::hash::DEFAULT_UPDATE_SEED;
+use datasketches::hash::compute_seed_hash;
+use datasketches::hash::register_seed_name;
+use datasketches::hash::seed_from_str;
+use datasketches::hash::seed_name;
 use datasketches::hash_value;
+use datasketches::hash_value::ValueRef;
+use datasketches::theta::CompactThetaSketch;
+use datasketches::theta::ThetaSketch;
 use datasketches::theta::ThetaSketchBuilder;
 
 #[test]
@@ -68,6 +77,39 @@ fn test_update_various_types() {
     assert_eq!(sketch.estimate(), 4.0);
 }
 
+#[test]
+fn test_update_value_ref() {
+    let mut dynamic = ThetaSketchBuilder::default().lg_k(12).build();
+    let mut typed = ThetaSketchBuilder::default().lg_k(12).build();
+    for i in 0..1_000_i64 {
+        let key = format!("key-{i}");
+        dynamic.update_value_ref(ValueRef::Str(&key));
+        dynamic.update_value_ref(ValueRef::I64(i));
+        dynamic.update_value_ref(ValueRef::F64(-0.0));
+        typed.update(hash_value::raw_bytes::from_str(&key));
+        typed.update(i);
+        typed.update(hash_value::canonical_float::from_f64(0.0));
+    }
+    assert_eq!(dynamic.estimate(), typed.estimate());
+    assert_eq!(
+        dynamic.compact(true).serialize(),
+        typed.compact(true).serialize()
+    );
+
+    // Bytes hash like the equivalent string, and u64 like the equivalent i64.
+    let mut sketch = ThetaSketchBuilder::default().build();
+    sketch.update_value_ref(ValueRef::Str("abc"));
+    sketch.update_value_ref(ValueRef::Bytes(b"abc"));
+    sketch.update_value_ref(ValueRef::I64(7));
+    sketch.update_value_ref(ValueRef::U64(7));
+    assert_eq!(sketch.estimate(), 2.0);
+    // Empty strings and byte strings are ignored, as by update_str and update_bytes.
+    let mut sketch = ThetaSketchBuilder::default().build();
+    sketch.update_value_ref(ValueRef::Str(""));
+    sketch.update_value_ref(ValueRef::Bytes(&[]));
+    assert!(sketch.is_empty());
+}
+
 #[test]
 fn test_duplicate_updates() {
     let mut sketch = ThetaSketchBuilder::default().lg_k(12).build();
@@ -146,6 +188,27 @@ fn test_iterator() {
     assert_eq!(count, sketch.num_retained());
 }
 
+#[test]
+fn test_iter_ordered() {
+    let mut sketch = ThetaSketchBuilder::default().lg_k(10).build();
+    for i in 0..10_000 {
+        sketch.update(i);
+    }
+
+    let ordered: Vec<u64> = sketch.iter_ordered().map(|e| e.hash()).collect();
+    let mut expected: Vec<u64> = sketch.iter().map(|e| e.hash()).collect();
+    expected.sort_unstable();
+    assert_eq!(ordered, expected);
+
+    for compact in [sketch.compact(true), sketch.compact(false)] {
+        let hashes: Vec<u64> = compact.iter_ordered().map(|e| e.hash()).collect();
+        assert_eq!(hashes, expected);
+    }
+
+    let empty = ThetaSketchBuilder::default().build();
+    assert_eq!(empty.iter_ordered().count(), 0);
+}
+
 #[test]
 fn test_bounds_empty_sketch() {
     let sketch = ThetaSketchBuilder::default().lg_k(12).build();
@@ -307,3 +370,422 @@ fn test_compact_preserves_logical_non_empty_after_screened_update() {
     assert_eq!(compact.num_retained(), 0);
     assert_eq!(compact.theta64(), sketch.theta64());
 }
+
+#[test]
+fn test_serialize_into_slice_matches_serialize() {
+    let mut sketch = ThetaSketchBuilder::default().lg_k(10).build();
+    for i in 0..5_000 {
+        sketch.update(i);
+    }
+    let compact = sketch.compact(true);
+
+    let expected = compact.serialize();
+    let mut buf = vec![0u8; expected.len()];
+    assert_eq!(
+        compact.serialize_into_slice(&mut buf).unwrap(),
+        expected.len()
+    );
+    assert_eq!(buf, expected);
+    assert!(compact.serialize_into_slice(&mut buf[..8]).is_err());
+
+    let expected = compact.serialize_compressed();
+    let mut buf = vec![0u8; expected.len()];
+    assert_eq!(
+        compact.serialize_compressed_into_slice(&mut buf).unwrap(),
+        expected.len()
+    );
+    assert_eq!(buf, expected);
+}
+
+fn sorted_hashes(sketch: &ThetaSketch) -> Vec<u64> {
+    let mut hashes: Vec<u64> = sketch.iter().map(|entry| entry.hash()).collect();
+    hashes.sort_unstable();
+    hashes
+}
+
+#[test]
+fn test_updatable_serialization_round_trip() {
+    for (resize_factor, p, n) in [
+        (ResizeFactor::X8, 1.0, 0),
+        (ResizeFactor::X8, 1.0, 100),
+        (ResizeFactor::X2, 1.0, 10_000),
+        (ResizeFactor::X1, 0.5, 0),
+        (ResizeFactor::X4, 0.5, 10_000),
+    ] {
+        let mut sketch = ThetaSketchBuilder::default()
+            .lg_k(9)
+            .resize_factor(resize_factor)
+            .sampling_probability(p)
+            .build();
+        for i in 0..n {
+            sketch.update(i);
+        }
+
+        let bytes = sketch.serialize();
+        assert_eq!(bytes.len(), sketch.serialized_size_bytes());
+        assert_eq!(bytes[0] & 0x3F, 3);
+        assert_eq!(bytes[0] >> 6, resize_factor.lg_value());
+        assert_eq!(bytes[2], 2);
+        assert_eq!(bytes[3], 9);
+
+        let mut restored = ThetaSketch::deserialize(&bytes).unwrap();
+        assert_eq!(restored.lg_k(), 9);
+        assert_eq!(restored.is_empty(), sketch.is_empty());
+        assert_eq!(restored.theta64(), sketch.theta64());
+        assert_eq!(restored.estimate(), sketch.estimate());
+        assert_eq!(sorted_hashes(&restored), sorted_hashes(&sketch));
+
+        // Resuming must behave exactly like the sketch that was never serialized.
+        for i in n..n + 20_000 {
+            sketch.update(i);
+            restored.update(i);
+        }
+        assert_eq!(restored.theta64(), sketch.theta64());
+        assert_eq!(sorted_hashes(&restored), sorted_hashes(&sketch));
+        assert_eq!(
+            restored.serialized_size_bytes(),
+            sketch.serialized_size_bytes()
+        );
+    }
+}
+
+#[test]
+fn test_updatable_serialization_seed() {
+    let mut sketch = ThetaSketchBuilder::default().seed(7).build();
+    sketch.update("apple");
+    let bytes = sketch.serialize();
+
+    assert!(ThetaSketch::deserialize(&bytes).is_err());
+    let mut restored = ThetaSketch::deserialize_with_seed(&bytes, 7).unwrap();
+    restored.update("apple");
+    restored.update("banana");
+    assert_eq!(restored.estimate(), 2.0);
+}
+
+#[test]
+fn test_updatable_serialization_rejects_invalid_images() {
+    let mut sketch = ThetaSketchBuilder::default().lg_k(5).build();
+    for i in 0..10 {
+        sketch.update(i);
+    }
+    let bytes = sketch.serialize();
+
+    // truncated hash table
+    assert!(ThetaSketch::deserialize(&bytes[..bytes.len() - 8]).is_err());
+    // compact images are a different family
+    assert!(ThetaSketch::deserialize(&sketch.compact(false).serialize()).is_err());
+
+    // retained count disagrees with the table
+    let mut corrupted = bytes.clone();
+    corrupted[8] += 1;
+    assert!(ThetaSketch::deserialize(&corrupted).is_err());
+
+    // table larger than lg_k allows
+    let mut corrupted = bytes.clone();
+    corrupted[4] = 7;
+    assert!(ThetaSketch::deserialize(&corrupted).is_err());
+
+    // sampling probability out of range
+    let mut corrupted = bytes;
+    corrupted[12..16].copy_from_slice(&0.0f32.to_le_bytes());
+    assert!(ThetaSketch::deserialize(&corrupted).is_err());
+}
+
+#[cfg(feature = "stats")]
+#[test]
+fn test_hash_table_stats() {
+    let mut sketch = ThetaSketchBuilder::default().lg_k(8).build();
+    let stats = sketch.stats();
+    assert_eq!(stats.load_factor(), 0.0);
+    assert_eq!(stats.num_lookups(), 0);
+    assert_eq!(stats.average_probe_length(), 0.0);
+
+    // Duplicates are looked up as well, but only new entries grow the table
+    for i in 0..100 {
+        sketch.update(i);
+        sketch.update(i);
+    }
+    let stats = sketch.stats();
+    assert_eq!(stats.num_lookups(), 200);
+    assert!(stats.num_resizes() > 0);
+    assert_eq!(stats.num_rebuilds(), 0);
+    assert_eq!(
+        stats.load_factor(),
+        100.0 / (1u64 << stats.lg_cur_size()) as f64
+    );
+    assert!(stats.average_probe_length() >= 1.0);
+
+    for i in 100..10_000 {
+        sketch.update(i);
+    }
+    let stats = sketch.stats();
+    assert!(stats.num_rebuilds() > 0);
+    assert!(stats.load_factor() <= 15.0 / 16.0);
+
+    sketch.reset();
+    assert_eq!(sketch.stats().num_lookups(), 0);
+    assert_eq!(sketch.stats().num_resizes(), 0);
+}
+
+#[test]
+fn test_merge() {
+    let mut sketch = ThetaSketchBuilder::default().lg_k(10).build();
+    for i in 0..800 {
+        sketch.update(i);
+    }
+    let mut other = ThetaSketchBuilder::default().lg_k(9).build();
+    for i in 400..20_000 {
+        other.update(i);
+    }
+    let other = other.compact(true);
+
+    sketch.merge(&other).unwrap();
+    assert!(sketch.theta64() <= other.theta64());
+    assert!(sketch.iter().all(|entry| entry.hash() < sketch.theta64()));
+    let expected = 20_000.0;
+    assert!(
+        (sketch.estimate() - expected).abs() <= expected * 0.15,
+        "estimate={}",
+        sketch.estimate()
+    );
+
+    // Merging an updatable sketch, and updating after the merge.
+    let mut exact = ThetaSketchBuilder::default().build();
+    let mut updatable = ThetaSketchBuilder::default().build();
+    exact.update("apple");
+    updatable.update("banana");
+    exact.merge(&updatable).unwrap();
+    exact.update("cherry");
+    assert_eq!(exact.estimate(), 3.0);
+
+    let empty = ThetaSketchBuilder::default().seed(7).build();
+    exact.merge(&empty).unwrap();
+    assert_eq!(exact.estimate(), 3.0);
+}
+
+#[test]
+fn test_merge_seed_mismatch() {
+    let mut sketch = ThetaSketchBuilder::default().build();
+    let mut other = ThetaSketchBuilder::default().seed(7).build();
+    other.update(1);
+    assert!(sketch.merge(&other).is_err());
+    assert!(sketch.is_empty());
+}
+
+#[test]
+fn test_seed_from_str() {
+    let seed = seed_from_str("theta-sketch-test");
+    let mut sketch = ThetaSketchBuilder::default()
+        .seed_from_str("theta-sketch-test")
+        .build();
+    sketch.update("apple");
+    assert_eq!(sketch.seed_hash(), compute_seed_hash(seed));
+
+    let bytes = sketch.compact(true).serialize();
+    let restored = CompactThetaSketch::deserialize_with_seed(&bytes, seed).unwrap();
+    assert_eq!(restored.estimate(), 1.0);
+
+    let mut other = ThetaSketchBuilder::default()
+        .seed_from_str("theta-sketch-test-other")
+        .build();
+    other.update("apple");
+    assert!(sketch.merge(&other).is_err());
+}
+
+#[test]
+fn test_seed_mismatch_error_names_registered_seeds() {
+    let seed = seed_from_str("theta-sketch-test-named");
+    let mut sketch = ThetaSketchBuilder::default().seed(seed).build();
+    sketch.update(1);
+    let bytes = sketch.compact(true).serialize();
+
+    let err = CompactThetaSketch::deserialize_with_seed(&bytes, DEFAULT_UPDATE_SEED).unwrap_err();
+    assert!(!err.message().contains("named"), "{err}");
+
+    register_seed_name(seed, "theta-sketch-test-named");
+    assert_eq!(
+        seed_name(compute_seed_hash(seed)).as_deref(),
+        Some("theta-sketch-test-named")
+    );
+    let err = CompactThetaSketch::deserialize_with_seed(&bytes, DEFAULT_UPDATE_SEED).unwrap_err();
+    let expected = format!(
+        "got {} (\"theta-sketch-test-named\")",
+        compute_seed_hash(seed)
+    );
+    assert!(err.message().contains(&expected), "{err}");
+
+    let mut default = ThetaSketchBuilder::default().build();
+    let err = default.merge(&sketch).unwrap_err();
+    assert!(err.message().contains(&expected), "{err}");
+}
+
+#[test]
+fn test_clone() {
+    let mut sketch = ThetaSketchBuilder::default().lg_k(5).build();
+    for i in 0..100 {
+        sketch.update(i);
+    }
+    let image = sketch.serialize();
+    let mut cloned = sketch.clone();
+    assert_eq!(cloned.serialize(), image);
+
+    for i in 100..1000 {
+        cloned.update(i);
+    }
+    assert!(cloned.theta64() < sketch.theta64());
+    assert_eq!(sketch.serialize(), image);
+}
+
+#[test]
+#[cfg(feature = "testing")]
+fn test_build_from_seeded_state() {
+    let theta = 1 << 60;
+    let step = theta / 100;
+    let hashes = (1..=60).map(|i| i * step).collect::<Vec<_>>();
+
+    // lg_k 5 with X1 resizing starts at the full table of 64 slots, which holds 60 entries.
+    let saturated = ThetaSketchBuilder::default()
+        .lg_k(5)
+        .resize_factor(ResizeFactor::X1)
+        .build_from_seeded_state(theta, hashes.clone())
+        .unwrap();
+    assert!(!saturated.is_empty());
+    assert_eq!(saturated.theta64(), theta);
+    assert_eq!(saturated.num_retained(), 60);
+
+    // One more entry rebuilds down to k entries, just as an update would.
+    let mut updated = saturated.clone();
+    updated.update_hash(61 * step);
+    assert_eq!(updated.num_retained(), 32);
+    assert_eq!(updated.theta64(), 33 * step);
+    let rebuilt = ThetaSketchBuilder::default()
+        .lg_k(5)
+        .resize_factor(ResizeFactor::X1)
+        .build_from_seeded_state(theta, (1..=61).map(|i| i * step))
+        .unwrap();
+    assert_eq!(rebuilt.theta64(), updated.theta64());
+    assert_eq!(
+        rebuilt.compact(true).serialize(),
+        updated.compact(true).serialize()
+    );
+
+    // Hashes at or above theta are screened by updates.
+    let mut boundary = ThetaSketchBuilder::default()
+        .build_from_seeded_state(theta, [theta - 1])
+        .unwrap();
+    boundary.update_hash(theta);
+    assert_eq!(boundary.num_retained(), 1);
+
+    let sampled = ThetaSketchBuilder::default()
+        .sampling_probability(0.5)
+        .build_from_seeded_state(u64::MAX >> 1, [])
+        .unwrap();
+    assert!(!sampled.is_empty());
+    assert!(sampled.theta() <= 0.5);
+
+    let builder = || ThetaSketchBuilder::default().lg_k(5);
+    assert!(builder().build_from_seeded_state(0, []).is_err());
+    assert!(builder().build_from_seeded_state(u64::MAX, []).is_err());
+    assert!(builder().build_from_seeded_state(theta, [0]).is_err());
+    assert!(builder().build_from_seeded_state(theta, [theta]).is_err());
+    assert!(builder().build_from_seeded_state(theta, [7, 3, 7]).is_err());
+}
+
+#[test]
+fn test_update_str_hashes_utf8_bytes() {
+    let mut sketch = ThetaSketchBuilder::default().lg_k(10).seed(7).build();
+    let mut expected = ThetaSketchBuilder::default().lg_k(10).seed(7).build();
+    for i in 0..5_000 {
+        let value = format!("item-{i}-é");
+        sketch.update_str(&value);
+        expected.update(hash_value::raw_bytes::from_str(&value));
+        if i % 2 == 0 {
+            sketch.update_bytes(value.as_bytes());
+        }
+    }
+    assert_eq!(sketch.serialize(), expected.serialize());
+
+    let mut empty = ThetaSketchBuilder::default().build();
+    empty.update_str("");
+    empty.update_bytes(&[]);
+    assert!(empty.is_empty());
+}
+
+#[test]
+fn test_contains_hash() {
+    for n in [0, 1, 100, 10_000] {
+        let mut sketch = ThetaSketchBuilder::default().lg_k(10).build();
+        for i in 0..n {
+            sketch.update(i);
+        }
+        let retained: Vec<u64> = sketch.iter().map(|entry| entry.hash()).collect();
+        let absent: Vec<u64> = (n..n + 1_000)
+            .map(|i| {
+                let mut single = ThetaSketchBuilder::default().build();
+                single.update(i);
+                single.iter().next().unwrap().hash()
+            })
+            .chain([0, sketch.theta64(), u64::MAX])
+            .collect();
+
+        for ordered in [false, true] {
+            let compact = sketch.compact(ordered);
+            let index = compact.hash_index();
+            assert_eq!(index.len(), retained.len());
+            for &hash in &retained {
+                assert!(sketch.contains_hash(hash));
+                assert!(compact.contains_hash(hash));
+                assert!(index.contains_hash(hash));
+            }
+            for &hash in &absent {
+                assert!(!sketch.contains_hash(hash));
+                assert!(!compact.contains_hash(hash));
+                assert!(!index.contains_hash(hash));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_filter() {
+    for n in [0, 100, 10_000] {
+        let mut sketch = ThetaSketchBuilder::default().lg_k(10).build();
+        for i in 0..n {
+            sketch.update(i);
+        }
+        for ordered in [false, true] {
+            let compact = sketch.compact(ordered);
+
+            let all = compact.filter(|_| true);
+            assert_eq!(all.is_empty(), compact.is_empty());
+            assert_eq!(all.theta64(), compact.theta64());
+            assert_eq!(all.estimate(), compact.estimate());
+            assert!(
+                all.iter()
+                    .map(|e| e.hash())
+                    .eq(compact.iter().map(|e| e.hash()))
+            );
+
+            let none = compact.filter(|_| false);
+            assert_eq!(none.num_retained(), 0);
+            assert_eq!(none.theta64(), compact.theta64());
+            assert_eq!(none.is_empty(), !compact.is_estimation_mode());
+            assert_eq!(none.estimate(), 0.0);
+
+            let half = compact.filter(|hash| hash % 2 == 0);
+            assert_eq!(half.theta64(), compact.theta64());
+            assert_eq!(half.is_ordered(), compact.is_ordered());
+            assert!(half.iter().all(|entry| entry.hash() % 2 == 0));
+            assert_eq!(
+                half.num_retained(),
+                compact.iter().filter(|entry| entry.hash() % 2 == 0).count()
+            );
+            if n == 10_000 {
+                let expected = n as f64 / 2.0;
+                assert!((half.estimate() - expected).abs() < expected * 0.1);
+                assert!(half.lower_bound(NumStdDev::Two) <= half.estimate());
+                assert!(half.upper_bound(NumStdDev::Two) >= half.estimate());
+            }
+        }
+    }
+}
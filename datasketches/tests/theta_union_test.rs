@@ -18,8 +18,10 @@
 #![cfg(feature = "theta")]
 
 use datasketches::theta::CompactThetaSketch;
+use datasketches::theta::ThetaSetOperationBuilder;
 use datasketches::theta::ThetaSketch;
 use datasketches::theta::ThetaSketchBuilder;
+use datasketches::theta::ThetaUnion;
 use datasketches::theta::ThetaUnionBuilder;
 
 fn sketch_with_range(lg_k: u8, start: i64, count: i64) -> ThetaSketch {
@@ -689,3 +691,241 @@ fn test_corner_case_union_states() {
         assert_eq!(compact_result.is_empty(), expected_empty);
     }
 }
+
+#[test]
+fn test_set_operation_builder_downsamples_union() {
+    let sketch1 = sketch_with_range(12, 0, 10_000);
+    let sketch2 = sketch_with_range(12, 5_000, 10_000);
+
+    let result = ThetaSetOperationBuilder::default()
+        .lg_k(10)
+        .union_many([&sketch1, &sketch2], true)
+        .unwrap();
+    assert_eq!(result.num_retained(), 1 << 10);
+    assert!(result.is_ordered());
+    assert_estimate_close(&result, 15_000.0, 15_000.0 * 0.1);
+}
+
+#[test]
+fn test_union_many_matches_stateful_union() {
+    let sketches: Vec<_> = (0..4)
+        .map(|i| sketch_with_range(12, i * 3_000, 5_000))
+        .collect();
+
+    let mut union = ThetaUnionBuilder::default().build();
+    for sketch in &sketches {
+        union.update(sketch).unwrap();
+    }
+    let expected = union.to_sketch(true);
+
+    let result = datasketches::theta::union_many(&sketches, true).unwrap();
+    assert_eq!(result.theta64(), expected.theta64());
+    let hashes = |sketch: &CompactThetaSketch| sketch.iter().map(|e| e.hash()).collect::<Vec<_>>();
+    assert_eq!(hashes(&result), hashes(&expected));
+}
+
+#[test]
+fn test_union_ordered_matches_union_many() {
+    let hashes = |sketch: &CompactThetaSketch| sketch.iter().map(|e| e.hash()).collect::<Vec<_>>();
+    let empty = ThetaSketchBuilder::default().build();
+    let cases = [
+        (
+            sketch_with_range(10, 0, 300),
+            sketch_with_range(10, 200, 300),
+        ),
+        (
+            sketch_with_range(12, 0, 20_000),
+            sketch_with_range(11, 10_000, 20_000),
+        ),
+        (sketch_with_range(12, 0, 10_000), empty.clone()),
+        (empty.clone(), empty),
+    ];
+    for lg_k in [10, 12] {
+        let builder = ThetaSetOperationBuilder::default().lg_k(lg_k);
+        for (a, b) in &cases {
+            let expected = builder.clone().union_many([a, b], true).unwrap();
+            for (a_ordered, b_ordered) in [(true, true), (true, false), (false, false)] {
+                let result = builder
+                    .union_ordered(&a.compact(a_ordered), &b.compact(b_ordered))
+                    .unwrap();
+                assert!(result.is_ordered());
+                assert_eq!(result.is_empty(), expected.is_empty());
+                assert_eq!(result.theta64(), expected.theta64());
+                assert_eq!(hashes(&result), hashes(&expected));
+            }
+        }
+    }
+
+    let sampled = ThetaSetOperationBuilder::default().sampling_probability(0.5);
+    let a = sketch_with_range(12, 0, 100).compact(true);
+    assert_eq!(
+        sampled.union_ordered(&a, &a).unwrap().serialize(),
+        sampled.clone().union_many([&a], true).unwrap().serialize()
+    );
+}
+
+#[test]
+fn test_union_ordered_seed_mismatch() {
+    let mut sketch = ThetaSketchBuilder::default().seed(7).build();
+    sketch.update(1);
+    let sketch = sketch.compact(true);
+    let empty = ThetaSketchBuilder::default().build().compact(true);
+    assert!(datasketches::theta::union_ordered(&empty, &sketch).is_err());
+    let result = ThetaSetOperationBuilder::default()
+        .seed(7)
+        .union_ordered(&sketch, &empty)
+        .unwrap();
+    assert_eq!(result.estimate(), 1.0);
+}
+
+#[test]
+fn test_set_operation_builder_seed() {
+    let mut sketch = ThetaSketchBuilder::default().seed(123).build();
+    sketch.update("apple");
+
+    assert!(datasketches::theta::union_many([&sketch], true).is_err());
+
+    let builder = ThetaSetOperationBuilder::default().seed(123);
+    let result = builder.clone().union_many([&sketch], true).unwrap();
+    assert_eq!(result.estimate(), 1.0);
+
+    let mut intersection = builder.clone().build_intersection();
+    intersection.update(&sketch).unwrap();
+    assert_eq!(intersection.to_sketch(true).estimate(), 1.0);
+
+    let a_not_b = builder
+        .build_a_not_b()
+        .compute(&sketch, &sketch, true)
+        .unwrap();
+    assert_eq!(a_not_b.estimate(), 0.0);
+}
+
+#[test]
+fn test_add_assign_matches_update() {
+    let mut update_sketch = ThetaSketchBuilder::default().build();
+    let mut compact_source = ThetaSketchBuilder::default().build();
+    for i in 0..1000 {
+        update_sketch.update(i);
+        compact_source.update(i + 500);
+    }
+    let compact = compact_source.compact(true);
+
+    let mut expected = ThetaUnionBuilder::default().build();
+    expected.update(&update_sketch).unwrap();
+    expected.update(&compact).unwrap();
+
+    let mut union = ThetaUnionBuilder::default().build();
+    union += &update_sketch;
+    union += &compact;
+    assert_eq!(
+        union.to_sketch(true).serialize(),
+        expected.to_sketch(true).serialize()
+    );
+}
+
+#[test]
+#[should_panic(expected = "cannot add sketch to ThetaUnion")]
+fn test_add_assign_seed_mismatch() {
+    let mut sketch = ThetaSketchBuilder::default().seed(123).build();
+    sketch.update("apple");
+    let mut union = ThetaUnionBuilder::default().build();
+    union += &sketch;
+}
+
+#[test]
+fn test_save_load_state() {
+    for count in [0, 10, 100_000] {
+        let mut union = ThetaUnionBuilder::default()
+            .lg_k(10)
+            .sampling_probability(0.5)
+            .seed(123)
+            .build();
+        let mut sketch = ThetaSketchBuilder::default().seed(123).build();
+        for value in 0..count {
+            sketch.update(value);
+        }
+        union.update(&sketch).unwrap();
+
+        let state = union.save_state();
+        let mut restored = ThetaUnion::load_state(&state).unwrap();
+        assert_eq!(
+            restored.to_sketch(true).serialize(),
+            union.to_sketch(true).serialize()
+        );
+
+        // The restored union keeps the seed and configuration of the original
+        let mut more = ThetaSketchBuilder::default().seed(123).build();
+        for value in count..count + 1_000 {
+            more.update(value);
+        }
+        union.update(&more).unwrap();
+        restored.update(&more).unwrap();
+        assert_eq!(
+            restored.to_sketch(true).serialize(),
+            union.to_sketch(true).serialize()
+        );
+    }
+}
+
+#[test]
+fn test_load_state_rejects_invalid_state() {
+    let mut sketch = ThetaSketchBuilder::default().build();
+    sketch.update("apple");
+    let mut union = ThetaUnionBuilder::default().build();
+    union.update(&sketch).unwrap();
+    let state = union.save_state();
+
+    // A plain serialized sketch is not a union state
+    assert!(ThetaUnion::load_state(&union.to_sketch(true).serialize()).is_err());
+
+    for len in 0..state.len() {
+        assert!(ThetaUnion::load_state(&state[..len]).is_err(), "len={len}");
+    }
+}
+
+#[test]
+fn test_estimate_union_size_matches_union() {
+    let builder = ThetaSetOperationBuilder::default().lg_k(10);
+    let exact = [
+        sketch_with_range(10, 0, 300).compact(true),
+        sketch_with_range(10, 200, 300).compact(false),
+    ];
+    assert_eq!(
+        builder.estimate_union_size(&exact).unwrap(),
+        builder.clone().union_many(&exact, true).unwrap().estimate()
+    );
+    assert_eq!(builder.estimate_union_size(&exact).unwrap(), 500.0);
+
+    let estimating = [
+        sketch_with_range(12, 0, 20_000).compact(true),
+        sketch_with_range(11, 10_000, 20_000).compact(false),
+        sketch_with_range(10, 25_000, 10_000).compact(true),
+        ThetaSketchBuilder::default().build().compact(true),
+    ];
+    let union = builder.clone().union_many(&estimating, true).unwrap();
+    let estimate = builder.estimate_union_size(&estimating).unwrap();
+    assert!(
+        (estimate - union.estimate()).abs() <= union.estimate() * 0.02,
+        "estimate={estimate}, union={}",
+        union.estimate()
+    );
+    assert!((estimate - 35_000.0).abs() <= 35_000.0 * 0.1);
+
+    let empty: [CompactThetaSketch; 0] = [];
+    assert_eq!(builder.estimate_union_size(&empty).unwrap(), 0.0);
+}
+
+#[test]
+fn test_estimate_union_size_seed_mismatch() {
+    let mut sketch = ThetaSketchBuilder::default().seed(7).build();
+    sketch.update(1);
+    let sketch = sketch.compact(true);
+    assert!(datasketches::theta::estimate_union_size([&sketch]).is_err());
+    assert_eq!(
+        ThetaSetOperationBuilder::default()
+            .seed(7)
+            .estimate_union_size([&sketch])
+            .unwrap(),
+        1.0
+    );
+}
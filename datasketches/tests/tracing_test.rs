@@ -0,0 +1,130 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+#![cfg(all(
+    feature = "tracing",
+    feature = "frequencies",
+    feature = "hll",
+    feature = "theta"
+))]
+
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use datasketches::frequencies::FrequentItemsSketch;
+use datasketches::hll::HllSketch;
+use datasketches::hll::HllType;
+use datasketches::theta::ThetaSketchBuilder;
+use tracing::Event;
+use tracing::Metadata;
+use tracing::Subscriber;
+use tracing::field::Field;
+use tracing::field::Visit;
+use tracing::span;
+
+/// Records the message of every event.
+#[derive(Default, Clone)]
+struct Recorder {
+    messages: Arc<Mutex<Vec<String>>>,
+}
+
+impl Recorder {
+    /// Runs `f` with this recorder as the default subscriber and returns the messages emitted.
+    fn record(f: impl FnOnce()) -> Vec<String> {
+        let recorder = Recorder::default();
+        tracing::subscriber::with_default(recorder.clone(), f);
+        let messages = recorder.messages.lock().unwrap();
+        messages.clone()
+    }
+}
+
+impl Visit for Recorder {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        if field.name() == "message" {
+            self.messages.lock().unwrap().push(format!("{value:?}"));
+        }
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, _span: &span::Attributes<'_>) -> span::Id {
+        span::Id::from_u64(1)
+    }
+
+    fn record(&self, _span: &span::Id, _values: &span::Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &span::Id, _follows: &span::Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        event.record(&mut self.clone());
+    }
+
+    fn enter(&self, _span: &span::Id) {}
+
+    fn exit(&self, _span: &span::Id) {}
+}
+
+fn count(messages: &[String], message: &str) -> usize {
+    messages.iter().filter(|m| *m == message).count()
+}
+
+#[test]
+fn test_theta_resize_and_rebuild_events() {
+    let messages = Recorder::record(|| {
+        let mut sketch = ThetaSketchBuilder::default().lg_k(10).build();
+        for i in 0..10_000 {
+            sketch.update(i);
+        }
+    });
+    assert!(count(&messages, "resized theta hash table") > 0);
+    assert!(count(&messages, "rebuilt theta hash table") > 0);
+}
+
+#[test]
+fn test_frequencies_purge_events() {
+    let mut num_purges = 0;
+    let messages = Recorder::record(|| {
+        let mut sketch = FrequentItemsSketch::<i64>::new(8);
+        for i in 0..1_000 {
+            sketch.update(i);
+        }
+        num_purges = sketch.num_purges();
+    });
+    assert!(num_purges > 0);
+    assert_eq!(
+        count(&messages, "purged frequent items map"),
+        num_purges as usize
+    );
+}
+
+#[test]
+fn test_hll_promotion_events() {
+    let messages = Recorder::record(|| {
+        let mut sketch = HllSketch::new(10, HllType::Hll4);
+        for i in 0..10_000 {
+            sketch.update(i);
+        }
+    });
+    // List to Set, then Set to HLL.
+    assert_eq!(count(&messages, "promoted HLL sketch"), 2);
+    assert!(count(&messages, "grew HLL coupon hash set") > 0);
+}
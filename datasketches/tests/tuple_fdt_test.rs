@@ -0,0 +1,226 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+#![cfg(feature = "tuple")]
+
+use datasketches::common::NumStdDev;
+use datasketches::error::ErrorKind;
+use datasketches::hash::DEFAULT_UPDATE_SEED;
+use datasketches::hash::MurmurHash3X64128;
+use datasketches::tuple::FdtSketch;
+use datasketches::tuple::FdtUnion;
+
+#[test]
+fn test_empty() {
+    let sketch = FdtSketch::new(12);
+    assert!(sketch.is_empty());
+    assert_eq!(sketch.estimate(), 0.0);
+    assert!(sketch.result(&[0], None, NumStdDev::Two, ',').is_empty());
+}
+
+#[test]
+fn test_exact_mode_groups() {
+    let mut sketch = FdtSketch::new(12);
+    for user in 0..10 {
+        sketch.update(&["US".to_string(), "web".to_string(), format!("user{user}")]);
+    }
+    for user in 0..5 {
+        sketch.update(&["US".to_string(), "app".to_string(), format!("user{user}")]);
+        sketch.update(&["FR".to_string(), "app".to_string(), format!("user{user}")]);
+    }
+    // Duplicate tuples are counted once.
+    sketch.update(&["FR", "app", "user0"]);
+
+    assert!(!sketch.is_estimation_mode());
+    assert_eq!(sketch.num_retained(), 20);
+
+    let groups = sketch.result(&[0], None, NumStdDev::Two, ',');
+    assert_eq!(groups.len(), 2);
+    assert_eq!(groups[0].primary_key(), "US");
+    assert_eq!(groups[0].count(), 15);
+    assert_eq!(groups[0].estimate(), 15.0);
+    assert_eq!(groups[0].lower_bound(), 15.0);
+    assert_eq!(groups[0].upper_bound(), 15.0);
+    assert_eq!(groups[0].fraction(), 0.75);
+    assert_eq!(groups[0].rse(), 0.0);
+    assert_eq!(groups[1].primary_key(), "FR");
+    assert_eq!(groups[1].count(), 5);
+
+    let groups = sketch.result(&[1, 0], None, NumStdDev::Two, '|');
+    let keys: Vec<_> = groups
+        .iter()
+        .map(|g| (g.primary_key(), g.count()))
+        .collect();
+    assert_eq!(keys, vec![("web|US", 10), ("app|FR", 5), ("app|US", 5)]);
+
+    let groups = sketch.result(&[1, 0], Some(1), NumStdDev::Two, '|');
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[0].primary_key(), "web|US");
+}
+
+#[test]
+fn test_estimation_mode_groups() {
+    let mut sketch = FdtSketch::new(10);
+    for user in 0..20_000 {
+        sketch.update(&["a".to_string(), user.to_string()]);
+    }
+    for user in 0..5_000 {
+        sketch.update(&["b".to_string(), user.to_string()]);
+    }
+    assert!(sketch.is_estimation_mode());
+
+    let groups = sketch.result(&[0], None, NumStdDev::Two, ',');
+    assert_eq!(groups.len(), 2);
+    let counts: u64 = groups.iter().map(|g| g.count()).sum();
+    assert_eq!(counts as usize, sketch.num_retained());
+
+    for (group, expected) in groups.iter().zip([20_000.0, 5_000.0]) {
+        assert!(group.lower_bound() <= group.estimate());
+        assert!(group.estimate() <= group.upper_bound());
+        assert!(
+            (group.estimate() - expected).abs() <= expected * 0.2,
+            "key={}, estimate={}",
+            group.primary_key(),
+            group.estimate()
+        );
+        assert!(group.rse() > 0.0);
+    }
+}
+
+#[test]
+fn test_with_threshold() {
+    let sketch = FdtSketch::with_threshold(0.01, 0.05);
+    assert_eq!(sketch.lg_k(), 16);
+}
+
+#[test]
+fn test_empty_key_is_ignored() {
+    let mut sketch = FdtSketch::new(12);
+    sketch.update::<&str>(&[]);
+    sketch.update(&[""]);
+    assert!(sketch.is_empty());
+
+    // Two empty strings join to ",", which is not empty.
+    sketch.update(&["", ""]);
+    assert_eq!(sketch.num_retained(), 1);
+}
+
+#[test]
+fn test_key_hash_matches_joined_tuple_bytes() {
+    let mut sketch = FdtSketch::new(12);
+    sketch.update(&["10.0.0.1", "alice"]);
+
+    let expected = MurmurHash3X64128::hash_bytes(b"10.0.0.1,alice", DEFAULT_UPDATE_SEED).0 >> 1;
+    let bytes = sketch.serialize();
+    // A single-entry image has a one-long preamble followed by the entry's hash.
+    assert_eq!(
+        u64::from_le_bytes(bytes[8..16].try_into().unwrap()),
+        expected
+    );
+}
+
+#[test]
+fn test_serialization_round_trip() {
+    let mut sketch = FdtSketch::new(12);
+    sketch.update(&["a", "x"]);
+    sketch.update(&["a", "y"]);
+    sketch.update(&["b", "x"]);
+
+    let decoded = FdtSketch::deserialize(&sketch.serialize(), 12).unwrap();
+    assert_eq!(decoded.num_retained(), 3);
+    assert_eq!(decoded.serialize(), sketch.serialize());
+
+    let mut tuples: Vec<_> = decoded.iter().map(|tuple| tuple.join(",")).collect();
+    tuples.sort();
+    assert_eq!(tuples, vec!["a,x", "a,y", "b,x"]);
+
+    let empty = FdtSketch::deserialize(&FdtSketch::new(12).serialize(), 12).unwrap();
+    assert!(empty.is_empty());
+}
+
+#[test]
+fn test_deserialize_estimation_mode_keeps_theta() {
+    let mut sketch = FdtSketch::new(10);
+    for user in 0..10_000 {
+        sketch.update(&["a".to_string(), user.to_string()]);
+    }
+    assert!(sketch.is_estimation_mode());
+
+    let decoded = FdtSketch::deserialize(&sketch.serialize(), 10).unwrap();
+    assert_eq!(decoded.theta(), sketch.theta());
+    assert_eq!(decoded.num_retained(), sketch.num_retained());
+    assert_eq!(decoded.estimate(), sketch.estimate());
+}
+
+#[test]
+fn test_deserialize_rejects_truncated_input() {
+    let mut sketch = FdtSketch::new(12);
+    sketch.update(&["a", "x"]);
+    let bytes = sketch.serialize();
+    let err = FdtSketch::deserialize(&bytes[..bytes.len() - 1], 12).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+}
+
+#[test]
+fn test_union() {
+    let mut a = FdtSketch::new(12);
+    let mut b = FdtSketch::new(12);
+    for user in 0..10 {
+        a.update(&["US".to_string(), format!("user{user}")]);
+    }
+    for user in 5..15 {
+        b.update(&["US".to_string(), format!("user{user}")]);
+        b.update(&["FR".to_string(), format!("user{user}")]);
+    }
+
+    let mut union = FdtUnion::new(12);
+    union.update(&a).unwrap();
+    union.update(&b).unwrap();
+    let result = union.to_sketch();
+    assert_eq!(result.lg_k(), 12);
+    assert_eq!(result.estimate(), 25.0);
+
+    let groups = result.result(&[0], None, NumStdDev::Two, ',');
+    let keys: Vec<_> = groups
+        .iter()
+        .map(|g| (g.primary_key(), g.count()))
+        .collect();
+    assert_eq!(keys, vec![("US", 15), ("FR", 10)]);
+
+    union.reset();
+    assert!(union.to_sketch().is_empty());
+}
+
+#[test]
+fn test_union_estimation_mode() {
+    let mut a = FdtSketch::new(10);
+    let mut b = FdtSketch::new(10);
+    for user in 0..20_000 {
+        a.update(&["a".to_string(), user.to_string()]);
+    }
+    for user in 10_000..30_000 {
+        b.update(&["a".to_string(), user.to_string()]);
+    }
+
+    let mut union = FdtUnion::new(10);
+    union.update(&a).unwrap();
+    union.update(&b).unwrap();
+    let result = union.to_sketch();
+    assert!(result.is_estimation_mode());
+    assert!(result.num_retained() <= 1 << 11);
+    assert!((result.estimate() - 30_000.0).abs() <= 30_000.0 * 0.1);
+}
@@ -315,3 +315,42 @@ fn test_compact_preserves_logical_non_empty_after_screened_update() {
     assert_eq!(compact.num_retained(), 0);
     assert_eq!(compact.theta64(), sketch.theta64());
 }
+
+#[cfg(feature = "hll")]
+#[test]
+fn test_hll_summary_two_level_distinct_counts() {
+    use datasketches::hll::HllSketch;
+    use datasketches::hll::HllType;
+    use datasketches::tuple::CompactTupleSketch;
+    use datasketches::tuple::HllSummaryPolicy;
+    use datasketches::tuple::TupleUnionBuilder;
+
+    let policy = HllSummaryPolicy::new(10, HllType::Hll8);
+    let mut a = TupleSketchBuilder::new(policy).build();
+    let mut b = TupleSketchBuilder::new(policy).build();
+    for campaign in 0..10u64 {
+        for user in 0..100u64 {
+            a.update(campaign, user);
+            b.update(campaign, user + 100);
+        }
+    }
+    b.update(10u64, 0u64);
+
+    let mut union = TupleUnionBuilder::new(policy).build();
+    union.update(&a).unwrap();
+    union.update(&b).unwrap();
+    let result = union.to_sketch(true);
+    assert_eq!(result.estimate(), 11.0);
+    for (_, users) in result.iter() {
+        let estimate = users.estimate();
+        assert!((estimate - 1.0).abs() < 0.01 || (estimate - 200.0).abs() < 10.0);
+    }
+
+    let bytes = result.serialize();
+    let decoded = CompactTupleSketch::<HllSketch>::deserialize(&bytes).unwrap();
+    assert_eq!(decoded.num_retained(), result.num_retained());
+    for ((hash1, users1), (hash2, users2)) in decoded.iter().zip(result.iter()) {
+        assert_eq!(hash1, hash2);
+        assert_eq!(users1, users2);
+    }
+}